@@ -9,7 +9,7 @@ use std::hint::black_box;
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Author {
     #[sql(pk)]
-    #[sql(relation(has_many -> Post, relation = "posts", on = author_id))]
+    #[sql(relation(has_many -> Post, name = "posts", on = author_id))]
     pub id: i64,
     pub name: String,
     #[sql(timestamp(created_at, chrono::Utc::now()))]
@@ -25,7 +25,7 @@ pub struct Post {
     pub id: i64,
     pub title: String,
     pub content: String,
-    #[sql(relation(belongs_to -> Author, relation = "author", on = id))]
+    #[sql(relation(belongs_to -> Author, name = "author", on = id))]
     pub author_id: i64,
     #[sql(timestamp(created_at, chrono::Utc::now()))]
     pub created_at: DateTime<Utc>,
@@ -33,6 +33,7 @@ pub struct Post {
     pub updated_at: DateTime<Utc>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, sqlx::FromRow)]
 struct RawAuthor {
     id: i64,
@@ -41,6 +42,7 @@ struct RawAuthor {
     updated_at: DateTime<Utc>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, sqlx::FromRow)]
 struct RawPost {
     id: i64,
@@ -51,6 +53,7 @@ struct RawPost {
     updated_at: DateTime<Utc>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, sqlx::FromRow)]
 struct RawAuthorPost {
     author_id: i64,
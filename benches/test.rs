@@ -22,6 +22,7 @@ pub struct User {
     pub updated_at: DateTime<Utc>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, sqlx::FromRow)]
 struct RawUser {
     id: i64,
@@ -120,8 +121,8 @@ fn crud_benchmark(c: &mut Criterion) {
                     .bind("Benchmark")
                     .bind("User")
                     .bind("Performance testing user")
-                    .bind(&now)
-                    .bind(&now)
+                    .bind(now)
+                    .bind(now)
                     .execute(&pool)
                     .await
                     .expect("insert failed"),
@@ -189,7 +190,7 @@ fn crud_benchmark(c: &mut Criterion) {
                 black_box(
                     sqlx::query("UPDATE users SET bio = ?, updated_at = ? WHERE id = ?")
                         .bind(&bio)
-                        .bind(&now)
+                        .bind(now)
                         .bind(update_user_id)
                         .execute(&pool)
                         .await
@@ -0,0 +1,16 @@
+//! `cargo sqlorm`, installed with `cargo install sqlorm-cli --features postgres` (or `sqlite`).
+//!
+//! Runs [`sqlorm_core::cli`] without entities; see there for running the `schema` commands
+//! against your own entities.
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+#[tokio::main]
+async fn main() -> std::process::ExitCode {
+    sqlorm_core::cli::run(&[]).await
+}
+
+#[cfg(not(any(feature = "postgres", feature = "sqlite")))]
+fn main() {
+    eprintln!("cargo-sqlorm was built without a database, reinstall it with `--features postgres` or `--features sqlite`");
+    std::process::exit(1);
+}
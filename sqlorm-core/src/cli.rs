@@ -0,0 +1,190 @@
+//! Commands of the `cargo sqlorm` CLI.
+//!
+//! The `cargo-sqlorm` binary of the `sqlorm-cli` crate runs them without entities, which is
+//! enough for `generate entity` and `seed run`. The `schema` commands compare entities with
+//! the database, so they need a binary of your project passing its schemas:
+//! ```rust ignore
+//! // src/bin/sqlorm.rs, run with `cargo run --bin sqlorm -- schema diff`
+//! use sqlorm::Schema;
+//!
+//! #[tokio::main]
+//! async fn main() -> std::process::ExitCode {
+//!     sqlorm::cli::run(&[User::schema(), Jar::schema()]).await
+//! }
+//! ```
+//!
+//! The database is read from `--database-url <url>` or the `DATABASE_URL` variable.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use crate::Sqlorm;
+use crate::codegen::generate_entity;
+use crate::driver::Pool;
+use crate::schema::{TableSchema, diff, verify_table};
+
+pub const USAGE: &str = "usage: cargo sqlorm [--database-url <url>] <command>
+
+commands:
+    generate entity <table>  print a #[table] entity for an existing table
+    schema diff              print the DDL creating missing tables and columns
+    schema validate          report differences between entities and tables
+    seed run [dir]           execute the .sql files of dir (default `seeds`) in name order";
+
+/// A parsed CLI command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    GenerateEntity { table: String },
+    SchemaDiff,
+    SchemaValidate,
+    SeedRun { dir: PathBuf },
+}
+
+/// Output of a [`Command`], and whether it succeeded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub output: String,
+    pub success: bool,
+}
+
+impl Report {
+    fn ok(output: String) -> Self {
+        Report {
+            output,
+            success: true,
+        }
+    }
+}
+
+impl Command {
+    /// Parses the arguments following the program name, returning the database url passed
+    /// with `--database-url`, if any.
+    pub fn parse<I, S>(args: I) -> Result<(Self, Option<String>), String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut args: Vec<String> = args.into_iter().map(Into::into).collect();
+        // cargo runs `cargo-sqlorm sqlorm ...` for `cargo sqlorm ...`
+        if args.first().is_some_and(|arg| arg == "sqlorm") {
+            args.remove(0);
+        }
+        let mut database_url = None;
+        if let Some(i) = args.iter().position(|arg| arg == "--database-url") {
+            args.remove(i);
+            if i >= args.len() {
+                return Err("--database-url needs a value".to_string());
+            }
+            database_url = Some(args.remove(i));
+        }
+
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let command = match args.as_slice() {
+            ["generate", "entity", table] => Command::GenerateEntity {
+                table: table.to_string(),
+            },
+            ["schema", "diff"] => Command::SchemaDiff,
+            ["schema", "validate"] => Command::SchemaValidate,
+            ["seed", "run"] => Command::SeedRun {
+                dir: PathBuf::from("seeds"),
+            },
+            ["seed", "run", dir] => Command::SeedRun {
+                dir: PathBuf::from(dir),
+            },
+            _ => return Err(USAGE.to_string()),
+        };
+        Ok((command, database_url))
+    }
+
+    /// Runs the command against `pool`, comparing `schemas` for the `schema` commands.
+    pub async fn execute(&self, pool: &Pool, schemas: &[TableSchema]) -> sqlx::Result<Report> {
+        if matches!(self, Command::SchemaDiff | Command::SchemaValidate) && schemas.is_empty() {
+            return Ok(Report {
+                output: "no entity schemas given, run the schema commands through a binary \
+                         calling `sqlorm::cli::run(&[...])`\n"
+                    .to_string(),
+                success: false,
+            });
+        }
+
+        match self {
+            Command::GenerateEntity { table } => Ok(Report::ok(generate_entity(pool, table).await?)),
+            Command::SchemaDiff => Ok(Report::ok(
+                diff(pool, schemas)
+                    .await?
+                    .iter()
+                    .map(|statement| format!("{}\n", statement))
+                    .collect(),
+            )),
+            Command::SchemaValidate => {
+                let mut output = String::new();
+                let mut success = true;
+                for schema in schemas {
+                    for mismatch in verify_table(pool, schema).await? {
+                        success = false;
+                        writeln!(output, "{}: {}", schema.name, mismatch).unwrap();
+                    }
+                }
+                if success {
+                    writeln!(output, "{} tables match", schemas.len()).unwrap();
+                }
+                Ok(Report { output, success })
+            }
+            Command::SeedRun { dir } => run_seeds(pool, dir).await.map(Report::ok),
+        }
+    }
+}
+
+/// Executes the `.sql` files of `dir` in name order, returning the list of executed files.
+async fn run_seeds(pool: &Pool, dir: &Path) -> sqlx::Result<String> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "sql"))
+        .collect();
+    files.sort();
+
+    let mut output = String::new();
+    for file in files {
+        let sql = std::fs::read_to_string(&file)?;
+        sqlx::raw_sql(&sql).execute(pool).await?;
+        writeln!(output, "ran {}", file.display()).unwrap();
+    }
+    Ok(output)
+}
+
+/// Runs the command given on the command line, printing its output.
+///
+/// Returns a failure exit code on invalid arguments, database errors and failed validations.
+pub async fn run(schemas: &[TableSchema]) -> ExitCode {
+    let (command, database_url) = match Command::parse(std::env::args().skip(1)) {
+        Ok(parsed) => parsed,
+        Err(usage) => {
+            eprintln!("{}", usage);
+            return ExitCode::FAILURE;
+        }
+    };
+    let Some(database_url) = database_url.or_else(|| std::env::var("DATABASE_URL").ok()) else {
+        eprintln!("set DATABASE_URL or pass --database-url");
+        return ExitCode::FAILURE;
+    };
+
+    let result = match Sqlorm::connect(&database_url).await {
+        Ok(pool) => command.execute(&pool, schemas).await,
+        Err(err) => Err(err),
+    };
+    match result {
+        Ok(report) if report.success => {
+            print!("{}", report.output);
+            ExitCode::SUCCESS
+        }
+        Ok(report) => {
+            eprint!("{}", report.output);
+            ExitCode::FAILURE
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
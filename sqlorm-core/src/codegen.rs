@@ -0,0 +1,159 @@
+//! Entity code generated from existing tables, to adopt sqlorm on an existing database.
+//!
+//! The primary key, single-column unique constraints and foreign keys of the table are turned
+//! into `pk`, `unique` and `belongs_to` attributes, and `created_at`/`updated_at`/`deleted_at`
+//! timestamp columns into `timestamp(...)` attributes.
+//!
+//! Example usage:
+//! ```rust ignore
+//! let source = sqlorm::codegen::generate_entity(&pool, "jar").await?;
+//! std::fs::write("src/entities/jar.rs", source)?;
+//! ```
+
+use std::fmt::Write;
+
+use crate::dialect;
+use crate::driver::Pool;
+use crate::schema::{DbColumn, DbForeignKey, table_columns, table_foreign_keys};
+
+/// Rust keywords that can't be used as field names.
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "yield",
+];
+
+/// Generates the source of a `#[table]` entity for the existing table `name`, possibly
+/// schema-qualified.
+///
+/// Columns of types without an obvious Rust type are generated as `String`, marked with a
+/// `TODO` comment. Fails with [`sqlx::Error::RowNotFound`] if the table doesn't exist.
+pub async fn generate_entity(pool: &Pool, name: &str) -> sqlx::Result<String> {
+    let columns = table_columns(pool, name).await?;
+    if columns.is_empty() {
+        return Err(sqlx::Error::RowNotFound);
+    }
+    let foreign_keys = table_foreign_keys(pool, name).await?;
+    Ok(render_entity(name, &columns, &foreign_keys))
+}
+
+/// Renders the source of a `#[table]` entity, see [`generate_entity`].
+pub fn render_entity(name: &str, columns: &[DbColumn], foreign_keys: &[DbForeignKey]) -> String {
+    let (schema, table) = match name.split_once('.') {
+        Some((schema, table)) => (Some(schema), table),
+        None => (None, name),
+    };
+
+    let mut out = match schema {
+        Some(schema) => format!("#[table(name = \"{}\", schema = \"{}\")]\n", table, schema),
+        None => format!("#[table(name = \"{}\")]\n", table),
+    };
+    writeln!(out, "#[derive(Debug, Clone, Default)]").unwrap();
+    writeln!(out, "pub struct {} {{", struct_name(table)).unwrap();
+    if !columns.iter().any(|c| c.primary_key) {
+        writeln!(out, "    // TODO: no primary key found, mark one field with #[sql(pk)]").unwrap();
+    }
+
+    for column in columns {
+        let field = field_name(&column.name);
+        let rust_type = dialect::rust_type(&column.sql_type);
+
+        if rust_type.is_none() {
+            writeln!(out, "    // TODO: no Rust type known for `{}`", column.sql_type).unwrap();
+        }
+        if column.primary_key {
+            writeln!(out, "    #[sql(pk)]").unwrap();
+        } else if column.unique {
+            writeln!(out, "    #[sql(unique)]").unwrap();
+        }
+        if field != column.name {
+            writeln!(out, "    #[sql(rename(\"{}\"))]", column.name).unwrap();
+        }
+        for fk in foreign_keys.iter().filter(|fk| fk.column == column.name) {
+            writeln!(
+                out,
+                "    #[sql(relation(belongs_to -> {}, name = \"{}\", on = {}))]",
+                struct_name(&fk.table),
+                relation_name(&field),
+                field_name(&fk.references)
+            )
+            .unwrap();
+        }
+
+        let rust_type = rust_type.unwrap_or("String");
+        let is_timestamp = rust_type == "chrono::DateTime<chrono::Utc>";
+        match field.as_str() {
+            "created_at" | "updated_at" if is_timestamp && !column.nullable => {
+                writeln!(out, "    #[sql(timestamp({}, chrono::Utc::now()))]", field).unwrap();
+            }
+            "deleted_at" if is_timestamp && column.nullable => {
+                writeln!(out, "    #[sql(timestamp(deleted_at, chrono::Utc::now()))]").unwrap();
+            }
+            _ => {}
+        }
+
+        if column.nullable && !column.primary_key {
+            writeln!(out, "    pub {}: Option<{}>,", field, rust_type).unwrap();
+        } else {
+            writeln!(out, "    pub {}: {},", field, rust_type).unwrap();
+        }
+    }
+    writeln!(out, "}}").unwrap();
+    out
+}
+
+/// Struct name of a table, e.g. `Jar` for `jars`, `Category` for `categories` and `OrderItem`
+/// for `order_items`.
+fn struct_name(table: &str) -> String {
+    let table = table.rsplit('.').next().unwrap_or(table);
+    let singular = if let Some(stem) = table.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else {
+        match table.strip_suffix('s') {
+            Some(singular) if !singular.ends_with('s') && !singular.is_empty() => singular,
+            _ => table,
+        }
+        .to_string()
+    };
+    singular
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_ascii_uppercase().to_string() + chars.as_str()
+            })
+        })
+        .collect()
+}
+
+/// Field name of a column, `snake_case` and not a keyword.
+fn field_name(column: &str) -> String {
+    let mut field = String::new();
+    for (i, c) in column.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 && !field.ends_with('_') {
+            field.push('_');
+        }
+        if c.is_ascii_alphanumeric() {
+            field.push(c.to_ascii_lowercase());
+        } else if !field.ends_with('_') {
+            field.push('_');
+        }
+    }
+    if field.is_empty() || field.starts_with(|c: char| c.is_ascii_digit()) {
+        field.insert(0, '_');
+    }
+    if KEYWORDS.contains(&field.as_str()) {
+        field.push('_');
+    }
+    field
+}
+
+/// Relation name of a foreign key field, e.g. `owner` for `owner_id`.
+fn relation_name(field: &str) -> String {
+    match field.strip_suffix("_id") {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => format!("{}_ref", field),
+    }
+}
@@ -0,0 +1,65 @@
+//! SQL comments tagging statements, in the style of [sqlcommenter], so database-side monitoring
+//! can attribute queries to application code.
+//!
+//! Comments added with `comment(...)` on the query and statement builders are rendered as a
+//! trailing `/* ... */`, followed by the trace id of [`inject_trace_id`], if set:
+//! ```rust ignore
+//! sqlorm::comment::inject_trace_id(|| current_span_traceparent());
+//!
+//! // SELECT ... FROM "jar" AS jar /* endpoint=GET /jars, traceparent='00-4bf9...-01' */
+//! let jars = Jar::query().comment("endpoint=GET /jars").fetch_all(&pool).await?;
+//! ```
+//!
+//! Statements differing by comment are prepared separately, so comments changing with every
+//! request, such as trace ids, are best combined with `not_persistent()`.
+//!
+//! [sqlcommenter]: https://google.github.io/sqlcommenter/
+
+use std::sync::{Arc, PoisonError, RwLock};
+
+type TraceIdProvider = Arc<dyn Fn() -> Option<String> + Send + Sync>;
+
+static TRACE_ID: RwLock<Option<TraceIdProvider>> = RwLock::new(None);
+
+/// Appends `traceparent='<id>'` to the comment of every statement built while `provider`
+/// returns an id, replacing the provider set before.
+pub fn inject_trace_id<F>(provider: F)
+where
+    F: Fn() -> Option<String> + Send + Sync + 'static,
+{
+    *TRACE_ID.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(provider));
+}
+
+/// Stops injecting trace ids set with [`inject_trace_id`].
+pub fn clear_trace_id() {
+    *TRACE_ID.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Renders `comments` and the injected trace id as ` /* ... */`, or an empty string if there
+/// is nothing to render.
+///
+/// `/*` and `*/` in comments are broken up so a comment can't nest, which PostgreSQL allows,
+/// or terminate early. Quotes in the trace id are escaped with a backslash.
+pub fn render(comments: &[String]) -> String {
+    let provider = TRACE_ID
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    let trace_id = provider
+        .and_then(|provider| provider())
+        .map(|id| {
+            let id = id.replace('\\', "\\\\").replace('\'', "\\'");
+            format!("traceparent='{}'", id)
+        });
+
+    let parts: Vec<String> = comments
+        .iter()
+        .cloned()
+        .chain(trace_id)
+        .map(|part| part.replace("*/", "* /").replace("/*", "/ *"))
+        .collect();
+    if parts.is_empty() {
+        return String::new();
+    }
+    format!(" /* {} */", parts.join(", "))
+}
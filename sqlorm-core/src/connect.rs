@@ -0,0 +1,109 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use sqlx::Executor;
+use sqlx::pool::PoolOptions;
+
+use crate::driver::{Driver, Pool};
+
+/// Entry point for opening a connection [`Pool`].
+///
+/// Example usage:
+/// ```rust ignore
+/// let pool = Sqlorm::connect("postgres://localhost/app").await?;
+///
+/// let pool = Sqlorm::options()
+///     .max_connections(1)
+///     .on_connect("PRAGMA foreign_keys = ON")
+///     .connect("sqlite::memory:")
+///     .await?;
+/// ```
+pub struct Sqlorm;
+
+impl Sqlorm {
+    /// Opens a pool with the default [`ConnectConfig`].
+    pub async fn connect(url: &str) -> sqlx::Result<Pool> {
+        Self::options().connect(url).await
+    }
+
+    /// Starts configuring a pool.
+    pub fn options() -> ConnectConfig {
+        ConnectConfig::default()
+    }
+}
+
+/// Pool configuration, created with [`Sqlorm::options`].
+#[must_use]
+#[derive(Default)]
+pub struct ConnectConfig {
+    options: PoolOptions<Driver>,
+    on_connect: Vec<String>,
+}
+
+impl ConnectConfig {
+    /// Maximum number of connections the pool keeps open.
+    ///
+    /// Use `1` for `sqlite::memory:`, where every connection opens its own database.
+    pub fn max_connections(mut self, max: u32) -> Self {
+        self.options = self.options.max_connections(max);
+        self
+    }
+
+    /// Number of connections the pool tries to keep open at all times.
+    pub fn min_connections(mut self, min: u32) -> Self {
+        self.options = self.options.min_connections(min);
+        self
+    }
+
+    /// How long acquiring a connection may wait before failing with `PoolTimedOut`.
+    pub fn acquire_timeout(mut self, timeout: Duration) -> Self {
+        self.options = self.options.acquire_timeout(timeout);
+        self
+    }
+
+    /// How long a connection may stay idle before it is closed.
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.options = self.options.idle_timeout(timeout);
+        self
+    }
+
+    /// Maximum lifetime of a connection before it is replaced.
+    pub fn max_lifetime(mut self, lifetime: Duration) -> Self {
+        self.options = self.options.max_lifetime(lifetime);
+        self
+    }
+
+    /// Statement executed on every new connection, e.g. `PRAGMA foreign_keys = ON` or
+    /// `SET search_path TO app`. Statements run in the order they were added.
+    pub fn on_connect(mut self, sql: impl Into<String>) -> Self {
+        self.on_connect.push(sql.into());
+        self
+    }
+
+    /// Opens the pool, connecting eagerly so a bad `url` fails here.
+    pub async fn connect(self, url: &str) -> sqlx::Result<Pool> {
+        self.into_pool_options().connect(url).await
+    }
+
+    /// Creates the pool without connecting; connections are opened on first use.
+    pub fn connect_lazy(self, url: &str) -> sqlx::Result<Pool> {
+        self.into_pool_options().connect_lazy(url)
+    }
+
+    fn into_pool_options(self) -> PoolOptions<Driver> {
+        if self.on_connect.is_empty() {
+            return self.options;
+        }
+
+        let statements = Arc::new(self.on_connect);
+        self.options.after_connect(move |conn, _| {
+            let statements = Arc::clone(&statements);
+            Box::pin(async move {
+                for sql in statements.iter() {
+                    conn.execute(sql.as_str()).await?;
+                }
+                Ok(())
+            })
+        })
+    }
+}
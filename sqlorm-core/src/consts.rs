@@ -1,3 +1,15 @@
 pub fn format_alised_col_name(alias: &str, column_name: &str) -> String {
     format!("{}__{}", alias, column_name)
 }
+
+/// Maximum number of bind parameters in a single statement.
+///
+/// Bulk statements are split into batches so they stay below this limit.
+#[cfg(feature = "postgres")]
+pub const MAX_BIND_PARAMS: usize = 65_535;
+
+/// Maximum number of bind parameters in a single statement.
+///
+/// Bulk statements are split into batches so they stay below this limit.
+#[cfg(feature = "sqlite")]
+pub const MAX_BIND_PARAMS: usize = 32_766;
@@ -0,0 +1,206 @@
+//! SQL rendering differences between the supported databases, defined for the
+//! driver selected with the `postgres` or `sqlite` feature.
+
+use crate::Isolation;
+
+/// Query listing the `(name, type, nullable, primary_key, unique)` of a table's columns in
+/// declaration order, binding the schema (`NULL` for the default one) and the table name.
+/// `unique` is only set for single-column unique constraints.
+#[cfg(feature = "postgres")]
+pub(crate) const TABLE_COLUMNS: &str = "SELECT c.column_name::text, c.udt_name::text, c.is_nullable = 'YES', \
+            EXISTS (SELECT 1 FROM pg_index i JOIN pg_attribute a \
+                ON a.attrelid = i.indrelid AND a.attnum = ANY(i.indkey) \
+                WHERE i.indrelid = format('%I.%I', c.table_schema, c.table_name)::regclass \
+                AND i.indisprimary AND a.attname = c.column_name), \
+            EXISTS (SELECT 1 FROM pg_index i JOIN pg_attribute a \
+                ON a.attrelid = i.indrelid AND a.attnum = i.indkey[0] \
+                WHERE i.indrelid = format('%I.%I', c.table_schema, c.table_name)::regclass \
+                AND i.indisunique AND NOT i.indisprimary AND i.indnatts = 1 \
+                AND a.attname = c.column_name) \
+        FROM information_schema.columns c \
+        WHERE c.table_schema = COALESCE($1::text, current_schema()::text) AND c.table_name = $2 \
+        ORDER BY c.ordinal_position";
+
+/// Query listing the `(name, type, nullable, primary_key, unique)` of a table's columns in
+/// declaration order, binding the schema (`NULL` for the default one) and the table name.
+/// `unique` is only set for single-column unique constraints.
+#[cfg(feature = "sqlite")]
+pub(crate) const TABLE_COLUMNS: &str = "SELECT c.name, c.type, c.\"notnull\" = 0, c.pk > 0, \
+            EXISTS (SELECT 1 FROM pragma_index_list(?2, COALESCE(?1, 'main')) il \
+                JOIN pragma_index_info(il.name, COALESCE(?1, 'main')) ii \
+                WHERE il.\"unique\" AND il.origin != 'pk' AND ii.name = c.name \
+                AND (SELECT COUNT(*) FROM pragma_index_info(il.name, COALESCE(?1, 'main'))) = 1) \
+        FROM pragma_table_info(?2, COALESCE(?1, 'main')) c ORDER BY c.cid";
+
+/// Query listing the `(column, referenced_table, referenced_column)` of a table's foreign
+/// keys, binding the schema and the table name like [`TABLE_COLUMNS`].
+#[cfg(feature = "postgres")]
+pub(crate) const FOREIGN_KEYS: &str = "SELECT kcu.column_name::text, ccu.table_name::text, ccu.column_name::text \
+        FROM information_schema.table_constraints tc \
+        JOIN information_schema.key_column_usage kcu \
+            ON kcu.constraint_name = tc.constraint_name AND kcu.table_schema = tc.table_schema \
+        JOIN information_schema.constraint_column_usage ccu \
+            ON ccu.constraint_name = tc.constraint_name AND ccu.constraint_schema = tc.table_schema \
+        WHERE tc.constraint_type = 'FOREIGN KEY' \
+            AND tc.table_schema = COALESCE($1::text, current_schema()::text) AND tc.table_name = $2 \
+        ORDER BY kcu.ordinal_position";
+
+/// Query listing the `(column, referenced_table, referenced_column)` of a table's foreign
+/// keys, binding the schema and the table name like [`TABLE_COLUMNS`].
+/// `to` is `NULL` for keys referencing the primary key implicitly, assumed to be `id`.
+#[cfg(feature = "sqlite")]
+pub(crate) const FOREIGN_KEYS: &str = "SELECT \"from\", \"table\", COALESCE(\"to\", 'id') \
+        FROM pragma_foreign_key_list(?2, COALESCE(?1, 'main')) ORDER BY id, seq";
+
+/// Statement starting a transaction with `isolation`.
+#[cfg(feature = "postgres")]
+pub(crate) fn begin(isolation: Isolation) -> &'static str {
+    match isolation {
+        Isolation::ReadCommitted => "BEGIN ISOLATION LEVEL READ COMMITTED",
+        Isolation::RepeatableRead => "BEGIN ISOLATION LEVEL REPEATABLE READ",
+        Isolation::Serializable => "BEGIN ISOLATION LEVEL SERIALIZABLE",
+    }
+}
+
+/// Statement starting a transaction with `isolation`.
+#[cfg(feature = "sqlite")]
+pub(crate) fn begin(isolation: Isolation) -> &'static str {
+    match isolation {
+        Isolation::ReadCommitted => "BEGIN DEFERRED",
+        Isolation::RepeatableRead => "BEGIN IMMEDIATE",
+        Isolation::Serializable => "BEGIN EXCLUSIVE",
+    }
+}
+
+/// Column type and constraint of a primary key generated by the database, given the
+/// `sql_type` of the key. Keys of types without a generator are only `PRIMARY KEY`.
+#[cfg(feature = "postgres")]
+pub(crate) fn auto_increment_pk(sql_type: &str) -> String {
+    match sql_type {
+        "INT8" => "BIGSERIAL PRIMARY KEY".to_string(),
+        "INT4" => "SERIAL PRIMARY KEY".to_string(),
+        "INT2" => "SMALLSERIAL PRIMARY KEY".to_string(),
+        other => format!("{} PRIMARY KEY", other),
+    }
+}
+
+/// Column type and constraint of a primary key generated by the database, given the
+/// `sql_type` of the key. Keys of types without a generator are only `PRIMARY KEY`.
+/// Only `INTEGER PRIMARY KEY` columns alias the rowid, `AUTOINCREMENT` keeps ids of
+/// deleted rows from being reused.
+#[cfg(feature = "sqlite")]
+pub(crate) fn auto_increment_pk(sql_type: &str) -> String {
+    match sql_type {
+        "INTEGER" => "INTEGER PRIMARY KEY AUTOINCREMENT".to_string(),
+        other => format!("{} PRIMARY KEY", other),
+    }
+}
+
+/// Canonical form of a column type, so that types written differently, e.g. by
+/// [`TABLE_COLUMNS`] and sqlx, compare equal. Drops case and modifiers such as
+/// `(12,2)` by default.
+/// Maps SQL standard names to the internal ones reported by `udt_name`, e.g. `BIGINT` to
+/// `INT8`, and `_int8` arrays to `INT8[]`.
+#[cfg(feature = "postgres")]
+pub(crate) fn normalize_type(sql_type: &str) -> String {
+    let base = sql_type.split('(').next().unwrap_or(sql_type).trim().to_uppercase();
+    let (base, array) = match base.strip_prefix('_') {
+        Some(element) => (element.to_string(), true),
+        None => match base.strip_suffix("[]") {
+            Some(element) => (element.to_string(), true),
+            None => (base, false),
+        },
+    };
+    let base = match base.as_str() {
+        "BIGINT" | "BIGSERIAL" => "INT8",
+        "INTEGER" | "INT" | "SERIAL" => "INT4",
+        "SMALLINT" | "SMALLSERIAL" => "INT2",
+        "BOOLEAN" => "BOOL",
+        "REAL" => "FLOAT4",
+        "DOUBLE PRECISION" => "FLOAT8",
+        "DECIMAL" => "NUMERIC",
+        "CHARACTER VARYING" => "VARCHAR",
+        "CHARACTER" | "CHAR" => "BPCHAR",
+        "TIMESTAMP WITH TIME ZONE" => "TIMESTAMPTZ",
+        "TIMESTAMP WITHOUT TIME ZONE" => "TIMESTAMP",
+        other => other,
+    };
+    if array {
+        format!("{}[]", base)
+    } else {
+        base.to_string()
+    }
+}
+
+/// Canonical form of a column type, so that types written differently, e.g. by
+/// [`TABLE_COLUMNS`] and sqlx, compare equal. Drops case and modifiers such as
+/// `(12,2)` by default.
+/// SQLite stores any value in any column, so types are compared by their affinity.
+/// `INTEGER` and `NUMERIC` affinity only differ in `CAST`, and are treated alike so that
+/// e.g. `BOOLEAN` and `DATETIME` columns match ones declared `INTEGER`.
+#[cfg(feature = "sqlite")]
+pub(crate) fn normalize_type(sql_type: &str) -> String {
+    let sql_type = sql_type.to_uppercase();
+    let affinity = if sql_type.contains("INT") {
+        "NUMERIC"
+    } else if ["CHAR", "CLOB", "TEXT"].iter().any(|t| sql_type.contains(t)) {
+        "TEXT"
+    } else if sql_type.contains("BLOB") || sql_type.trim().is_empty() {
+        "BLOB"
+    } else if ["REAL", "FLOA", "DOUB"].iter().any(|t| sql_type.contains(t)) {
+        "REAL"
+    } else {
+        "NUMERIC"
+    };
+    affinity.to_string()
+}
+
+/// Rust type decoding a column of `sql_type`, as used in generated entities, or `None`
+/// when there is no obvious one.
+#[cfg(feature = "postgres")]
+pub(crate) fn rust_type(sql_type: &str) -> Option<&'static str> {
+    let rust_type = match normalize_type(sql_type).as_str() {
+        "INT8" => "i64",
+        "INT4" => "i32",
+        "INT2" => "i16",
+        "FLOAT8" => "f64",
+        "FLOAT4" => "f32",
+        "BOOL" => "bool",
+        "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" | "CITEXT" => "String",
+        "BYTEA" => "Vec<u8>",
+        "TIMESTAMPTZ" => "chrono::DateTime<chrono::Utc>",
+        "TIMESTAMP" => "chrono::NaiveDateTime",
+        "DATE" => "chrono::NaiveDate",
+        "TIME" => "chrono::NaiveTime",
+        "UUID" => "uuid::Uuid",
+        "JSON" | "JSONB" => "serde_json::Value",
+        _ => return None,
+    };
+    Some(rust_type)
+}
+
+/// Rust type decoding a column of `sql_type`, as used in generated entities, or `None`
+/// when there is no obvious one.
+/// Maps declared types by name first, e.g. `BOOLEAN` and `DATETIME`, then by affinity.
+#[cfg(feature = "sqlite")]
+pub(crate) fn rust_type(sql_type: &str) -> Option<&'static str> {
+    let upper = sql_type.to_uppercase();
+    let rust_type = if upper.starts_with("BOOL") {
+        "bool"
+    } else if upper.contains("DATETIME") || upper.contains("TIMESTAMP") {
+        "chrono::DateTime<chrono::Utc>"
+    } else if upper == "DATE" {
+        "chrono::NaiveDate"
+    } else if upper == "JSON" || upper == "JSONB" {
+        "serde_json::Value"
+    } else {
+        match normalize_type(sql_type).as_str() {
+            "NUMERIC" if upper.contains("INT") => "i64",
+            "TEXT" => "String",
+            "REAL" => "f64",
+            "BLOB" if !upper.trim().is_empty() => "Vec<u8>",
+            _ => return None,
+        }
+    };
+    Some(rust_type)
+}
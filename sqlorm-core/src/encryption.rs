@@ -0,0 +1,218 @@
+use std::marker::PhantomData;
+
+use sqlx::error::BoxDynError;
+
+use crate::{Condition, Selectable, Updatable, UpdateWhere};
+
+/// Cipher of `#[sql(encrypted = MyCipher)]` columns.
+///
+/// Implement it on a unit struct holding no state; keys are usually loaded once into a
+/// `static`. Values are encrypted before binding and stored as `BYTEA` (postgres) or `BLOB`
+/// (sqlite).
+///
+/// Example usage:
+/// ```rust ignore
+/// struct PiiCipher;
+///
+/// impl sqlorm::Cipher for PiiCipher {
+///     fn encrypt(plaintext: &[u8]) -> Vec<u8> {
+///         KEY.seal(plaintext)
+///     }
+///
+///     fn decrypt(ciphertext: &[u8]) -> Result<Vec<u8>, sqlorm::sqlx::error::BoxDynError> {
+///         Ok(KEY.open(ciphertext)?)
+///     }
+/// }
+/// ```
+pub trait Cipher: Send + Sync + 'static {
+    fn encrypt(plaintext: &[u8]) -> Vec<u8>;
+    fn decrypt(ciphertext: &[u8]) -> Result<Vec<u8>, BoxDynError>;
+}
+
+/// Marks a [`Cipher`] producing the same ciphertext for the same plaintext, e.g. AES-SIV.
+///
+/// Only columns encrypted with a deterministic cipher can be filtered by equality.
+pub trait DeterministicCipher: Cipher {}
+
+/// Value which can be stored in an encrypted column.
+pub trait Encryptable: Sized {
+    fn to_plaintext(&self) -> Vec<u8>;
+    fn from_plaintext(plaintext: Vec<u8>) -> Result<Self, BoxDynError>;
+}
+
+impl Encryptable for String {
+    fn to_plaintext(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn from_plaintext(plaintext: Vec<u8>) -> Result<Self, BoxDynError> {
+        Ok(String::from_utf8(plaintext)?)
+    }
+}
+
+impl Encryptable for Vec<u8> {
+    fn to_plaintext(&self) -> Vec<u8> {
+        self.clone()
+    }
+
+    fn from_plaintext(plaintext: Vec<u8>) -> Result<Self, BoxDynError> {
+        Ok(plaintext)
+    }
+}
+
+/// Field type of an encrypted column, `String` or `Vec<u8>`, optionally wrapped in `Option`.
+///
+/// `None` is stored as SQL `NULL` rather than encrypted.
+pub trait EncryptedField: Sized {
+    fn encrypt<C: Cipher>(&self) -> Option<Vec<u8>>;
+    fn decrypt<C: Cipher>(ciphertext: Option<Vec<u8>>) -> Result<Self, BoxDynError>;
+}
+
+macro_rules! impl_encrypted_field {
+    ( $( $Type:ty ),+ ) => {
+        $(
+            impl EncryptedField for $Type {
+                fn encrypt<C: Cipher>(&self) -> Option<Vec<u8>> {
+                    Some(C::encrypt(&self.to_plaintext()))
+                }
+
+                fn decrypt<C: Cipher>(ciphertext: Option<Vec<u8>>) -> Result<Self, BoxDynError> {
+                    let ciphertext = ciphertext.ok_or("unexpected NULL in encrypted column")?;
+                    <$Type>::from_plaintext(C::decrypt(&ciphertext)?)
+                }
+            }
+
+            impl EncryptedField for Option<$Type> {
+                fn encrypt<C: Cipher>(&self) -> Option<Vec<u8>> {
+                    self.as_ref().map(|value| C::encrypt(&value.to_plaintext()))
+                }
+
+                fn decrypt<C: Cipher>(ciphertext: Option<Vec<u8>>) -> Result<Self, BoxDynError> {
+                    ciphertext
+                        .map(|ciphertext| <$Type>::from_plaintext(C::decrypt(&ciphertext)?))
+                        .transpose()
+                }
+            }
+        )+
+    };
+}
+
+impl_encrypted_field!(String, Vec<u8>);
+
+/// Encrypts `value` for binding. Used by the generated code of encrypted columns.
+pub fn encrypt<C: Cipher, T: EncryptedField>(value: &T) -> Option<Vec<u8>> {
+    value.encrypt::<C>()
+}
+
+/// Decrypts the value read from `column`. Used by the generated code of encrypted columns.
+pub fn decrypt<C: Cipher, T: EncryptedField>(
+    column: &str,
+    ciphertext: Option<Vec<u8>>,
+) -> sqlx::Result<T> {
+    T::decrypt::<C>(ciphertext).map_err(|source| sqlx::Error::ColumnDecode {
+        index: column.to_string(),
+        source,
+    })
+}
+
+/// Column of an `#[sql(encrypted = C)]` field.
+///
+/// Unlike [`Column`](crate::Column), only equality filters are available, and only when `C`
+/// is a [`DeterministicCipher`].
+#[derive(Debug)]
+pub struct EncryptedColumn<T, C> {
+    /// The column name as it appears in SQL.
+    pub name: &'static str,
+    /// The column name with table alias, e.g. `__user.ssn`.
+    pub aliased_name: &'static str,
+    /// The table alias to use when generating SQL conditions.
+    pub table_alias: &'static str,
+    pub _marker: PhantomData<(T, C)>,
+}
+
+impl<T, C> AsRef<str> for EncryptedColumn<T, C> {
+    fn as_ref(&self) -> &str {
+        self.name
+    }
+}
+
+impl<T, C> Copy for EncryptedColumn<T, C> {}
+impl<T, C> Clone for EncryptedColumn<T, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, C> EncryptedColumn<T, C> {
+    fn qualified_name(&self) -> String {
+        format!("{}.{}", self.table_alias, self.name)
+    }
+
+    /// Create a condition: `column IS NULL`
+    pub fn is_null(self) -> Condition {
+        Condition::none(format!("{} IS NULL", self.qualified_name()))
+    }
+
+    /// Create a condition: `column IS NOT NULL`
+    pub fn is_not_null(self) -> Condition {
+        Condition::none(format!("{} IS NOT NULL", self.qualified_name()))
+    }
+}
+
+impl<T, C> EncryptedColumn<T, C>
+where
+    T: EncryptedField,
+    C: DeterministicCipher,
+{
+    /// Create a condition: `column = ?`, comparing ciphertexts
+    pub fn eq(self, val: T) -> Condition {
+        Condition::new(format!("{} = ?", self.qualified_name()), val.encrypt::<C>())
+    }
+
+    /// Create a condition: `column <> ?`, comparing ciphertexts
+    pub fn ne(self, val: T) -> Condition {
+        Condition::new(format!("{} <> ?", self.qualified_name()), val.encrypt::<C>())
+    }
+
+    /// Create a condition: `column IN (?, ?, ...)`, comparing ciphertexts
+    ///
+    /// Panics if `vals` is empty
+    pub fn in_(self, vals: Vec<T>) -> Condition {
+        if vals.is_empty() {
+            panic!(
+                "Cannot create IN condition with empty value list. At least one value must be specified."
+            );
+        }
+        let placeholders: Vec<String> = (0..vals.len()).map(|_| "?".to_string()).collect();
+        let sql = format!("{} IN ({})", self.qualified_name(), placeholders.join(", "));
+        Condition::multi(sql, vals.iter().map(|v| v.encrypt::<C>()).collect())
+    }
+}
+
+impl<E> UpdateWhere<E> {
+    /// Sets the encrypted `column` to `value`, encrypted with `C`.
+    pub fn set_encrypted<T: EncryptedField, C: Cipher>(
+        self,
+        column: EncryptedColumn<T, C>,
+        value: T,
+    ) -> Self {
+        self.assign(
+            column.name,
+            Condition::new("?".to_string(), value.encrypt::<C>()),
+        )
+    }
+}
+
+/// Selecting an encrypted column yields its ciphertext.
+impl<T, C> Selectable for EncryptedColumn<T, C> {
+    type Row = Option<Vec<u8>>;
+    fn collect(&self) -> Vec<&'static str> {
+        vec![self.name]
+    }
+
+    fn collect_qualified(&self) -> Vec<String> {
+        vec![self.qualified_name()]
+    }
+}
+
+impl<T, C> Updatable for EncryptedColumn<T, C> {}
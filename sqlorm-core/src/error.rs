@@ -0,0 +1,131 @@
+use std::error::Error as StdError;
+use std::fmt::Display;
+
+use sqlx::error::{DatabaseError, ErrorKind};
+
+/// Optimistic locking failure: an update of an entity with a `#[sql(version)]` column
+/// matched no row, because the row was modified (or deleted) since it was loaded.
+///
+/// Returned as [`sqlx::Error::Database`], use [`StaleObject::is`] to detect it.
+///
+/// Example usage:
+/// ```rust ignore
+/// match jar.save(&pool).await {
+///     Err(e) if StaleObject::is(&e) => { /* reload and retry */ }
+///     other => other?,
+/// };
+/// ```
+#[derive(Debug)]
+pub struct StaleObject {
+    table: &'static str,
+    message: String,
+}
+
+impl StaleObject {
+    pub fn new(table: &'static str) -> Self {
+        StaleObject {
+            table,
+            message: format!("stale object: row of \"{}\" was modified concurrently", table),
+        }
+    }
+
+    /// Returns true if `err` is a [`StaleObject`] error.
+    pub fn is(err: &sqlx::Error) -> bool {
+        err.as_database_error()
+            .is_some_and(|e| e.try_downcast_ref::<StaleObject>().is_some())
+    }
+}
+
+impl Display for StaleObject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl StdError for StaleObject {}
+
+impl DatabaseError for StaleObject {
+    fn message(&self) -> &str {
+        &self.message
+    }
+
+    fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+        self
+    }
+
+    fn table(&self) -> Option<&str> {
+        Some(self.table)
+    }
+
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+/// Recursive relation loading reached a row twice, because the self-referential foreign
+/// keys form a cycle, e.g. a category being its own ancestor.
+///
+/// Returned as [`sqlx::Error::Database`] by the generated `load_descendants()`, use
+/// [`RelationCycle::is`] to detect it.
+#[derive(Debug)]
+pub struct RelationCycle {
+    table: &'static str,
+    message: String,
+}
+
+impl RelationCycle {
+    pub fn new(table: &'static str) -> Self {
+        RelationCycle {
+            table,
+            message: format!("relation cycle: rows of \"{}\" reference each other", table),
+        }
+    }
+
+    /// Returns true if `err` is a [`RelationCycle`] error.
+    pub fn is(err: &sqlx::Error) -> bool {
+        err.as_database_error()
+            .is_some_and(|e| e.try_downcast_ref::<RelationCycle>().is_some())
+    }
+}
+
+impl Display for RelationCycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl StdError for RelationCycle {}
+
+impl DatabaseError for RelationCycle {
+    fn message(&self) -> &str {
+        &self.message
+    }
+
+    fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+        self
+    }
+
+    fn table(&self) -> Option<&str> {
+        Some(self.table)
+    }
+
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
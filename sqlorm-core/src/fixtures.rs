@@ -0,0 +1,227 @@
+//! Declarative test fixtures loaded from JSON, or YAML with the `fixtures-yaml` feature.
+//!
+//! A fixture file maps record names to entities, only listing the fields that differ from
+//! `Default`:
+//! ```yaml
+//! # users.yaml
+//! alice:
+//!   email: alice@example.com
+//!   username: alice
+//! ```
+//!
+//! Strings of the form `$set.record` reference the primary key of a record loaded before,
+//! where `set` is the file stem, and `$set.record.field` any of its fields. A reference put in
+//! a relation field such as `owner` is written to its `owner_id` foreign key:
+//! ```yaml
+//! # jars.yaml
+//! savings:
+//!   alias: savings
+//!   owner: $users.alice
+//! ```
+//!
+//! Example usage:
+//! ```rust ignore
+//! let fixtures = sqlorm::fixtures!(&pool, "fixtures/users.yaml" => User, "fixtures/jars.yaml" => Jar).await?;
+//! let alice: User = fixtures.get("users", "alice").unwrap();
+//! ```
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use sqlx::{Acquire, FromRow, QueryBuilder};
+
+use crate::driver::{Driver, Row};
+use crate::{Insertable, with_quotes};
+
+/// Format of a fixture file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixtureFormat {
+    Json,
+    #[cfg(feature = "fixtures-yaml")]
+    Yaml,
+}
+
+impl FixtureFormat {
+    /// Format of the file at `path`, YAML for `.yaml`/`.yml` and JSON otherwise.
+    pub fn from_path(path: &str) -> Self {
+        #[cfg(feature = "fixtures-yaml")]
+        if path.ends_with(".yaml") || path.ends_with(".yml") {
+            return FixtureFormat::Yaml;
+        }
+        let _ = path;
+        FixtureFormat::Json
+    }
+}
+
+/// Records inserted from fixture files, by set and record name.
+#[derive(Debug, Default)]
+pub struct Fixtures {
+    sets: HashMap<String, FixtureSet>,
+}
+
+#[derive(Debug)]
+struct FixtureSet {
+    pk: &'static str,
+    records: HashMap<String, Value>,
+}
+
+impl Fixtures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the records of `source` as entities of `T`, registering them as set `name`.
+    ///
+    /// Records are inserted in name order, so references only point to sets loaded before.
+    pub async fn load<'a, T, A>(
+        &mut self,
+        acquirer: A,
+        name: &str,
+        format: FixtureFormat,
+        source: &str,
+    ) -> sqlx::Result<Vec<T>>
+    where
+        T: Insertable + Default + Serialize + DeserializeOwned + Send + Unpin,
+        T: for<'r> FromRow<'r, Row>,
+        A: Send + Acquire<'a, Database = Driver>,
+    {
+        let records = parse(format, source)?;
+        let Value::Object(defaults) = serde_json::to_value(T::default()).map_err(decode)? else {
+            return Err(decode(format!("{} doesn't serialize to an object", T::TABLE_NAME)));
+        };
+
+        let mut conn = acquirer.acquire().await?;
+        let mut set = FixtureSet {
+            pk: T::PK,
+            records: HashMap::new(),
+        };
+        let mut inserted = Vec::with_capacity(records.len());
+        for (record, fields) in records {
+            let mut value = defaults.clone();
+            let Value::Object(fields) = fields else {
+                return Err(decode(format!("fixture {}.{} is not an object", name, record)));
+            };
+            for (field, field_value) in fields {
+                // `owner: $users.alice` sets the `owner_id` foreign key, also when `owner` is
+                // a relation field of the entity
+                let foreign_key = format!("{}_id", field);
+                let field = if defaults.contains_key(&foreign_key)
+                    && (!defaults.contains_key(&field) || is_record_reference(&field_value))
+                {
+                    foreign_key
+                } else {
+                    field
+                };
+                value.insert(field, self.resolve(field_value)?);
+            }
+
+            let mut entity: T = serde_json::from_value(Value::Object(value)).map_err(decode)?;
+            entity.prepare_insert();
+            let columns = T::insert_columns();
+            let mut builder = QueryBuilder::<Driver>::new(format!(
+                "INSERT INTO {} ({}) ",
+                with_quotes(T::TABLE_NAME),
+                columns.join(", ")
+            ));
+            builder.push_values(std::iter::once(&entity), |mut row, entity| {
+                entity.push_insert_values(&mut row);
+            });
+            builder.push(" RETURNING *");
+            let entity: T = builder.build_query_as().fetch_one(&mut *conn).await?;
+
+            set.records
+                .insert(record, serde_json::to_value(&entity).map_err(decode)?);
+            inserted.push(entity);
+        }
+        self.sets.insert(name.to_string(), set);
+        Ok(inserted)
+    }
+
+    /// The inserted record `record` of set `set`.
+    pub fn get<T: DeserializeOwned>(&self, set: &str, record: &str) -> Option<T> {
+        let value = self.sets.get(set)?.records.get(record)?;
+        serde_json::from_value(value.clone()).ok()
+    }
+
+    /// Replaces `$set.record` and `$set.record.field` references with the referenced values.
+    fn resolve(&self, value: Value) -> sqlx::Result<Value> {
+        let Value::String(s) = &value else {
+            return Ok(value);
+        };
+        let Some(reference) = s.strip_prefix('$') else {
+            return Ok(value);
+        };
+
+        let mut parts = reference.splitn(3, '.');
+        let (Some(set_name), Some(record)) = (parts.next(), parts.next()) else {
+            return Err(decode(format!("invalid fixture reference {}", s)));
+        };
+        let set = self
+            .sets
+            .get(set_name)
+            .ok_or_else(|| decode(format!("unknown fixture set in {}", s)))?;
+        let field = parts.next().unwrap_or(set.pk);
+        set.records
+            .get(record)
+            .and_then(|r| r.get(field))
+            .cloned()
+            .ok_or_else(|| decode(format!("unknown fixture reference {}", s)))
+    }
+}
+
+/// Whether `value` is a `$set.record` reference, resolving to the primary key of the record.
+fn is_record_reference(value: &Value) -> bool {
+    matches!(value, Value::String(s) if s.starts_with('$') && s.split('.').count() == 2)
+}
+
+/// Parses a fixture file into record names and fields, sorted by name.
+fn parse(format: FixtureFormat, source: &str) -> sqlx::Result<Map<String, Value>> {
+    let value: Value = match format {
+        FixtureFormat::Json => serde_json::from_str(source).map_err(decode)?,
+        #[cfg(feature = "fixtures-yaml")]
+        FixtureFormat::Yaml => serde_yaml::from_str(source).map_err(decode)?,
+    };
+    match value {
+        Value::Object(records) => Ok(records),
+        Value::Null => Ok(Map::new()),
+        _ => Err(decode("fixture file must map record names to records")),
+    }
+}
+
+fn decode(err: impl Into<sqlx::error::BoxDynError>) -> sqlx::Error {
+    sqlx::Error::Decode(err.into())
+}
+
+/// Loads fixture files into the database, returning the [`Fixtures`] to look records up.
+///
+/// Files are embedded with `include_str!`, so paths are relative to the calling file, and are
+/// loaded in order under their file stem as set name.
+///
+/// ```rust ignore
+/// let fixtures = sqlorm::fixtures!(&pool, "fixtures/users.json" => User, "fixtures/jars.json" => Jar).await?;
+/// ```
+#[macro_export]
+macro_rules! fixtures {
+    ($acquirer:expr, $($path:literal => $entity:ty),+ $(,)?) => {
+        async {
+            let mut fixtures = $crate::fixtures::Fixtures::new();
+            $(
+                let name = ::std::path::Path::new($path)
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .unwrap_or($path);
+                fixtures
+                    .load::<$entity, _>(
+                        $acquirer,
+                        name,
+                        $crate::fixtures::FixtureFormat::from_path($path),
+                        include_str!($path),
+                    )
+                    .await?;
+            )+
+            ::std::result::Result::<_, $crate::sqlx::Error>::Ok(fixtures)
+        }
+    };
+}
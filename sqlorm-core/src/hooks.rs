@@ -0,0 +1,68 @@
+use async_trait::async_trait;
+
+use crate::driver::Connection;
+
+/// Context passed to [`Hooks`].
+pub struct HookContext<'c> {
+    /// Connection the statement runs on, so queries of a hook take part in its transaction.
+    pub conn: &'c mut Connection,
+}
+
+impl<'c> HookContext<'c> {
+    pub fn new(conn: &'c mut Connection) -> Self {
+        HookContext { conn }
+    }
+}
+
+/// Callbacks around the writes of an entity, all doing nothing by default.
+///
+/// `before_*` hooks run before the statement is built, so changed fields are written, and an
+/// error aborts the write. `after_*` hooks receive the row as returned by the database, also
+/// for `insert_returning()` and `execute_returning()`, which only return selected columns.
+/// `insert_many()` and `upsert_many()` run the insert hooks of every entity, while
+/// `update_where()` and `delete_where()` never run hooks.
+///
+/// Hooks run for entities declared with `#[table(hooks)]`, which must implement this trait:
+/// ```rust ignore
+/// #[table(hooks)]
+/// #[derive(Debug, Clone, Default)]
+/// pub struct User {
+///     #[sql(pk)]
+///     pub id: i64,
+///     pub password: String,
+/// }
+///
+/// #[sqlorm::async_trait]
+/// impl sqlorm::Hooks for User {
+///     async fn before_insert(&mut self, _ctx: &mut HookContext<'_>) -> sqlx::Result<()> {
+///         self.password = hash(&self.password);
+///         Ok(())
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait Hooks: Send {
+    async fn before_insert(&mut self, _ctx: &mut HookContext<'_>) -> sqlx::Result<()> {
+        Ok(())
+    }
+
+    async fn after_insert(&mut self, _ctx: &mut HookContext<'_>) -> sqlx::Result<()> {
+        Ok(())
+    }
+
+    async fn before_update(&mut self, _ctx: &mut HookContext<'_>) -> sqlx::Result<()> {
+        Ok(())
+    }
+
+    async fn after_update(&mut self, _ctx: &mut HookContext<'_>) -> sqlx::Result<()> {
+        Ok(())
+    }
+
+    async fn before_delete(&mut self, _ctx: &mut HookContext<'_>) -> sqlx::Result<()> {
+        Ok(())
+    }
+
+    async fn after_delete(&mut self, _ctx: &mut HookContext<'_>) -> sqlx::Result<()> {
+        Ok(())
+    }
+}
@@ -1,9 +1,36 @@
 #![cfg(any(feature = "postgres", feature = "sqlite"))]
 
+pub mod cli;
+pub mod codegen;
+pub mod comment;
+mod connect;
 mod consts;
+mod dialect;
+mod encryption;
+mod error;
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+mod hooks;
+#[cfg(feature = "postgres")]
+pub mod notify;
+pub mod observer;
+mod pivot;
 pub mod qb;
+pub mod schema;
 mod selectable;
+pub mod slow_query;
+pub mod validation;
+pub use connect::{ConnectConfig, Sqlorm};
 pub use consts::*;
+pub use encryption::{
+    Cipher, DeterministicCipher, EncryptedColumn, EncryptedField, Encryptable, decrypt, encrypt,
+};
+pub use error::{RelationCycle, StaleObject};
+pub use hooks::{HookContext, Hooks};
+pub use observer::{ChangeEvent, Observable, observe, observe_after_commit};
+pub use schema::Schema;
+pub use validation::{Validate, ValidationErrors};
+pub use pivot::{PivotValues, attach_pivot, detach_pivot, fetch_pivot, sync_pivot};
 use sqlx::Acquire;
 mod sb;
 
@@ -13,19 +40,33 @@ pub use qb::Column;
 pub use qb::Condition;
 pub use qb::*;
 mod traits;
+mod transaction;
 
 #[cfg(any(feature = "postgres", feature = "sqlite"))]
 pub use driver::{Connection, Driver, Pool, Row};
 
 pub use sb::Delete;
+pub use sb::DeleteWhere;
+pub use sb::Expr;
 pub use sb::SB;
 pub use sb::Update;
+pub use sb::UpdateFrom;
+pub use sb::UpdateWhere;
+pub use sb::UpsertMany;
 
+pub use futures_core::stream::BoxStream;
 use sqlx::FromRow;
+pub use traits::ColumnMeta;
 pub use traits::FromAliasedRow;
 pub use traits::GenericExecutor;
+pub use traits::Insertable;
+pub use traits::Patch;
+pub use traits::Projection;
+pub use selectable::{Selectable, Updatable};
 pub use traits::StatementExecutor;
 pub use traits::Table;
+pub use traits::Unset;
+pub use transaction::{Isolation, NestedTransaction, TransactionConfig, is_transient, transaction};
 
 #[async_trait]
 impl<T> GenericExecutor<T> for QB<T>
@@ -39,8 +80,15 @@ where
         let mut conn = acquirer.acquire().await?;
         self.eager.clear();
         self.batch.clear();
-        let row = self.build_query().build().fetch_one(&mut *conn).await?;
-        T::from_row(&row)
+        let timer = self.slow_query_timer();
+        let row = self
+            .build_query()
+            .build()
+            .persistent(self.persistent)
+            .fetch_one(&mut *conn)
+            .await;
+        timer.finish();
+        T::from_row(&row?)
     }
 
     async fn fetch_all_as<'a, A: Send + Acquire<'a, Database = Driver>>(
@@ -48,8 +96,23 @@ where
         acquirer: A,
     ) -> sqlx::Result<Vec<T>> {
         let mut conn = acquirer.acquire().await?;
-        let rows = self.build_query().build().fetch_all(&mut *conn).await?;
-        rows.iter().map(T::from_row).collect()
+        let timer = self.slow_query_timer();
+        let rows = self
+            .build_query()
+            .build()
+            .persistent(self.persistent)
+            .fetch_all(&mut *conn)
+            .await;
+        timer.finish();
+        rows?.iter().map(T::from_row).collect()
+    }
+
+    fn fetch_stream_as<'e, E>(self, executor: E) -> BoxStream<'e, sqlx::Result<T>>
+    where
+        E: 'e + sqlx::Executor<'e, Database = Driver>,
+        T: 'e,
+    {
+        self.stream_with(executor, |row| T::from_row(row))
     }
 }
 
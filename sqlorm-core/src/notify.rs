@@ -0,0 +1,120 @@
+//! Change notifications over PostgreSQL `LISTEN`/`NOTIFY`.
+//!
+//! [`install_trigger`] adds a trigger publishing every insert, update and delete of an
+//! entity's table on its [`Notify::notify_channel`], and [`listen`] streams them as [`Change`]s.
+//!
+//! Example usage:
+//! ```rust ignore
+//! use sqlorm::notify::{self, Notify};
+//!
+//! notify::install_trigger::<User>(&pool).await?;
+//!
+//! let mut changes = notify::listen::<User>(&pool).await?;
+//! while let Some(change) = changes.next().await {
+//!     let change = change?;
+//!     cache.invalidate(&change.pk);
+//! }
+//! ```
+
+use futures_core::stream::BoxStream;
+use futures_util::StreamExt;
+use sqlx::postgres::PgListener;
+
+use crate::driver::Pool;
+use crate::{Table, with_quotes};
+
+/// Operation that changed a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeOp {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// A row change published by the trigger of [`install_trigger`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub op: ChangeOp,
+    /// Primary key of the changed row, as text.
+    pub pk: String,
+}
+
+impl Change {
+    /// Parses the `OP:pk` payload sent by the trigger.
+    pub fn parse(payload: &str) -> Option<Self> {
+        let (op, pk) = payload.split_once(':')?;
+        let op = match op {
+            "INSERT" => ChangeOp::Insert,
+            "UPDATE" => ChangeOp::Update,
+            "DELETE" => ChangeOp::Delete,
+            _ => return None,
+        };
+        Some(Self {
+            op,
+            pk: pk.to_string(),
+        })
+    }
+}
+
+/// Channel names of entities, implemented for every [`Table`].
+pub trait Notify: Table {
+    /// Channel changes of this entity are published on, e.g. `sqlorm_users` or
+    /// `sqlorm_billing_invoices` for schema-qualified tables.
+    fn notify_channel() -> String {
+        format!("sqlorm_{}", Self::TABLE_NAME.replace('.', "_"))
+    }
+}
+
+impl<T: Table> Notify for T {}
+
+/// DDL of the function and trigger publishing changes of `T` on its channel.
+///
+/// Can be run directly with [`install_trigger`] or copied into a migration.
+pub fn trigger_sql<T: Table>() -> String {
+    let channel = T::notify_channel();
+    let function = with_quotes(&format!("{}_notify", channel));
+    let trigger = with_quotes(&format!("{}_trigger", channel));
+    let pk = with_quotes(T::PK);
+
+    format!(
+        "CREATE OR REPLACE FUNCTION {function}() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        PERFORM pg_notify('{channel}', TG_OP || ':' || OLD.{pk}::text);
+    ELSE
+        PERFORM pg_notify('{channel}', TG_OP || ':' || NEW.{pk}::text);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+DROP TRIGGER IF EXISTS {trigger} ON {table};
+CREATE TRIGGER {trigger} AFTER INSERT OR UPDATE OR DELETE ON {table}
+    FOR EACH ROW EXECUTE FUNCTION {function}();",
+        table = T::SQL_NAME,
+    )
+}
+
+/// Creates, or replaces, the trigger publishing changes of `T`.
+pub async fn install_trigger<T: Table>(pool: &Pool) -> sqlx::Result<()> {
+    sqlx::raw_sql(&trigger_sql::<T>()).execute(pool).await?;
+    Ok(())
+}
+
+/// Listens on the channel of `T`, yielding every change published by its trigger.
+///
+/// The listener holds its own connection and reconnects when it's lost; notifications sent
+/// while disconnected are missed. Payloads not sent by [`install_trigger`] are skipped.
+pub async fn listen<T: Table>(pool: &Pool) -> sqlx::Result<BoxStream<'static, sqlx::Result<Change>>> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(&T::notify_channel()).await?;
+
+    Ok(listener
+        .into_stream()
+        .filter_map(|notification| async move {
+            match notification {
+                Ok(notification) => Change::parse(notification.payload()).map(Ok),
+                Err(err) => Some(Err(err)),
+            }
+        })
+        .boxed())
+}
@@ -0,0 +1,227 @@
+//! Observers notified of the inserts, updates and deletes of an entity, e.g. to enqueue
+//! webhooks or invalidate caches.
+//!
+//! Entities are observed when declared with `#[table(observable)]`, which requires `Clone`:
+//! ```rust ignore
+//! sqlorm::observe::<User>(|event| match event {
+//!     ChangeEvent::Deleted(user) => cache.remove(user.id),
+//!     ChangeEvent::Inserted(user) | ChangeEvent::Updated(user) => cache.insert(user.id, user),
+//! });
+//! ```
+//!
+//! Observers run synchronously once the statement succeeded. Observers registered with
+//! [`observe_after_commit`] are held back until the [`transaction`](crate::transaction)
+//! running the statement commits, and dropped if it rolls back. Within it, a
+//! [`nested`](crate::NestedTransaction::nested) unit rolled back to its savepoint drops its
+//! own notifications. Statements outside of [`transaction`](crate::transaction), including
+//! the ones on transactions begun directly with sqlx, are considered committed right away.
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::task::{Context, Poll};
+
+/// Change of an entity, holding the entity as written to the database.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeEvent<T> {
+    Inserted(T),
+    Updated(T),
+    Deleted(T),
+}
+
+impl<T> ChangeEvent<T> {
+    pub fn entity(&self) -> &T {
+        match self {
+            ChangeEvent::Inserted(entity)
+            | ChangeEvent::Updated(entity)
+            | ChangeEvent::Deleted(entity) => entity,
+        }
+    }
+
+    pub fn into_entity(self) -> T {
+        match self {
+            ChangeEvent::Inserted(entity)
+            | ChangeEvent::Updated(entity)
+            | ChangeEvent::Deleted(entity) => entity,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> ChangeEvent<U> {
+        match self {
+            ChangeEvent::Inserted(entity) => ChangeEvent::Inserted(f(entity)),
+            ChangeEvent::Updated(entity) => ChangeEvent::Updated(f(entity)),
+            ChangeEvent::Deleted(entity) => ChangeEvent::Deleted(f(entity)),
+        }
+    }
+}
+
+/// Entities declared with `#[table(observable)]`.
+pub trait Observable: Clone + Send + Sync + 'static {}
+
+type Callback<T> = Arc<dyn Fn(ChangeEvent<T>) + Send + Sync>;
+
+struct Observer<T> {
+    after_commit: bool,
+    callback: Callback<T>,
+}
+
+/// Observers by entity type, each entry a `Vec<Observer<T>>`.
+static OBSERVERS: RwLock<Option<HashMap<TypeId, Box<dyn Any + Send + Sync>>>> = RwLock::new(None);
+
+fn register<T: Observable>(after_commit: bool, callback: Callback<T>) {
+    let mut observers = OBSERVERS.write().unwrap_or_else(PoisonError::into_inner);
+    observers
+        .get_or_insert_with(HashMap::new)
+        .entry(TypeId::of::<T>())
+        .or_insert_with(|| Box::new(Vec::<Observer<T>>::new()))
+        .downcast_mut::<Vec<Observer<T>>>()
+        .expect("observers are stored by their entity type")
+        .push(Observer {
+            after_commit,
+            callback,
+        });
+}
+
+/// Calls `callback` after every successful insert, update and delete of a `T`.
+pub fn observe<T: Observable>(callback: impl Fn(ChangeEvent<T>) + Send + Sync + 'static) {
+    register(false, Arc::new(callback));
+}
+
+/// Calls `callback` after every insert, update and delete of a `T`, once the
+/// [`transaction`](crate::transaction) it ran in is committed.
+pub fn observe_after_commit<T: Observable>(callback: impl Fn(ChangeEvent<T>) + Send + Sync + 'static) {
+    register(true, Arc::new(callback));
+}
+
+/// Removes the observers of `T`.
+pub fn clear<T: Observable>() {
+    let mut observers = OBSERVERS.write().unwrap_or_else(PoisonError::into_inner);
+    if let Some(observers) = observers.as_mut() {
+        observers.remove(&TypeId::of::<T>());
+    }
+}
+
+/// Notifies the observers of `T` of `event`, called by the generated write methods.
+#[doc(hidden)]
+pub fn notify<T: Observable>(event: ChangeEvent<&T>) {
+    // callbacks run without the lock, so they may register observers themselves
+    let (immediate, after_commit): (Vec<_>, Vec<_>) = {
+        let observers = OBSERVERS.read().unwrap_or_else(PoisonError::into_inner);
+        let Some(observers) = observers
+            .as_ref()
+            .and_then(|observers| observers.get(&TypeId::of::<T>()))
+            .and_then(|observers| observers.downcast_ref::<Vec<Observer<T>>>())
+        else {
+            return;
+        };
+        observers
+            .iter()
+            .map(|observer| (observer.after_commit, observer.callback.clone()))
+            .partition(|(after_commit, _)| !after_commit)
+    };
+
+    for (_, callback) in immediate {
+        callback(event.clone().map(T::clone));
+    }
+    if after_commit.is_empty() {
+        return;
+    }
+    let event = event.map(T::clone);
+    defer(Box::new(move || {
+        for (_, callback) in after_commit {
+            callback(event.clone());
+        }
+    }));
+}
+
+type Deferred = Box<dyn FnOnce() + Send>;
+
+thread_local! {
+    /// Notifications held back by the transaction being polled on this thread, if any.
+    static DEFERRED: RefCell<Option<Vec<Deferred>>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` once the current transaction commits, or right away outside of one.
+fn defer(f: Deferred) {
+    let f = DEFERRED.with(|deferred| match deferred.borrow_mut().as_mut() {
+        Some(deferred) => {
+            deferred.push(f);
+            None
+        }
+        None => Some(f),
+    });
+    if let Some(f) = f {
+        f();
+    }
+}
+
+/// Future collecting the notifications deferred while polling `future`, returned along
+/// with its output to be run after commit.
+#[doc(hidden)]
+pub struct Collect<F> {
+    future: Pin<Box<F>>,
+    deferred: Option<Vec<Deferred>>,
+}
+
+/// Collects the notifications deferred by `future`, see [`Collect`]. Also called by the
+/// generated `save_all()`.
+#[doc(hidden)]
+pub fn collect<F: Future>(future: F) -> Collect<F> {
+    Collect {
+        future: Box::pin(future),
+        deferred: Some(Vec::new()),
+    }
+}
+
+/// Restores the notifications of the enclosing transaction, also when polling panics.
+struct Restore<'a> {
+    outer: Option<Vec<Deferred>>,
+    deferred: &'a mut Option<Vec<Deferred>>,
+}
+
+impl Drop for Restore<'_> {
+    fn drop(&mut self) {
+        *self.deferred = DEFERRED.with(|deferred| deferred.replace(self.outer.take()));
+    }
+}
+
+impl<F: Future> Future for Collect<F> {
+    type Output = (F::Output, Committed);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let poll = {
+            let outer = DEFERRED.with(|deferred| deferred.replace(this.deferred.take()));
+            let _restore = Restore {
+                outer,
+                deferred: &mut this.deferred,
+            };
+            this.future.as_mut().poll(cx)
+        };
+        poll.map(|output| (output, Committed(this.deferred.take().unwrap_or_default())))
+    }
+}
+
+/// Notifications of a transaction, run by [`Committed::run`] after the commit.
+#[must_use]
+#[doc(hidden)]
+pub struct Committed(Vec<Deferred>);
+
+impl Committed {
+    pub(crate) fn run(self) {
+        for f in self.0 {
+            f();
+        }
+    }
+
+    /// Hands the notifications of a released savepoint to the enclosing transaction, or runs
+    /// them when there is none.
+    pub fn defer(self) {
+        for f in self.0 {
+            defer(f);
+        }
+    }
+}
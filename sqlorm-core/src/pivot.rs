@@ -0,0 +1,253 @@
+use sqlx::{Decode, QueryBuilder, Type};
+
+use crate::driver::{Connection, Driver};
+use crate::qb::BindValue;
+use crate::qb::condition::AnyValue;
+use crate::{Column, MAX_BIND_PARAMS, with_quotes};
+
+/// Extra column values of join table rows written by the generated `attach_<related>_with()`.
+///
+/// Example usage:
+/// ```rust ignore
+/// post.attach_tag_with(tag.id, PivotValues::new().set("pinned", true), &pool).await?;
+/// ```
+#[derive(Clone, Default)]
+pub struct PivotValues {
+    columns: Vec<(&'static str, Box<dyn AnyValue>)>,
+}
+
+impl PivotValues {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets `column` of the attached rows to `value`.
+    pub fn set<V: BindValue + Clone>(mut self, column: &'static str, value: V) -> Self {
+        self.columns.push((column, Box::new(value)));
+        self
+    }
+}
+
+/// Fetches `(parent key, related key)` rows of a many-to-many join table for `parent_ids`,
+/// in batches below the driver's bind parameter limit.
+///
+/// Used by generated many-to-many loaders; `related` only fixes the related key type.
+pub async fn fetch_pivot<P, O>(
+    conn: &mut Connection,
+    table: &str,
+    parent_key: &str,
+    related_key: &str,
+    parent_ids: Vec<P>,
+    _related: Column<O>,
+) -> sqlx::Result<Vec<(P, O)>>
+where
+    P: BindValue + for<'r> Decode<'r, Driver> + Type<Driver> + Unpin,
+    O: BindValue + for<'r> Decode<'r, Driver> + Type<Driver> + Unpin,
+{
+    let mut parent_ids = parent_ids.into_iter().peekable();
+    let mut links = Vec::new();
+
+    while parent_ids.peek().is_some() {
+        let mut builder = QueryBuilder::<Driver>::new(format!(
+            "SELECT {1}, {2} FROM {0} WHERE {1} IN (",
+            with_quotes(table),
+            parent_key,
+            related_key
+        ));
+        let mut ids = builder.separated(", ");
+        for id in parent_ids.by_ref().take(MAX_BIND_PARAMS) {
+            ids.push_bind(id);
+        }
+        builder.push(")");
+
+        let rows = builder
+            .build_query_as::<(P, O)>()
+            .fetch_all(&mut *conn)
+            .await?;
+        links.extend(rows);
+    }
+
+    Ok(links)
+}
+
+/// Inserts join table rows linking `parent_id` to each of `related_ids`, skipping existing
+/// links. Rows are inserted in batches, in a transaction.
+///
+/// Used by generated many-to-many `attach_*` helpers.
+pub async fn attach_pivot<P, O>(
+    conn: &mut Connection,
+    table: &str,
+    parent_key: &str,
+    related_key: &str,
+    parent_id: P,
+    related_ids: Vec<O>,
+    values: &PivotValues,
+) -> sqlx::Result<()>
+where
+    P: BindValue + Clone,
+    O: BindValue + Clone,
+{
+    if related_ids.is_empty() {
+        return Ok(());
+    }
+
+    let columns: Vec<&str> = [parent_key, related_key]
+        .into_iter()
+        .chain(values.columns.iter().map(|(column, _)| *column))
+        .collect();
+    let batch_size = (MAX_BIND_PARAMS / columns.len()).max(1);
+    let mut tx = sqlx::Connection::begin(&mut *conn).await?;
+
+    for chunk in related_ids.chunks(batch_size) {
+        let mut builder = QueryBuilder::<Driver>::new(format!(
+            "INSERT INTO {} ({}) VALUES ",
+            with_quotes(table),
+            columns.join(", ")
+        ));
+        for (i, related_id) in chunk.iter().enumerate() {
+            if i > 0 {
+                builder.push(", ");
+            }
+            builder.push("(");
+            builder.push_bind(parent_id.clone());
+            builder.push(", ");
+            builder.push_bind(related_id.clone());
+            for (_, value) in &values.columns {
+                builder.push(", ");
+                value.bind(&mut builder);
+            }
+            builder.push(")");
+        }
+        builder.push(" ON CONFLICT DO NOTHING");
+        builder.build().execute(&mut *tx).await?;
+    }
+
+    tx.commit().await
+}
+
+/// Deletes join table rows linking `parent_id` to any of `related_ids`, returning their count.
+/// Rows are deleted in batches, in a transaction.
+///
+/// Used by generated many-to-many `detach_*` helpers.
+pub async fn detach_pivot<P, O>(
+    conn: &mut Connection,
+    table: &str,
+    parent_key: &str,
+    related_key: &str,
+    parent_id: P,
+    related_ids: Vec<O>,
+) -> sqlx::Result<u64>
+where
+    P: BindValue + Clone,
+    O: BindValue,
+{
+    if related_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let mut related_ids = related_ids.into_iter().peekable();
+    let mut tx = sqlx::Connection::begin(&mut *conn).await?;
+    let mut removed = 0;
+
+    while related_ids.peek().is_some() {
+        let mut builder = pivot_delete(table, parent_key, parent_id.clone());
+        builder.push(format!(" AND {} IN (", related_key));
+        let mut ids = builder.separated(", ");
+        // one parameter is taken by the parent key
+        for id in related_ids.by_ref().take(MAX_BIND_PARAMS - 1) {
+            ids.push_bind(id);
+        }
+        builder.push(")");
+
+        removed += builder.build().execute(&mut *tx).await?.rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(removed)
+}
+
+/// Makes `related_ids` the only rows linked to `parent_id`, in a transaction.
+///
+/// Ids that don't fit in a single statement are staged in a temporary table.
+///
+/// Used by generated many-to-many `sync_*` helpers.
+pub async fn sync_pivot<P, O>(
+    conn: &mut Connection,
+    table: &str,
+    parent_key: &str,
+    related_key: &str,
+    parent_id: P,
+    related_ids: Vec<O>,
+) -> sqlx::Result<()>
+where
+    P: BindValue + Clone,
+    O: BindValue + Clone,
+{
+    const STAGED: &str = "__sqlorm_sync_ids";
+    let mut tx = sqlx::Connection::begin(&mut *conn).await?;
+
+    let mut builder = pivot_delete(table, parent_key, parent_id.clone());
+    let staged = related_ids.len() >= MAX_BIND_PARAMS;
+    if staged {
+        // copies the type of the related key column
+        sqlx::query(&format!(
+            "CREATE TEMPORARY TABLE {STAGED} AS SELECT {related_key} FROM {} WHERE 1 = 0",
+            with_quotes(table)
+        ))
+        .execute(&mut *tx)
+        .await?;
+        for chunk in related_ids.chunks(MAX_BIND_PARAMS) {
+            let mut insert = QueryBuilder::<Driver>::new(format!(
+                "INSERT INTO {STAGED} ({related_key}) "
+            ));
+            insert.push_values(chunk, |mut row, id| {
+                row.push_bind(id.clone());
+            });
+            insert.build().execute(&mut *tx).await?;
+        }
+        builder.push(format!(
+            " AND {related_key} NOT IN (SELECT {related_key} FROM {STAGED})"
+        ));
+    } else if !related_ids.is_empty() {
+        builder.push(format!(" AND {} NOT IN (", related_key));
+        let mut ids = builder.separated(", ");
+        for id in related_ids.iter().cloned() {
+            ids.push_bind(id);
+        }
+        builder.push(")");
+    }
+    builder.build().execute(&mut *tx).await?;
+    if staged {
+        sqlx::query(&format!("DROP TABLE {STAGED}"))
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    attach_pivot(
+        &mut tx,
+        table,
+        parent_key,
+        related_key,
+        parent_id,
+        related_ids,
+        &PivotValues::new(),
+    )
+    .await?;
+
+    tx.commit().await
+}
+
+/// `DELETE` of the join table rows of `parent_id`, to be narrowed by the caller.
+fn pivot_delete<P: BindValue>(
+    table: &str,
+    parent_key: &str,
+    parent_id: P,
+) -> QueryBuilder<'static, Driver> {
+    let mut builder = QueryBuilder::new(format!(
+        "DELETE FROM {} WHERE {} = ",
+        with_quotes(table),
+        parent_key
+    ));
+    builder.push_bind(parent_id);
+    builder
+}
@@ -0,0 +1,47 @@
+use crate::{Condition, QB, with_quotes};
+
+impl<T> QB<T> {
+    /// Keeps rows having at least one `related` row matching `on` and the related filters.
+    ///
+    /// `on` is SQL correlating the related table alias with the base table alias. Called by
+    /// the generated `has_<relation>()`.
+    pub fn filter_exists<U>(self, related: QB<U>, on: String) -> Self {
+        let cond = related.exists_condition(on);
+        self.filter(cond)
+    }
+
+    /// Keeps rows without any `related` row matching `on`. See [`QB::filter_exists`].
+    pub fn filter_not_exists<U>(self, related: QB<U>, on: String) -> Self {
+        let mut cond = related.exists_condition(on);
+        cond.sql = format!("NOT {}", cond.sql);
+        self.filter(cond)
+    }
+
+    /// Renames the base table alias, re-targeting filters to it.
+    ///
+    /// Used to nest a query on the same table as the outer query.
+    pub fn realias(mut self, alias: String) -> Self {
+        let (from, to) = (format!("{}.", self.base.alias), format!("{}.", alias));
+        for cond in &mut self.filters {
+            cond.sql = cond.sql.replace(&from, &to);
+        }
+        self.base.alias = alias;
+        self
+    }
+
+    fn exists_condition(self, on: String) -> Condition {
+        let mut sql = vec![on];
+        sql.extend(self.filters.iter().map(|cond| cond.sql.clone()));
+        sql.extend(self.soft_delete_sql());
+
+        Condition {
+            sql: format!(
+                "EXISTS (SELECT 1 FROM {} AS {} WHERE {})",
+                with_quotes(self.base.name),
+                self.base.alias,
+                sql.join(" AND ")
+            ),
+            values: self.filters.into_iter().flat_map(|cond| cond.values).collect(),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use sqlx::{Acquire, QueryBuilder, Row};
+
+use crate::QB;
+use crate::driver::Driver;
+
+impl<T> QB<T> {
+    /// Returns the query plan chosen by the database for this query.
+    ///
+    /// Uses `EXPLAIN` on PostgreSQL and `EXPLAIN QUERY PLAN` on SQLite. The query itself
+    /// is not executed. Plan lines are joined with `\n`.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// let plan = User::query()
+    ///     .filter(User::EMAIL.eq("alice@example.com".to_string()))
+    ///     .explain(&pool)
+    ///     .await?;
+    /// println!("{plan}");
+    /// ```
+    pub async fn explain<'a, A: Send + Acquire<'a, Database = Driver>>(
+        &self,
+        acquirer: A,
+    ) -> sqlx::Result<String> {
+        #[cfg(feature = "postgres")]
+        let prefix = "EXPLAIN ";
+        #[cfg(feature = "sqlite")]
+        let prefix = "EXPLAIN QUERY PLAN ";
+
+        self.fetch_plan(prefix, acquirer).await
+    }
+
+    /// Executes the query and returns the plan annotated with actual timings.
+    ///
+    /// **Note:** the query is really executed, so be careful with expensive queries.
+    #[cfg(feature = "postgres")]
+    pub async fn explain_analyze<'a, A: Send + Acquire<'a, Database = Driver>>(
+        &self,
+        acquirer: A,
+    ) -> sqlx::Result<String> {
+        self.fetch_plan("EXPLAIN ANALYZE ", acquirer).await
+    }
+
+    async fn fetch_plan<'a, A: Send + Acquire<'a, Database = Driver>>(
+        &self,
+        prefix: &'static str,
+        acquirer: A,
+    ) -> sqlx::Result<String> {
+        let mut conn = acquirer.acquire().await?;
+        let mut builder = QueryBuilder::new(prefix);
+        self.apply_query(&mut builder);
+
+        let rows = builder.build().fetch_all(&mut *conn).await?;
+
+        #[cfg(feature = "postgres")]
+        let column = 0;
+        #[cfg(feature = "sqlite")]
+        let column = "detail";
+
+        let lines = rows
+            .iter()
+            .map(|row| row.try_get::<String, _>(column))
+            .collect::<sqlx::Result<Vec<_>>>()?;
+
+        Ok(lines.join("\n"))
+    }
+}
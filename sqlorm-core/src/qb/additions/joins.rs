@@ -1,4 +1,6 @@
-use crate::{QB, TableInfo};
+use sqlx::{Row as _, ValueRef};
+
+use crate::{Condition, OrderBySpec, QB, Row, TableInfo, format_alised_col_name};
 
 #[derive(Clone, Debug)]
 /// Join type for related tables.
@@ -18,16 +20,124 @@ pub struct JoinSpec {
     pub foreign_table: TableInfo,
     /// Join key mapping as (base_pk, foreign_fk).
     pub on: (&'static str, &'static str),
+    /// Alias of the joined table within the query. Defaults to `foreign_table.alias`.
+    ///
+    /// Set by [`QB::join_eager`] when the table is already present in the query,
+    /// so the same table can be joined more than once.
+    pub alias: Option<String>,
+    /// Conditions on the related rows, applied to the query loading a batch relation.
+    pub filters: Vec<Condition>,
+    /// Order of the related rows of a batch relation.
+    pub order_by: Vec<OrderBySpec>,
+    /// Maximum number of related rows loaded per parent of a batch relation.
+    pub limit: Option<u64>,
+}
+
+impl JoinSpec {
+    /// Alias the joined table is referenced by in the query.
+    pub fn alias(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.foreign_table.alias)
+    }
+
+    /// Whether `row` has a joined row, i.e. its primary key column `pk` is not `NULL`.
+    ///
+    /// A `LEFT JOIN` without a matching row, e.g. on a `NULL` foreign key, projects `NULL`s.
+    pub fn is_joined(&self, row: &Row, pk: &str) -> sqlx::Result<bool> {
+        let column = format_alised_col_name(self.alias(), pk);
+        Ok(!row.try_get_raw(column.as_str())?.is_null())
+    }
+
+    /// Applies the filters and ordering requested for the related rows to `qb`.
+    pub fn refine<U>(&self, qb: QB<U>) -> QB<U> {
+        let qb = self.filters.iter().cloned().fold(qb, QB::filter);
+        self.order_by.iter().cloned().fold(qb, QB::order_by)
+    }
 }
 
 impl<T> QB<T> {
-    pub fn join_eager(mut self, spec: JoinSpec) -> Self {
+    /// Adds an eager relation. Requesting an already joined relation again only changes its
+    /// join type, e.g. `with_owner().with_owner_inner()` emits a single `INNER JOIN`.
+    pub fn join_eager(mut self, mut spec: JoinSpec) -> Self {
+        if let Some(existing) = self
+            .eager
+            .iter_mut()
+            .find(|join| join.relation_name == spec.relation_name)
+        {
+            existing.join_type = spec.join_type;
+            return self;
+        }
+        if spec.alias.is_none() && self.is_alias_taken(&spec.foreign_table.alias) {
+            spec.alias = Some(format!(
+                "{}_{}",
+                spec.foreign_table.alias, spec.relation_name
+            ));
+        }
         self.eager.push(spec);
         self
     }
 
+    /// Adds a batch relation, merging its options into an already requested one.
     pub fn join_batch(mut self, spec: JoinSpec) -> Self {
-        self.batch.push(spec);
+        match self
+            .batch
+            .iter_mut()
+            .find(|join| join.relation_name == spec.relation_name)
+        {
+            Some(existing) => {
+                existing.filters.extend(spec.filters);
+                existing.order_by.extend(spec.order_by);
+                existing.limit = spec.limit.or(existing.limit);
+            }
+            None => self.batch.push(spec),
+        }
         self
     }
 }
+
+impl<T> QB<T> {
+    /// Adds a condition on the table joined for `relation`.
+    ///
+    /// Conditions built from the related entity's columns reference its default alias.
+    /// This re-targets them to the alias of the join, which differs when the same
+    /// table is joined more than once.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// let transfers = Transfer::query()
+    ///     .with_sender()
+    ///     .with_receiver()
+    ///     .filter_relation("receiver", User::USERNAME.eq("bob".to_string()))
+    ///     .fetch_all(&pool)
+    ///     .await?;
+    /// ```
+    pub fn filter_relation(self, relation: &str, mut cond: Condition) -> Self {
+        if let Some((from, to)) = self.relation_aliases(relation) {
+            cond.sql = cond.sql.replace(&format!("{}.", from), &format!("{}.", to));
+        }
+        self.filter(cond)
+    }
+
+    /// Orders by a column of the table joined for `relation`. See [`QB::filter_relation`].
+    pub fn order_by_relation(self, relation: &str, mut spec: OrderBySpec) -> Self {
+        if let Some((from, to)) = self.relation_aliases(relation) {
+            spec.column = spec.column.replace(&format!("{}.", from), &format!("{}.", to));
+            if let Some(col) = spec.aliased_column.strip_prefix(&format!("{}__", from)) {
+                spec.aliased_column = format!("{}__{}", to, col);
+            }
+        }
+        self.order_by(spec)
+    }
+
+    /// Default and in-query aliases of the eager join for `relation`, if they differ.
+    fn relation_aliases(&self, relation: &str) -> Option<(String, String)> {
+        self.eager
+            .iter()
+            .find(|join| join.relation_name == relation)
+            .filter(|join| join.alias() != join.foreign_table.alias)
+            .map(|join| (join.foreign_table.alias.clone(), join.alias().to_string()))
+    }
+
+    fn is_alias_taken(&self, alias: &str) -> bool {
+        self.base.alias == alias || self.eager.iter().any(|join| join.alias() == alias)
+    }
+}
@@ -1,13 +1,142 @@
-use crate::QB;
+use crate::qb::checked_limit;
+use crate::{Column, Condition, QB, with_quotes};
 
 impl<T> QB<T> {
-    pub fn limit(mut self, limit: i32) -> Self {
-        self.limit = Some(limit);
+    /// Limits the number of returned rows.
+    ///
+    /// Panics if `limit` is above `i64::MAX`
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(checked_limit(limit, "limit"));
         self
     }
 
-    pub fn offset(mut self, offset: i32) -> Self {
-        self.offset = Some(offset);
+    /// Skips the first `offset` rows.
+    ///
+    /// Panics if `offset` is above `i64::MAX`
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(checked_limit(offset, "offset"));
         self
     }
+
+    /// Keeps at most `limit` rows per distinct value of `partition`, the first ones in the
+    /// query's order. Joins, filters and ordering added afterwards are not taken into account.
+    ///
+    /// Rendered with `ROW_NUMBER()` on PostgreSQL and a correlated `LIMIT` on SQLite.
+    /// Rows are matched by `ctid` on PostgreSQL and `rowid` on SQLite, so the query must
+    /// select from a table: views and SQLite `WITHOUT ROWID` tables fail with a database error.
+    /// Panics if `limit` is above `i64::MAX`.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// // latest 5 donations of each jar
+    /// let donations = Donation::query()
+    ///     .filter(Donation::JAR_ID.in_(jar_ids))
+    ///     .order_by(Donation::CREATED_AT.desc())
+    ///     .limit_per(Donation::JAR_ID, 5)
+    ///     .fetch_all(&pool)
+    ///     .await?;
+    /// ```
+    pub fn limit_per<C, W>(mut self, partition: Column<C, W>, limit: u64) -> Self {
+        let limit = checked_limit(limit, "limit");
+        let mut conds = self.filters.clone();
+        conds.extend(self.soft_delete_sql().map(Condition::none));
+        let cond = self.limit_per_condition(partition, limit, conds);
+
+        // parenthesized so an `or()` filter doesn't escape the per-partition limit
+        for filter in &mut self.filters {
+            filter.sql = format!("({})", filter.sql);
+        }
+        self.filter(cond)
+    }
+
+    #[cfg(feature = "postgres")]
+    fn limit_per_condition<C, W>(
+        &self,
+        partition: Column<C, W>,
+        limit: u64,
+        conds: Vec<Condition>,
+    ) -> Condition {
+        let alias = &self.base.alias;
+        let row_number = crate::window::row_number().over(partition, self.order_by.clone());
+        let (filters, values) = where_clause(conds);
+
+        Condition {
+            sql: format!(
+                "{alias}.ctid IN (SELECT __ranked.ctid FROM (SELECT {alias}.ctid, {row_number} AS __row_number \
+                 FROM {table} AS {alias}{joins}{filters}) AS __ranked WHERE __ranked.__row_number <= {limit})",
+                table = with_quotes(self.base.name),
+                joins = self.joins_sql(),
+            ),
+            values,
+        }
+    }
+
+    /// The subquery re-targets joins, filters and ordering to its own alias, the same way
+    /// [`QB::filter_relation`] does.
+    #[cfg(feature = "sqlite")]
+    fn limit_per_condition<C, W>(
+        &self,
+        partition: Column<C, W>,
+        limit: u64,
+        conds: Vec<Condition>,
+    ) -> Condition {
+        const INNER: &str = "__limited";
+        let alias = &self.base.alias;
+        let requalify = |sql: &str| sql.replace(&format!("{}.", alias), &format!("{}.", INNER));
+
+        let mut correlated = vec![Condition::none(format!(
+            "{INNER}.{column} = {alias}.{column}",
+            column = partition.name
+        ))];
+        correlated.extend(conds.into_iter().map(|cond| Condition {
+            sql: requalify(&cond.sql),
+            values: cond.values,
+        }));
+        let (filters, values) = where_clause(correlated);
+
+        let order: Vec<String> = self
+            .order_by
+            .iter()
+            .map(|spec| requalify(&spec.to_sql()))
+            .collect();
+        let order = if order.is_empty() {
+            String::new()
+        } else {
+            format!(" ORDER BY {}", order.join(", "))
+        };
+
+        Condition {
+            sql: format!(
+                "{alias}.rowid IN (SELECT {INNER}.rowid FROM {table} AS {INNER}{joins}{filters}{order} LIMIT {limit})",
+                table = with_quotes(self.base.name),
+                joins = requalify(&self.joins_sql()),
+            ),
+            values,
+        }
+    }
+}
+
+/// Renders `conds` as a ` WHERE ...` clause, along with their values in order.
+fn where_clause(conds: Vec<Condition>) -> (String, Vec<Box<dyn crate::qb::condition::AnyValue>>) {
+    if conds.is_empty() {
+        return (String::new(), Vec::new());
+    }
+
+    let grouped = conds.len() > 1;
+    let sql = format!(
+        " WHERE {}",
+        conds
+            .iter()
+            .map(|cond| {
+                if grouped {
+                    format!("({})", cond.sql)
+                } else {
+                    cond.sql.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    );
+    let values = conds.into_iter().flat_map(|cond| cond.values).collect();
+    (sql, values)
 }
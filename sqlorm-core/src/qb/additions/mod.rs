@@ -1,6 +1,14 @@
+mod exists;
+mod explain;
 mod joins;
 mod limit_offset;
 mod order_by;
+mod pagination;
 mod select;
+mod soft_delete;
+mod stream;
 pub use joins::*;
 pub use order_by::*;
+pub use pagination::*;
+pub use select::SelectExpr;
+pub use soft_delete::*;
@@ -2,16 +2,88 @@ use std::fmt::Display;
 
 use crate::QB;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum Ordering {
     Asc,
     Desc,
+    /// Random order. The spec's column holds the random function and no direction is rendered.
+    Random,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+/// Placement of `NULL` values in ordered results.
+pub enum Nulls {
+    First,
+    Last,
+}
+
+#[derive(Clone, Debug)]
 pub struct OrderBySpec {
+    /// Column qualified with table alias, e.g. `__user.id`.
     pub column: String,
+    /// Projected column name, e.g. `__user__id`. Used when ordering compound queries.
+    pub aliased_column: String,
     pub order: Ordering,
+    /// Placement of `NULL` values. Database default when `None`.
+    pub nulls: Option<Nulls>,
+}
+
+impl OrderBySpec {
+    /// Orders rows randomly, i.e. `RANDOM()` on SQLite and `random()` on PostgreSQL.
+    pub fn random() -> Self {
+        #[cfg(feature = "postgres")]
+        let function = "random()";
+        #[cfg(feature = "sqlite")]
+        let function = "RANDOM()";
+
+        OrderBySpec {
+            column: function.to_string(),
+            aliased_column: function.to_string(),
+            order: Ordering::Random,
+            nulls: None,
+        }
+    }
+
+    /// Places `NULL` values before all other values.
+    pub fn nulls_first(mut self) -> Self {
+        self.nulls = Some(Nulls::First);
+        self
+    }
+
+    /// Places `NULL` values after all other values.
+    pub fn nulls_last(mut self) -> Self {
+        self.nulls = Some(Nulls::Last);
+        self
+    }
+
+    /// Renders the spec as an `ORDER BY` item.
+    pub fn to_sql(&self) -> String {
+        self.render(&self.column)
+    }
+
+    /// Renders the spec as an `ORDER BY` item referencing the projected column name.
+    pub fn to_aliased_sql(&self) -> String {
+        self.render(&self.aliased_column)
+    }
+
+    /// SQLite has no `NULLS FIRST/LAST`, so it's emulated with an `IS NULL` sort key.
+    fn render(&self, column: &str) -> String {
+        if let Ordering::Random = self.order {
+            return column.to_string();
+        }
+
+        match &self.nulls {
+            None => format!("{} {}", column, self.order),
+            #[cfg(feature = "postgres")]
+            Some(Nulls::First) => format!("{} {} NULLS FIRST", column, self.order),
+            #[cfg(feature = "postgres")]
+            Some(Nulls::Last) => format!("{} {} NULLS LAST", column, self.order),
+            #[cfg(feature = "sqlite")]
+            Some(Nulls::First) => format!("{} IS NULL desc, {} {}", column, column, self.order),
+            #[cfg(feature = "sqlite")]
+            Some(Nulls::Last) => format!("{} IS NULL asc, {} {}", column, column, self.order),
+        }
+    }
 }
 
 impl<T> QB<T> {
@@ -30,6 +102,9 @@ impl<T> QB<T> {
     ///
     /// User::query().order_by(User::RATING.desc()).order_by(User::NAME.asc()).fetch_many(&pool);
     /// // turns into: select ... from "user" order by rating desc, name asc
+    ///
+    /// // columns of eagerly joined relations can be used as well
+    /// Jar::query().with_owner().order_by(User::USERNAME.asc()).fetch_many(&pool);
     /// ```
     ///
     pub fn order_by(mut self, stmt: OrderBySpec) -> QB<T> {
@@ -37,12 +112,23 @@ impl<T> QB<T> {
 
         self
     }
+
+    /// Orders rows randomly, e.g. to sample rows.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// let featured = Jar::query().order_random().limit(1).fetch_one(&pool).await?;
+    /// ```
+    pub fn order_random(self) -> QB<T> {
+        self.order_by(OrderBySpec::random())
+    }
 }
 impl Display for Ordering {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Ordering::Desc => f.write_str("desc"),
             Ordering::Asc => f.write_str("asc"),
+            Ordering::Random => Ok(()),
         }
     }
 }
@@ -0,0 +1,70 @@
+use sqlx::QueryBuilder;
+
+use crate::{QB, comment};
+use crate::driver::Driver;
+
+/// A single page of results returned by `paginate`.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    /// Rows of the current page.
+    pub items: Vec<T>,
+    /// Total number of rows matching the query, ignoring pagination.
+    pub total: i64,
+    /// Current page number, starting from 1.
+    pub page: u64,
+    /// Maximum number of rows per page.
+    pub per_page: u64,
+    /// Total number of pages.
+    pub total_pages: u64,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, total: i64, page: u64, per_page: u64) -> Self {
+        let total_rows = u64::try_from(total).unwrap_or(0);
+        let total_pages = if per_page == 0 {
+            0
+        } else {
+            total_rows.div_ceil(per_page)
+        };
+
+        Page {
+            items,
+            total,
+            page,
+            per_page,
+            total_pages,
+        }
+    }
+}
+
+impl<T> QB<T> {
+    /// Builds `SELECT COUNT(*)` query with the same joins and filters as this query.
+    ///
+    /// Ordering, limit and offset are ignored.
+    pub fn build_count_query(&self) -> QueryBuilder<'static, Driver> {
+        let mut builder = QueryBuilder::new("SELECT COUNT(*) ");
+
+        self.apply_from_clause(&mut builder);
+        self.apply_joins(&mut builder);
+        self.apply_filters(&mut builder);
+        builder.push(comment::render(&self.comments));
+
+        builder
+    }
+
+    /// Applies limit and offset for given page. Pages start from 1.
+    ///
+    /// Panics if `per_page` or the offset of the page is above `i64::MAX`.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// let page: Page<User> = User::query()
+    ///     .order_by(User::ID.asc())
+    ///     .paginate(2, 20, &pool)
+    ///     .await?;
+    /// ```
+    pub fn for_page(self, page: u64, per_page: u64) -> Self {
+        let offset = page.saturating_sub(1).saturating_mul(per_page);
+        self.limit(per_page).offset(offset)
+    }
+}
@@ -1,7 +1,20 @@
-use crate::selectable::Selectable;
+use sqlx::{Acquire, Row};
+
+use crate::driver::Driver;
+use crate::selectable::{AppendRow, Selectable};
+use crate::{Column, FromAliasedRow, Projection, with_quotes};
+
+#[derive(Clone, Debug)]
+/// Arbitrary SQL expression projected under an alias, e.g. `amount + tip AS total`.
+pub struct SelectExpr {
+    /// Raw SQL of the expression.
+    pub sql: String,
+    /// Alias of the projected value.
+    pub alias: String,
+}
 
 impl<T> crate::QB<T> {
-    pub fn select<'a, S: Selectable>(mut self, cols: S) -> crate::QB<S::Row> {
+    pub fn select<S: Selectable>(mut self, cols: S) -> crate::QB<S::Row> {
         let cols = cols.collect();
         if cols.is_empty() {
             panic!("Cannot select empty column list. At least one column must be specified.");
@@ -11,11 +24,210 @@ impl<T> crate::QB<T> {
             base: self.base,
             eager: self.eager,
             batch: self.batch,
+            exprs: Vec::new(),
+            order_by: self.order_by,
+            limit: self.limit,
+            offset: self.offset,
+            filters: self.filters,
+            soft_delete: self.soft_delete,
+            persistent: self.persistent,
+            comments: self.comments,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Selects columns required by projection `D` and maps rows into it.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// #[derive(FromAliasedRow, Default)]
+    /// #[sql(entity = User)]
+    /// struct UserSummary {
+    ///     id: i64,
+    ///     email: String,
+    /// }
+    ///
+    /// let summaries: Vec<UserSummary> = User::query()
+    ///     .select_as::<UserSummary>()
+    ///     .fetch_all(&pool)
+    ///     .await?;
+    /// ```
+    pub fn select_as<D: Projection>(mut self) -> crate::QB<D> {
+        self.base.columns = D::columns();
+        crate::QB {
+            base: self.base,
+            eager: self.eager,
+            batch: self.batch,
+            exprs: Vec::new(),
+            order_by: self.order_by,
+            limit: self.limit,
+            offset: self.offset,
+            filters: self.filters,
+            soft_delete: self.soft_delete,
+            persistent: self.persistent,
+            comments: self.comments,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Projects a computed SQL expression after already selected columns.
+    ///
+    /// The value is appended to the selected row tuple, so it can be fetched with
+    /// `fetch_one_as` / `fetch_all_as` alongside typed columns.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// let (id, total): (Uuid, f64) = Donation::query()
+    ///     .select((Donation::ID,))
+    ///     .select_expr::<f64>("amount + tip", "total")
+    ///     .fetch_one_as(&pool)
+    ///     .await?;
+    /// ```
+    pub fn select_expr<X>(mut self, sql: impl Into<String>, alias: &str) -> crate::QB<T::Out>
+    where
+        T: AppendRow<X>,
+    {
+        self.exprs.push(SelectExpr {
+            sql: sql.into(),
+            alias: alias.to_string(),
+        });
+        crate::QB {
+            base: self.base,
+            eager: self.eager,
+            batch: self.batch,
+            exprs: self.exprs,
             order_by: self.order_by,
             limit: self.limit,
             offset: self.offset,
             filters: self.filters,
+            soft_delete: self.soft_delete,
+            persistent: self.persistent,
+            comments: self.comments,
             _marker: std::marker::PhantomData,
         }
     }
+
+    /// Projects the number of `related` rows matching `on` under `alias`.
+    ///
+    /// `on` is SQL correlating the related table alias with the base table alias. Only the
+    /// table and soft delete mode of `related` are used. Called by the generated
+    /// `with_<relation>_count()`.
+    pub fn select_count<U>(mut self, alias: &str, related: crate::QB<U>, on: String) -> Self {
+        let mut conds = vec![on];
+        conds.extend(related.soft_delete_sql());
+        self.exprs.push(SelectExpr {
+            sql: format!(
+                "SELECT COUNT(*) FROM {} AS {} WHERE {}",
+                with_quotes(related.base.name),
+                related.base.alias,
+                conds.join(" AND ")
+            ),
+            alias: alias.to_string(),
+        });
+        self
+    }
+
+    /// Projects `related` rows matching `on` as a JSON array under `alias`, so they load in
+    /// the same round trip as the base rows.
+    ///
+    /// Rows are serialized with `json_agg()`, keyed by column name. Only the table and soft
+    /// delete mode of `related` are used. Called by the generated `with_<relation>_json()`.
+    #[cfg(feature = "postgres")]
+    pub fn select_json_agg<U>(mut self, alias: &str, related: crate::QB<U>, on: String) -> Self {
+        let mut conds = vec![on];
+        conds.extend(related.soft_delete_sql());
+        self.exprs.push(SelectExpr {
+            sql: format!(
+                "SELECT COALESCE(json_agg({1}), '[]'::json) FROM {0} AS {1} WHERE {2}",
+                with_quotes(related.base.name),
+                related.base.alias,
+                conds.join(" AND ")
+            ),
+            alias: alias.to_string(),
+        });
+        self
+    }
+
+    /// Fetches values of a single column.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// let emails: Vec<String> = User::query()
+    ///     .filter(User::BIO.is_not_null())
+    ///     .pluck(User::EMAIL, &pool)
+    ///     .await?;
+    /// ```
+    pub async fn pluck<'a, C, W, A>(self, column: Column<C, W>, acquirer: A) -> sqlx::Result<Vec<C>>
+    where
+        C: for<'r> sqlx::Decode<'r, Driver> + sqlx::Type<Driver> + Send + Unpin,
+        A: Send + Acquire<'a, Database = Driver>,
+    {
+        let mut conn = acquirer.acquire().await?;
+        let qb = self.select((column,));
+        let timer = qb.slow_query_timer();
+        let rows = qb
+            .build_query()
+            .build()
+            .persistent(qb.persistent)
+            .fetch_all(&mut *conn)
+            .await;
+        timer.finish();
+        rows?.iter().map(|row| row.try_get::<C, _>(0)).collect()
+    }
+}
+
+impl<D> crate::QB<D>
+where
+    D: Projection + FromAliasedRow + Default + Send,
+{
+    /// Executes the query and maps a single row into projection `D`.
+    pub async fn fetch_one<'a, A>(self, acquirer: A) -> sqlx::Result<D>
+    where
+        A: Send + Acquire<'a, Database = Driver>,
+    {
+        let mut conn = acquirer.acquire().await?;
+        let timer = self.slow_query_timer();
+        let row = self
+            .build_query()
+            .build()
+            .persistent(self.persistent)
+            .fetch_one(&mut *conn)
+            .await;
+        timer.finish();
+        D::from_aliased_row(&row?)
+    }
+
+    /// Executes the query and maps an optional row into projection `D`.
+    pub async fn fetch_optional<'a, A>(self, acquirer: A) -> sqlx::Result<Option<D>>
+    where
+        A: Send + Acquire<'a, Database = Driver>,
+    {
+        let mut conn = acquirer.acquire().await?;
+        let timer = self.slow_query_timer();
+        let row = self
+            .build_query()
+            .build()
+            .persistent(self.persistent)
+            .fetch_optional(&mut *conn)
+            .await;
+        timer.finish();
+        row?.as_ref().map(D::from_aliased_row).transpose()
+    }
+
+    /// Executes the query and maps all rows into projection `D`.
+    pub async fn fetch_all<'a, A>(self, acquirer: A) -> sqlx::Result<Vec<D>>
+    where
+        A: Send + Acquire<'a, Database = Driver>,
+    {
+        let mut conn = acquirer.acquire().await?;
+        let timer = self.slow_query_timer();
+        let rows = self
+            .build_query()
+            .build()
+            .persistent(self.persistent)
+            .fetch_all(&mut *conn)
+            .await;
+        timer.finish();
+        rows?.iter().map(D::from_aliased_row).collect()
+    }
 }
@@ -0,0 +1,89 @@
+use crate::QB;
+
+/// Which rows of a soft-deletable entity a query returns.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SoftDeleteFilter {
+    /// Only rows not soft deleted, i.e. `deleted_at IS NULL` or `is_deleted = FALSE`.
+    #[default]
+    Exclude,
+    /// All rows, soft deleted or not.
+    Include,
+    /// Only soft deleted rows, i.e. `deleted_at IS NOT NULL` or `is_deleted = TRUE`.
+    Only,
+}
+
+#[derive(Clone, Copy, Debug)]
+/// Soft delete column of the queried entity and the rows to return.
+pub struct SoftDelete {
+    /// Column holding the deletion timestamp, e.g. `deleted_at`, or the flag, e.g. `is_deleted`.
+    pub column: &'static str,
+    /// Whether `column` is a boolean flag rather than a nullable timestamp.
+    pub flag: bool,
+    pub filter: SoftDeleteFilter,
+}
+
+impl<T> QB<T> {
+    /// Marks the queried entity as soft-deletable, excluding rows with `column` set.
+    ///
+    /// Called by the generated `Entity::query()` for entities with a `deleted_at` timestamp.
+    pub fn soft_delete(mut self, column: &'static str) -> Self {
+        self.soft_delete = Some(SoftDelete {
+            column,
+            flag: false,
+            filter: SoftDeleteFilter::Exclude,
+        });
+        self
+    }
+
+    /// Marks the queried entity as soft-deletable, excluding rows with the boolean `column` set.
+    ///
+    /// Called by the generated `Entity::query()` for entities with a `#[sql(soft_delete)]` flag.
+    pub fn soft_delete_flag(mut self, column: &'static str) -> Self {
+        self.soft_delete = Some(SoftDelete {
+            column,
+            flag: true,
+            filter: SoftDeleteFilter::Exclude,
+        });
+        self
+    }
+
+    /// Includes soft deleted rows. Does nothing for entities without soft delete.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// let all_users = User::query().with_deleted().fetch_all(&pool).await?;
+    /// ```
+    pub fn with_deleted(self) -> Self {
+        self.soft_delete_filter(SoftDeleteFilter::Include)
+    }
+
+    /// Returns only soft deleted rows. Does nothing for entities without soft delete.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// let trashed = User::query().only_deleted().fetch_all(&pool).await?;
+    /// ```
+    pub fn only_deleted(self) -> Self {
+        self.soft_delete_filter(SoftDeleteFilter::Only)
+    }
+
+    fn soft_delete_filter(mut self, filter: SoftDeleteFilter) -> Self {
+        if let Some(soft_delete) = &mut self.soft_delete {
+            soft_delete.filter = filter;
+        }
+        self
+    }
+
+    /// SQL of the soft delete condition on the base table, if any.
+    pub(crate) fn soft_delete_sql(&self) -> Option<String> {
+        let soft_delete = self.soft_delete?;
+        let column = format!("{}.{}", self.base.alias, soft_delete.column);
+        match (soft_delete.filter, soft_delete.flag) {
+            (SoftDeleteFilter::Include, _) => None,
+            (SoftDeleteFilter::Exclude, false) => Some(format!("{} IS NULL", column)),
+            (SoftDeleteFilter::Exclude, true) => Some(format!("{} = FALSE", column)),
+            (SoftDeleteFilter::Only, false) => Some(format!("{} IS NOT NULL", column)),
+            (SoftDeleteFilter::Only, true) => Some(format!("{} = TRUE", column)),
+        }
+    }
+}
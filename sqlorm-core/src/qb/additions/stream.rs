@@ -0,0 +1,30 @@
+use futures_core::stream::BoxStream;
+use futures_util::StreamExt;
+
+use crate::QB;
+use crate::driver::{Driver, Row};
+
+impl<T> QB<T> {
+    /// Executes the query and lazily maps each returned row with `map`.
+    ///
+    /// Rows are pulled from the database one by one, instead of buffering the
+    /// whole result set in memory.
+    pub fn stream_with<'e, E, R, F>(self, executor: E, map: F) -> BoxStream<'e, sqlx::Result<R>>
+    where
+        T: Send + 'e,
+        E: 'e + sqlx::Executor<'e, Database = Driver>,
+        R: Send + 'e,
+        F: Fn(&Row) -> sqlx::Result<R> + Send + 'e,
+    {
+        Box::pin(async_stream::stream! {
+            let mut builder = self.build_query();
+            let mut rows = builder
+                .build()
+                .persistent(self.persistent)
+                .fetch(executor);
+            while let Some(row) = rows.next().await {
+                yield row.and_then(|row| map(&row));
+            }
+        })
+    }
+}
@@ -17,8 +17,11 @@ use std::marker::PhantomData;
 /// let cond: Condition = ID.eq(42);
 /// assert_eq!(cond.sql, "user__.id = ?");
 /// ```
+///
+/// `W` is [`Mutable`] unless the field is marked `#[sql(immutable)]`, in which case it is
+/// [`Immutable`] and the column is rejected by update APIs at compile time.
 #[derive(Debug)]
-pub struct Column<T> {
+pub struct Column<T, W = Mutable> {
     /// The column name as it appears in SQL.
     pub name: &'static str,
 
@@ -30,22 +33,31 @@ pub struct Column<T> {
     pub table_alias: &'static str,
 
     /// Marker to carry the type information for the column.
-    pub _marker: PhantomData<T>,
+    pub _marker: PhantomData<(T, W)>,
 }
-impl<T> AsRef<str> for Column<T> {
+
+/// Marker of columns written by both `INSERT` and `UPDATE`.
+#[derive(Debug)]
+pub struct Mutable;
+
+/// Marker of `#[sql(immutable)]` columns, written only by `INSERT`.
+#[derive(Debug)]
+pub struct Immutable;
+
+impl<T, W> AsRef<str> for Column<T, W> {
     fn as_ref(&self) -> &str {
         self.name
     }
 }
 
-impl<T> Copy for Column<T> {}
-impl<T> Clone for Column<T> {
+impl<T, W> Copy for Column<T, W> {}
+impl<T, W> Clone for Column<T, W> {
     fn clone(&self) -> Self {
         *self
     }
 }
 
-impl<T> Column<T>
+impl<T, W> Column<T, W>
 where
     T: BindValue + Clone,
 {
@@ -89,6 +101,63 @@ where
         Condition::new(format!("{} LIKE ?", self.qualified_name()), val)
     }
 
+    /// Create a case-insensitive condition: `column ILIKE ?`
+    ///
+    /// SQLite has no `ILIKE`, so both sides are wrapped in `LOWER()` instead.
+    pub fn ilike(self, val: T) -> Condition {
+        Condition::ilike(&self.qualified_name(), val)
+    }
+
+    /// Create a case-insensitive condition: `LOWER(column) = LOWER(?)`
+    pub fn eq_ignore_case(self, val: T) -> Condition {
+        Condition::eq_ignore_case(&self.qualified_name(), val)
+    }
+
+    /// Create a JSONB containment condition: `column @> ?`
+    ///
+    /// `val` is usually `sqlx::types::JsonValue` or `sqlx::types::Json<_>`.
+    #[cfg(feature = "postgres")]
+    pub fn json_contains<V: BindValue + Clone>(self, val: V) -> Condition {
+        Condition::json_contains(&self.qualified_name(), val)
+    }
+
+    /// Create a condition checking that JSONB object has top-level `key`.
+    #[cfg(feature = "postgres")]
+    pub fn json_exists_key(self, key: &str) -> Condition {
+        Condition::json_exists_key(&self.qualified_name(), key.to_string())
+    }
+
+    /// Reference a top-level JSON field as text: `column ->> key`
+    ///
+    /// ```ignore
+    /// User::query().filter(User::SETTINGS.json_get("theme").eq("dark"));
+    /// ```
+    #[cfg(feature = "postgres")]
+    pub fn json_get(self, key: &str) -> JsonField {
+        JsonField {
+            column: self.qualified_name(),
+            key: key.to_string(),
+        }
+    }
+
+    /// Create a condition for array columns: `? = ANY(column)`
+    ///
+    /// ```ignore
+    /// Post::query().filter(Post::TAGS.any_eq("rust".to_string()));
+    /// ```
+    #[cfg(feature = "postgres")]
+    pub fn any_eq<E: BindValue + Clone>(self, val: E) -> Condition {
+        Condition::any_eq(&self.qualified_name(), val)
+    }
+
+    /// Create a condition for array columns: `column && ?`
+    ///
+    /// Matches rows whose array shares at least one element with `vals`.
+    #[cfg(feature = "postgres")]
+    pub fn overlaps(self, vals: T) -> Condition {
+        Condition::overlaps(&self.qualified_name(), vals)
+    }
+
     /// Create a condition: `column IN (?, ?, ...)`
     ///
     /// The number of placeholders matches the number of values provided.
@@ -123,6 +192,20 @@ where
         Condition::multi(sql, vals)
     }
 
+    /// Create a condition comparing two columns: `column = other`
+    ///
+    /// ```ignore
+    /// let on = Jar::ID.eq_col(Donation::JAR_ID);
+    /// assert_eq!(on.sql, "__jar.id = __donation.jar_id");
+    /// ```
+    pub fn eq_col<O>(self, other: Column<T, O>) -> Condition {
+        Condition::none(format!(
+            "{} = {}",
+            self.qualified_name(),
+            other.qualified_name()
+        ))
+    }
+
     /// Create a condition: `column IS NULL`
     pub fn is_null(self) -> Condition {
         Condition::none(format!("{} IS NULL", self.qualified_name()))
@@ -148,13 +231,48 @@ where
     pub fn desc(self) -> OrderBySpec {
         OrderBySpec {
             column: format!("{}.{}", self.table_alias, self.name),
+            aliased_column: self.aliased_name.to_string(),
             order: Ordering::Desc,
+            nulls: None,
         }
     }
     pub fn asc(self) -> OrderBySpec {
         OrderBySpec {
             column: format!("{}.{}", self.table_alias, self.name),
+            aliased_column: self.aliased_name.to_string(),
             order: Ordering::Asc,
+            nulls: None,
         }
     }
 }
+
+/// Top-level field of a JSON column, extracted as text with `->>`.
+#[cfg(feature = "postgres")]
+#[derive(Debug, Clone)]
+pub struct JsonField {
+    column: String,
+    key: String,
+}
+
+#[cfg(feature = "postgres")]
+impl JsonField {
+    /// Create a condition: `(column ->> key) = ?`
+    pub fn eq(self, val: impl Into<String>) -> Condition {
+        Condition::json_field(&self.column, self.key, "=", val.into())
+    }
+
+    /// Create a condition: `(column ->> key) <> ?`
+    pub fn ne(self, val: impl Into<String>) -> Condition {
+        Condition::json_field(&self.column, self.key, "<>", val.into())
+    }
+
+    /// Create a condition: `(column ->> key) LIKE ?`
+    pub fn like(self, val: impl Into<String>) -> Condition {
+        Condition::json_field(&self.column, self.key, "LIKE", val.into())
+    }
+
+    /// Create a condition: `(column ->> key) IS NULL`
+    pub fn is_null(self) -> Condition {
+        Condition::new(format!("({} ->> ?) IS NULL", self.column), self.key)
+    }
+}
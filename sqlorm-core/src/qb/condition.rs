@@ -2,12 +2,17 @@ use crate::driver::Driver;
 use crate::qb::BindValue;
 use sqlx::QueryBuilder;
 
+/// Prepared query of the selected driver, as returned by [`sqlx::query`].
+pub type DriverQuery<'q> =
+    sqlx::query::Query<'q, Driver, <Driver as sqlx::Database>::Arguments<'q>>;
+
 /// Represents a SQL condition fragment with its associated bound values.
 ///
 /// A `Condition` is essentially a piece of SQL (e.g. `"id = $1"`)
 /// along with one or more values that should be bound into the query.
 /// It is designed to be used with [`sqlx::QueryBuilder`] for dynamic
 /// query construction.
+#[derive(Clone)]
 pub struct Condition {
     /// The raw SQL fragment (e.g. `"id = $1"`, `"name IN (...)"`).
     pub sql: String,
@@ -27,6 +32,15 @@ pub struct Condition {
 pub trait AnyValue: Send + Sync {
     /// Bind this value into the given [`QueryBuilder`].
     fn bind(&self, builder: &mut QueryBuilder<'static, Driver>);
+
+    /// Bind this value to a prepared [`sqlx::query::Query`].
+    fn bind_query<'q>(&self, query: DriverQuery<'q>) -> DriverQuery<'q>;
+
+    /// Human-readable representation of this value, used by `debug_sql`.
+    fn debug_value(&self) -> String;
+
+    /// Clones the value behind the trait object.
+    fn clone_box(&self) -> Box<dyn AnyValue>;
 }
 
 impl<T> AnyValue for T
@@ -36,9 +50,40 @@ where
     fn bind(&self, builder: &mut QueryBuilder<'static, Driver>) {
         builder.push_bind(self.clone());
     }
+
+    fn bind_query<'q>(&self, query: DriverQuery<'q>) -> DriverQuery<'q> {
+        query.bind(self.clone())
+    }
+
+    fn debug_value(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn clone_box(&self) -> Box<dyn AnyValue> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn AnyValue> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
 }
 
 impl Condition {
+    /// Pushes the SQL fragment into `builder`, binding values in place of `?`.
+    pub(crate) fn push_to(&self, builder: &mut QueryBuilder<'static, Driver>) {
+        let mut parts = self.sql.split('?');
+        if let Some(first) = parts.next() {
+            builder.push(first);
+        }
+
+        for (val, part) in self.values.iter().zip(parts) {
+            val.bind(builder);
+            builder.push(part);
+        }
+    }
+
     /// Create a new `Condition` with a single bound value.
     ///
     /// # Example
@@ -99,6 +144,55 @@ impl Condition {
         }
     }
 
+    /// Create a case-insensitive pattern match on `column`.
+    ///
+    /// Renders `column ILIKE ?` on PostgreSQL and `LOWER(column) LIKE LOWER(?)` on SQLite.
+    pub fn ilike<T: BindValue + Clone + 'static>(column: &str, val: T) -> Self {
+        #[cfg(feature = "postgres")]
+        let sql = format!("{} ILIKE ?", column);
+        #[cfg(feature = "sqlite")]
+        let sql = format!("LOWER({}) LIKE LOWER(?)", column);
+
+        Self::new(sql, val)
+    }
+
+    /// Create a case-insensitive equality check on `column`: `LOWER(column) = LOWER(?)`.
+    pub fn eq_ignore_case<T: BindValue + Clone + 'static>(column: &str, val: T) -> Self {
+        Self::new(format!("LOWER({}) = LOWER(?)", column), val)
+    }
+
+    /// Create a JSONB containment check: `column @> ?`
+    #[cfg(feature = "postgres")]
+    pub fn json_contains<T: BindValue + Clone + 'static>(column: &str, val: T) -> Self {
+        Self::new(format!("{} @> ?", column), val)
+    }
+
+    /// Create a JSONB top-level key existence check.
+    ///
+    /// Renders `jsonb_exists(column, ?)`, since the `?` operator would clash with placeholders.
+    #[cfg(feature = "postgres")]
+    pub fn json_exists_key(column: &str, key: String) -> Self {
+        Self::new(format!("jsonb_exists({}, ?)", column), key)
+    }
+
+    /// Create a comparison on a JSON field extracted as text: `(column ->> ?) <op> ?`
+    #[cfg(feature = "postgres")]
+    pub fn json_field(column: &str, key: String, op: &str, val: String) -> Self {
+        Self::multi(format!("({} ->> ?) {} ?", column, op), vec![key, val])
+    }
+
+    /// Create an array membership check: `? = ANY(column)`
+    #[cfg(feature = "postgres")]
+    pub fn any_eq<T: BindValue + Clone + 'static>(column: &str, val: T) -> Self {
+        Self::new(format!("? = ANY({})", column), val)
+    }
+
+    /// Create an array overlap check: `column && ?`
+    #[cfg(feature = "postgres")]
+    pub fn overlaps<T: BindValue + Clone + 'static>(column: &str, vals: T) -> Self {
+        Self::new(format!("{} && ?", column), vals)
+    }
+
     /// Combine two conditions with `AND`
     ///
     /// Wraps both conditions in parentheses to preserve operator precedence.
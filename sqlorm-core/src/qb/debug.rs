@@ -0,0 +1,146 @@
+use crate::qb::{QB, UnionQB, bind_limit};
+use crate::slow_query::Timer;
+
+/// Replaces bind placeholders (`$1` on PostgreSQL, `?` on SQLite) in `sql` with `values`.
+///
+/// `/* ... */` comments are copied as is, so `?` or `$1` in them are not replaced.
+///
+/// Intended for logging only: values are not escaped, so the output must never be executed.
+pub fn interpolate_debug(sql: &str, values: &[String]) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    #[cfg(feature = "sqlite")]
+    let mut next = 0;
+
+    while let Some(c) = chars.next() {
+        if c == '/' && chars.peek() == Some(&'*') {
+            chars.next();
+            out.push_str("/*");
+            let mut prev = None;
+            for c in chars.by_ref() {
+                out.push(c);
+                if prev == Some('*') && c == '/' {
+                    break;
+                }
+                prev = Some(c);
+            }
+            continue;
+        }
+
+        #[cfg(feature = "postgres")]
+        if c == '$' {
+            let mut digits = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                digits.push(d);
+                chars.next();
+            }
+            let value = digits
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| i.checked_sub(1))
+                .and_then(|i| values.get(i));
+            match value {
+                Some(value) => out.push_str(value),
+                None => {
+                    out.push(c);
+                    out.push_str(&digits);
+                }
+            }
+            continue;
+        }
+
+        #[cfg(feature = "sqlite")]
+        if c == '?' {
+            match values.get(next) {
+                Some(value) => out.push_str(value),
+                None => out.push(c),
+            }
+            next += 1;
+            continue;
+        }
+
+        out.push(c);
+    }
+
+    out
+}
+
+pub(crate) fn debug_limit_offset(limit: Option<u64>, offset: Option<u64>) -> Vec<String> {
+    let mut values = Vec::new();
+
+    if let Some(l) = limit {
+        values.push(bind_limit(l).to_string());
+    }
+
+    if let Some(o) = offset {
+        #[cfg(feature = "sqlite")]
+        if limit.is_none() {
+            values.push("-1".to_string());
+        }
+        values.push(bind_limit(o).to_string());
+    }
+
+    values
+}
+
+impl<T> QB<T> {
+    /// Debug representations of values bound by the filters, in binding order.
+    fn filter_debug_values(&self) -> Vec<String> {
+        self.filters
+            .iter()
+            .flat_map(|cond| cond.values.iter().map(|v| v.debug_value()))
+            .collect()
+    }
+
+    /// Debug representations of bound values, in binding order.
+    fn debug_values(&self) -> Vec<String> {
+        let mut values = self.filter_debug_values();
+        values.extend(debug_limit_offset(self.limit, self.offset));
+        values
+    }
+
+    /// Renders the query with bound values interpolated, e.g. `WHERE u.id = 42`.
+    ///
+    /// Values are formatted with `Debug` and are not escaped. **Use it for logging only,
+    /// never execute the returned string.** Use [`QB::to_sql`] for the real query.
+    pub fn debug_sql(&self) -> String {
+        interpolate_debug(&self.to_sql(), &self.debug_values())
+    }
+
+    /// Starts timing the execution of the query for [`crate::slow_query`].
+    #[doc(hidden)]
+    pub fn slow_query_timer(&self) -> Timer {
+        Timer::start(|| (self.to_sql(), self.debug_values()))
+    }
+
+    /// Starts timing the execution of [`QB::build_count_query`] for [`crate::slow_query`].
+    #[doc(hidden)]
+    pub fn count_query_timer(&self) -> Timer {
+        Timer::start(|| {
+            let sql = self.build_count_query().sql().to_string();
+            (sql, self.filter_debug_values())
+        })
+    }
+}
+
+impl<T> UnionQB<T> {
+    /// Limits and offsets of the combined queries are not rendered, so only their
+    /// filters bind values.
+    fn debug_values(&self) -> Vec<String> {
+        let mut values = self.first.filter_debug_values();
+        for (_, qb) in &self.rest {
+            values.extend(qb.filter_debug_values());
+        }
+        values.extend(debug_limit_offset(self.limit, self.offset));
+        values
+    }
+
+    /// Renders the union with bound values interpolated. **For logging only.**
+    pub fn debug_sql(&self) -> String {
+        interpolate_debug(&self.to_sql(), &self.debug_values())
+    }
+
+    pub(crate) fn slow_query_timer(&self) -> Timer {
+        Timer::start(|| (self.to_sql(), self.debug_values()))
+    }
+}
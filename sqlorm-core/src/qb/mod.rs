@@ -2,25 +2,42 @@ mod additions;
 mod bind;
 mod column;
 pub mod condition;
+mod debug;
+mod union;
+pub mod window;
 use std::fmt::Debug;
 
 #[cfg(any(feature = "postgres", feature = "sqlite"))]
 use crate::driver::Driver;
-use crate::format_alised_col_name;
+use crate::{comment, format_alised_col_name};
 pub use additions::JoinSpec;
 pub use additions::JoinType;
+pub use additions::Nulls;
 pub use additions::OrderBySpec;
+pub use additions::Page;
+pub use additions::SelectExpr;
+pub use additions::SoftDelete;
+pub use additions::SoftDeleteFilter;
 pub use bind::BindValue;
-pub use column::Column;
+pub use column::{Column, Immutable, Mutable};
+#[cfg(feature = "postgres")]
+pub use column::JsonField;
 pub use condition::Condition;
+pub use debug::interpolate_debug;
+pub use union::{UnionKind, UnionQB};
 use sqlx::QueryBuilder;
 
 /// Quote identifiers appropriately for the target database
 /// Both PostgreSQL and SQLite support double quotes for identifiers
+///
+/// Schema-qualified names are quoted part by part, `billing.jars` becomes `"billing"."jars"`.
 pub fn with_quotes(s: &str) -> String {
     // Double quotes work for both PostgreSQL and SQLite
     // This ensures consistent behavior across databases
-    format!("\"{}\"", s)
+    s.split('.')
+        .map(|part| format!("\"{}\"", part))
+        .collect::<Vec<_>>()
+        .join(".")
 }
 
 /// Query builder for composing SELECT statements with optional joins and filters.
@@ -32,16 +49,44 @@ pub struct QB<T> {
     pub eager: Vec<JoinSpec>,
     /// Batch joins for has-many relations.
     pub batch: Vec<JoinSpec>,
+    /// Computed expressions projected after columns.
+    pub exprs: Vec<SelectExpr>,
 
     /// WHERE clause conditions combined with AND.
     pub filters: Vec<Condition>,
+    /// Soft delete column of the base table, filtered according to its mode.
+    pub soft_delete: Option<SoftDelete>,
     pub order_by: Vec<OrderBySpec>,
 
-    pub limit: Option<i32>,
-    pub offset: Option<i32>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+
+    /// Whether the statement is cached as a prepared statement, see [`QB::not_persistent`].
+    pub persistent: bool,
+    /// Comments rendered after the statement, see [`QB::comment`].
+    pub comments: Vec<String>,
 
     _marker: std::marker::PhantomData<T>,
 }
+impl<T> Clone for QB<T> {
+    fn clone(&self) -> Self {
+        QB {
+            base: self.base.clone(),
+            eager: self.eager.clone(),
+            batch: self.batch.clone(),
+            exprs: self.exprs.clone(),
+            filters: self.filters.clone(),
+            soft_delete: self.soft_delete,
+            order_by: self.order_by.clone(),
+            limit: self.limit,
+            offset: self.offset,
+            persistent: self.persistent,
+            comments: self.comments.clone(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 /// Static information about a table used to build queries.
 pub struct TableInfo {
@@ -60,18 +105,77 @@ impl<T> QB<T> {
             eager: Vec::new(),
             order_by: Vec::new(),
             batch: Vec::new(),
+            exprs: Vec::new(),
             filters: Vec::new(),
+            soft_delete: None,
             _marker: std::marker::PhantomData,
             limit: None,
             offset: None,
+            persistent: true,
+            comments: Vec::new(),
         }
     }
 
+    /// Executes the query without caching it as a server-side prepared statement.
+    ///
+    /// Useful for one-off queries, e.g. built from arbitrary filter combinations, which would
+    /// otherwise fill the connection's statement cache without ever being reused.
+    pub fn not_persistent(mut self) -> Self {
+        self.persistent = false;
+        self
+    }
+
+    /// Tags the query with a trailing `/* comment */`, e.g. `endpoint=GET /jars`, so it can be
+    /// attributed in database-side monitoring. See [`crate::comment`].
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comments.push(comment.into());
+        self
+    }
+
     pub fn filter(mut self, cond: Condition) -> Self {
         self.filters.push(cond);
         self
     }
 
+    /// Adds the condition produced by `cond` only if `apply` is true.
+    ///
+    /// ```ignore
+    /// User::query()
+    ///     .filter_if(search.is_some(), || User::USERNAME.like(format!("%{}%", search.unwrap())))
+    ///     .fetch_all(&pool)
+    ///     .await?;
+    /// ```
+    pub fn filter_if(self, apply: bool, cond: impl FnOnce() -> Condition) -> Self {
+        if apply { self.filter(cond()) } else { self }
+    }
+
+    /// Applies a reusable scope, i.e. a function refining the query.
+    ///
+    /// ```ignore
+    /// impl User {
+    ///     pub fn active(qb: QB<User>) -> QB<User> {
+    ///         qb.filter(User::DELETED_AT.is_null())
+    ///     }
+    /// }
+    ///
+    /// let users = User::query().scope(User::active).fetch_all(&pool).await?;
+    /// ```
+    pub fn scope(self, scope: impl FnOnce(Self) -> Self) -> Self {
+        scope(self)
+    }
+
+    /// Applies `f` to the query only if `apply` is true.
+    ///
+    /// ```ignore
+    /// User::query()
+    ///     .apply_if(newest_first, |qb| qb.order_by(User::CREATED_AT.desc()))
+    ///     .fetch_all(&pool)
+    ///     .await?;
+    /// ```
+    pub fn apply_if(self, apply: bool, f: impl FnOnce(Self) -> Self) -> Self {
+        if apply { f(self) } else { self }
+    }
+
     fn apply_projections(&self, builder: &mut QueryBuilder<'static, Driver>) {
         let mut projections = Vec::new();
 
@@ -83,12 +187,16 @@ impl<T> QB<T> {
 
         for join in &self.eager {
             for col in &join.foreign_table.columns {
-                let field = format!("{}.{}", join.foreign_table.alias, col);
-                let as_field = format_alised_col_name(&join.foreign_table.alias, col);
+                let field = format!("{}.{}", join.alias(), col);
+                let as_field = format_alised_col_name(join.alias(), col);
                 projections.push(format!("{} AS {}", field, as_field));
             }
         }
 
+        for expr in &self.exprs {
+            projections.push(format!("({}) AS {}", expr.sql, expr.alias));
+        }
+
         builder.push(projections.join(", "));
 
         builder.push(" ");
@@ -105,13 +213,18 @@ impl<T> QB<T> {
     }
 
     fn apply_joins(&self, builder: &mut QueryBuilder<'static, Driver>) {
+        builder.push(self.joins_sql());
+    }
+
+    /// `JOIN` clauses of the eager relations.
+    fn joins_sql(&self) -> String {
         let mut joins = String::new();
 
         for join in &self.eager {
             let other_table = format!(
                 "{} AS {}",
                 with_quotes(join.foreign_table.name),
-                join.foreign_table.alias
+                join.alias()
             );
 
             let jt = match join.join_type {
@@ -120,7 +233,7 @@ impl<T> QB<T> {
             };
 
             let on_base = format!("{}.{}", self.base.alias, join.on.0);
-            let on_other = format!("{}.{}", join.foreign_table.alias, join.on.1);
+            let on_other = format!("{}.{}", join.alias(), join.on.1);
 
             joins.push_str(&format!(
                 " {} {} ON {} = {}",
@@ -128,47 +241,38 @@ impl<T> QB<T> {
             ));
         }
 
-        builder.push(joins);
+        joins
     }
 
-    fn apply_limit<'args>(&self, builder: &mut QueryBuilder<'args, Driver>) {
-        if let Some(l) = self.limit {
-            builder.push(" LIMIT ");
-            builder.push_bind(l);
+    fn apply_filters(&self, builder: &mut QueryBuilder<'static, Driver>) {
+        let soft_delete = self.soft_delete_sql();
+        if self.filters.is_empty() && soft_delete.is_none() {
+            return;
         }
-    }
 
-    fn apply_offset<'args>(&self, builder: &mut QueryBuilder<'args, Driver>) {
-        if let Some(o) = self.offset {
-            #[cfg(feature = "sqlite")]
-            if let None = self.limit {
-                builder.push(" LIMIT ");
-                builder.push_bind(-1);
+        builder.push(" WHERE ");
+
+        // parenthesized so an `or()` filter doesn't escape the soft delete condition
+        let grouped = soft_delete.is_some();
+        for (i, cond) in self.filters.iter().enumerate() {
+            if i > 0 {
+                builder.push(" AND ");
+            }
+
+            if grouped {
+                builder.push("(");
+            }
+            cond.push_to(builder);
+            if grouped {
+                builder.push(")");
             }
-            builder.push(" OFFSET ");
-            builder.push_bind(o);
         }
-    }
 
-    fn apply_filters(&self, builder: &mut QueryBuilder<'static, Driver>) {
-        if !self.filters.is_empty() {
-            builder.push(" WHERE ");
-
-            for (i, cond) in self.filters.iter().enumerate() {
-                if i > 0 {
-                    builder.push(" AND ");
-                }
-
-                let mut parts = cond.sql.split('?');
-                if let Some(first) = parts.next() {
-                    builder.push(first);
-                }
-
-                for (val, part) in cond.values.iter().zip(parts) {
-                    val.bind(builder);
-                    builder.push(part);
-                }
+        if let Some(sql) = soft_delete {
+            if !self.filters.is_empty() {
+                builder.push(" AND ");
             }
+            builder.push(sql);
         }
     }
 
@@ -183,21 +287,31 @@ impl<T> QB<T> {
             if i > 0 {
                 builder.push(", ");
             }
-            builder.push(format!("{} {}", spec.column, spec.order));
+            builder.push(spec.to_sql());
         }
     }
 
-    pub fn build_query(&self) -> QueryBuilder<'static, Driver> {
-        let mut builder = QueryBuilder::new("SELECT ");
+    /// Pushes `SELECT` statement without ordering and limits.
+    fn apply_select(&self, builder: &mut QueryBuilder<'static, Driver>) {
+        builder.push("SELECT ");
+
+        self.apply_projections(builder);
+        self.apply_from_clause(builder);
+        self.apply_joins(builder);
+        self.apply_filters(builder);
+    }
 
-        self.apply_projections(&mut builder);
-        self.apply_from_clause(&mut builder);
-        self.apply_joins(&mut builder);
-        self.apply_filters(&mut builder);
-        self.apply_order_by(&mut builder);
-        self.apply_limit(&mut builder);
-        self.apply_offset(&mut builder);
+    /// Pushes the full `SELECT` statement, including ordering and limits.
+    fn apply_query(&self, builder: &mut QueryBuilder<'static, Driver>) {
+        self.apply_select(builder);
+        self.apply_order_by(builder);
+        apply_limit_offset(builder, self.limit, self.offset);
+    }
 
+    pub fn build_query(&self) -> QueryBuilder<'static, Driver> {
+        let mut builder = QueryBuilder::new("");
+        self.apply_query(&mut builder);
+        builder.push(comment::render(&self.comments));
         builder
     }
 
@@ -205,3 +319,39 @@ impl<T> QB<T> {
         self.build_query().sql().to_string()
     }
 }
+
+/// Checks that a limit or offset fits the signed 64-bit integer both drivers bind.
+///
+/// Panics if `value` is above `i64::MAX`
+pub(crate) fn checked_limit(value: u64, what: &str) -> u64 {
+    if i64::try_from(value).is_err() {
+        panic!("Cannot use {what} {value}. Values above {} are not supported.", i64::MAX);
+    }
+    value
+}
+
+/// Converts limit/offset into the signed 64-bit integer both drivers bind.
+pub(crate) fn bind_limit(value: u64) -> i64 {
+    i64::try_from(value).expect("limit and offset are checked by their setters")
+}
+
+fn apply_limit_offset(
+    builder: &mut QueryBuilder<'_, Driver>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+) {
+    if let Some(l) = limit {
+        builder.push(" LIMIT ");
+        builder.push_bind(bind_limit(l));
+    }
+
+    if let Some(o) = offset {
+        #[cfg(feature = "sqlite")]
+        if limit.is_none() {
+            builder.push(" LIMIT ");
+            builder.push_bind(-1_i64);
+        }
+        builder.push(" OFFSET ");
+        builder.push_bind(bind_limit(o));
+    }
+}
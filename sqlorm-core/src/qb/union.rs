@@ -0,0 +1,197 @@
+use std::fmt::Display;
+
+use sqlx::{Acquire, FromRow, QueryBuilder};
+
+use crate::comment;
+use crate::driver::{Driver, Row};
+use crate::qb::{OrderBySpec, QB, apply_limit_offset, checked_limit};
+use crate::traits::FromAliasedRow;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Set operator used to combine queries.
+pub enum UnionKind {
+    /// `UNION`, duplicate rows are removed.
+    Distinct,
+    /// `UNION ALL`, duplicate rows are kept.
+    All,
+}
+
+impl Display for UnionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnionKind::Distinct => f.write_str("UNION"),
+            UnionKind::All => f.write_str("UNION ALL"),
+        }
+    }
+}
+
+/// Compound query combining several [`QB`]s with `UNION` / `UNION ALL`.
+///
+/// All combined queries must project the same columns. Ordering, limit and offset
+/// of the combined queries are ignored, apply them to the union instead.
+///
+/// Example usage:
+/// ```rust ignore
+/// let users: Vec<User> = User::query()
+///     .filter(User::ID.lt(10))
+///     .union(User::query().filter(User::BIO.is_null()))
+///     .order_by(User::ID.desc())
+///     .limit(5)
+///     .fetch_all(&pool)
+///     .await?;
+/// ```
+pub struct UnionQB<T> {
+    /// The leftmost query.
+    pub first: QB<T>,
+    /// Queries appended to `first` with their set operator.
+    pub rest: Vec<(UnionKind, QB<T>)>,
+    pub order_by: Vec<OrderBySpec>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+impl<T> Clone for UnionQB<T> {
+    fn clone(&self) -> Self {
+        UnionQB {
+            first: self.first.clone(),
+            rest: self.rest.clone(),
+            order_by: self.order_by.clone(),
+            limit: self.limit,
+            offset: self.offset,
+        }
+    }
+}
+
+impl<T> QB<T> {
+    /// Combines this query with `other` using `UNION`.
+    pub fn union(self, other: QB<T>) -> UnionQB<T> {
+        UnionQB::new(self).union(other)
+    }
+
+    /// Combines this query with `other` using `UNION ALL`.
+    pub fn union_all(self, other: QB<T>) -> UnionQB<T> {
+        UnionQB::new(self).union_all(other)
+    }
+}
+
+impl<T> UnionQB<T> {
+    pub fn new(first: QB<T>) -> UnionQB<T> {
+        UnionQB {
+            first,
+            rest: Vec::new(),
+            order_by: Vec::new(),
+            limit: None,
+            offset: None,
+        }
+    }
+
+    pub fn union(mut self, other: QB<T>) -> Self {
+        self.rest.push((UnionKind::Distinct, other));
+        self
+    }
+
+    pub fn union_all(mut self, other: QB<T>) -> Self {
+        self.rest.push((UnionKind::All, other));
+        self
+    }
+
+    /// Orders the combined result. Columns are referenced by their projected names.
+    pub fn order_by(mut self, stmt: OrderBySpec) -> Self {
+        self.order_by.push(stmt);
+        self
+    }
+
+    /// Limits the combined result.
+    ///
+    /// Panics if `limit` is above `i64::MAX`
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(checked_limit(limit, "limit"));
+        self
+    }
+
+    /// Skips the first `offset` rows of the combined result.
+    ///
+    /// Panics if `offset` is above `i64::MAX`
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(checked_limit(offset, "offset"));
+        self
+    }
+
+    /// Comments of the combined queries, rendered once after the union.
+    fn comments(&self) -> Vec<String> {
+        let rest = self.rest.iter().flat_map(|(_, qb)| &qb.comments);
+        self.first.comments.iter().chain(rest).cloned().collect()
+    }
+
+    pub fn build_query(&self) -> QueryBuilder<'static, Driver> {
+        let mut builder = QueryBuilder::new("");
+
+        self.first.apply_select(&mut builder);
+        for (kind, qb) in &self.rest {
+            builder.push(format!(" {} ", kind));
+            qb.apply_select(&mut builder);
+        }
+
+        if !self.order_by.is_empty() {
+            builder.push(" ORDER BY ");
+            for (i, spec) in self.order_by.iter().enumerate() {
+                if i > 0 {
+                    builder.push(", ");
+                }
+                builder.push(spec.to_aliased_sql());
+            }
+        }
+
+        apply_limit_offset(&mut builder, self.limit, self.offset);
+        builder.push(comment::render(&self.comments()));
+
+        builder
+    }
+
+    pub fn to_sql(&self) -> String {
+        self.build_query().sql().to_string()
+    }
+
+    /// Executes the union and maps rows using `FromAliasedRow`.
+    ///
+    /// Relations requested on the combined queries are not loaded.
+    pub async fn fetch_all<'a, A: Send + Acquire<'a, Database = Driver>>(
+        self,
+        acquirer: A,
+    ) -> sqlx::Result<Vec<T>>
+    where
+        T: FromAliasedRow + Default,
+    {
+        let mut conn = acquirer.acquire().await?;
+        let timer = self.slow_query_timer();
+        let rows = self
+            .build_query()
+            .build()
+            .persistent(self.first.persistent)
+            .fetch_all(&mut *conn)
+            .await;
+        timer.finish();
+        rows?.iter().map(T::from_aliased_row).collect()
+    }
+
+    /// Executes the union and maps rows using `sqlx::FromRow`, e.g. into tuples
+    /// produced by [`QB::select`].
+    pub async fn fetch_all_as<'a, A: Send + Acquire<'a, Database = Driver>>(
+        self,
+        acquirer: A,
+    ) -> sqlx::Result<Vec<T>>
+    where
+        T: for<'r> FromRow<'r, Row>,
+    {
+        let mut conn = acquirer.acquire().await?;
+        let timer = self.slow_query_timer();
+        let rows = self
+            .build_query()
+            .build()
+            .persistent(self.first.persistent)
+            .fetch_all(&mut *conn)
+            .await;
+        timer.finish();
+        rows?.iter().map(T::from_row).collect()
+    }
+}
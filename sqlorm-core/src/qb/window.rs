@@ -0,0 +1,132 @@
+//! Window functions usable in select projections.
+//!
+//! Example usage:
+//! ```rust ignore
+//! use sqlorm::window::row_number;
+//!
+//! let rows: Vec<(Uuid, i64, i64)> = Donation::query()
+//!     .select((Donation::ID, Donation::JAR_ID))
+//!     .select_expr::<i64>(
+//!         row_number().over(Donation::JAR_ID, vec![Donation::CREATED_AT.desc()]),
+//!         "rn",
+//!     )
+//!     .fetch_all_as(&pool)
+//!     .await?;
+//! ```
+
+use std::fmt::Display;
+
+use crate::qb::{Column, OrderBySpec};
+use crate::selectable::Selectable;
+
+/// Window function call which is not yet bound to a window.
+#[derive(Clone, Debug)]
+pub struct WindowFn {
+    call: String,
+}
+
+/// Window function call with its `OVER (...)` clause.
+#[derive(Clone, Debug)]
+pub struct WindowExpr {
+    sql: String,
+}
+
+fn aggregate<T, W>(func: &str, column: Column<T, W>) -> WindowFn {
+    WindowFn {
+        call: format!("{}({}.{})", func, column.table_alias, column.name),
+    }
+}
+
+/// `ROW_NUMBER()`
+pub fn row_number() -> WindowFn {
+    WindowFn {
+        call: "ROW_NUMBER()".to_string(),
+    }
+}
+
+/// `RANK()`
+pub fn rank() -> WindowFn {
+    WindowFn {
+        call: "RANK()".to_string(),
+    }
+}
+
+/// `DENSE_RANK()`
+pub fn dense_rank() -> WindowFn {
+    WindowFn {
+        call: "DENSE_RANK()".to_string(),
+    }
+}
+
+/// `COUNT(*)`
+pub fn count() -> WindowFn {
+    WindowFn {
+        call: "COUNT(*)".to_string(),
+    }
+}
+
+/// `SUM(column)`
+pub fn sum<T, W>(column: Column<T, W>) -> WindowFn {
+    aggregate("SUM", column)
+}
+
+/// `AVG(column)`
+pub fn avg<T, W>(column: Column<T, W>) -> WindowFn {
+    aggregate("AVG", column)
+}
+
+/// `MIN(column)`
+pub fn min<T, W>(column: Column<T, W>) -> WindowFn {
+    aggregate("MIN", column)
+}
+
+/// `MAX(column)`
+pub fn max<T, W>(column: Column<T, W>) -> WindowFn {
+    aggregate("MAX", column)
+}
+
+impl WindowFn {
+    /// Renders `fn() OVER (PARTITION BY ... ORDER BY ...)`.
+    ///
+    /// `order_by` may be empty.
+    pub fn over(self, partition_by: impl Selectable, order_by: Vec<OrderBySpec>) -> WindowExpr {
+        self.render(&partition_by.collect_qualified(), &order_by)
+    }
+
+    /// Renders `fn() OVER (ORDER BY ...)`, treating the whole result as one partition.
+    pub fn over_all(self, order_by: Vec<OrderBySpec>) -> WindowExpr {
+        self.render(&[], &order_by)
+    }
+
+    fn render(self, partition_by: &[String], order_by: &[OrderBySpec]) -> WindowExpr {
+        let mut window = Vec::new();
+
+        if !partition_by.is_empty() {
+            window.push(format!("PARTITION BY {}", partition_by.join(", ")));
+        }
+
+        if !order_by.is_empty() {
+            let specs: Vec<String> = order_by
+                .iter()
+                .map(OrderBySpec::to_sql)
+                .collect();
+            window.push(format!("ORDER BY {}", specs.join(", ")));
+        }
+
+        WindowExpr {
+            sql: format!("{} OVER ({})", self.call, window.join(" ")),
+        }
+    }
+}
+
+impl Display for WindowExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.sql)
+    }
+}
+
+impl From<WindowExpr> for String {
+    fn from(expr: WindowExpr) -> Self {
+        expr.sql
+    }
+}
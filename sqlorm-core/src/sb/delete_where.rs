@@ -0,0 +1,135 @@
+use std::marker::PhantomData;
+
+use sqlx::{Acquire, QueryBuilder};
+
+use crate::comment;
+use crate::driver::Driver;
+use crate::qb::{BindValue, interpolate_debug};
+use crate::sb::push_where;
+use crate::slow_query::Timer;
+use crate::{Column, Condition, TableInfo, with_quotes};
+
+/// Statement builder for deleting every row matching the filters, without loading entities.
+///
+/// Created with the generated `Entity::delete_where()`. For entities with a `deleted_at`
+/// timestamp the rows are soft deleted, i.e. `deleted_at` is set on rows not deleted yet.
+///
+/// Example usage:
+/// ```rust ignore
+/// let affected: u64 = User::delete_where()
+///     .filter(User::CREATED_AT.lt(cutoff))
+///     .execute(&pool)
+///     .await?;
+/// ```
+pub struct DeleteWhere<T> {
+    /// Base table information.
+    pub base: TableInfo,
+    /// WHERE clause conditions combined with AND. Every row is deleted when empty.
+    pub filters: Vec<Condition>,
+    /// Assignment marking rows as deleted, e.g. `deleted_at = ?`. Hard delete when `None`.
+    pub soft_delete: Option<Condition>,
+    /// Whether the statement is cached as a prepared statement.
+    pub persistent: bool,
+    /// Comments rendered after the statement, see [`QB::comment`](crate::QB::comment).
+    pub comments: Vec<String>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> DeleteWhere<T> {
+    pub fn new(base: TableInfo) -> DeleteWhere<T> {
+        DeleteWhere {
+            base,
+            filters: Vec::new(),
+            soft_delete: None,
+            persistent: true,
+            comments: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Executes the statement without caching it as a server-side prepared statement,
+    /// see [`QB::not_persistent`](crate::QB::not_persistent).
+    pub fn not_persistent(mut self) -> Self {
+        self.persistent = false;
+        self
+    }
+
+    /// Tags the statement with a trailing `/* comment */`, see [`QB::comment`](crate::QB::comment).
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comments.push(comment.into());
+        self
+    }
+
+    /// Turns the statement into an update setting `column` to `value` on rows where it is `NULL`.
+    pub fn soft_delete<C>(mut self, column: Column<Option<C>>, value: C) -> Self
+    where
+        C: BindValue + Clone,
+        Option<C>: BindValue + Clone,
+    {
+        self.soft_delete = Some(Condition::new(format!("{} = ?", column.name), value));
+        self.filter(column.is_null())
+    }
+
+    /// Turns the statement into an update setting the boolean `column` on rows where it is unset.
+    pub fn soft_delete_flag(mut self, column: Column<bool>) -> Self {
+        self.soft_delete = Some(Condition::new(format!("{} = ?", column.name), true));
+        self.filter(column.eq(false))
+    }
+
+    pub fn filter(mut self, cond: Condition) -> Self {
+        self.filters.push(cond);
+        self
+    }
+
+    pub fn build_query(&self) -> QueryBuilder<'static, Driver> {
+        let table = format!("{} AS {}", with_quotes(self.base.name), self.base.alias);
+
+        let mut builder = match &self.soft_delete {
+            Some(assignment) => {
+                let mut builder = QueryBuilder::new(format!("UPDATE {} SET ", table));
+                assignment.push_to(&mut builder);
+                builder
+            }
+            None => QueryBuilder::new(format!("DELETE FROM {}", table)),
+        };
+
+        push_where(&mut builder, &self.filters);
+        builder.push(comment::render(&self.comments));
+
+        builder
+    }
+
+    pub fn to_sql(&self) -> String {
+        self.build_query().sql().to_string()
+    }
+
+    /// Renders the statement with bound values interpolated. **For logging only.**
+    pub fn debug_sql(&self) -> String {
+        interpolate_debug(&self.to_sql(), &self.debug_values())
+    }
+
+    fn debug_values(&self) -> Vec<String> {
+        self.soft_delete
+            .iter()
+            .chain(&self.filters)
+            .flat_map(|cond| cond.values.iter().map(|v| v.debug_value()))
+            .collect()
+    }
+
+    /// Executes the delete and returns the number of affected rows.
+    pub async fn execute<'a, A>(self, acquirer: A) -> sqlx::Result<u64>
+    where
+        A: Send + Acquire<'a, Database = Driver>,
+    {
+        let mut conn = acquirer.acquire().await?;
+        let timer = Timer::start(|| (self.to_sql(), self.debug_values()));
+        let result = self
+            .build_query()
+            .build()
+            .persistent(self.persistent)
+            .execute(&mut *conn)
+            .await;
+        timer.finish();
+        Ok(result?.rows_affected())
+    }
+}
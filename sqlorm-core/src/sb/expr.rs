@@ -0,0 +1,104 @@
+use crate::Column;
+use crate::qb::BindValue;
+use crate::qb::condition::AnyValue;
+
+/// SQL expression assigned to a column in an update, e.g. `total_donations + 1`.
+///
+/// Lets counters be changed atomically in SQL instead of read-modify-write.
+///
+/// Example usage:
+/// ```rust ignore
+/// jar.update()
+///     .set_expr(Jar::TOTAL_DONATIONS, Expr::col(Jar::TOTAL_DONATIONS).add(1))
+///     .execute(&pool)
+///     .await?;
+/// ```
+#[derive(Clone)]
+pub struct Expr {
+    /// SQL fragment with `?` placeholders for `values`.
+    pub sql: String,
+    /// Values bound in place of the placeholders.
+    pub values: Vec<Box<dyn AnyValue>>,
+}
+
+impl Expr {
+    /// References the current value of `column`.
+    pub fn col<T, W>(column: Column<T, W>) -> Expr {
+        Expr::raw(column.name)
+    }
+
+    /// References `column` qualified with its table alias, e.g. a column of the table
+    /// joined with [`UpdateWhere::from`](crate::UpdateWhere::from).
+    pub fn qualified<T, W>(column: Column<T, W>) -> Expr {
+        Expr::raw(format!("{}.{}", column.table_alias, column.name))
+    }
+
+    /// A bound value.
+    pub fn value<T: BindValue + Clone>(val: T) -> Expr {
+        Expr {
+            sql: "?".to_string(),
+            values: vec![Box::new(val)],
+        }
+    }
+
+    /// Raw SQL without bound values. Never pass user input here.
+    pub fn raw(sql: impl Into<String>) -> Expr {
+        Expr {
+            sql: sql.into(),
+            values: Vec::new(),
+        }
+    }
+
+    /// `(self + val)`
+    #[allow(clippy::should_implement_trait)]
+    pub fn add<T: BindValue + Clone>(self, val: T) -> Expr {
+        self.binary("+", val)
+    }
+
+    /// `(self - val)`
+    #[allow(clippy::should_implement_trait)]
+    pub fn sub<T: BindValue + Clone>(self, val: T) -> Expr {
+        self.binary("-", val)
+    }
+
+    /// `(self * val)`
+    #[allow(clippy::should_implement_trait)]
+    pub fn mul<T: BindValue + Clone>(self, val: T) -> Expr {
+        self.binary("*", val)
+    }
+
+    /// `(self / val)`
+    #[allow(clippy::should_implement_trait)]
+    pub fn div<T: BindValue + Clone>(self, val: T) -> Expr {
+        self.binary("/", val)
+    }
+
+    fn binary<T: BindValue + Clone>(mut self, op: &str, val: T) -> Expr {
+        self.sql = format!("({} {} ?)", self.sql, op);
+        self.values.push(Box::new(val));
+        self
+    }
+
+    /// Renders the expression with driver placeholders, numbering PostgreSQL
+    /// placeholders after `bound` already bound values. `bound` is advanced.
+    pub fn render(&self, bound: &mut usize) -> String {
+        #[cfg(feature = "postgres")]
+        {
+            let mut out = String::with_capacity(self.sql.len());
+            for c in self.sql.chars() {
+                if c == '?' {
+                    *bound += 1;
+                    out.push_str(&format!("${}", bound));
+                } else {
+                    out.push(c);
+                }
+            }
+            out
+        }
+        #[cfg(feature = "sqlite")]
+        {
+            *bound += self.values.len();
+            self.sql.clone()
+        }
+    }
+}
@@ -1,4 +1,42 @@
-use crate::{Condition, TableInfo, selectable::Selectable};
+use sqlx::QueryBuilder;
+
+use crate::driver::Driver;
+use crate::qb::BindValue;
+use crate::qb::condition::AnyValue;
+use crate::{Column, Condition, TableInfo, selectable::Updatable};
+
+mod delete_where;
+mod expr;
+mod update_where;
+mod upsert_many;
+pub use delete_where::DeleteWhere;
+pub use expr::Expr;
+pub use update_where::{UpdateFrom, UpdateWhere};
+pub use upsert_many::UpsertMany;
+
+/// Pushes `WHERE` clause of `filters` combined with AND, if any.
+///
+/// Combined conditions are parenthesized, so an `or()` condition doesn't escape the AND.
+pub(crate) fn push_where(builder: &mut QueryBuilder<'static, Driver>, filters: &[Condition]) {
+    if filters.is_empty() {
+        return;
+    }
+
+    builder.push(" WHERE ");
+    let grouped = filters.len() > 1;
+    for (i, cond) in filters.iter().enumerate() {
+        if i > 0 {
+            builder.push(" AND ");
+        }
+        if grouped {
+            builder.push("(");
+        }
+        cond.push_to(builder);
+        if grouped {
+            builder.push(")");
+        }
+    }
+}
 
 pub struct Update;
 pub struct Delete;
@@ -10,8 +48,18 @@ pub struct SB<T, Stage> {
     pub fields: Option<Vec<&'static str>>,
     /// WHERE clause conditions combined with AND.
     pub filters: Vec<Condition>,
+    /// Columns assigned SQL expressions instead of entity values.
+    pub exprs: Vec<(&'static str, Expr)>,
+    /// Raw assignments such as `title = title || ?`, rendered as is.
+    pub raw_sets: Vec<Expr>,
     /// The entity to operate on
     pub entity: T,
+    /// Whether the statement is cached as a prepared statement.
+    pub persistent: bool,
+    /// Comments rendered after the statement, see [`SB::comment`].
+    pub comments: Vec<String>,
+    /// Whether the entity is validated before the update, see [`SB::unchecked`].
+    pub validate: bool,
     _marker: std::marker::PhantomData<Stage>,
 }
 
@@ -20,20 +68,113 @@ impl<T, Stage> SB<T, Stage> {
         SB {
             base,
             filters: Vec::new(),
+            exprs: Vec::new(),
+            raw_sets: Vec::new(),
             fields: None,
             entity,
+            persistent: true,
+            comments: Vec::new(),
+            validate: true,
             _marker: std::marker::PhantomData,
         }
     }
+
+    /// Tags the statement with a trailing `/* comment */`, see [`QB::comment`](crate::QB::comment).
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comments.push(comment.into());
+        self
+    }
 }
 impl<T> SB<T, Update> {
-    pub fn columns(mut self, fields: impl Selectable) -> Self {
+    /// Restricts the update to `fields`. `#[sql(immutable)]` columns are rejected at compile time.
+    pub fn columns(mut self, fields: impl Updatable) -> Self {
         self.fields = Some(fields.collect());
         self
     }
 
+    /// Executes the update without caching it as a server-side prepared statement,
+    /// see [`QB::not_persistent`](crate::QB::not_persistent).
+    ///
+    /// Updates restricted with `columns(...)` render different SQL per column set, so
+    /// rarely repeated combinations are good candidates.
+    pub fn not_persistent(mut self) -> Self {
+        self.persistent = false;
+        self
+    }
+
+    /// Skips the validation of entities with `#[sql(validate(...))]` fields or declared with
+    /// `#[table(validate)]`.
+    pub fn unchecked(mut self) -> Self {
+        self.validate = false;
+        self
+    }
+
     pub fn filter(mut self, cond: Condition) -> Self {
         self.filters.push(cond);
         self
     }
+
+    /// Assigns `expr` to `column` instead of the entity's value.
+    ///
+    /// The entity field is not refreshed with the resulting value.
+    pub fn set_expr<C>(mut self, column: Column<C>, expr: Expr) -> Self {
+        self.exprs.retain(|(name, _)| *name != column.name);
+        self.exprs.push((column.name, expr));
+        self
+    }
+
+    /// Sets `column` to `value` instead of the entity's value.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// jar.update()
+    ///     .columns((Jar::TITLE,))
+    ///     .set(Jar::HIDE_EARNINGS, true)
+    ///     .execute(&pool)
+    ///     .await?;
+    /// ```
+    pub fn set<C>(self, column: Column<C>, value: C) -> Self
+    where
+        C: BindValue + Clone + 'static,
+    {
+        self.set_expr(column, Expr::value(value))
+    }
+
+    /// Adds a raw assignment, with `?` placeholders bound to `binds` in order.
+    ///
+    /// The assigned column, i.e. the identifier before `=`, is no longer taken from the
+    /// entity. Never pass user input as `sql`.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// jar.update()
+    ///     .set_raw("title = title || ?", vec![" (archived)".to_string()])
+    ///     .execute(&pool)
+    ///     .await?;
+    /// ```
+    pub fn set_raw<V>(mut self, sql: impl Into<String>, binds: Vec<V>) -> Self
+    where
+        V: BindValue + Clone + 'static,
+    {
+        self.raw_sets.push(Expr {
+            sql: sql.into(),
+            values: binds
+                .into_iter()
+                .map(|v| Box::new(v) as Box<dyn AnyValue>)
+                .collect(),
+        });
+        self
+    }
+
+    /// Whether `column` is assigned by [`SB::set`], [`SB::set_expr`] or [`SB::set_raw`],
+    /// so the entity's value must not be written.
+    pub fn is_assigned(&self, column: &str) -> bool {
+        self.exprs.iter().any(|(name, _)| *name == column)
+            || self.raw_sets.iter().any(|raw| {
+                raw.sql
+                    .split('=')
+                    .next()
+                    .is_some_and(|target| target.trim().trim_matches('"') == column)
+            })
+    }
 }
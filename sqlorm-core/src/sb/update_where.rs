@@ -0,0 +1,294 @@
+use std::marker::PhantomData;
+
+use sqlx::{Acquire, QueryBuilder};
+
+use crate::comment;
+use crate::driver::Driver;
+use crate::qb::{BindValue, interpolate_debug};
+use crate::sb::{Expr, push_where};
+use crate::slow_query::Timer;
+use crate::{Column, Condition, Patch, QB, TableInfo, with_quotes};
+
+/// Statement builder for updating every row matching the filters, without loading entities.
+///
+/// Created with the generated `Entity::update_where()`.
+///
+/// Example usage:
+/// ```rust ignore
+/// let affected: u64 = User::update_where()
+///     .set(User::BIO, None::<String>)
+///     .filter(User::ID.in_(ids))
+///     .execute(&pool)
+///     .await?;
+/// ```
+pub struct UpdateWhere<T> {
+    /// Base table information.
+    pub base: TableInfo,
+    /// Assigned values as `(column, "?")`, at most one per column.
+    pub sets: Vec<(&'static str, Condition)>,
+    /// WHERE clause conditions combined with AND. Every row is updated when empty.
+    pub filters: Vec<Condition>,
+    /// Other table the assignments and filters may reference, see [`UpdateWhere::from`].
+    pub from: Option<UpdateFrom>,
+    /// Whether the statement is cached as a prepared statement.
+    pub persistent: bool,
+    /// Comments rendered after the statement, see [`QB::comment`](crate::QB::comment).
+    pub comments: Vec<String>,
+    _marker: PhantomData<T>,
+}
+
+/// Table referenced by an [`UpdateWhere`], with the conditions selecting its rows.
+#[derive(Clone)]
+pub struct UpdateFrom {
+    pub table: TableInfo,
+    /// Join condition followed by the filters of the source query.
+    pub filters: Vec<Condition>,
+}
+
+impl<T> UpdateWhere<T> {
+    pub fn new(base: TableInfo) -> UpdateWhere<T> {
+        UpdateWhere {
+            base,
+            sets: Vec::new(),
+            filters: Vec::new(),
+            from: None,
+            persistent: true,
+            comments: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Executes the statement without caching it as a server-side prepared statement,
+    /// see [`QB::not_persistent`](crate::QB::not_persistent).
+    pub fn not_persistent(mut self) -> Self {
+        self.persistent = false;
+        self
+    }
+
+    /// Tags the statement with a trailing `/* comment */`, see [`QB::comment`](crate::QB::comment).
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comments.push(comment.into());
+        self
+    }
+
+    /// Sets `column` to `value`, replacing a previous assignment of the same column.
+    pub fn set<C>(self, column: Column<C>, value: C) -> Self
+    where
+        C: BindValue + Clone,
+    {
+        self.assign(column.name, Condition::new("?".to_string(), value))
+    }
+
+    /// Assigns `expr` to `column`, e.g. `Expr::col(Jar::TOTAL_DONATIONS).add(1)`.
+    pub fn set_expr<C>(self, column: Column<C>, expr: Expr) -> Self {
+        let value = Condition {
+            sql: expr.sql,
+            values: expr.values,
+        };
+        self.assign(column.name, value)
+    }
+
+    /// Sets every field provided by `patch`, the generated `{Entity}Patch` struct.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// User::update_where()
+    ///     .set_patch(UserPatch {
+    ///         bio: Some(None),
+    ///         ..Default::default()
+    ///     })
+    ///     .filter(User::ID.eq(5))
+    ///     .execute(&pool)
+    ///     .await?;
+    /// ```
+    pub fn set_patch<P: Patch<Entity = T>>(self, patch: P) -> Self {
+        patch.assign(self)
+    }
+
+    pub fn filter(mut self, cond: Condition) -> Self {
+        self.filters.push(cond);
+        self
+    }
+
+    /// Updates only rows having a row of `source` matching `on`, so assignments can use
+    /// its columns through [`Expr::qualified`].
+    ///
+    /// Rendered as `UPDATE ... FROM` on PostgreSQL and as correlated subqueries on SQLite.
+    /// If several source rows match, the one used is unspecified.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// Jar::update_where()
+    ///     .set_expr(Jar::DESCRIPTION, Expr::qualified(Donation::NOTE))
+    ///     .from(
+    ///         Donation::query().filter(Donation::IS_PAYED.eq(true)),
+    ///         Jar::ID.eq_col(Donation::JAR_ID),
+    ///     )
+    ///     .execute(&pool)
+    ///     .await?;
+    /// ```
+    pub fn from<U>(mut self, source: QB<U>, on: Condition) -> Self {
+        let mut filters = vec![on];
+        if let Some(sql) = source.soft_delete_sql() {
+            filters.push(Condition::none(sql));
+        }
+        filters.extend(source.filters);
+
+        self.from = Some(UpdateFrom {
+            table: source.base,
+            filters,
+        });
+        self
+    }
+
+    pub(crate) fn assign(mut self, column: &'static str, value: Condition) -> Self {
+        match self.sets.iter_mut().find(|(name, _)| *name == column) {
+            Some((_, existing)) => *existing = value,
+            None => self.sets.push((column, value)),
+        }
+        self
+    }
+
+    /// Assignments and filters in rendering order, with the `from` source applied.
+    #[cfg(feature = "postgres")]
+    fn statement_parts(&self) -> (Vec<(&'static str, Condition)>, Vec<Condition>) {
+        let mut filters = self.filters.clone();
+        if let Some(from) = &self.from {
+            filters.extend(from.filters.iter().cloned());
+        }
+        (self.sets.clone(), filters)
+    }
+
+    /// Assignments and filters in rendering order, with the `from` source applied.
+    ///
+    /// Each assignment reads from a correlated subquery on the source table, and rows
+    /// without a matching source row are skipped with `EXISTS`.
+    #[cfg(feature = "sqlite")]
+    fn statement_parts(&self) -> (Vec<(&'static str, Condition)>, Vec<Condition>) {
+        let Some(from) = &self.from else {
+            return (self.sets.clone(), self.filters.clone());
+        };
+
+        let source = format!("{} AS {}", with_quotes(from.table.name), from.table.alias);
+        let matching = and_all(&from.filters);
+
+        let sets = self
+            .sets
+            .iter()
+            .map(|(column, value)| {
+                let mut values = value.values.clone();
+                values.extend(matching.values.iter().cloned());
+                let subquery = Condition {
+                    sql: format!("(SELECT {} FROM {} WHERE {})", value.sql, source, matching.sql),
+                    values,
+                };
+                (*column, subquery)
+            })
+            .collect();
+
+        let mut filters = self.filters.clone();
+        filters.push(Condition {
+            sql: format!("EXISTS (SELECT 1 FROM {} WHERE {})", source, matching.sql),
+            values: matching.values,
+        });
+
+        (sets, filters)
+    }
+
+    pub fn build_query(&self) -> QueryBuilder<'static, Driver> {
+        let (sets, filters) = self.statement_parts();
+
+        let mut builder = QueryBuilder::new(format!(
+            "UPDATE {} AS {} SET ",
+            with_quotes(self.base.name),
+            self.base.alias
+        ));
+
+        for (i, (column, value)) in sets.iter().enumerate() {
+            if i > 0 {
+                builder.push(", ");
+            }
+            builder.push(format!("{} = ", column));
+            value.push_to(&mut builder);
+        }
+
+        #[cfg(feature = "postgres")]
+        if let Some(from) = &self.from {
+            builder.push(format!(
+                " FROM {} AS {}",
+                with_quotes(from.table.name),
+                from.table.alias
+            ));
+        }
+
+        push_where(&mut builder, &filters);
+        builder.push(comment::render(&self.comments));
+
+        builder
+    }
+
+    pub fn to_sql(&self) -> String {
+        self.build_query().sql().to_string()
+    }
+
+    /// Renders the statement with bound values interpolated. **For logging only.**
+    pub fn debug_sql(&self) -> String {
+        interpolate_debug(&self.to_sql(), &self.debug_values())
+    }
+
+    fn debug_values(&self) -> Vec<String> {
+        let (sets, filters) = self.statement_parts();
+        sets.iter()
+            .map(|(_, value)| value)
+            .chain(&filters)
+            .flat_map(|cond| cond.values.iter().map(|v| v.debug_value()))
+            .collect()
+    }
+
+    /// Executes the update and returns the number of affected rows.
+    ///
+    /// Does nothing if no column is set.
+    pub async fn execute<'a, A>(self, acquirer: A) -> sqlx::Result<u64>
+    where
+        A: Send + Acquire<'a, Database = Driver>,
+    {
+        if self.sets.is_empty() {
+            return Ok(0);
+        }
+
+        let mut conn = acquirer.acquire().await?;
+        let timer = Timer::start(|| (self.to_sql(), self.debug_values()));
+        let result = self
+            .build_query()
+            .build()
+            .persistent(self.persistent)
+            .execute(&mut *conn)
+            .await;
+        timer.finish();
+        Ok(result?.rows_affected())
+    }
+}
+
+/// Combines `conds` into a single condition joined with AND, parenthesized like
+/// [`push_where`].
+#[cfg(feature = "sqlite")]
+fn and_all(conds: &[Condition]) -> Condition {
+    let grouped = conds.len() > 1;
+    Condition {
+        sql: conds
+            .iter()
+            .map(|cond| {
+                if grouped {
+                    format!("({})", cond.sql)
+                } else {
+                    cond.sql.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" AND "),
+        values: conds
+            .iter()
+            .flat_map(|cond| cond.values.iter().cloned())
+            .collect(),
+    }
+}
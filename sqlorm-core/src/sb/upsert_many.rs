@@ -0,0 +1,174 @@
+use sqlx::{Acquire, FromRow, QueryBuilder};
+
+use crate::driver::{Connection, Driver, Row};
+use crate::slow_query::Timer;
+use crate::{Insertable, MAX_BIND_PARAMS, Selectable, Updatable, comment, observer, with_quotes};
+
+/// Statement builder inserting many entities at once, updating rows that conflict.
+///
+/// Created with the generated `Entity::upsert_many()`. Without a conflict target conflicting
+/// rows are skipped, and without update columns they are left untouched.
+///
+/// Entities are validated and go through the `insert` hooks and observers like with
+/// `insert_many()`. The database does not tell inserted and updated rows apart, so updated
+/// rows are reported as inserted too.
+///
+/// Example usage:
+/// ```rust ignore
+/// let jars: Vec<Jar> = Jar::upsert_many(jars)
+///     .on_conflict(Jar::ALIAS)
+///     .update((Jar::TITLE, Jar::GOAL))
+///     .execute(&pool)
+///     .await?;
+/// ```
+pub struct UpsertMany<T> {
+    /// Entities to insert.
+    pub entities: Vec<T>,
+    /// Columns of the unique constraint detecting conflicts.
+    pub conflict_target: Vec<&'static str>,
+    /// Columns overwritten with the new values on conflict.
+    pub updates: Vec<&'static str>,
+    /// Whether the statements are cached as prepared statements.
+    pub persistent: bool,
+    /// Comments rendered after the statements, see [`QB::comment`](crate::QB::comment).
+    pub comments: Vec<String>,
+}
+
+impl<T: Insertable> UpsertMany<T> {
+    pub fn new(entities: Vec<T>) -> UpsertMany<T> {
+        UpsertMany {
+            entities,
+            conflict_target: Vec::new(),
+            updates: Vec::new(),
+            persistent: true,
+            comments: Vec::new(),
+        }
+    }
+
+    /// Executes the statements without caching them as server-side prepared statements,
+    /// see [`QB::not_persistent`](crate::QB::not_persistent).
+    pub fn not_persistent(mut self) -> Self {
+        self.persistent = false;
+        self
+    }
+
+    /// Tags the statements with a trailing `/* comment */`, see [`QB::comment`](crate::QB::comment).
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comments.push(comment.into());
+        self
+    }
+
+    /// Sets the unique columns detecting conflicts, e.g. `Jar::ALIAS`.
+    pub fn on_conflict(mut self, columns: impl Selectable) -> Self {
+        self.conflict_target = columns.collect();
+        self
+    }
+
+    /// Sets the columns overwritten on conflict, e.g. `(Jar::TITLE, Jar::GOAL)`.
+    ///
+    /// Requires the conflict target set with [`UpsertMany::on_conflict`].
+    pub fn update(mut self, columns: impl Updatable) -> Self {
+        self.updates = columns.collect();
+        self
+    }
+
+    /// Renders the `ON CONFLICT` clause.
+    fn conflict_clause(&self) -> String {
+        let target = if self.conflict_target.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", self.conflict_target.join(", "))
+        };
+
+        if self.updates.is_empty() {
+            return format!(" ON CONFLICT{} DO NOTHING", target);
+        }
+
+        let assignments = self
+            .updates
+            .iter()
+            .map(|column| format!("{0} = EXCLUDED.{0}", column))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(" ON CONFLICT{} DO UPDATE SET {}", target, assignments)
+    }
+
+    /// Inserts the entities in batches below the driver's bind parameter limit, in a single
+    /// transaction, and returns the inserted or updated rows. Rows skipped by `DO NOTHING`
+    /// are not returned.
+    ///
+    /// Fails with `sqlx::Error::InvalidArgument` if update columns are set without a conflict
+    /// target.
+    pub async fn execute<'a, A>(self, acquirer: A) -> sqlx::Result<Vec<T>>
+    where
+        A: Send + Acquire<'a, Database = Driver>,
+        T: for<'r> FromRow<'r, Row> + Send + Unpin,
+    {
+        if !self.updates.is_empty() && self.conflict_target.is_empty() {
+            return Err(sqlx::Error::InvalidArgument(
+                "upsert_many: update() requires the conflict target set with on_conflict()"
+                    .to_string(),
+            ));
+        }
+        if self.entities.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut tx = acquirer.begin().await?;
+        let (saved, committed) = observer::collect(self.write(&mut tx)).await;
+        let saved = saved?;
+        tx.commit().await?;
+        committed.defer();
+        Ok(saved)
+    }
+
+    /// Writes the batches on `conn`, running the hooks of every entity.
+    async fn write(self, conn: &mut Connection) -> sqlx::Result<Vec<T>>
+    where
+        T: for<'r> FromRow<'r, Row> + Send + Unpin,
+    {
+        let columns = T::insert_columns();
+        let conflict_clause = self.conflict_clause();
+        let prefix = format!(
+            "INSERT INTO {} ({}) ",
+            with_quotes(T::TABLE_NAME),
+            columns.join(", ")
+        );
+
+        let mut entities = self.entities;
+        for entity in &mut entities {
+            entity.prepare_insert();
+        }
+        for entity in &mut entities {
+            entity.before_bulk_insert(conn).await?;
+        }
+
+        let batch_size = (MAX_BIND_PARAMS / columns.len().max(1)).max(1);
+        let mut saved = Vec::with_capacity(entities.len());
+
+        for batch in entities.chunks(batch_size) {
+            let mut builder = QueryBuilder::<Driver>::new(&prefix);
+            builder.push_values(batch, |mut row, entity| {
+                entity.push_insert_values(&mut row);
+            });
+            builder.push(&conflict_clause);
+            builder.push(" RETURNING *");
+            builder.push(comment::render(&self.comments));
+
+            let timer = Timer::start(|| (builder.sql().to_string(), Vec::new()));
+            let rows = builder
+                .build_query_as::<T>()
+                .persistent(self.persistent)
+                .fetch_all(&mut *conn)
+                .await;
+            timer.finish();
+            saved.extend(rows?);
+        }
+
+        for entity in &mut saved {
+            entity.after_bulk_insert(conn).await?;
+        }
+
+        Ok(saved)
+    }
+}
@@ -0,0 +1,513 @@
+//! Table schemas of entities and the DDL generated from them.
+//!
+//! Every `#[table]` entity implements [`Schema`], describing its columns with the SQL types
+//! sqlx maps their Rust types to, or `#[sql(column_type = "...")]` when set.
+//!
+//! Example usage:
+//! ```rust ignore
+//! use sqlorm::schema::{self, Schema};
+//!
+//! println!("{}", User::create_table_sql());
+//! schema::create_table::<User>(&pool).await?;
+//!
+//! // or, while prototyping, create whatever is missing
+//! schema::sync(&pool, &[User::schema(), Jar::schema()]).await?;
+//!
+//! // or, on startup, check the entity matches the database
+//! let mismatches = schema::verify::<User>(&pool).await?;
+//! assert!(mismatches.is_empty(), "{:?}", mismatches);
+//! ```
+
+use std::fmt::Display;
+
+use sqlx::{Acquire, TypeInfo};
+
+use crate::driver::{Driver, Pool};
+use crate::{Table, dialect, with_quotes};
+
+/// Schema of an entity's table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSchema {
+    /// Table name, schema-qualified when `#[table(schema = ...)]` is set.
+    pub name: &'static str,
+    pub columns: Vec<ColumnSchema>,
+    /// Secondary indexes declared with `index(...)` or `unique(...)`.
+    pub indexes: Vec<IndexSchema>,
+    /// Foreign keys of `belongs_to` relations.
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+/// Column of a [`TableSchema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSchema {
+    pub name: &'static str,
+    /// SQL type, e.g. `INT8` on PostgreSQL or `INTEGER` on SQLite.
+    pub sql_type: String,
+    pub nullable: bool,
+    pub primary_key: bool,
+    /// Whether the key is left to the database, i.e. a primary key without factory. Only
+    /// integer keys get a generator.
+    pub auto_increment: bool,
+    /// Whether the column is marked `#[sql(unique)]`.
+    pub unique: bool,
+    /// SQL default, e.g. `CURRENT_TIMESTAMP` for timestamps or a literal `#[sql(default = ...)]`.
+    pub default: Option<String>,
+}
+
+/// Index of a [`TableSchema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexSchema {
+    pub name: String,
+    pub columns: Vec<&'static str>,
+    pub unique: bool,
+}
+
+/// Foreign key of a [`TableSchema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForeignKey {
+    pub column: &'static str,
+    /// Referenced table, schema-qualified when set.
+    pub table: &'static str,
+    pub references: &'static str,
+}
+
+/// Column of an existing table, as reported by the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbColumn {
+    pub name: String,
+    /// Type as named by the database, e.g. `int8` on PostgreSQL or `INTEGER` on SQLite.
+    pub sql_type: String,
+    pub nullable: bool,
+    pub primary_key: bool,
+    /// Whether the column alone has a unique constraint.
+    pub unique: bool,
+}
+
+/// Foreign key of an existing table, as reported by the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbForeignKey {
+    pub column: String,
+    pub table: String,
+    pub references: String,
+}
+
+/// Difference between an entity and its table, found by [`verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaMismatch {
+    /// The table doesn't exist.
+    MissingTable { table: &'static str },
+    /// The entity has a column the table lacks.
+    MissingColumn { column: &'static str },
+    /// The column type differs, ignoring spelling variants such as `BIGINT` and `INT8`, or
+    /// by affinity on SQLite.
+    TypeMismatch {
+        column: &'static str,
+        expected: String,
+        found: String,
+    },
+    /// The column is nullable in only one of the entity and the table.
+    NullableMismatch {
+        column: &'static str,
+        expected: bool,
+        found: bool,
+    },
+}
+
+impl Display for SchemaMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaMismatch::MissingTable { table } => write!(f, "table \"{}\" is missing", table),
+            SchemaMismatch::MissingColumn { column } => {
+                write!(f, "column \"{}\" is missing", column)
+            }
+            SchemaMismatch::TypeMismatch {
+                column,
+                expected,
+                found,
+            } => write!(
+                f,
+                "column \"{}\" has type {}, expected {}",
+                column, found, expected
+            ),
+            SchemaMismatch::NullableMismatch {
+                column, expected, ..
+            } => write!(
+                f,
+                "column \"{}\" is {}, expected {}",
+                column,
+                if *expected { "NOT NULL" } else { "nullable" },
+                if *expected { "nullable" } else { "NOT NULL" }
+            ),
+        }
+    }
+}
+
+/// Table schema of an entity, implemented by `#[table]`.
+pub trait Schema: Table {
+    fn schema() -> TableSchema;
+
+    /// `CREATE TABLE IF NOT EXISTS` statement of the entity, followed by its indexes.
+    fn create_table_sql() -> String {
+        Self::schema().create_table_sql()
+    }
+}
+
+impl ColumnSchema {
+    /// `NOT NULL` column named `name` of the SQL type sqlx maps `T` to, as in generated DDL.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// let bio = ColumnSchema::new::<String>("bio").nullable();
+    /// let visits = ColumnSchema::new::<i64>("visits").default("0");
+    /// ```
+    pub fn new<T: sqlx::Type<Driver>>(name: &'static str) -> Self {
+        ColumnSchema {
+            name,
+            sql_type: T::type_info().name().to_string(),
+            nullable: false,
+            primary_key: false,
+            auto_increment: false,
+            unique: false,
+            default: None,
+        }
+    }
+
+    /// Allows `NULL` values.
+    pub fn nullable(mut self) -> Self {
+        self.nullable = true;
+        self
+    }
+
+    /// Adds a `UNIQUE` constraint.
+    pub fn unique(mut self) -> Self {
+        self.unique = true;
+        self
+    }
+
+    /// Sets the SQL default, e.g. `0`, `'draft'` or `CURRENT_TIMESTAMP`.
+    pub fn default(mut self, sql: impl Into<String>) -> Self {
+        self.default = Some(sql.into());
+        self
+    }
+
+    /// Column definition as used in `CREATE TABLE` and `ADD COLUMN`.
+    pub fn definition(&self) -> String {
+        let mut sql = format!("{} ", with_quotes(self.name));
+        if self.primary_key && self.auto_increment {
+            sql.push_str(&dialect::auto_increment_pk(&self.sql_type));
+            return sql;
+        }
+
+        sql.push_str(&self.sql_type);
+        if self.primary_key {
+            sql.push_str(" PRIMARY KEY");
+        } else if !self.nullable {
+            sql.push_str(" NOT NULL");
+        }
+        if self.unique && !self.primary_key {
+            sql.push_str(" UNIQUE");
+        }
+        if let Some(default) = &self.default {
+            sql.push_str(" DEFAULT ");
+            sql.push_str(default);
+        }
+        sql
+    }
+}
+
+impl IndexSchema {
+    /// `CREATE INDEX IF NOT EXISTS` statement of the index on `table`.
+    pub fn create_sql(&self, table: &str) -> String {
+        let columns: Vec<String> = self.columns.iter().map(|c| with_quotes(c)).collect();
+        format!(
+            "CREATE {}INDEX IF NOT EXISTS {} ON {} ({})",
+            if self.unique { "UNIQUE " } else { "" },
+            with_quotes(&self.name),
+            with_quotes(table),
+            columns.join(", ")
+        )
+    }
+}
+
+impl TableSchema {
+    /// `CREATE TABLE IF NOT EXISTS` statement, followed by the indexes, separated by `;`.
+    pub fn create_table_sql(&self) -> String {
+        let mut definitions: Vec<String> = self.columns.iter().map(|c| c.definition()).collect();
+        definitions.extend(self.foreign_keys.iter().map(|fk| {
+            format!(
+                "FOREIGN KEY ({}) REFERENCES {}({})",
+                with_quotes(fk.column),
+                with_quotes(fk.table),
+                with_quotes(fk.references)
+            )
+        }));
+
+        let mut statements = vec![format!(
+            "CREATE TABLE IF NOT EXISTS {} (\n    {}\n)",
+            with_quotes(self.name),
+            definitions.join(",\n    ")
+        )];
+        statements.extend(self.indexes.iter().map(|index| index.create_sql(self.name)));
+        statements.join(";\n") + ";"
+    }
+}
+
+/// Creates the table of `T` and its indexes, unless they already exist.
+pub async fn create_table<T: Schema>(pool: &Pool) -> sqlx::Result<()> {
+    sqlx::raw_sql(&T::create_table_sql()).execute(pool).await?;
+    Ok(())
+}
+
+/// Columns of the existing table `name`, possibly schema-qualified, in declaration order.
+///
+/// Returns no columns if the table doesn't exist.
+pub async fn table_columns(pool: &Pool, name: &str) -> sqlx::Result<Vec<DbColumn>> {
+    let (schema, table) = split_name(name);
+    let rows: Vec<(String, String, bool, bool, bool)> =
+        sqlx::query_as(dialect::TABLE_COLUMNS)
+            .bind(schema)
+            .bind(table)
+            .fetch_all(pool)
+            .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(name, sql_type, nullable, primary_key, unique)| DbColumn {
+            name,
+            sql_type,
+            nullable,
+            primary_key,
+            unique,
+        })
+        .collect())
+}
+
+/// Foreign keys of the existing table `name`, possibly schema-qualified.
+pub async fn table_foreign_keys(pool: &Pool, name: &str) -> sqlx::Result<Vec<DbForeignKey>> {
+    let (schema, table) = split_name(name);
+    let rows: Vec<(String, String, String)> = sqlx::query_as(dialect::FOREIGN_KEYS)
+        .bind(schema)
+        .bind(table)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(column, table, references)| DbForeignKey {
+            column,
+            table,
+            references,
+        })
+        .collect())
+}
+
+/// `(schema, table)` of a possibly schema-qualified table name.
+fn split_name(name: &str) -> (Option<&str>, &str) {
+    match name.split_once('.') {
+        Some((schema, table)) => (Some(schema), table),
+        None => (None, name),
+    }
+}
+
+/// Statements creating the missing tables and adding the missing columns of `schemas`, in
+/// order, without executing them. See [`sync`].
+pub async fn diff(pool: &Pool, schemas: &[TableSchema]) -> sqlx::Result<Vec<String>> {
+    let mut statements = Vec::new();
+    for schema in schemas {
+        let existing = table_columns(pool, schema.name).await?;
+        if existing.is_empty() {
+            statements.push(schema.create_table_sql());
+            continue;
+        }
+
+        for column in &schema.columns {
+            if existing.iter().any(|c| c.name == column.name) {
+                continue;
+            }
+            statements.push(format!(
+                "ALTER TABLE {} ADD COLUMN {};",
+                with_quotes(schema.name),
+                column.definition()
+            ));
+        }
+    }
+    Ok(statements)
+}
+
+/// Creates missing tables and adds missing columns and indexes, without ever dropping or
+/// altering what exists. Meant for prototyping and tests, use migrations in production.
+///
+/// Tables are synced in the given order, so referenced tables must come first. Added columns
+/// get no foreign key and are subject to the database's `ADD COLUMN` restrictions: `NOT NULL`
+/// columns without default only fit empty tables, and SQLite rejects `UNIQUE` columns and
+/// `CURRENT_TIMESTAMP` defaults.
+pub async fn sync(pool: &Pool, schemas: &[TableSchema]) -> sqlx::Result<()> {
+    for statement in diff(pool, schemas).await? {
+        sqlx::raw_sql(&statement).execute(pool).await?;
+    }
+    for schema in schemas {
+        for index in &schema.indexes {
+            sqlx::raw_sql(&index.create_sql(schema.name)).execute(pool).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Compares the table of `T` with the database, returning every difference found.
+///
+/// Meant to run on startup, turning a stale schema into a readable report instead of a decode
+/// error on the first query. Columns of the table unknown to the entity are not reported.
+/// Nullability of primary keys isn't checked, as SQLite reports `INTEGER PRIMARY KEY`
+/// columns as nullable.
+pub async fn verify<T: Schema>(pool: &Pool) -> sqlx::Result<Vec<SchemaMismatch>> {
+    verify_table(pool, &T::schema()).await
+}
+
+/// Compares `schema` with the database, see [`verify`].
+pub async fn verify_table(pool: &Pool, schema: &TableSchema) -> sqlx::Result<Vec<SchemaMismatch>> {
+    let existing = table_columns(pool, schema.name).await?;
+    if existing.is_empty() {
+        return Ok(vec![SchemaMismatch::MissingTable { table: schema.name }]);
+    }
+
+    let mut mismatches = Vec::new();
+    for column in &schema.columns {
+        let Some(found) = existing.iter().find(|c| c.name == column.name) else {
+            mismatches.push(SchemaMismatch::MissingColumn {
+                column: column.name,
+            });
+            continue;
+        };
+
+        let expected_type = dialect::normalize_type(&column.sql_type);
+        let found_type = dialect::normalize_type(&found.sql_type);
+        if expected_type != found_type {
+            mismatches.push(SchemaMismatch::TypeMismatch {
+                column: column.name,
+                expected: expected_type,
+                found: found_type,
+            });
+        }
+        if !column.primary_key && column.nullable != found.nullable {
+            mismatches.push(SchemaMismatch::NullableMismatch {
+                column: column.name,
+                expected: column.nullable,
+                found: found.nullable,
+            });
+        }
+    }
+    Ok(mismatches)
+}
+
+/// Starts altering the existing table `table`, possibly schema-qualified.
+///
+/// Example usage:
+/// ```rust ignore
+/// schema::alter_table("users")
+///     .add_column(ColumnSchema::new::<String>("nickname").nullable())
+///     .rename_column("bio", "about")
+///     .drop_column("legacy_flag")
+///     .execute(&pool)
+///     .await?;
+/// ```
+pub fn alter_table(table: impl Into<String>) -> AlterTable {
+    AlterTable {
+        table: table.into(),
+        statements: Vec::new(),
+    }
+}
+
+/// Changes of an existing table, created with [`alter_table`].
+///
+/// Every change is its own statement, as SQLite only allows one per `ALTER TABLE`.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct AlterTable {
+    table: String,
+    statements: Vec<String>,
+}
+
+impl AlterTable {
+    fn alter(mut self, change: String) -> Self {
+        self.statements
+            .push(format!("ALTER TABLE {} {}", with_quotes(&self.table), change));
+        self
+    }
+
+    /// Adds `column`, subject to the database's `ADD COLUMN` restrictions, see [`sync`].
+    pub fn add_column(self, column: ColumnSchema) -> Self {
+        let change = format!("ADD COLUMN {}", column.definition());
+        self.alter(change)
+    }
+
+    /// Drops the column `name`.
+    pub fn drop_column(self, name: &str) -> Self {
+        let change = format!("DROP COLUMN {}", with_quotes(name));
+        self.alter(change)
+    }
+
+    /// Renames the column `from` to `to`.
+    pub fn rename_column(self, from: &str, to: &str) -> Self {
+        let change = format!("RENAME COLUMN {} TO {}", with_quotes(from), with_quotes(to));
+        self.alter(change)
+    }
+
+    /// Creates an index over `columns`, named like the indexes of generated DDL.
+    pub fn add_index(mut self, columns: &[&'static str], unique: bool) -> Self {
+        let raw = self.table.rsplit('.').next().unwrap_or(&self.table);
+        let index = IndexSchema {
+            name: format!(
+                "{}_{}_{}",
+                raw,
+                columns.join("_"),
+                if unique { "key" } else { "idx" }
+            ),
+            columns: columns.to_vec(),
+            unique,
+        };
+        self.statements.push(index.create_sql(&self.table));
+        self
+    }
+
+    /// Renames the table to `name`, unqualified. Later changes apply to the renamed table.
+    pub fn rename_to(mut self, name: &str) -> Self {
+        let change = format!("RENAME TO {}", with_quotes(name));
+        self = self.alter(change);
+        self.table = match self.table.split_once('.') {
+            Some((schema, _)) => format!("{}.{}", schema, name),
+            None => name.to_string(),
+        };
+        self
+    }
+
+    /// The statements making the changes, in order.
+    pub fn to_sql(&self) -> Vec<String> {
+        self.statements.clone()
+    }
+
+    /// Executes the changes in order. Pass a transaction to apply them atomically where the
+    /// database supports transactional DDL.
+    pub async fn execute<'a, A>(self, acquirer: A) -> sqlx::Result<()>
+    where
+        A: Send + Acquire<'a, Database = Driver>,
+    {
+        let mut conn = acquirer.acquire().await?;
+        for statement in &self.statements {
+            sqlx::raw_sql(statement).execute(&mut *conn).await?;
+        }
+        Ok(())
+    }
+}
+
+/// `DROP TABLE IF EXISTS` statement of `table`, possibly schema-qualified.
+pub fn drop_table_sql(table: &str) -> String {
+    format!("DROP TABLE IF EXISTS {}", with_quotes(table))
+}
+
+/// Drops the table `table`, possibly schema-qualified, if it exists.
+pub async fn drop_table<'a, A>(acquirer: A, table: &str) -> sqlx::Result<()>
+where
+    A: Send + Acquire<'a, Database = Driver>,
+{
+    let mut conn = acquirer.acquire().await?;
+    sqlx::raw_sql(&drop_table_sql(table)).execute(&mut *conn).await?;
+    Ok(())
+}
@@ -3,15 +3,33 @@ use crate::Column;
 pub trait Selectable {
     type Row;
     fn collect(&self) -> Vec<&'static str>;
+    /// Column names qualified with their table alias, e.g. `__user.id`.
+    fn collect_qualified(&self) -> Vec<String>;
 }
 
-impl<T> Selectable for Column<T> {
+/// Columns accepted by update APIs such as `update().columns(...)`.
+///
+/// Implemented for every column except `#[sql(immutable)]` ones, and tuples of such columns.
+pub trait Updatable: Selectable {}
+
+/// Row tuple which can be extended with one more value of type `X`.
+pub trait AppendRow<X> {
+    type Out;
+}
+
+impl<T, W> Selectable for Column<T, W> {
     type Row = T;
     fn collect(&self) -> Vec<&'static str> {
         vec![self.name]
     }
+
+    fn collect_qualified(&self) -> Vec<String> {
+        vec![format!("{}.{}", self.table_alias, self.name)]
+    }
 }
 
+impl<T> Updatable for Column<T> {}
+
 macro_rules! impl_selectable_for_tuples {
     ( $( $Type:ident : $var:ident ),+ ) => {
         impl<$( $Type ),+> Selectable for ( $( $Type, )+ )
@@ -28,6 +46,21 @@ macro_rules! impl_selectable_for_tuples {
                 )+
                 out
             }
+
+            fn collect_qualified(&self) -> Vec<String> {
+                let ( $( $var, )+ ) = self;
+                let mut out = Vec::new();
+                $(
+                    out.extend($var.collect_qualified());
+                )+
+                out
+            }
+        }
+
+        impl<$( $Type ),+> Updatable for ( $( $Type, )+ )
+        where
+            $( $Type: Updatable ),+
+        {
         }
     };
 }
@@ -47,3 +80,27 @@ impl_selectable_for_tuples!(A:a, B:b, C:c, D:d, E:e, F:f, G:g, H:h, I:i, J:j, K:
 impl_selectable_for_tuples!(A:a, B:b, C:c, D:d, E:e, F:f, G:g, H:h, I:i, J:j, K:k, L:l, M:m);
 impl_selectable_for_tuples!(A:a, B:b, C:c, D:d, E:e, F:f, G:g, H:h, I:i, J:j, K:k, L:l, M:m, N:n);
 impl_selectable_for_tuples!(A:a, B:b, C:c, D:d, E:e, F:f, G:g, H:h, I:i, J:j, K:k, L:l, M:m, N:n, O:o);
+
+macro_rules! impl_append_row_for_tuples {
+    ( $( $Type:ident ),+ ) => {
+        impl<$( $Type, )+ X> AppendRow<X> for ( $( $Type, )+ ) {
+            type Out = ( $( $Type, )+ X, );
+        }
+    };
+}
+
+impl_append_row_for_tuples!(A);
+impl_append_row_for_tuples!(A, B);
+impl_append_row_for_tuples!(A, B, C);
+impl_append_row_for_tuples!(A, B, C, D);
+impl_append_row_for_tuples!(A, B, C, D, E);
+impl_append_row_for_tuples!(A, B, C, D, E, F);
+impl_append_row_for_tuples!(A, B, C, D, E, F, G);
+impl_append_row_for_tuples!(A, B, C, D, E, F, G, H);
+impl_append_row_for_tuples!(A, B, C, D, E, F, G, H, I);
+impl_append_row_for_tuples!(A, B, C, D, E, F, G, H, I, J);
+impl_append_row_for_tuples!(A, B, C, D, E, F, G, H, I, J, K);
+impl_append_row_for_tuples!(A, B, C, D, E, F, G, H, I, J, K, L);
+impl_append_row_for_tuples!(A, B, C, D, E, F, G, H, I, J, K, L, M);
+impl_append_row_for_tuples!(A, B, C, D, E, F, G, H, I, J, K, L, M, N);
+impl_append_row_for_tuples!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
@@ -0,0 +1,109 @@
+//! Reporting of statements slower than a threshold, to flag production issues while full query
+//! logging is disabled.
+//!
+//! Statements executed by the query builder (including `paginate()` counts and relation
+//! loading), `union()`, `update_where()`, `delete_where()` and `upsert_many()` are timed once a
+//! callback is registered; the callback receives the SQL and the values bound by filters,
+//! limits and sets. Values of the rows written by `upsert_many()` are not reported.
+//!
+//! Other statements, such as those of `save()`, `update()`, `delete()`, `insert_many()`,
+//! streams and `explain()`, are not timed.
+//!
+//! Example usage:
+//! ```rust ignore
+//! use std::time::Duration;
+//!
+//! sqlorm::slow_query::on_slow_query(Duration::from_millis(200), |query| {
+//!     eprintln!("slow query ({:?}): {}", query.elapsed, query.debug_sql());
+//! });
+//! ```
+
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::qb::interpolate_debug;
+
+type Callback = Arc<dyn Fn(&SlowQuery) + Send + Sync>;
+
+static SLOW_QUERY: RwLock<Option<(Duration, Callback)>> = RwLock::new(None);
+
+/// A statement that took at least the configured threshold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlowQuery {
+    /// SQL as sent to the database, with bind placeholders.
+    pub sql: String,
+    /// Debug representations of the bound values, in binding order.
+    pub values: Vec<String>,
+    pub elapsed: Duration,
+}
+
+impl SlowQuery {
+    /// Renders the statement with bound values interpolated. **For logging only.**
+    pub fn debug_sql(&self) -> String {
+        interpolate_debug(&self.sql, &self.values)
+    }
+}
+
+/// Calls `callback` for every timed statement taking at least `threshold`, replacing the
+/// callback registered before.
+pub fn on_slow_query<F>(threshold: Duration, callback: F)
+where
+    F: Fn(&SlowQuery) + Send + Sync + 'static,
+{
+    *SLOW_QUERY.write().unwrap_or_else(PoisonError::into_inner) =
+        Some((threshold, Arc::new(callback)));
+}
+
+/// Removes the callback registered with [`on_slow_query`], statements are no longer timed.
+pub fn clear() {
+    *SLOW_QUERY.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Times a statement, reporting it on [`Timer::finish`] if it was slow.
+///
+/// Holds the rendered statement rather than borrowing the builder, so it can be kept across
+/// `.await` points of `Send` futures.
+#[must_use]
+pub struct Timer(Option<Running>);
+
+struct Running {
+    query: SlowQuery,
+    threshold: Duration,
+    callback: Callback,
+    start: Instant,
+}
+
+impl Timer {
+    /// Starts timing a statement described by `describe`, which returns its SQL and bound
+    /// values and is only called while a callback is registered.
+    pub fn start(describe: impl FnOnce() -> (String, Vec<String>)) -> Self {
+        let config = SLOW_QUERY
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        Timer(config.map(|(threshold, callback)| {
+            let (sql, values) = describe();
+            Running {
+                query: SlowQuery {
+                    sql,
+                    values,
+                    elapsed: Duration::ZERO,
+                },
+                threshold,
+                callback,
+                start: Instant::now(),
+            }
+        }))
+    }
+
+    /// Stops timing, calling the callback if the statement took at least the threshold.
+    pub fn finish(self) {
+        let Some(mut running) = self.0 else {
+            return;
+        };
+        running.query.elapsed = running.start.elapsed();
+        if running.query.elapsed >= running.threshold {
+            (running.callback)(&running.query);
+        }
+    }
+}
@@ -1,7 +1,11 @@
+use crate::Connection;
 use crate::Driver;
 use crate::Row;
+use crate::Selectable;
 use crate::TableInfo;
+use crate::UpdateWhere;
 use async_trait::async_trait;
+use futures_core::stream::BoxStream;
 use sqlx::Acquire;
 
 /// Describes a database table and its metadata used by the query builder.
@@ -20,6 +24,55 @@ pub trait Table {
 
     /// Returns a TableInfo instance used by the query builder.
     fn table_info() -> TableInfo;
+
+    /// Metadata of every column in declaration order, e.g. for admin UIs or CSV exports.
+    fn columns() -> &'static [ColumnMeta];
+}
+
+/// Column of a [`Table`], as returned by [`Table::columns`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnMeta {
+    /// The column name as it appears in SQL.
+    pub name: &'static str,
+    /// The Rust type of the field, e.g. `Option<String>`.
+    pub rust_type: &'static str,
+    /// Whether the column accepts `NULL`, i.e. the field is an `Option`.
+    pub nullable: bool,
+    /// Whether the column is the primary key.
+    pub is_pk: bool,
+    /// Whether the column is the primary key or marked `#[sql(unique)]`.
+    pub is_unique: bool,
+}
+
+/// Binds an entity's values into multi-row `INSERT` statements built outside the entity,
+/// e.g. by [`UpsertMany`](crate::UpsertMany).
+///
+/// Implemented by the `#[table]` macro.
+pub trait Insertable: Table {
+    /// Columns written by `INSERT`, in binding order.
+    fn insert_columns() -> Vec<&'static str>;
+
+    /// Populates timestamps and generated primary keys before insert.
+    fn prepare_insert(&mut self);
+
+    /// Binds the values of [`Insertable::insert_columns`] to one `VALUES` row.
+    fn push_insert_values<'args>(
+        &'args self,
+        row: &mut sqlx::query_builder::Separated<'_, 'args, Driver, &'static str>,
+    );
+
+    /// Validates the entity and runs its `before_insert` hook, before a bulk insert.
+    fn before_bulk_insert(
+        &mut self,
+        conn: &mut Connection,
+    ) -> impl Future<Output = sqlx::Result<()>> + Send;
+
+    /// Runs the `after_insert` hook of a row returned by a bulk insert and notifies the
+    /// observers of the entity.
+    fn after_bulk_insert(
+        &mut self,
+        conn: &mut Connection,
+    ) -> impl Future<Output = sqlx::Result<()>> + Send;
 }
 
 /// Constructs a value from a database row where columns were projected with aliases.
@@ -31,8 +84,45 @@ pub trait FromAliasedRow {
     fn from_aliased_row(row: &Row) -> sqlx::Result<Self>
     where
         Self: Sized + Default;
+
+    /// Builds `Self` from a row where columns were projected with the given table alias,
+    /// e.g. a relation joined under a non-default alias.
+    ///
+    /// Defaults to [`FromAliasedRow::from_aliased_row`], ignoring `alias`.
+    fn from_row_with_alias(row: &Row, alias: &str) -> sqlx::Result<Self>
+    where
+        Self: Sized + Default,
+    {
+        let _ = alias;
+        Self::from_aliased_row(row)
+    }
+}
+
+/// A struct projecting a subset of an entity's columns, selected with `QB::select_as`.
+///
+/// Usually implemented with `#[derive(FromAliasedRow)]`.
+pub trait Projection {
+    /// Column names of the source entity read by this projection.
+    fn columns() -> Vec<&'static str>;
 }
 
+/// Partial update of an entity, with one `Option` per writable field.
+///
+/// Implemented by the generated `{Entity}Patch` structs, applied with `entity.apply_patch(...)`
+/// or [`UpdateWhere::set_patch`].
+pub trait Patch {
+    type Entity;
+
+    /// Adds an assignment to `update` for every provided field.
+    fn assign(self, update: UpdateWhere<Self::Entity>) -> UpdateWhere<Self::Entity>;
+}
+
+/// Required field of a generated `{Entity}Insert` builder which was not provided yet.
+///
+/// `save()` is only available once every required field has been set.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Unset;
+
 /// Executes a built query and returns typed results.
 ///
 /// This trait is implemented for the query builder type, allowing you to fetch typed
@@ -92,6 +182,11 @@ pub trait GenericExecutor<T> {
         self,
         pool: A,
     ) -> sqlx::Result<Vec<T>>;
+    /// Executes the query and streams rows mapped as `T`, without buffering them.
+    fn fetch_stream_as<'e, E>(self, executor: E) -> BoxStream<'e, sqlx::Result<T>>
+    where
+        E: 'e + sqlx::Executor<'e, Database = Driver>,
+        T: 'e;
 }
 
 #[async_trait]
@@ -99,4 +194,40 @@ pub trait StatementExecutor<T: Table> {
     async fn execute<'a, E>(self, acquirer: E) -> sqlx::Result<T>
     where
         E: Send + crate::sqlx::Acquire<'a, Database = Driver>;
+
+    /// Executes the statement and returns the number of affected rows, without `RETURNING`
+    /// or refreshing the entity.
+    ///
+    /// A versioned entity that changed since it was loaded yields `0` instead of a
+    /// `StaleObject` error.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// let affected: u64 = jar.delete().execute_affected(&pool).await?;
+    /// ```
+    async fn execute_affected<'a, E>(self, acquirer: E) -> sqlx::Result<u64>
+    where
+        E: Send + crate::sqlx::Acquire<'a, Database = Driver>;
+
+    /// Executes the statement and returns only `columns` of the affected row via `RETURNING`.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// let (updated_at,) = user
+    ///     .update()
+    ///     .columns((User::BIO,))
+    ///     .execute_returning((User::UPDATED_AT,), &pool)
+    ///     .await?;
+    /// ```
+    async fn execute_returning<'a, C, E>(self, columns: C, acquirer: E) -> sqlx::Result<C::Row>
+    where
+        C: Selectable + Send,
+        C::Row: for<'r> sqlx::FromRow<'r, Row> + Send + Unpin,
+        E: Send + crate::sqlx::Acquire<'a, Database = Driver>;
+
+    /// Renders the statement with bound values interpolated.
+    ///
+    /// Values are formatted with `Debug` and are not escaped. **Use it for logging only,
+    /// never execute the returned string.**
+    fn debug_sql(&self) -> String;
 }
@@ -0,0 +1,229 @@
+use std::any::Any;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+use sqlx::Transaction;
+
+use crate::dialect;
+use crate::observer;
+use crate::driver::{Connection, Driver, Pool};
+
+/// Runs `f` inside a transaction, committing when it returns `Ok` and rolling back otherwise.
+///
+/// The transaction is also rolled back if `f` panics, since sqlx rolls back transactions
+/// dropped without a commit. Use [`TransactionConfig`] to retry failed attempts.
+///
+/// Observers registered with [`observe_after_commit`](crate::observe_after_commit) are
+/// notified of the writes of `f` after the commit.
+///
+/// Example usage:
+/// ```rust ignore
+/// let user = sqlorm::transaction(&pool, async |tx| {
+///     let user = user.save(&mut **tx).await?;
+///     Jar::test_jar(user.id, "savings").save(&mut **tx).await?;
+///     Ok::<_, sqlx::Error>(user)
+/// })
+/// .await?;
+/// ```
+pub async fn transaction<T, E, F>(pool: &Pool, f: F) -> Result<T, E>
+where
+    F: AsyncFnMut(&mut Transaction<'static, Driver>) -> Result<T, E>,
+    E: From<sqlx::Error> + 'static,
+{
+    TransactionConfig::default().run(pool, f).await
+}
+
+/// Transaction isolation level, see [`TransactionConfig::isolation`].
+///
+/// SQLite transactions are always serializable; there the level picks when locks are taken:
+/// `ReadCommitted` on first access (`BEGIN DEFERRED`), `RepeatableRead` takes the write lock
+/// upfront (`BEGIN IMMEDIATE`), avoiding `SQLITE_BUSY` when a reader later writes, and
+/// `Serializable` locks out readers too (`BEGIN EXCLUSIVE`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Isolation {
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+/// Configures how [`transaction`] runs, e.g. the number of retries.
+///
+/// Example usage:
+/// ```rust ignore
+/// let total = TransactionConfig::default()
+///     .isolation(Isolation::Serializable)
+///     .retries(3)
+///     .run(&pool, async |tx| transfer(tx, from, to, amount).await)
+///     .await?;
+/// ```
+#[derive(Clone, Default)]
+#[must_use]
+pub struct TransactionConfig {
+    retries: u32,
+    isolation: Option<Isolation>,
+    retry_if: Option<RetryPredicate>,
+}
+
+type RetryPredicate = Arc<dyn Fn(&dyn Any) -> bool + Send + Sync>;
+
+impl fmt::Debug for TransactionConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransactionConfig")
+            .field("retries", &self.retries)
+            .field("isolation", &self.isolation)
+            .field("retry_if", &self.retry_if.is_some())
+            .finish()
+    }
+}
+
+impl TransactionConfig {
+    /// Runs the transaction again, up to `retries` times, when the closure or the commit fails
+    /// with a transient error, see [`is_transient`] and [`Self::retry_if`].
+    ///
+    /// Every attempt starts from a fresh transaction, so the closure must not have side effects
+    /// outside of it.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Decides which errors are retried instead of [`is_transient`], e.g. for closures failing
+    /// with their own error type. Errors of other types than `E` are not retried.
+    ///
+    /// Example usage:
+    /// ```rust ignore
+    /// TransactionConfig::default()
+    ///     .retries(3)
+    ///     .retry_if(|err: &AppError| matches!(err, AppError::Db(db) if sqlorm::is_transient(db)))
+    ///     .run(&pool, async |tx| transfer(tx, from, to, amount).await)
+    ///     .await?;
+    /// ```
+    pub fn retry_if<E: 'static>(
+        mut self,
+        predicate: impl Fn(&E) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.retry_if = Some(Arc::new(move |err: &dyn Any| {
+            err.downcast_ref::<E>().is_some_and(&predicate)
+        }));
+        self
+    }
+
+    /// Starts the transaction with `isolation` instead of the database default.
+    ///
+    /// Serialization failures under `Serializable` are best combined with [`Self::retries`].
+    pub fn isolation(mut self, isolation: Isolation) -> Self {
+        self.isolation = Some(isolation);
+        self
+    }
+
+    /// Runs `f` inside a transaction, see [`transaction`].
+    pub async fn run<T, E, F>(self, pool: &Pool, mut f: F) -> Result<T, E>
+    where
+        F: AsyncFnMut(&mut Transaction<'static, Driver>) -> Result<T, E>,
+        E: From<sqlx::Error> + 'static,
+    {
+        let mut attempt = 0;
+        loop {
+            match self.attempt(pool, &mut f).await {
+                Err(err) if attempt < self.retries && self.retries_on(&err) => attempt += 1,
+                result => return result,
+            }
+        }
+    }
+
+    fn retries_on(&self, err: &dyn Any) -> bool {
+        match &self.retry_if {
+            Some(predicate) => predicate(err),
+            None => err.downcast_ref::<sqlx::Error>().is_some_and(is_transient),
+        }
+    }
+
+    async fn attempt<T, E, F>(&self, pool: &Pool, f: &mut F) -> Result<T, E>
+    where
+        F: AsyncFnMut(&mut Transaction<'static, Driver>) -> Result<T, E>,
+        E: From<sqlx::Error>,
+    {
+        let mut tx = match self.isolation {
+            Some(isolation) => pool.begin_with(dialect::begin(isolation)).await?,
+            None => pool.begin().await?,
+        };
+        let (result, committed) = observer::collect(f(&mut tx)).await;
+        match result {
+            Ok(value) => {
+                tx.commit().await?;
+                committed.run();
+                Ok(value)
+            }
+            Err(err) => {
+                // the closure's error is more useful than a failed rollback, which also
+                // happens on drop
+                tx.rollback().await.ok();
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Whether `err` is a transient failure that may succeed when the transaction is retried:
+/// a serialization failure (`40001`) or deadlock (`40P01`) on PostgreSQL, and `SQLITE_BUSY`
+/// on SQLite.
+pub fn is_transient(err: &sqlx::Error) -> bool {
+    let Some(code) = err.as_database_error().and_then(|err| err.code()) else {
+        return false;
+    };
+    #[cfg(feature = "postgres")]
+    return code == "40001" || code == "40P01";
+    // extended result codes keep the primary code in the low byte
+    #[cfg(feature = "sqlite")]
+    return code.parse::<i32>().is_ok_and(|code| code & 0xff == 5);
+}
+
+/// Transactional units that compose: [`nested`](NestedTransaction::nested) opens a transaction
+/// on a plain connection, and a `SAVEPOINT` inside an open transaction.
+///
+/// Library code can take a `&mut Connection` and wrap its work in `nested` without caring
+/// whether the caller already opened a transaction. Rolling back a nested unit only undoes
+/// its own changes, the outer transaction stays usable.
+///
+/// Example usage:
+/// ```rust ignore
+/// let mut tx = pool.begin().await?;
+/// user.save(&mut *tx).await?;
+/// let jar = tx
+///     .nested(async |inner| Jar::test_jar(user.id, "savings").save(&mut **inner).await)
+///     .await;
+/// // the user is saved even if the jar failed
+/// tx.commit().await?;
+/// ```
+pub trait NestedTransaction {
+    /// Runs `f` inside a nested transaction, releasing the savepoint when it returns `Ok` and
+    /// rolling back to it otherwise.
+    fn nested<T, E, F>(&mut self, f: F) -> impl Future<Output = Result<T, E>>
+    where
+        F: AsyncFnOnce(&mut Transaction<'_, Driver>) -> Result<T, E>,
+        E: From<sqlx::Error>;
+}
+
+impl NestedTransaction for Connection {
+    async fn nested<T, E, F>(&mut self, f: F) -> Result<T, E>
+    where
+        F: AsyncFnOnce(&mut Transaction<'_, Driver>) -> Result<T, E>,
+        E: From<sqlx::Error>,
+    {
+        // sqlx issues `SAVEPOINT` instead of `BEGIN` when a transaction is already open
+        let mut inner = sqlx::Connection::begin(self).await?;
+        let (result, committed) = observer::collect(f(&mut inner)).await;
+        match result {
+            Ok(value) => {
+                inner.commit().await?;
+                committed.defer();
+                Ok(value)
+            }
+            Err(err) => {
+                inner.rollback().await.ok();
+                Err(err)
+            }
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Validation of entities before they are written.
+//!
+//! Entities with `#[sql(validate(...))]` fields or declared with `#[table(validate)]` get a
+//! generated `validate()` method, called by `insert()`, `update()` and `save()` before any
+//! statement runs. A failed validation is returned as [`sqlx::Error::Database`] holding the
+//! [`ValidationErrors`]:
+//! ```rust ignore
+//! match user.save(&pool).await {
+//!     Err(e) if ValidationErrors::is(&e) => {
+//!         let errors = ValidationErrors::from_error(&e).unwrap();
+//!         println!("{:?}", errors.field("email"));
+//!     }
+//!     other => other?,
+//! };
+//! ```
+//!
+//! The functions of this module implement the attribute validators and return the error
+//! message of an invalid value.
+
+use std::collections::BTreeMap;
+use std::error::Error as StdError;
+use std::fmt::Display;
+
+use sqlx::error::{DatabaseError, ErrorKind};
+
+/// Custom validation of an entity declared with `#[table(validate)]`, run after the
+/// `#[sql(validate(...))]` checks of its fields.
+///
+/// ```rust ignore
+/// impl sqlorm::Validate for Event {
+///     fn validate(&self) -> Result<(), ValidationErrors> {
+///         let mut errors = ValidationErrors::new();
+///         if self.ends_at < self.starts_at {
+///             errors.add("ends_at", "must be after starts_at");
+///         }
+///         errors.into_result()
+///     }
+/// }
+/// ```
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+/// Error messages of invalid fields, by field name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationErrors {
+    errors: BTreeMap<String, Vec<String>>,
+}
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `message` to the messages of `field`.
+    pub fn add(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.errors
+            .entry(field.into())
+            .or_default()
+            .push(message.into());
+    }
+
+    /// Adds the messages of `other`.
+    pub fn merge(&mut self, other: ValidationErrors) {
+        for (field, messages) in other.errors {
+            self.errors.entry(field).or_default().extend(messages);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Messages of `field`, empty if it is valid.
+    pub fn field(&self, field: &str) -> &[String] {
+        self.errors.get(field).map_or(&[], Vec::as_slice)
+    }
+
+    /// Invalid fields and their messages, in field name order.
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.errors
+            .iter()
+            .map(|(field, messages)| (field.as_str(), messages.as_slice()))
+    }
+
+    /// `Ok(())` if there are no errors, `Err(self)` otherwise.
+    pub fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.is_empty() { Ok(()) } else { Err(self) }
+    }
+
+    /// Returns true if `err` is a [`ValidationErrors`] error.
+    pub fn is(err: &sqlx::Error) -> bool {
+        Self::from_error(err).is_some()
+    }
+
+    /// The [`ValidationErrors`] held by `err`, if any.
+    pub fn from_error(err: &sqlx::Error) -> Option<&ValidationErrors> {
+        err.as_database_error()?.try_downcast_ref::<ValidationErrors>()
+    }
+}
+
+impl Display for ValidationErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("validation failed")?;
+        for (i, (field, messages)) in self.errors.iter().enumerate() {
+            f.write_str(if i == 0 { ": " } else { "; " })?;
+            write!(f, "{} {}", field, messages.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl StdError for ValidationErrors {}
+
+impl DatabaseError for ValidationErrors {
+    fn message(&self) -> &str {
+        "validation failed"
+    }
+
+    fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+        self
+    }
+
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+/// Values with a length checked by `length(min = .., max = ..)`.
+pub trait Length {
+    fn length(&self) -> usize;
+}
+
+impl Length for str {
+    /// Length in characters.
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl Length for String {
+    fn length(&self) -> usize {
+        self.as_str().length()
+    }
+}
+
+impl<T> Length for [T] {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for Vec<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Checks that `value` has between `min` and `max` characters or items.
+pub fn length<T: Length + ?Sized>(value: &T, min: Option<usize>, max: Option<usize>) -> Option<String> {
+    let length = value.length();
+    match (min, max) {
+        (Some(min), Some(max)) if length < min || length > max => {
+            Some(format!("length must be between {} and {}", min, max))
+        }
+        (Some(min), _) if length < min => Some(format!("length must be at least {}", min)),
+        (_, Some(max)) if length > max => Some(format!("length must be at most {}", max)),
+        _ => None,
+    }
+}
+
+/// Checks that `value` is between `min` and `max`, inclusive.
+pub fn range<T: PartialOrd + Display>(value: &T, min: Option<T>, max: Option<T>) -> Option<String> {
+    match (min, max) {
+        (Some(min), Some(max)) if *value < min || *value > max => {
+            Some(format!("must be between {} and {}", min, max))
+        }
+        (Some(min), _) if *value < min => Some(format!("must be at least {}", min)),
+        (_, Some(max)) if *value > max => Some(format!("must be at most {}", max)),
+        _ => None,
+    }
+}
+
+/// Checks that `value` looks like an email address: a local part, `@` and a dotted domain.
+pub fn email(value: &str) -> Option<String> {
+    let valid = value.split_once('@').is_some_and(|(local, domain)| {
+        !local.is_empty()
+            && !domain.contains('@')
+            && domain.split('.').count() > 1
+            && domain.split('.').all(|part| !part.is_empty())
+            && !value.chars().any(char::is_whitespace)
+    });
+    if valid {
+        None
+    } else {
+        Some("must be an email address".to_string())
+    }
+}
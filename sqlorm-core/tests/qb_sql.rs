@@ -1,4 +1,5 @@
 use sqlorm_core::TableInfo;
+use sqlorm_core::{DeleteWhere, Expr, UpdateWhere};
 use sqlorm_core::qb::{Column, JoinSpec, JoinType, QB};
 use std::marker::PhantomData;
 
@@ -21,6 +22,21 @@ fn select_and_from_sql() {
     );
 }
 
+#[test]
+fn schema_qualified_from_sql() {
+    let base = TableInfo {
+        name: "billing.invoices",
+        alias: "i".to_string(),
+        columns: vec!["id"],
+    };
+    let qb = QB::<()>::new(base);
+    let sql = normalize(&qb.to_sql());
+    assert_eq!(
+        sql,
+        "SELECT i.id AS i__id FROM \"billing\".\"invoices\" AS i"
+    );
+}
+
 #[test]
 fn join_sql() {
     let base = TableInfo {
@@ -38,6 +54,10 @@ fn join_sql() {
         relation_name: "profile",
         foreign_table: foreign,
         on: ("id", "user_id"),
+        alias: None,
+        filters: Vec::new(),
+        order_by: Vec::new(),
+        limit: None,
     };
     let qb = QB::<()>::new(base).join_eager(join);
     let sql = normalize(&qb.to_sql());
@@ -49,6 +69,38 @@ fn join_sql() {
     );
 }
 
+#[test]
+fn join_type_of_repeated_eager_join_sql() {
+    let base = TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let join = |join_type| JoinSpec {
+        join_type,
+        relation_name: "profile",
+        foreign_table: TableInfo {
+            name: "profiles",
+            alias: "p".to_string(),
+            columns: vec!["user_id"],
+        },
+        on: ("id", "user_id"),
+        alias: None,
+        filters: Vec::new(),
+        order_by: Vec::new(),
+        limit: None,
+    };
+    let qb = QB::<()>::new(base)
+        .join_eager(join(JoinType::Left))
+        .join_eager(join(JoinType::Inner));
+    assert_eq!(
+        normalize(&qb.to_sql()),
+        "SELECT u.id AS u__id, p.user_id AS p__user_id \
+         FROM \"users\" AS u \
+         INNER JOIN \"profiles\" AS p ON u.id = p.user_id"
+    );
+}
+
 #[test]
 fn single_filter_sql() {
     let base = TableInfo {
@@ -115,3 +167,925 @@ fn multiple_filters_and_in_sql() {
          WHERE u.id > ? AND u.name IN (?, ?) AND u.name LIKE ?"
     );
 }
+
+#[test]
+fn count_query_sql() {
+    let base = TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id", "name"],
+    };
+    let id = Column::<i32> {
+        name: "id",
+        table_alias: "u",
+        aliased_name: "u__id",
+        _marker: PhantomData,
+    };
+    let qb = QB::<()>::new(base)
+        .filter(id.gt(10))
+        .order_by(id.asc())
+        .for_page(2, 10);
+    let sql = normalize(qb.build_count_query().sql());
+    #[cfg(feature = "postgres")]
+    assert_eq!(
+        sql,
+        "SELECT COUNT(*) FROM \"users\" AS u WHERE u.id > $1"
+    );
+    #[cfg(feature = "sqlite")]
+    assert_eq!(sql, "SELECT COUNT(*) FROM \"users\" AS u WHERE u.id > ?");
+}
+
+#[test]
+fn union_sql() {
+    let base = || TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let id = Column::<i32> {
+        name: "id",
+        table_alias: "u",
+        aliased_name: "u__id",
+        _marker: PhantomData,
+    };
+    let qb = QB::<()>::new(base())
+        .filter(id.lt(10))
+        .union_all(QB::<()>::new(base()).filter(id.gt(20)))
+        .order_by(id.desc())
+        .limit(5);
+    let sql = normalize(&qb.to_sql());
+    #[cfg(feature = "postgres")]
+    assert_eq!(
+        sql,
+        "SELECT u.id AS u__id FROM \"users\" AS u WHERE u.id < $1 \
+         UNION ALL \
+         SELECT u.id AS u__id FROM \"users\" AS u WHERE u.id > $2 \
+         ORDER BY u__id desc LIMIT $3"
+    );
+    #[cfg(feature = "sqlite")]
+    assert_eq!(
+        sql,
+        "SELECT u.id AS u__id FROM \"users\" AS u WHERE u.id < ? \
+         UNION ALL \
+         SELECT u.id AS u__id FROM \"users\" AS u WHERE u.id > ? \
+         ORDER BY u__id desc LIMIT ?"
+    );
+}
+
+#[test]
+fn union_debug_sql_skips_part_limits() {
+    let base = || TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let id = Column::<i32> {
+        name: "id",
+        table_alias: "u",
+        aliased_name: "u__id",
+        _marker: PhantomData,
+    };
+    let qb = QB::<()>::new(base())
+        .filter(id.lt(10))
+        .limit(3)
+        .union_all(QB::<()>::new(base()).filter(id.gt(20)).offset(7))
+        .limit(5);
+    assert_eq!(
+        normalize(&qb.debug_sql()),
+        "SELECT u.id AS u__id FROM \"users\" AS u WHERE u.id < 10 \
+         UNION ALL \
+         SELECT u.id AS u__id FROM \"users\" AS u WHERE u.id > 20 \
+         LIMIT 5"
+    );
+}
+
+#[test]
+fn select_expr_sql() {
+    let base = TableInfo {
+        name: "donations",
+        alias: "d".to_string(),
+        columns: vec!["id", "amount", "tip"],
+    };
+    let id = Column::<i32> {
+        name: "id",
+        table_alias: "d",
+        aliased_name: "d__id",
+        _marker: PhantomData,
+    };
+    let qb = QB::<()>::new(base)
+        .select((id,))
+        .select_expr::<f64>("d.amount + d.tip", "total");
+    let sql = normalize(&qb.to_sql());
+    assert_eq!(
+        sql,
+        "SELECT d.id AS d__id, (d.amount + d.tip) AS total FROM \"donations\" AS d"
+    );
+}
+
+#[test]
+fn window_function_sql() {
+    use sqlorm_core::window::{row_number, sum};
+
+    let base = TableInfo {
+        name: "donations",
+        alias: "d".to_string(),
+        columns: vec!["id"],
+    };
+    let id = Column::<i32> {
+        name: "id",
+        table_alias: "d",
+        aliased_name: "d__id",
+        _marker: PhantomData,
+    };
+    let jar_id = Column::<i32> {
+        name: "jar_id",
+        table_alias: "d",
+        aliased_name: "d__jar_id",
+        _marker: PhantomData,
+    };
+    let amount = Column::<f64> {
+        name: "amount",
+        table_alias: "d",
+        aliased_name: "d__amount",
+        _marker: PhantomData,
+    };
+    let qb = QB::<()>::new(base)
+        .select((id,))
+        .select_expr::<i64>(row_number().over(jar_id, vec![id.desc()]), "rn")
+        .select_expr::<f64>(sum(amount).over_all(vec![]), "total");
+    let sql = normalize(&qb.to_sql());
+    assert_eq!(
+        sql,
+        "SELECT d.id AS d__id, \
+         (ROW_NUMBER() OVER (PARTITION BY d.jar_id ORDER BY d.id desc)) AS rn, \
+         (SUM(d.amount) OVER ()) AS total \
+         FROM \"donations\" AS d"
+    );
+}
+
+#[test]
+fn case_insensitive_filters_sql() {
+    let base = TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let name = Column::<String> {
+        name: "name",
+        table_alias: "u",
+        aliased_name: "u__name",
+        _marker: PhantomData,
+    };
+    let qb = QB::<()>::new(base)
+        .filter(name.ilike("jo%".to_string()))
+        .filter(name.eq_ignore_case("John".to_string()));
+    let sql = normalize(&qb.to_sql());
+    #[cfg(feature = "postgres")]
+    assert_eq!(
+        sql,
+        "SELECT u.id AS u__id FROM \"users\" AS u \
+         WHERE u.name ILIKE $1 AND LOWER(u.name) = LOWER($2)"
+    );
+    #[cfg(feature = "sqlite")]
+    assert_eq!(
+        sql,
+        "SELECT u.id AS u__id FROM \"users\" AS u \
+         WHERE LOWER(u.name) LIKE LOWER(?) AND LOWER(u.name) = LOWER(?)"
+    );
+}
+
+#[cfg(feature = "postgres")]
+#[test]
+fn json_filters_sql() {
+    use sqlx::types::JsonValue;
+
+    let base = TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let settings = Column::<JsonValue> {
+        name: "settings",
+        table_alias: "u",
+        aliased_name: "u__settings",
+        _marker: PhantomData,
+    };
+    let qb = QB::<()>::new(base)
+        .filter(settings.json_contains(JsonValue::Bool(true)))
+        .filter(settings.json_get("theme").eq("dark"))
+        .filter(settings.json_exists_key("lang"));
+    let sql = normalize(&qb.to_sql());
+    assert_eq!(
+        sql,
+        "SELECT u.id AS u__id FROM \"users\" AS u \
+         WHERE u.settings @> $1 AND (u.settings ->> $2) = $3 AND jsonb_exists(u.settings, $4)"
+    );
+}
+
+#[cfg(feature = "postgres")]
+#[test]
+fn array_filters_sql() {
+    let base = TableInfo {
+        name: "posts",
+        alias: "p".to_string(),
+        columns: vec!["id"],
+    };
+    let tags = Column::<Vec<String>> {
+        name: "tags",
+        table_alias: "p",
+        aliased_name: "p__tags",
+        _marker: PhantomData,
+    };
+    let qb = QB::<()>::new(base)
+        .filter(tags.any_eq("rust".to_string()))
+        .filter(tags.overlaps(vec!["orm".to_string(), "sql".to_string()]));
+    let sql = normalize(&qb.to_sql());
+    assert_eq!(
+        sql,
+        "SELECT p.id AS p__id FROM \"posts\" AS p WHERE $1 = ANY(p.tags) AND p.tags && $2"
+    );
+}
+
+#[test]
+fn debug_sql_interpolates_values() {
+    let base = TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let id = Column::<i32> {
+        name: "id",
+        table_alias: "u",
+        aliased_name: "u__id",
+        _marker: PhantomData,
+    };
+    let name = Column::<String> {
+        name: "name",
+        table_alias: "u",
+        aliased_name: "u__name",
+        _marker: PhantomData,
+    };
+    let qb = QB::<()>::new(base)
+        .filter(id.in_(vec![1, 2]))
+        .filter(name.eq("john".to_string()))
+        .limit(10);
+    let sql = normalize(&qb.debug_sql());
+    assert_eq!(
+        sql,
+        "SELECT u.id AS u__id FROM \"users\" AS u \
+         WHERE u.id IN (1, 2) AND u.name = \"john\" LIMIT 10"
+    );
+}
+
+#[test]
+fn order_by_nulls_sql() {
+    let base = TableInfo {
+        name: "donations",
+        alias: "d".to_string(),
+        columns: vec!["id"],
+    };
+    let payed_at = Column::<Option<i64>> {
+        name: "payed_at",
+        table_alias: "d",
+        aliased_name: "d__payed_at",
+        _marker: PhantomData,
+    };
+    let qb = QB::<()>::new(base).order_by(payed_at.desc().nulls_last());
+    let sql = normalize(&qb.to_sql());
+    #[cfg(feature = "postgres")]
+    assert_eq!(
+        sql,
+        "SELECT d.id AS d__id FROM \"donations\" AS d ORDER BY d.payed_at desc NULLS LAST"
+    );
+    #[cfg(feature = "sqlite")]
+    assert_eq!(
+        sql,
+        "SELECT d.id AS d__id FROM \"donations\" AS d \
+         ORDER BY d.payed_at IS NULL asc, d.payed_at desc"
+    );
+}
+
+#[test]
+fn max_limit_offset_sql() {
+    let base = TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let qb = QB::<()>::new(base).offset(i64::MAX as u64);
+    let sql = normalize(&qb.debug_sql());
+    #[cfg(feature = "postgres")]
+    assert_eq!(
+        sql,
+        "SELECT u.id AS u__id FROM \"users\" AS u OFFSET 9223372036854775807"
+    );
+    #[cfg(feature = "sqlite")]
+    assert_eq!(
+        sql,
+        "SELECT u.id AS u__id FROM \"users\" AS u LIMIT -1 OFFSET 9223372036854775807"
+    );
+}
+
+#[test]
+#[should_panic(expected = "Cannot use offset 18446744073709551615")]
+fn offset_above_i64_max_panics() {
+    let base = TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let _ = QB::<()>::new(base).offset(u64::MAX);
+}
+
+#[test]
+#[should_panic(expected = "Cannot use limit 9223372036854775808")]
+fn limit_above_i64_max_panics() {
+    let base = TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let _ = QB::<()>::new(base).limit(i64::MAX as u64 + 1);
+}
+
+#[test]
+fn order_by_joined_column_sql() {
+    let base = TableInfo {
+        name: "jars",
+        alias: "j".to_string(),
+        columns: vec!["id"],
+    };
+    let foreign = TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let username = Column::<String> {
+        name: "username",
+        table_alias: "u",
+        aliased_name: "u__username",
+        _marker: PhantomData,
+    };
+    let join = JoinSpec {
+        join_type: JoinType::Left,
+        relation_name: "owner",
+        foreign_table: foreign,
+        on: ("owner_id", "id"),
+        alias: None,
+        filters: Vec::new(),
+        order_by: Vec::new(),
+        limit: None,
+    };
+    let qb = QB::<()>::new(base)
+        .join_eager(join)
+        .order_by(username.asc());
+    let sql = normalize(&qb.to_sql());
+    assert_eq!(
+        sql,
+        "SELECT j.id AS j__id, u.id AS u__id \
+         FROM \"jars\" AS j \
+         LEFT JOIN \"users\" AS u ON j.owner_id = u.id \
+         ORDER BY u.username asc"
+    );
+}
+
+#[test]
+fn same_table_joined_twice_sql() {
+    let base = TableInfo {
+        name: "transfers",
+        alias: "t".to_string(),
+        columns: vec!["id"],
+    };
+    let users = || TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let username = Column::<String> {
+        name: "username",
+        table_alias: "u",
+        aliased_name: "u__username",
+        _marker: PhantomData,
+    };
+    let qb = QB::<()>::new(base)
+        .join_eager(JoinSpec {
+            join_type: JoinType::Left,
+            relation_name: "sender",
+            foreign_table: users(),
+            on: ("sender_id", "id"),
+            alias: None,
+            filters: Vec::new(),
+            order_by: Vec::new(),
+            limit: None,
+        })
+        .join_eager(JoinSpec {
+            join_type: JoinType::Left,
+            relation_name: "receiver",
+            foreign_table: users(),
+            on: ("receiver_id", "id"),
+            alias: None,
+            filters: Vec::new(),
+            order_by: Vec::new(),
+            limit: None,
+        })
+        .filter(username.eq("alice".to_string()))
+        .filter_relation("receiver", username.eq("bob".to_string()))
+        .order_by_relation("receiver", username.asc());
+    let sql = normalize(&qb.to_sql());
+    #[cfg(feature = "postgres")]
+    assert_eq!(
+        sql,
+        "SELECT t.id AS t__id, u.id AS u__id, u_receiver.id AS u_receiver__id \
+         FROM \"transfers\" AS t \
+         LEFT JOIN \"users\" AS u ON t.sender_id = u.id \
+         LEFT JOIN \"users\" AS u_receiver ON t.receiver_id = u_receiver.id \
+         WHERE u.username = $1 AND u_receiver.username = $2 \
+         ORDER BY u_receiver.username asc"
+    );
+    #[cfg(feature = "sqlite")]
+    assert_eq!(
+        sql,
+        "SELECT t.id AS t__id, u.id AS u__id, u_receiver.id AS u_receiver__id \
+         FROM \"transfers\" AS t \
+         LEFT JOIN \"users\" AS u ON t.sender_id = u.id \
+         LEFT JOIN \"users\" AS u_receiver ON t.receiver_id = u_receiver.id \
+         WHERE u.username = ? AND u_receiver.username = ? \
+         ORDER BY u_receiver.username asc"
+    );
+}
+
+#[test]
+fn order_random_sql() {
+    let base = TableInfo {
+        name: "jars",
+        alias: "j".to_string(),
+        columns: vec!["id"],
+    };
+    let qb = QB::<()>::new(base).order_random().limit(1);
+    let sql = normalize(&qb.to_sql());
+    #[cfg(feature = "postgres")]
+    assert_eq!(
+        sql,
+        "SELECT j.id AS j__id FROM \"jars\" AS j ORDER BY random() LIMIT $1"
+    );
+    #[cfg(feature = "sqlite")]
+    assert_eq!(
+        sql,
+        "SELECT j.id AS j__id FROM \"jars\" AS j ORDER BY RANDOM() LIMIT ?"
+    );
+}
+
+#[test]
+fn update_where_sql() {
+    let base = TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id", "bio"],
+    };
+    let id = Column::<i64> {
+        name: "id",
+        table_alias: "u",
+        aliased_name: "u__id",
+        _marker: PhantomData,
+    };
+    let bio = Column::<Option<String>> {
+        name: "bio",
+        table_alias: "u",
+        aliased_name: "u__bio",
+        _marker: PhantomData,
+    };
+    let update = UpdateWhere::<()>::new(base)
+        .set(bio, Some("old".to_string()))
+        .set(bio, None)
+        .filter(id.in_(vec![1, 2]));
+    let sql = normalize(&update.to_sql());
+    #[cfg(feature = "postgres")]
+    assert_eq!(
+        sql,
+        "UPDATE \"users\" AS u SET bio = $1 WHERE u.id IN ($2, $3)"
+    );
+    #[cfg(feature = "sqlite")]
+    assert_eq!(sql, "UPDATE \"users\" AS u SET bio = ? WHERE u.id IN (?, ?)");
+    assert_eq!(
+        normalize(&update.debug_sql()),
+        "UPDATE \"users\" AS u SET bio = None WHERE u.id IN (1, 2)"
+    );
+}
+
+#[test]
+fn delete_where_sql() {
+    let base = || TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let id = Column::<i64> {
+        name: "id",
+        table_alias: "u",
+        aliased_name: "u__id",
+        _marker: PhantomData,
+    };
+    let deleted_at = Column::<Option<i64>> {
+        name: "deleted_at",
+        table_alias: "u",
+        aliased_name: "u__deleted_at",
+        _marker: PhantomData,
+    };
+
+    let hard = DeleteWhere::<()>::new(base()).filter(id.gt(10));
+    let soft = DeleteWhere::<()>::new(base())
+        .soft_delete(deleted_at, 42)
+        .filter(id.gt(10));
+    let either = DeleteWhere::<()>::new(base())
+        .filter(id.gt(10))
+        .filter(id.eq(1).or(id.eq(2)));
+
+    #[cfg(feature = "postgres")]
+    {
+        assert_eq!(
+            normalize(&hard.to_sql()),
+            "DELETE FROM \"users\" AS u WHERE u.id > $1"
+        );
+        assert_eq!(
+            normalize(&soft.to_sql()),
+            "UPDATE \"users\" AS u SET deleted_at = $1 WHERE (u.deleted_at IS NULL) AND (u.id > $2)"
+        );
+        assert_eq!(
+            normalize(&either.to_sql()),
+            "DELETE FROM \"users\" AS u WHERE (u.id > $1) AND ((u.id = $2) OR (u.id = $3))"
+        );
+    }
+    #[cfg(feature = "sqlite")]
+    {
+        assert_eq!(
+            normalize(&hard.to_sql()),
+            "DELETE FROM \"users\" AS u WHERE u.id > ?"
+        );
+        assert_eq!(
+            normalize(&soft.to_sql()),
+            "UPDATE \"users\" AS u SET deleted_at = ? WHERE (u.deleted_at IS NULL) AND (u.id > ?)"
+        );
+        assert_eq!(
+            normalize(&either.to_sql()),
+            "DELETE FROM \"users\" AS u WHERE (u.id > ?) AND ((u.id = ?) OR (u.id = ?))"
+        );
+    }
+}
+
+#[test]
+fn soft_delete_filter_sql() {
+    let base = TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let id: Column<i64> = Column {
+        name: "id",
+        aliased_name: "u__id",
+        table_alias: "u",
+        _marker: PhantomData,
+    };
+
+    let qb = QB::<()>::new(base).soft_delete("deleted_at");
+    assert_eq!(
+        normalize(&qb.to_sql()),
+        "SELECT u.id AS u__id FROM \"users\" AS u WHERE u.deleted_at IS NULL"
+    );
+
+    let filtered = normalize(&qb.clone().filter(id.eq(1)).to_sql());
+    #[cfg(feature = "postgres")]
+    assert!(filtered.ends_with("WHERE (u.id = $1) AND u.deleted_at IS NULL"));
+    #[cfg(feature = "sqlite")]
+    assert!(filtered.ends_with("WHERE (u.id = ?) AND u.deleted_at IS NULL"));
+
+    let either = normalize(&qb.clone().filter(id.eq(1).or(id.eq(2))).to_sql());
+    #[cfg(feature = "postgres")]
+    assert!(either.ends_with("WHERE ((u.id = $1) OR (u.id = $2)) AND u.deleted_at IS NULL"));
+    #[cfg(feature = "sqlite")]
+    assert!(either.ends_with("WHERE ((u.id = ?) OR (u.id = ?)) AND u.deleted_at IS NULL"));
+
+    let with_deleted = qb.clone().with_deleted();
+    assert_eq!(
+        normalize(&with_deleted.to_sql()),
+        "SELECT u.id AS u__id FROM \"users\" AS u"
+    );
+
+    let only_deleted = qb.only_deleted();
+    assert!(normalize(&only_deleted.to_sql()).ends_with("WHERE u.deleted_at IS NOT NULL"));
+}
+
+#[test]
+fn soft_delete_flag_sql() {
+    let base = TableInfo {
+        name: "notes",
+        alias: "n".to_string(),
+        columns: vec!["id"],
+    };
+    let is_deleted: Column<bool> = Column {
+        name: "is_deleted",
+        aliased_name: "n__is_deleted",
+        table_alias: "n",
+        _marker: PhantomData,
+    };
+
+    let qb = QB::<()>::new(base.clone()).soft_delete_flag("is_deleted");
+    assert_eq!(
+        normalize(&qb.to_sql()),
+        "SELECT n.id AS n__id FROM \"notes\" AS n WHERE n.is_deleted = FALSE"
+    );
+    assert!(normalize(&qb.only_deleted().to_sql()).ends_with("WHERE n.is_deleted = TRUE"));
+
+    let soft = DeleteWhere::<()>::new(base).soft_delete_flag(is_deleted);
+    #[cfg(feature = "postgres")]
+    assert_eq!(
+        normalize(&soft.to_sql()),
+        "UPDATE \"notes\" AS n SET is_deleted = $1 WHERE n.is_deleted = $2"
+    );
+    #[cfg(feature = "sqlite")]
+    assert_eq!(
+        normalize(&soft.to_sql()),
+        "UPDATE \"notes\" AS n SET is_deleted = ? WHERE n.is_deleted = ?"
+    );
+}
+
+#[test]
+fn update_where_from_sql() {
+    let jars = TableInfo {
+        name: "jars",
+        alias: "j".to_string(),
+        columns: vec!["id", "note"],
+    };
+    let donations = TableInfo {
+        name: "donations",
+        alias: "d".to_string(),
+        columns: vec!["jar_id", "note"],
+    };
+    let jar_id = Column::<i64> {
+        name: "id",
+        table_alias: "j",
+        aliased_name: "j__id",
+        _marker: PhantomData,
+    };
+    let jar_note = Column::<Option<String>> {
+        name: "note",
+        table_alias: "j",
+        aliased_name: "j__note",
+        _marker: PhantomData,
+    };
+    let donation_jar_id = Column::<i64> {
+        name: "jar_id",
+        table_alias: "d",
+        aliased_name: "d__jar_id",
+        _marker: PhantomData,
+    };
+    let donation_note = Column::<Option<String>> {
+        name: "note",
+        table_alias: "d",
+        aliased_name: "d__note",
+        _marker: PhantomData,
+    };
+
+    let update = UpdateWhere::<()>::new(jars)
+        .set_expr(jar_note, Expr::qualified(donation_note))
+        .from(
+            QB::<()>::new(donations).filter(donation_jar_id.gt(0)),
+            jar_id.eq_col(donation_jar_id),
+        );
+    let sql = normalize(&update.to_sql());
+    #[cfg(feature = "postgres")]
+    assert_eq!(
+        sql,
+        "UPDATE \"jars\" AS j SET note = d.note FROM \"donations\" AS d \
+         WHERE (j.id = d.jar_id) AND (d.jar_id > $1)"
+    );
+    #[cfg(feature = "sqlite")]
+    assert_eq!(
+        sql,
+        "UPDATE \"jars\" AS j SET note = (SELECT d.note FROM \"donations\" AS d \
+         WHERE (j.id = d.jar_id) AND (d.jar_id > ?)) \
+         WHERE EXISTS (SELECT 1 FROM \"donations\" AS d WHERE (j.id = d.jar_id) AND (d.jar_id > ?))"
+    );
+}
+
+#[test]
+fn limit_per_sql() {
+    let base = TableInfo {
+        name: "donations",
+        alias: "d".to_string(),
+        columns: vec!["id"],
+    };
+    let jar_id = || Column::<i64> {
+        name: "jar_id",
+        table_alias: "d",
+        aliased_name: "d__jar_id",
+        _marker: PhantomData,
+    };
+    let created_at = Column::<i64> {
+        name: "created_at",
+        table_alias: "d",
+        aliased_name: "d__created_at",
+        _marker: PhantomData,
+    };
+    let qb = QB::<()>::new(base)
+        .filter(jar_id().in_(vec![1, 2]))
+        .order_by(created_at.desc())
+        .limit_per(jar_id(), 5);
+    let sql = normalize(&qb.to_sql());
+    #[cfg(feature = "postgres")]
+    assert_eq!(
+        sql,
+        "SELECT d.id AS d__id FROM \"donations\" AS d \
+         WHERE (d.jar_id IN ($1, $2)) AND d.ctid IN (SELECT __ranked.ctid FROM \
+         (SELECT d.ctid, ROW_NUMBER() OVER (PARTITION BY d.jar_id ORDER BY d.created_at desc) AS __row_number \
+         FROM \"donations\" AS d WHERE d.jar_id IN ($3, $4)) AS __ranked WHERE __ranked.__row_number <= 5) \
+         ORDER BY d.created_at desc"
+    );
+    #[cfg(feature = "sqlite")]
+    assert_eq!(
+        sql,
+        "SELECT d.id AS d__id FROM \"donations\" AS d \
+         WHERE (d.jar_id IN (?, ?)) AND d.rowid IN (SELECT __limited.rowid FROM \"donations\" AS __limited \
+         WHERE (__limited.jar_id = d.jar_id) AND (__limited.jar_id IN (?, ?)) \
+         ORDER BY __limited.created_at desc LIMIT 5) \
+         ORDER BY d.created_at desc"
+    );
+}
+
+#[test]
+fn limit_per_with_join_sql() {
+    let base = TableInfo {
+        name: "donations",
+        alias: "d".to_string(),
+        columns: vec!["id"],
+    };
+    let jar_id = Column::<i64> {
+        name: "jar_id",
+        table_alias: "d",
+        aliased_name: "d__jar_id",
+        _marker: PhantomData,
+    };
+    let username = Column::<String> {
+        name: "username",
+        table_alias: "u",
+        aliased_name: "u__username",
+        _marker: PhantomData,
+    };
+    let qb = QB::<()>::new(base)
+        .join_eager(JoinSpec {
+            join_type: JoinType::Inner,
+            relation_name: "payer",
+            foreign_table: TableInfo {
+                name: "users",
+                alias: "u".to_string(),
+                columns: vec!["id"],
+            },
+            on: ("payer_id", "id"),
+            alias: None,
+            filters: Vec::new(),
+            order_by: Vec::new(),
+            limit: None,
+        })
+        .filter(username.eq("bob".to_string()).or(username.eq("amy".to_string())))
+        .limit_per(jar_id, 2);
+    let sql = normalize(&qb.to_sql());
+    #[cfg(feature = "postgres")]
+    assert_eq!(
+        sql,
+        "SELECT d.id AS d__id, u.id AS u__id FROM \"donations\" AS d \
+         INNER JOIN \"users\" AS u ON d.payer_id = u.id \
+         WHERE ((u.username = $1) OR (u.username = $2)) AND d.ctid IN (SELECT __ranked.ctid FROM \
+         (SELECT d.ctid, ROW_NUMBER() OVER (PARTITION BY d.jar_id) AS __row_number \
+         FROM \"donations\" AS d INNER JOIN \"users\" AS u ON d.payer_id = u.id \
+         WHERE (u.username = $3) OR (u.username = $4)) AS __ranked WHERE __ranked.__row_number <= 2)"
+    );
+    #[cfg(feature = "sqlite")]
+    assert_eq!(
+        sql,
+        "SELECT d.id AS d__id, u.id AS u__id FROM \"donations\" AS d \
+         INNER JOIN \"users\" AS u ON d.payer_id = u.id \
+         WHERE ((u.username = ?) OR (u.username = ?)) AND d.rowid IN (SELECT __limited.rowid \
+         FROM \"donations\" AS __limited INNER JOIN \"users\" AS u ON __limited.payer_id = u.id \
+         WHERE (__limited.jar_id = d.jar_id) AND ((u.username = ?) OR (u.username = ?)) LIMIT 2)"
+    );
+}
+
+#[test]
+fn filter_exists_sql() {
+    let users = TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let jars = TableInfo {
+        name: "jars",
+        alias: "j".to_string(),
+        columns: vec!["id"],
+    };
+    let goal = Column::<i64> {
+        name: "goal",
+        table_alias: "j",
+        aliased_name: "j__goal",
+        _marker: PhantomData,
+    };
+    let related = QB::<()>::new(jars).filter(goal.gt(100));
+    let qb = QB::<()>::new(users.clone()).filter_exists(related, "j.owner_id = u.id".to_string());
+    let sql = normalize(&qb.to_sql());
+    #[cfg(feature = "postgres")]
+    assert_eq!(
+        sql,
+        "SELECT u.id AS u__id FROM \"users\" AS u \
+         WHERE EXISTS (SELECT 1 FROM \"jars\" AS j WHERE j.owner_id = u.id AND j.goal > $1)"
+    );
+    #[cfg(feature = "sqlite")]
+    assert_eq!(
+        sql,
+        "SELECT u.id AS u__id FROM \"users\" AS u \
+         WHERE EXISTS (SELECT 1 FROM \"jars\" AS j WHERE j.owner_id = u.id AND j.goal > ?)"
+    );
+
+    let self_related = QB::<()>::new(users.clone()).realias("u_parent".to_string());
+    let qb = QB::<()>::new(users)
+        .filter_not_exists(self_related, "u_parent.id = u.parent_id".to_string());
+    assert_eq!(
+        normalize(&qb.to_sql()),
+        "SELECT u.id AS u__id FROM \"users\" AS u \
+         WHERE NOT EXISTS (SELECT 1 FROM \"users\" AS u_parent WHERE u_parent.id = u.parent_id)"
+    );
+}
+
+#[cfg(feature = "postgres")]
+#[test]
+fn select_json_agg_sql() {
+    let users = TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let jars = TableInfo {
+        name: "jars",
+        alias: "j".to_string(),
+        columns: vec!["id"],
+    };
+    let qb = QB::<()>::new(users).select_json_agg(
+        "jars_json",
+        QB::<()>::new(jars),
+        "j.owner_id = u.id".to_string(),
+    );
+    assert_eq!(
+        normalize(&qb.to_sql()),
+        "SELECT u.id AS u__id, (SELECT COALESCE(json_agg(j), '[]'::json) FROM \"jars\" AS j \
+         WHERE j.owner_id = u.id) AS jars_json FROM \"users\" AS u"
+    );
+}
+
+#[test]
+fn not_persistent_keeps_sql() {
+    let base = TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let qb = QB::<()>::new(base);
+    assert!(qb.persistent);
+    let sql = qb.to_sql();
+
+    let qb = qb.not_persistent();
+    assert!(!qb.persistent);
+    assert_eq!(qb.to_sql(), sql);
+}
+
+#[test]
+fn comment_sql() {
+    let base = TableInfo {
+        name: "users",
+        alias: "u".to_string(),
+        columns: vec!["id"],
+    };
+    let id = Column::<i32> {
+        name: "id",
+        table_alias: "u",
+        aliased_name: "u__id",
+        _marker: PhantomData,
+    };
+    let qb = QB::<()>::new(base.clone())
+        .filter(id.eq(1))
+        .comment("endpoint=GET /users?page=2")
+        .comment("evil=*/ DROP TABLE users");
+    let sql = normalize(&qb.to_sql());
+    assert!(
+        sql.ends_with("/* endpoint=GET /users?page=2, evil=* / DROP TABLE users */"),
+        "{}",
+        sql
+    );
+    assert_eq!(
+        normalize(&qb.debug_sql()),
+        "SELECT u.id AS u__id FROM \"users\" AS u WHERE u.id = 1 \
+         /* endpoint=GET /users?page=2, evil=* / DROP TABLE users */"
+    );
+
+    let delete = DeleteWhere::<()>::new(base).comment("job=cleanup");
+    assert_eq!(
+        normalize(&delete.to_sql()),
+        "DELETE FROM \"users\" AS u /* job=cleanup */"
+    );
+}
@@ -4,11 +4,12 @@
 //! on struct fields, converting them into the appropriate `EntityField` metadata
 //! for code generation.
 
-use syn::{Expr, Field, Ident, LitStr, Result, Token, parse::ParseStream};
+use syn::{Expr, Field, Ident, LitStr, Result, Token, Type, parse::ParseStream};
 
 use crate::{
     entity::{EntityField, FieldKind, TimestampKind},
-    relations::{Relation, RelationType},
+    relations::{OnRemove, Relation, RelationType, Through},
+    validators::Validator,
 };
 
 /// Parses a single struct field into an `EntityField` with all its metadata.
@@ -23,8 +24,24 @@ use crate::{
 ///
 /// ## `#[sql(...)]`
 /// - `pk` - Mark as primary key
+/// - `factory = expr` - With `pk`, generate the id client-side on insert, e.g. `ulid::Ulid::new()`
 /// - `unique` - Mark as unique (generates find_by_* methods)
 /// - `timestamp(field_name, factory_fn())` - Automatic timestamp management with custom factory
+/// - `default = expr` - Value inserted instead of `Default::default()`, e.g. `"pending".to_string()`
+/// - `json` - Store any `Serialize + DeserializeOwned` type as JSONB (postgres) or TEXT (sqlite)
+/// - `column_type = "NUMERIC(12,2)"` - SQL type of the column in generated DDL, e.g. for money,
+///   `citext` or custom domains
+/// - `encrypted = MyCipher` - Encrypt the column with a `sqlorm::Cipher`, equality filters
+///   require a `sqlorm::DeterministicCipher`
+/// - `immutable` - Write-once column, inserted but never updated, e.g. `created_by`
+/// - `soft_delete` - Boolean flag marking soft deleted rows, e.g. `is_deleted`, instead of a
+///   `deleted_at` timestamp
+/// - `version` - Optimistic locking counter, incremented and checked on every update
+/// - `flatten(street: String, city: String)` - Store the fields of an embedded `Default`
+///   struct in columns `{field}_street`, `{field}_city`; the column prefix is set with
+///   `prefix = "home_"`
+/// - `validate(length(min = 1, max = 255), range(min = 0), email, custom = check_fn)` - Checks
+///   run before writes, skipping `None` values of nullable fields
 /// - `relation(...)` - Define relationships
 ///
 pub fn parse_entity_field(field: &Field) -> Result<EntityField> {
@@ -32,6 +49,16 @@ pub fn parse_entity_field(field: &Field) -> Result<EntityField> {
     let ident = field.ident.clone().unwrap();
     let mut name = ident.to_string();
     let mut relations: Vec<Relation> = Vec::new();
+    let mut pk_factory: Option<Expr> = None;
+    let mut default: Option<Expr> = None;
+    let mut json = false;
+    let mut column_type: Option<String> = None;
+    let mut immutable = false;
+    let mut encrypted: Option<Type> = None;
+    let mut flatten: Option<Vec<(Ident, Type)>> = None;
+    let mut prefix: Option<LitStr> = None;
+    let mut validators: Vec<Validator> = Vec::new();
+    let field_ty = &field.ty;
 
     for attr in &field.attrs {
         if attr.path().is_ident("sql") {
@@ -50,7 +77,64 @@ pub fn parse_entity_field(field: &Field) -> Result<EntityField> {
                         kind = FieldKind::Ignored;
                     }
                     "pk" => {
-                        kind = FieldKind::PrimaryKey;
+                        kind = FieldKind::PrimaryKey { factory: None };
+                    }
+                    "factory" => {
+                        pk_factory = Some(meta.value()?.parse()?);
+                    }
+                    "default" => {
+                        default = Some(meta.value()?.parse()?);
+                    }
+                    "json" => {
+                        json = true;
+                    }
+                    "column_type" => {
+                        let ty: LitStr = meta.value()?.parse()?;
+                        if ty.value().trim().is_empty() {
+                            return Err(syn::Error::new_spanned(ty, "column type must not be empty"));
+                        }
+                        column_type = Some(ty.value());
+                    }
+                    "encrypted" => {
+                        encrypted = Some(meta.value()?.parse()?);
+                    }
+                    "immutable" => {
+                        immutable = true;
+                    }
+                    "flatten" => {
+                        let content;
+                        syn::parenthesized!(content in meta.input);
+                        let mut columns = Vec::new();
+                        while !content.is_empty() {
+                            let column: Ident = content.parse()?;
+                            content.parse::<Token![:]>()?;
+                            columns.push((column, content.parse::<Type>()?));
+                            if !content.is_empty() {
+                                content.parse::<Token![,]>()?;
+                            }
+                        }
+                        if columns.is_empty() {
+                            return Err(meta.error("`flatten` must list the embedded fields, e.g. `flatten(street: String)`"));
+                        }
+                        flatten = Some(columns);
+                    }
+                    "prefix" => {
+                        prefix = Some(meta.value()?.parse()?);
+                    }
+                    "validate" => {
+                        Validator::parse(&meta, &mut validators)?;
+                    }
+                    "version" => {
+                        kind = FieldKind::Version;
+                    }
+                    "soft_delete" => {
+                        if quote::quote!(#field_ty).to_string() != "bool" {
+                            return Err(syn::Error::new_spanned(
+                                field_ty,
+                                "`soft_delete` flag must be a `bool` field",
+                            ));
+                        }
+                        kind = FieldKind::SoftDelete;
                     }
                     "rename" => {
                         let content;
@@ -77,9 +161,106 @@ pub fn parse_entity_field(field: &Field) -> Result<EntityField> {
         }
     }
 
+    if let Some(factory) = pk_factory {
+        match &mut kind {
+            FieldKind::PrimaryKey { factory: pk } => *pk = Some(factory),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    factory,
+                    "`factory` is only supported on primary keys, use `#[sql(pk, factory = ...)]`",
+                ));
+            }
+        }
+    }
+
+    if let Some(default) = &default
+        && !matches!(kind, FieldKind::Regular { .. })
+    {
+        return Err(syn::Error::new_spanned(
+            default,
+            "`default` is only supported on regular fields, primary keys use `factory`",
+        ));
+    }
+
+    if json && !matches!(kind, FieldKind::Regular { .. }) {
+        return Err(syn::Error::new_spanned(
+            &field.ty,
+            "`json` is only supported on regular fields",
+        ));
+    }
+
+    if immutable && !matches!(kind, FieldKind::Regular { .. }) {
+        return Err(syn::Error::new_spanned(
+            &field.ty,
+            "`immutable` is only supported on regular fields",
+        ));
+    }
+
+    if let Some(cipher) = &encrypted
+        && (json || immutable || !matches!(kind, FieldKind::Regular { .. }))
+    {
+        return Err(syn::Error::new_spanned(
+            cipher,
+            "`encrypted` is only supported on regular fields without `json` or `immutable`",
+        ));
+    }
+
+    if let Some(prefix) = &prefix
+        && flatten.is_none()
+    {
+        return Err(syn::Error::new_spanned(
+            prefix,
+            "`prefix` is only supported together with `flatten(...)`",
+        ));
+    }
+
+    let mut flattened = Vec::new();
+    if let Some(columns) = flatten {
+        if !matches!(kind, FieldKind::Regular { unique: false })
+            || json
+            || default.is_some()
+            || encrypted.is_some()
+            || !relations.is_empty()
+        {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "`flatten` can only be combined with `prefix` and `immutable`",
+            ));
+        }
+        // the embedded struct is filled column by column, the field itself is not stored
+        kind = FieldKind::Ignored;
+        let prefix = prefix.map_or_else(|| format!("{}_", ident), |p| p.value());
+        for (column, ty) in columns {
+            let column_ident = quote::format_ident!("{}{}", prefix, column);
+            flattened.push(EntityField {
+                name: column_ident.to_string(),
+                ident: column_ident,
+                ty,
+                kind: FieldKind::Regular { unique: false },
+                relations: None,
+                default: None,
+                json: false,
+                column_type: None,
+                immutable,
+                encrypted: None,
+                flattened: Vec::new(),
+                embedded: Some((ident.clone(), column)),
+                validators: Vec::new(),
+            });
+        }
+    }
+
     Ok(EntityField {
         ident,
         ty: field.ty.clone(),
+        default,
+        json,
+        column_type,
+        immutable: immutable && flattened.is_empty(),
+        encrypted,
+        flattened,
+        embedded: None,
+        validators,
         name,
         kind,
         relations: if relations.is_empty() {
@@ -99,7 +280,7 @@ pub fn parse_entity_field(field: &Field) -> Result<EntityField> {
 /// ```
 ///
 /// Where:
-/// - `TYPE` is one of: `belongs_to`, `has_many`, `has_one`
+/// - `TYPE` is one of: `belongs_to`, `has_many`, `has_one`, `many_to_many`
 /// - `TargetEntity` is the related entity struct name
 /// - `"field_name"` is the name of the field that will hold the relationship
 /// - `foreign_key` is the field name that contains the foreign key
@@ -113,17 +294,60 @@ pub fn parse_entity_field(field: &Field) -> Result<EntityField> {
 ///
 /// This creates a `has_many` relationship to `Post` entities, accessible via
 /// a `posts` field, where the `Post` table has a `user_id` foreign key.
+///
+/// `many_to_many` relations also name the join table and its two key columns,
+/// referencing this field and the target's `on` field respectively:
+///
+/// ```ignore
+/// #[sql(relation(many_to_many -> Tag, name = "tags", on = id, through = "post_tags", keys = (post_id, tag_id)))]
+/// pub id: i64,
+/// ```
+///
+/// `has_many` relations may set what `remove_<child>()` does with the removed row:
+/// `on_remove = delete` (default) deletes it, `on_remove = nullify` resets its foreign key.
+///
+/// ```ignore
+/// #[sql(relation(has_many -> Post, name = "posts", on = author_id, on_remove = nullify))]
+/// pub id: i64,
+/// ```
+///
+/// `has_many` relations may opt into `load = json` (Postgres only), generating
+/// `with_<relation>_json()` which loads children in the parent query through `json_agg()`.
+/// The target must implement `serde::Deserialize` with keys matching its column names.
+///
+/// ```ignore
+/// #[sql(relation(has_many -> Post, name = "posts", on = author_id, load = json))]
+/// pub id: i64,
+/// ```
+///
+/// `has_many` and `many_to_many` relations may opt into `count`, injecting a
+/// `<relation>_count: Option<i64>` field filled by `with_<relation>_count()`.
+///
+/// ```ignore
+/// #[sql(relation(has_many -> Post, name = "posts", on = author_id, count))]
+/// pub id: i64,
+/// ```
+///
+/// `has_many` relations may go through an intermediate entity instead, naming its field
+/// referencing this field and its field referenced by the target's `on` field:
+///
+/// ```ignore
+/// // donations received across all jars owned by the user
+/// #[sql(relation(has_many -> Donation, name = "received_donations", on = jar_id, through = Jar, keys = (owner_id, id)))]
+/// pub id: i64,
+/// ```
 pub fn parse_relation(input: ParseStream, self_ident: Ident) -> Result<Relation> {
     let rel_type_ident: Ident = input.parse()?;
     let relation_type = match rel_type_ident.to_string().as_str() {
         "belongs_to" => RelationType::BelongsTo,
         "has_many" => RelationType::HasMany,
         "has_one" => RelationType::HasOne,
+        "many_to_many" => RelationType::ManyToMany,
         other => {
             return Err(syn::Error::new_spanned(
                 rel_type_ident,
                 format!(
-                    "invalid relation type `{}`. Expected one of: belongs_to, has_many, has_one",
+                    "invalid relation type `{}`. Expected one of: belongs_to, has_many, has_one, many_to_many",
                     other
                 ),
             ));
@@ -144,6 +368,12 @@ pub fn parse_relation(input: ParseStream, self_ident: Ident) -> Result<Relation>
     input.parse::<Token![=]>()?;
     let relation_val: LitStr = input.parse()?;
     let relation_name = relation_val.value();
+    if relation_name == "all" {
+        return Err(syn::Error::new_spanned(
+            relation_val,
+            "relation name `all` is reserved for the generated `with_all()`",
+        ));
+    }
 
     input.parse::<Token![,]>()?;
     let on_ident_kw: Ident = input.parse()?;
@@ -153,14 +383,160 @@ pub fn parse_relation(input: ParseStream, self_ident: Ident) -> Result<Relation>
     input.parse::<Token![=]>()?;
     let other_field: Ident = input.parse()?;
 
+    let mut through_table = None;
+    let mut through_keys = None;
+    let mut on_remove = None;
+    let mut load_json = false;
+    let mut count = false;
+    while input.peek(Token![,]) {
+        input.parse::<Token![,]>()?;
+        if input.is_empty() {
+            break;
+        }
+        let key: Ident = input.parse()?;
+        if key == "count" {
+            count = true;
+            continue;
+        }
+        input.parse::<Token![=]>()?;
+        match key.to_string().as_str() {
+            "through" => {
+                if input.peek(LitStr) {
+                    let table: LitStr = input.parse()?;
+                    through_table = Some(ThroughTarget::Table(table.value()));
+                } else {
+                    through_table = Some(ThroughTarget::Entity(input.parse()?));
+                }
+            }
+            "keys" => {
+                let content;
+                syn::parenthesized!(content in input);
+                let self_key: Ident = content.parse()?;
+                content.parse::<Token![,]>()?;
+                let other_key: Ident = content.parse()?;
+                through_keys = Some((self_key, other_key));
+            }
+            "on_remove" => {
+                let strategy: Ident = input.parse()?;
+                on_remove = Some(match strategy.to_string().as_str() {
+                    "delete" => OnRemove::Delete,
+                    "nullify" => OnRemove::Nullify,
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            strategy,
+                            "expected `delete` or `nullify`",
+                        ));
+                    }
+                });
+            }
+            "load" => {
+                let strategy: Ident = input.parse()?;
+                load_json = match strategy.to_string().as_str() {
+                    "batch" => false,
+                    "json" => true,
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            strategy,
+                            "expected `batch` or `json`",
+                        ));
+                    }
+                };
+                if load_json && !cfg!(feature = "postgres") {
+                    return Err(syn::Error::new_spanned(
+                        strategy,
+                        "`load = json` requires the postgres feature",
+                    ));
+                }
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "unexpected relation option, expected `through`, `keys`, `on_remove`, `load` or `count`",
+                ));
+            }
+        }
+    }
+
+    let through = match (&relation_type, through_table, through_keys) {
+        (
+            RelationType::ManyToMany,
+            Some(ThroughTarget::Table(table)),
+            Some((self_key, other_key)),
+        ) => Some(Through::Table {
+            table,
+            self_key,
+            other_key,
+        }),
+        (RelationType::ManyToMany, _, _) => {
+            return Err(syn::Error::new_spanned(
+                rel_type_ident,
+                "many_to_many requires `through = \"join_table\", keys = (my_key, other_key)`",
+            ));
+        }
+        (
+            RelationType::HasMany,
+            Some(ThroughTarget::Entity(entity)),
+            Some((self_key, other_key)),
+        ) => Some(Through::Entity {
+            entity,
+            self_key,
+            other_key,
+        }),
+        (RelationType::HasMany, Some(_), _) | (RelationType::HasMany, _, Some(_)) => {
+            return Err(syn::Error::new_spanned(
+                rel_type_ident,
+                "has_many through requires `through = Entity, keys = (entity_key_to_me, entity_key_to_other)`",
+            ));
+        }
+        (_, None, None) => None,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                rel_type_ident,
+                "`through` and `keys` are only supported by has_many and many_to_many",
+            ));
+        }
+    };
+
+    if on_remove.is_some() && !(matches!(relation_type, RelationType::HasMany) && through.is_none())
+    {
+        return Err(syn::Error::new_spanned(
+            rel_type_ident,
+            "`on_remove` is only supported by has_many relations without `through`",
+        ));
+    }
+
+    if load_json && !(matches!(relation_type, RelationType::HasMany) && through.is_none()) {
+        return Err(syn::Error::new_spanned(
+            rel_type_ident,
+            "`load = json` is only supported by has_many relations without `through`",
+        ));
+    }
+
+    if count && !matches!(relation_type, RelationType::HasMany | RelationType::ManyToMany) {
+        return Err(syn::Error::new_spanned(
+            rel_type_ident,
+            "`count` is only supported by has_many and many_to_many relations",
+        ));
+    }
+
     Ok(Relation {
         kind: relation_type,
         other: ref_table,
         relation_name,
         on: (self_ident, other_field),
+        through,
+        on_remove: on_remove.unwrap_or_default(),
+        load_json,
+        count,
     })
 }
 
+/// Value of a relation's `through = ...` option.
+enum ThroughTarget {
+    Table(String),
+    Entity(Ident),
+}
+
 /// Parses a timestamp attribute into a `TimestampKind`.
 ///
 /// Expected syntax:
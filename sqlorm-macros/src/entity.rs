@@ -11,9 +11,11 @@ use syn::{
 
 use crate::{
     attrs::{self},
-    gen_columns,
+    gen_columns, projection,
     relations::{self, validate_relations},
     sql,
+    observers,
+    validators::{self, Validator},
 };
 
 /// Represents a single field in an entity struct during macro processing.
@@ -32,6 +34,26 @@ pub struct EntityField {
     pub kind: FieldKind,
     /// Associated relationships if any (has_many, belongs_to, etc.)
     pub relations: Option<Vec<relations::Relation>>,
+    /// Value applied on insert when the field is `Default::default()`, set with
+    /// `#[sql(default = expr)]`
+    pub default: Option<Expr>,
+    /// Stored as JSON through serde, set with `#[sql(json)]`
+    pub json: bool,
+    /// SQL type used for the column in generated DDL instead of the one mapped from `ty`,
+    /// set with `#[sql(column_type = "NUMERIC(12,2)")]`
+    pub column_type: Option<String>,
+    /// Written by INSERT only, set with `#[sql(immutable)]`
+    pub immutable: bool,
+    /// Cipher encrypting the column, set with `#[sql(encrypted = MyCipher)]`
+    pub encrypted: Option<Type>,
+    /// Columns expanded from a `#[sql(flatten(street: String, ...))]` field, which is itself
+    /// skipped
+    pub flattened: Vec<EntityField>,
+    /// For a column expanded from a flattened field, the flattened field and the field of the
+    /// embedded struct it is stored in, e.g. `(address, street)`
+    pub embedded: Option<(Ident, Ident)>,
+    /// Checks run before writes, set with `#[sql(validate(length(max = 255), email))]`
+    pub validators: Vec<Validator>,
 }
 
 /// Categorizes the semantic meaning of an entity field for code generation.
@@ -40,11 +62,18 @@ pub struct EntityField {
 #[derive(Debug, Clone)]
 pub enum FieldKind {
     /// Primary key field marked with `#[sql(pk)]`
-    PrimaryKey,
+    PrimaryKey {
+        /// Client-side id generator set with `#[sql(pk, factory = expr)]`
+        factory: Option<Expr>,
+    },
     /// Timestamp field with automatic management
     Timestamp(TimestampKind),
     /// Field excluded from SQL operations via `#[sql(skip)]`
     Ignored,
+    /// Optimistic locking counter marked with `#[sql(version)]`
+    Version,
+    /// Boolean soft delete flag marked with `#[sql(soft_delete)]`, e.g. `is_deleted`
+    SoftDelete,
     /// Regular database field
     Regular {
         /// Whether the field is unique (generates `find_by_*` methods)
@@ -66,6 +95,57 @@ pub enum TimestampKind {
     Deleted { factory: Expr },
 }
 
+/// How soft deleted rows are marked, see [`EntityStruct::soft_delete`].
+pub enum SoftDelete<'a> {
+    /// Nullable timestamp set to `factory`, from `#[sql(timestamp(deleted_at, factory))]`
+    Timestamp {
+        field: &'a EntityField,
+        factory: &'a Expr,
+    },
+    /// Boolean flag set to `true`, from `#[sql(soft_delete)]`
+    Flag { field: &'a EntityField },
+}
+
+impl<'a> SoftDelete<'a> {
+    pub fn field(&self) -> &'a EntityField {
+        match self {
+            SoftDelete::Timestamp { field, .. } | SoftDelete::Flag { field } => field,
+        }
+    }
+
+    /// Value bound to the column when deleting.
+    pub fn deleted_value(&self) -> TokenStream {
+        match self {
+            SoftDelete::Timestamp { factory, .. } => quote! { #factory },
+            SoftDelete::Flag { .. } => quote! { true },
+        }
+    }
+
+    /// Field value of a deleted entity, given the bound `value`.
+    pub fn deleted_field_value(&self, value: &TokenStream) -> TokenStream {
+        match self {
+            SoftDelete::Timestamp { .. } => quote! { Some(#value) },
+            SoftDelete::Flag { .. } => quote! { #value },
+        }
+    }
+
+    /// SQL literal of the column for rows not deleted.
+    pub fn restored_sql(&self) -> &'static str {
+        match self {
+            SoftDelete::Timestamp { .. } => "NULL",
+            SoftDelete::Flag { .. } => "FALSE",
+        }
+    }
+
+    /// Field value of an entity not deleted.
+    pub fn restored_field_value(&self) -> TokenStream {
+        match self {
+            SoftDelete::Timestamp { .. } => quote! { None },
+            SoftDelete::Flag { .. } => quote! { false },
+        }
+    }
+}
+
 /// Complete representation of an entity struct during macro processing.
 ///
 /// Contains all information needed to generate the full set of database methods,
@@ -82,14 +162,171 @@ pub struct EntityStruct {
     pub pk: EntityField,
     /// All relationships defined on this entity
     pub relations: Vec<relations::Relation>,
+    /// Secondary indexes declared with `#[table(index(columns(a, b), unique))]`, including
+    /// unique constraints declared with `#[table(unique(a, b))]`
+    pub indexes: Vec<Index>,
+    /// Column subsets declared with `#[table(projection(Summary = (id, email)))]`
+    pub projections: Vec<NamedProjection>,
+    /// Generated pieces opted out of with `#[table(skip_generate(...))]`
+    pub skip_generate: SkipGenerate,
+    /// Visibility of generated traits, structs, methods and constants, `pub` unless set with
+    /// `#[table(visibility = "pub(crate)")]`
+    pub vis: syn::Visibility,
+    /// Whether writes run the entity's `sqlorm::Hooks`, set with `#[table(hooks)]`
+    pub hooks: bool,
+    /// Whether writes run the entity's `sqlorm::Validate`, set with `#[table(validate)]`
+    pub validate: bool,
+    /// Whether writes notify the observers of the entity, set with `#[table(observable)]`
+    pub observable: bool,
+    /// Kind of relation backing the entity, a view with `#[table(view)]` or
+    /// `#[table(materialized_view)]`
+    pub kind: TableKind,
+}
+
+/// What an entity is read from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TableKind {
+    #[default]
+    Table,
+    /// Read-only view, set with `#[table(view)]`: inserts, updates and deletes are not generated
+    View,
+    /// Read-only postgres materialized view, set with `#[table(materialized_view)]`, also
+    /// generating `refresh()`
+    MaterializedView,
+}
+
+impl TableKind {
+    /// Whether rows can't be written through the entity.
+    pub fn is_read_only(&self) -> bool {
+        !matches!(self, TableKind::Table)
+    }
+}
+
+/// Generated API surface disabled with `#[table(skip_generate(find_by, delete, relations_trait))]`.
+#[derive(Debug, Default)]
+pub struct SkipGenerate {
+    /// `find_by_*` methods of unique fields and indexes
+    pub find_by: bool,
+    /// `delete()`, `force_delete()`, `delete_where()` and `truncate()`
+    pub delete: bool,
+    /// The `{Entity}Relations` trait with `with_*` loaders
+    pub relations_trait: bool,
+}
+
+impl SkipGenerate {
+    fn parse(meta: &syn::meta::ParseNestedMeta, skip: &mut Self) -> Result<()> {
+        meta.parse_nested_meta(|inner| {
+            if inner.path.is_ident("find_by") {
+                skip.find_by = true;
+            } else if inner.path.is_ident("delete") {
+                skip.delete = true;
+            } else if inner.path.is_ident("relations_trait") {
+                skip.relations_trait = true;
+            } else {
+                return Err(inner.error("expected `find_by`, `delete` or `relations_trait`"));
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Secondary index declared on the table.
+#[derive(Debug)]
+pub struct Index {
+    /// Indexed fields, in index order
+    pub columns: Vec<Ident>,
+    /// Whether the index is `UNIQUE`
+    pub unique: bool,
+}
+
+impl Index {
+    fn parse(meta: &syn::meta::ParseNestedMeta) -> Result<Self> {
+        let mut columns = Vec::new();
+        let mut unique = false;
+        meta.parse_nested_meta(|inner| {
+            if inner.path.is_ident("columns") {
+                inner.parse_nested_meta(|col| {
+                    columns.push(col.path.require_ident()?.clone());
+                    Ok(())
+                })
+            } else if inner.path.is_ident("unique") {
+                unique = true;
+                Ok(())
+            } else {
+                Err(inner.error("expected `columns(...)` or `unique`"))
+            }
+        })?;
+        if columns.is_empty() {
+            return Err(meta.error("index must list at least one column in `columns(...)`"));
+        }
+        Ok(Self { columns, unique })
+    }
+
+    /// Parses the `unique(a, b)` constraint shorthand for `index(columns(a, b), unique)`.
+    fn parse_unique(meta: &syn::meta::ParseNestedMeta) -> Result<Self> {
+        let mut columns = Vec::new();
+        meta.parse_nested_meta(|col| {
+            columns.push(col.path.require_ident()?.clone());
+            Ok(())
+        })?;
+        if columns.is_empty() {
+            return Err(meta.error("unique constraint must list at least one column"));
+        }
+        Ok(Self {
+            columns,
+            unique: true,
+        })
+    }
+}
+
+/// Projection DTO declared on the table, generated as `{Entity}{name}`.
+#[derive(Debug)]
+pub struct NamedProjection {
+    /// Suffix of the generated struct, e.g. `Summary` for `UserSummary`
+    pub name: Ident,
+    /// Selected fields, in declaration order
+    pub columns: Vec<Ident>,
+}
+
+impl NamedProjection {
+    /// Parses `projection(Summary = (id, email), ...)`.
+    fn parse(meta: &syn::meta::ParseNestedMeta, out: &mut Vec<Self>) -> Result<()> {
+        meta.parse_nested_meta(|inner| {
+            let name = inner.path.require_ident()?.clone();
+            let content;
+            let value = inner.value()?;
+            syn::parenthesized!(content in value);
+            let columns: Vec<Ident> =
+                syn::punctuated::Punctuated::<Ident, syn::Token![,]>::parse_terminated(&content)?
+                    .into_iter()
+                    .collect();
+            if columns.is_empty() {
+                return Err(inner.error("projection must list at least one column"));
+            }
+            out.push(Self { name, columns });
+            Ok(())
+        })
+    }
 }
 
 #[derive(Debug)]
 pub struct TableName {
     /// Either struct name (`"User".to_lowercase()`), or user-defined value (`#[table(name = "users")]`). Always lowercase.
     pub raw: String,
-    /// Usually `"__" + self.raw.to_lowercase()`
+    /// Usually `"__" + self.raw.to_lowercase()`, `"__{schema.len()}_{schema}_{raw}"` when schema-qualified
     pub alias: String,
+    /// Schema the table lives in, set with `#[table(schema = "billing")]`
+    pub schema: Option<String>,
+}
+
+impl TableName {
+    /// Name as used in SQL before quoting, `schema.raw` when schema-qualified.
+    pub fn qualified(&self) -> String {
+        match &self.schema {
+            Some(schema) => format!("{}.{}", schema, self.raw),
+            None => self.raw.clone(),
+        }
+    }
 }
 
 impl Parse for EntityStruct {
@@ -97,6 +334,15 @@ impl Parse for EntityStruct {
         let derive_input: DeriveInput = input.parse()?;
         let struct_ident = derive_input.ident.clone();
 
+        let mut indexes = Vec::new();
+        let mut projections = Vec::new();
+        let mut skip_generate = SkipGenerate::default();
+        let mut vis: syn::Visibility = syn::parse_quote!(pub);
+        let mut schema = None;
+        let mut kind = TableKind::Table;
+        let mut hooks = false;
+        let mut validate = false;
+        let mut observable = false;
         let table_name_raw = {
             let mut result = None;
             for attr in &derive_input.attrs {
@@ -106,6 +352,45 @@ impl Parse for EntityStruct {
                             let lit: syn::LitStr = meta.value()?.parse()?;
                             result = Some(lit.value());
                             Ok(())
+                        } else if meta.path.is_ident("schema") {
+                            let lit: syn::LitStr = meta.value()?.parse()?;
+                            schema = Some(lit.value());
+                            Ok(())
+                        } else if meta.path.is_ident("index") {
+                            indexes.push(Index::parse(&meta)?);
+                            Ok(())
+                        } else if meta.path.is_ident("unique") {
+                            indexes.push(Index::parse_unique(&meta)?);
+                            Ok(())
+                        } else if meta.path.is_ident("projection") {
+                            NamedProjection::parse(&meta, &mut projections)
+                        } else if meta.path.is_ident("skip_generate") {
+                            SkipGenerate::parse(&meta, &mut skip_generate)
+                        } else if meta.path.is_ident("hooks") {
+                            hooks = true;
+                            Ok(())
+                        } else if meta.path.is_ident("validate") {
+                            validate = true;
+                            Ok(())
+                        } else if meta.path.is_ident("observable") {
+                            observable = true;
+                            Ok(())
+                        } else if meta.path.is_ident("view") {
+                            kind = TableKind::View;
+                            Ok(())
+                        } else if meta.path.is_ident("materialized_view") {
+                            if !cfg!(feature = "postgres") {
+                                return Err(syn::Error::new_spanned(
+                                    &struct_ident,
+                                    "materialized views are only supported by postgres",
+                                ));
+                            }
+                            kind = TableKind::MaterializedView;
+                            Ok(())
+                        } else if meta.path.is_ident("visibility") {
+                            let lit: syn::LitStr = meta.value()?.parse()?;
+                            vis = lit.parse()?;
+                            Ok(())
                         } else {
                             Err(syn::Error::new_spanned(
                                 &struct_ident,
@@ -118,15 +403,20 @@ impl Parse for EntityStruct {
             result
         }
         .ok_or_else(|| {
-            return syn::Error::new_spanned(
+            syn::Error::new_spanned(
                 &struct_ident,
                 "If you see this, something bad has happened. Contact maintainer",
-            );
+            )
         })?;
-        let alias = format!("__{}", table_name_raw);
+        let alias = match &schema {
+            // the length prefix keeps `a_b.c`, `a.b_c` and unqualified `a_b_c` apart
+            Some(schema) => format!("__{}_{}_{}", schema.len(), schema, table_name_raw),
+            None => format!("__{}", table_name_raw),
+        };
         let table_name = TableName {
             raw: table_name_raw,
             alias,
+            schema,
         };
 
         let fields: Vec<EntityField> = match derive_input.data {
@@ -135,7 +425,13 @@ impl Parse for EntityStruct {
                     .named
                     .into_iter()
                     .map(|f: Field| attrs::parse_entity_field(&f))
-                    .collect::<Result<Vec<_>>>()?,
+                    .collect::<Result<Vec<_>>>()?
+                    .into_iter()
+                    .flat_map(|f| {
+                        let columns = f.flattened.clone();
+                        std::iter::once(f).chain(columns)
+                    })
+                    .collect(),
                 _ => {
                     return Err(syn::Error::new_spanned(
                         struct_ident,
@@ -169,7 +465,7 @@ impl Parse for EntityStruct {
 
         let pk_count = fields
             .iter()
-            .filter(|f| matches!(f.kind, FieldKind::PrimaryKey))
+            .filter(|f| f.is_pk())
             .count();
 
         if pk_count == 0 {
@@ -178,12 +474,51 @@ impl Parse for EntityStruct {
                 "Entity must have a primary key",
             ));
         }
+        let soft_delete_count = fields
+            .iter()
+            .filter(|f| {
+                matches!(
+                    f.kind,
+                    FieldKind::SoftDelete | FieldKind::Timestamp(TimestampKind::Deleted { .. })
+                )
+            })
+            .count();
+        if soft_delete_count > 1 {
+            return Err(syn::Error::new_spanned(
+                struct_ident,
+                "Entity must have at most 1 soft delete field (`deleted_at` or `soft_delete`)",
+            ));
+        }
+        if fields.iter().filter(|f| f.is_version()).count() > 1 {
+            return Err(syn::Error::new_spanned(
+                struct_ident,
+                "Entity must have at most 1 version field",
+            ));
+        }
         if pk_count > 1 {
             return Err(syn::Error::new_spanned(
                 struct_ident,
                 "Entity must have only 1 primary key",
             ));
         }
+        for column in indexes.iter().flat_map(|i| &i.columns) {
+            if !fields.iter().any(|f| &f.ident == column && !f.is_ignored()) {
+                return Err(syn::Error::new_spanned(
+                    column,
+                    "index column must be a non-skipped field of this struct",
+                ));
+            }
+        }
+
+        for column in projections.iter().flat_map(|p| &p.columns) {
+            let field = fields.iter().find(|f| &f.ident == column && !f.is_ignored());
+            if !field.is_some_and(|f| !f.json && f.encrypted.is_none()) {
+                return Err(syn::Error::new_spanned(
+                    column,
+                    "projection column must be a non-skipped, non-json, non-encrypted field of this struct",
+                ));
+            }
+        }
 
         Ok(Self {
             struct_ident,
@@ -191,6 +526,33 @@ impl Parse for EntityStruct {
             fields,
             relations,
             pk,
+            indexes,
+            projections,
+            skip_generate,
+            vis,
+            hooks,
+            validate,
+            observable,
+            kind,
+        })
+    }
+}
+
+impl EntityStruct {
+    /// Returns true if writes validate the entity, i.e. it has `#[sql(validate(...))]` fields
+    /// or is declared with `#[table(validate)]`.
+    pub fn validates(&self) -> bool {
+        self.validate || self.fields.iter().any(|f| !f.validators.is_empty())
+    }
+
+    /// Soft delete column of the entity, either a `deleted_at` timestamp or a boolean flag.
+    pub fn soft_delete(&self) -> Option<SoftDelete<'_>> {
+        self.fields.iter().find_map(|field| match &field.kind {
+            FieldKind::Timestamp(TimestampKind::Deleted { factory }) => {
+                Some(SoftDelete::Timestamp { field, factory })
+            }
+            FieldKind::SoftDelete => Some(SoftDelete::Flag { field }),
+            _ => None,
         })
     }
 }
@@ -201,12 +563,24 @@ pub fn handle(es: EntityStruct) -> TokenStream {
     let relations = relations::relations(&es);
     let traits = traits::traits(&es);
     let qb = qb::qb(&es);
-    let sb = sb::sb(&es);
+    // views are read-only, so neither inserts nor statement builders are generated
+    let sb = if es.kind.is_read_only() {
+        quote! {}
+    } else {
+        sb::sb(&es)
+    };
+    let projections = projection::entity_projections(&es);
+    let validate = validators::validate(&es);
+    let observable = observers::observable(&es);
     quote! (
         #cols
 
         #sql
 
+        #validate
+
+        #observable
+
         #relations
 
         #traits
@@ -214,13 +588,23 @@ pub fn handle(es: EntityStruct) -> TokenStream {
         #qb
 
         #sb
+
+        #projections
     )
 }
 
 impl EntityField {
     /// Returns true if this field is the primary key.
     pub fn is_pk(&self) -> bool {
-        matches!(self.kind, FieldKind::PrimaryKey)
+        matches!(self.kind, FieldKind::PrimaryKey { .. })
+    }
+
+    /// Returns the id generator of a primary key marked with `#[sql(pk, factory = expr)]`.
+    pub fn pk_factory(&self) -> Option<&Expr> {
+        match &self.kind {
+            FieldKind::PrimaryKey { factory } => factory.as_ref(),
+            _ => None,
+        }
     }
 
     /// Returns true if this field is unique (either primary key or marked as unique).
@@ -228,7 +612,7 @@ impl EntityField {
     /// Unique fields generate `find_by_*` methods.
     pub fn is_unique(&self) -> bool {
         match self.kind {
-            FieldKind::PrimaryKey => true,
+            FieldKind::PrimaryKey { .. } => true,
             FieldKind::Regular { unique } => unique,
             _ => false,
         }
@@ -241,4 +625,69 @@ impl EntityField {
     pub fn is_ignored(&self) -> bool {
         matches!(self.kind, FieldKind::Ignored)
     }
+
+    /// Returns true if this field is the optimistic locking version.
+    pub fn is_version(&self) -> bool {
+        matches!(self.kind, FieldKind::Version)
+    }
+
+    /// Expression binding this field of `owner`, e.g. `&self.email`.
+    ///
+    /// `#[sql(json)]` fields are wrapped in `sqlx::types::Json`, nullable ones bind `None`
+    /// as SQL `NULL` rather than JSON `null`. `#[sql(encrypted = C)]` fields bind ciphertext.
+    pub fn bind_value(&self, owner: &TokenStream) -> TokenStream {
+        let value = self.access(owner);
+        if let Some(cipher) = &self.encrypted {
+            return quote! { ::sqlorm::encrypt::<#cipher, _>(&#value) };
+        }
+        match (self.json, self.is_nullable()) {
+            (true, true) => quote! { #value.as_ref().map(::sqlorm::sqlx::types::Json) },
+            (true, false) => quote! { ::sqlorm::sqlx::types::Json(&#value) },
+            (false, _) => quote! { &#value },
+        }
+    }
+
+    /// Place expression of this field on `owner`, e.g. `self.email`, or `self.address.street`
+    /// for a column expanded from a flattened field.
+    pub fn access(&self, owner: &TokenStream) -> TokenStream {
+        match &self.embedded {
+            Some((parent, field)) => quote! { #owner.#parent.#field },
+            None => {
+                let ident = &self.ident;
+                quote! { #owner.#ident }
+            }
+        }
+    }
+
+    /// Expression reading this field from `row`'s column `column`, propagating errors.
+    pub fn decode_value(&self, column: &TokenStream) -> TokenStream {
+        let ty = &self.ty;
+        if let Some(cipher) = &self.encrypted {
+            return quote! {{
+                let column: &str = #column;
+                ::sqlorm::decrypt::<#cipher, #ty>(
+                    column,
+                    row.try_get::<Option<Vec<u8>>, &str>(column)?,
+                )?
+            }};
+        }
+        match (self.json, self.is_nullable()) {
+            (true, true) => quote! {
+                row.try_get::<Option<::sqlorm::sqlx::types::Json<#ty>>, &str>(#column)?
+                    .and_then(|json| json.0)
+            },
+            (true, false) => {
+                quote! { row.try_get::<::sqlorm::sqlx::types::Json<#ty>, &str>(#column)?.0 }
+            }
+            (false, _) => quote! { row.try_get::<#ty, &str>(#column)? },
+        }
+    }
+
+    /// Returns true if this field is an `Option<T>`, i.e. a nullable column.
+    pub fn is_nullable(&self) -> bool {
+        matches!(
+            &self.ty,
+            Type::Path(path) if path.path.segments.last().is_some_and(|seg| seg.ident == "Option")
+        )
+    }
 }
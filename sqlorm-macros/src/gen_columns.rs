@@ -1,11 +1,12 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use sqlorm_core::format_alised_col_name;
+use sqlorm_core::{format_alised_col_name, with_quotes};
 use syn::Ident;
 
 use crate::entity::{EntityField, EntityStruct};
 
 pub fn handle(entity: &EntityStruct) -> TokenStream {
+    let vis = &entity.vis;
     let struct_ident = entity.struct_ident.clone();
     let fields: Vec<&EntityField> = entity.fields.iter().filter(|f| !f.is_ignored()).collect();
     let field_count = fields.len();
@@ -14,12 +15,48 @@ pub fn handle(entity: &EntityStruct) -> TokenStream {
 
     let field_names: Vec<String> = fields.iter().map(|f| f.name.clone()).collect();
 
+    let table_name = entity.table_name.qualified();
+    let sql_name = with_quotes(&table_name);
+    // `"id", "email", ...` for raw `SELECT` lists
+    let all_columns = field_names
+        .iter()
+        .map(|name| with_quotes(name))
+        .collect::<Vec<_>>()
+        .join(", ");
+
     let aliased_field_names: Vec<String> = fields
         .iter()
-        .map(|f| format_alised_col_name(table_alias, &f.ident.to_string()))
+        .map(|f| format_alised_col_name(table_alias, &f.name))
+        .collect();
+
+    // immutable columns are rejected by `update().columns(...)` and `set(...)`
+    let column_tys: Vec<TokenStream> = fields.iter().map(|f| column_type(f)).collect();
+    let column_structs: Vec<TokenStream> = fields
+        .iter()
+        .map(|f| match f.encrypted {
+            Some(_) => quote! { sqlorm::EncryptedColumn },
+            None => quote! { sqlorm::Column },
+        })
         .collect();
 
-    let field_ty: Vec<&syn::Type> = fields.iter().map(|f| &f.ty).collect();
+    let column_meta: Vec<TokenStream> = fields
+        .iter()
+        .map(|f| {
+            let ty = &f.ty;
+            let name = &f.name;
+            let rust_type = quote!(#ty).to_string().replace(' ', "");
+            let (nullable, is_pk, is_unique) = (f.is_nullable(), f.is_pk(), f.is_unique());
+            quote! {
+                sqlorm::ColumnMeta {
+                    name: #name,
+                    rust_type: #rust_type,
+                    nullable: #nullable,
+                    is_pk: #is_pk,
+                    is_unique: #is_unique,
+                }
+            }
+        })
+        .collect();
 
     let const_idents: Vec<Ident> = fields
         .iter()
@@ -29,17 +66,81 @@ pub fn handle(entity: &EntityStruct) -> TokenStream {
         })
         .collect();
 
+    // `(Self::TENANT_ID, Self::EMAIL)` for every multi-column unique constraint
+    let unique_constraints: Vec<TokenStream> = entity
+        .indexes
+        .iter()
+        .filter(|index| index.unique && index.columns.len() > 1)
+        .map(|index| {
+            let names: Vec<String> = index.columns.iter().map(|c| c.to_string()).collect();
+            let const_ident = Ident::new(
+                &names.join("_and_").to_uppercase(),
+                index.columns[0].span(),
+            );
+            let doc = format!(
+                "Columns of the unique constraint on ({}), e.g. for `upsert_many().on_conflict(...)`.",
+                names.join(", ")
+            );
+            let cols: Vec<Ident> = index
+                .columns
+                .iter()
+                .map(|c| Ident::new(&c.to_string().to_uppercase(), c.span()))
+                .collect();
+            let tys: Vec<TokenStream> = index
+                .columns
+                .iter()
+                .map(|c| column_type(entity.fields.iter().find(|f| &f.ident == c).unwrap()))
+                .collect();
+            quote! {
+                #[doc = #doc]
+                #vis const #const_ident: (#(#tys,)*) = (#(Self::#cols,)*);
+            }
+        })
+        .collect();
+
     quote! {
         #[automatically_derived]
         impl #struct_ident {
+            /// Table name, schema-qualified when `#[table(schema = ...)]` is set.
+            #vis const TABLE_NAME: &'static str = #table_name;
+
+            /// Quoted table name, ready to be used in raw SQL.
+            #vis const SQL_NAME: &'static str = #sql_name;
+
+            /// Quoted, comma separated column list, e.g. for `SELECT {ALL_COLUMNS} FROM {SQL_NAME}`.
+            #vis const ALL_COLUMNS: &'static str = #all_columns;
+
             /// All column names of this entity in declaration order.
-            pub const COLUMNS: [&'static str; #field_count] = [#(#field_names),*];
+            #vis const COLUMNS: [&'static str; #field_count] = [#(#field_names),*];
+
+            /// Metadata of all columns in declaration order, see [`sqlorm::Table::columns`].
+            #vis const COLUMN_META: [sqlorm::ColumnMeta; #field_count] = [#(#column_meta),*];
+
+            /// Quoted column name qualified with the table, e.g. `"users"."email"` for
+            /// `User::qualified(User::EMAIL)`.
+            #vis fn qualified(column: impl AsRef<str>) -> String {
+                format!("{}.{}", Self::SQL_NAME, sqlorm::with_quotes(column.as_ref()))
+            }
+
+            #(#unique_constraints)*
 
             #(
                 /// Column reference for the `#field_names` field.
-                pub const #const_idents: sqlorm::Column<#field_ty> =
-                    sqlorm::Column { name: #field_names, aliased_name: #aliased_field_names, table_alias: #table_alias, _marker: std::marker::PhantomData };
+                #vis const #const_idents: #column_tys =
+                    #column_structs { name: #field_names, aliased_name: #aliased_field_names, table_alias: #table_alias, _marker: std::marker::PhantomData };
             )*
         }
     }
 }
+
+/// Type of the column constant generated for `f`.
+pub fn column_type(f: &EntityField) -> TokenStream {
+    let ty = &f.ty;
+    if let Some(cipher) = &f.encrypted {
+        quote! { sqlorm::EncryptedColumn<#ty, #cipher> }
+    } else if f.immutable {
+        quote! { sqlorm::Column<#ty, sqlorm::Immutable> }
+    } else {
+        quote! { sqlorm::Column<#ty> }
+    }
+}
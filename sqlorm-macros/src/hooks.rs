@@ -0,0 +1,30 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+use crate::EntityStruct;
+
+/// Calls hook `name` of `sqlorm::Hooks` on `entity`, a mutable place, with `conn` as context.
+///
+/// Empty unless the entity is declared with `#[table(hooks)]`.
+pub fn call(es: &EntityStruct, name: &str, entity: TokenStream, conn: &TokenStream) -> TokenStream {
+    if !es.hooks {
+        return quote! {};
+    }
+    let method = Ident::new(name, Span::call_site());
+    quote! {
+        ::sqlorm::Hooks::#method(&mut #entity, &mut ::sqlorm::HookContext::new(&mut *#conn)).await?;
+    }
+}
+
+/// Calls hook `name` on the immutable binding `var`, rebinding it as mutable first.
+pub fn call_on(es: &EntityStruct, name: &str, var: &Ident, conn: &TokenStream) -> TokenStream {
+    if !es.hooks {
+        return quote! {};
+    }
+    let call = call(es, name, quote! { #var }, conn);
+    quote! {
+        let mut #var = #var;
+        #call
+    }
+}
@@ -15,7 +15,12 @@ mod sql;
 
 mod attrs;
 mod gen_columns;
+mod hooks;
+mod observers;
+mod projection;
 mod relations;
+mod sql_enum;
+mod validators;
 
 #[proc_macro_derive(Entity, attributes(sql))]
 pub fn entity(input: TokenStream) -> TokenStream {
@@ -23,6 +28,71 @@ pub fn entity(input: TokenStream) -> TokenStream {
     entity::handle(es).into()
 }
 
+/// Maps a subset of an entity's columns into a plain struct, for use with `QB::select_as`.
+///
+/// Each field is read from the column of the same name on the entity given in
+/// `#[sql(entity = ...)]`. Fields marked with `#[sql(skip)]` are filled with `Default`.
+///
+/// ```rust,ignore
+/// #[derive(FromAliasedRow, Default)]
+/// #[sql(entity = User)]
+/// struct UserSummary {
+///     id: i64,
+///     email: String,
+/// }
+///
+/// let summaries = User::query().select_as::<UserSummary>().fetch_all(&pool).await?;
+/// ```
+#[proc_macro_derive(FromAliasedRow, attributes(sql))]
+pub fn from_aliased_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    match projection::ProjectionStruct::parse(input) {
+        Ok(ps) => projection::handle(ps).into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Stores a fieldless enum in a TEXT column as its variant names.
+///
+/// Generates the sqlx `Type`, `Encode` and `Decode` impls, so the enum can be used as an
+/// entity field and in column filters. Unknown values read from the database fail to decode.
+/// A variant may be stored under another name with `#[sql(rename("..."))]`.
+///
+/// With `#[sql(enum(repr = i16))]` (or `i32`, `i64`) the enum is stored in an integer column
+/// as its discriminants instead.
+///
+/// ```rust,ignore
+/// #[derive(SqlEnum, Debug, Clone, PartialEq, Default)]
+/// enum DonationStatus {
+///     #[default]
+///     Pending,
+///     Paid,
+///     #[sql(rename("refunded_by_admin"))]
+///     Refunded,
+/// }
+///
+/// let paid = Donation::query()
+///     .filter(Donation::STATUS.eq(DonationStatus::Paid))
+///     .fetch_all(&pool)
+///     .await?;
+///
+/// #[derive(SqlEnum, Debug, Clone, PartialEq, Default)]
+/// #[sql(enum(repr = i16))]
+/// enum Priority {
+///     #[default]
+///     Low = 1,
+///     High = 10,
+/// }
+/// ```
+#[proc_macro_derive(SqlEnum, attributes(sql))]
+pub fn sql_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    match sql_enum::SqlEnum::parse(input) {
+        Ok(se) => sql_enum::handle(se).into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
 /// Transforms a struct into a database entity with ORM capabilities.
 ///
 /// This is the primary way to define database entities in SQLOrm. The macro automatically
@@ -53,27 +123,250 @@ pub fn entity(input: TokenStream) -> TokenStream {
 /// }
 /// ```
 ///
-/// # **⚠️ Important:**
-/// [`sqlorm::table`] attribute must go before any other attributes, otherwise code won't compile.
-/// Incorrect usage:
+/// # Schema
+///
+/// Tables outside the default schema are qualified with `schema`, rendered as `"billing"."invoices"`.
+///
+/// ```rust,ignore
+/// #[table(name = "invoices", schema = "billing")]
+/// struct Invoice {
+///     #[sql(pk)]
+///     id: i64,
+/// }
+/// ```
+///
+/// # Indexes
+///
+/// Secondary indexes are declared with `index(columns(...))`, optionally `unique`.
+/// `unique(a, b)` is a shorthand for `index(columns(a, b), unique)`.
+/// With feature `extra-traits` each one gets a `find_by_*` method over its columns.
+/// Multi-column unique constraints also get an `A_AND_B` constant usable as an upsert conflict
+/// target.
+///
+/// ```rust,ignore
+/// #[table(name = "users", index(columns(first_name, last_name)))]
+/// struct User {
+///     #[sql(pk)]
+///     id: i64,
+///     first_name: String,
+///     last_name: String,
+/// }
+///
+/// let smiths: Vec<User> = User::find_by_first_name_and_last_name(&pool, "John".into(), "Smith".into()).await?;
+/// ```
+///
+/// # Raw SQL
+///
+/// Queries written with sqlx directly can reuse the generated names instead of hard-coding
+/// them: `TABLE_NAME`, the quoted `SQL_NAME`, the quoted `ALL_COLUMNS` list and
+/// `qualified(column)`, all respecting renames and schemas. `Table::columns()` describes every
+/// column at runtime.
+///
+/// ```rust,ignore
+/// let sql = format!(
+///     "SELECT {} FROM {} WHERE {} = $1",
+///     User::ALL_COLUMNS,
+///     User::SQL_NAME,
+///     User::qualified(User::EMAIL),
+/// );
+/// ```
+///
+/// # Schema generation
+///
+/// Every entity implements `sqlorm::Schema`, describing its table with the SQL types sqlx maps
+/// the field types to for the enabled driver. `#[sql(column_type = "...")]` overrides the type
+/// of a column. Primary keys without `factory` become `BIGSERIAL`/`INTEGER ... AUTOINCREMENT`,
+/// `belongs_to` relations foreign keys, and `index(...)`/`unique(...)` indexes.
+///
+/// ```rust,ignore
+/// use sqlorm::Schema;
+///
+/// println!("{}", User::create_table_sql());
+/// sqlorm::schema::create_table::<User>(&pool).await?;
+/// ```
+///
+/// `sqlorm::schema::sync` creates missing tables and columns while prototyping, and
+/// `sqlorm::schema::verify::<User>` reports how an existing table differs from the entity.
+///
+/// # Views
+///
+/// `#[table(view)]` maps an entity to a database view. Queries, `find_by_*` and relations are
+/// generated as usual, but `save()`, `insert()`, `update()`, `delete()` and the other writes
+/// are not, so attempting one fails to compile.
+///
+/// ```rust,ignore
+/// #[table(name = "jar_stats", view)]
+/// struct JarStat {
+///     #[sql(pk)]
+///     id: i64,
+///     #[sql(relation(belongs_to -> User, name = "owner", on = id))]
+///     owner_id: i64,
+///     donation_count: i64,
+/// }
+///
+/// let busy = JarStat::query().filter(JarStat::DONATION_COUNT.gt(10)).fetch_all(&pool).await?;
+/// ```
+///
+/// `has_many` relations can't point to a view, as their `add_*` methods save the child; declare
+/// the relation on the view with `belongs_to` instead.
+///
+/// With postgres, `#[table(materialized_view)]` maps an entity to a materialized view the same
+/// way and generates `refresh()`, concurrently refreshing the view when passed `true`:
+///
+/// ```rust,ignore
+/// JarStat::refresh(&pool, true).await?;
+/// ```
+///
+/// # Hooks
+///
+/// `#[table(hooks)]` runs the methods of `sqlorm::Hooks`, which the entity must implement,
+/// around its inserts, updates and deletes. Hooks get the connection of the statement, and an
+/// error returned by a `before_*` hook aborts the write.
+///
+/// ```rust,ignore
+/// #[table(name = "users", hooks)]
+/// struct User {
+///     #[sql(pk)]
+///     id: i64,
+///     email: String,
+/// }
+///
+/// #[sqlorm::async_trait]
+/// impl sqlorm::Hooks for User {
+///     async fn before_insert(&mut self, _ctx: &mut sqlorm::HookContext<'_>) -> sqlx::Result<()> {
+///         self.email = self.email.to_lowercase();
+///         Ok(())
+///     }
+/// }
+/// ```
+///
+/// # Validation
+///
+/// Fields declared with `#[sql(validate(...))]` are checked by a generated `validate()`, which
+/// `insert()`, `update()` and `save()` call before their `before_*` hooks, failing with a
+/// `sqlorm::ValidationErrors` listing the messages of every invalid field. Entities declared
+/// with `#[table(validate)]` implement `sqlorm::Validate` for checks spanning several fields,
+/// run after the field validators. `save_unchecked()`, `insert_unchecked()` and
+/// `update().unchecked()` skip validation.
+///
 /// ```rust,ignore
-/// #[derive(Debug)]
-/// #[table(name = "app_users")] // incorrect: placed after derive attr
+/// fn no_spaces(value: &str) -> Result<(), String> {
+///     if value.contains(' ') { Err("must not contain spaces".into()) } else { Ok(()) }
+/// }
+///
+/// #[table(name = "users")]
 /// struct User {
 ///     #[sql(pk)]
 ///     id: i64,
+///     #[sql(validate(length(max = 255), email))]
 ///     email: String,
+///     #[sql(validate(length(min = 3, max = 32), custom = no_spaces))]
+///     username: String,
+/// }
+///
+/// if let Err(errors) = user.validate() {
+///     println!("{:?}", errors.field("email"));
 /// }
 /// ```
-/// Correct usage:
+///
+/// # Observers
+///
+/// Entities declared with `#[table(observable)]`, which must implement `Clone`, notify the
+/// callbacks registered with `sqlorm::observe` of every successful insert, update and delete.
+/// Callbacks registered with `sqlorm::observe_after_commit` wait for the commit of the
+/// `sqlorm::transaction` running the write.
+///
 /// ```rust,ignore
-/// #[table(name = "app_users")] // correct: placed before derive attr
-/// #[derive(Debug)]
+/// #[table(name = "users", observable)]
+/// #[derive(Debug, Clone, Default)]
 /// struct User {
 ///     #[sql(pk)]
 ///     id: i64,
 ///     email: String,
 /// }
+///
+/// sqlorm::observe_after_commit::<User>(move |event| webhooks.enqueue(event));
+/// ```
+///
+/// # Projections
+///
+/// Frequently selected column subsets are declared with `projection(Name = (columns...))`,
+/// generating a `{Entity}{Name}` struct and a `select_{name}()` method on the query builder,
+/// provided by the `{Entity}Projections` trait.
+///
+/// ```rust,ignore
+/// #[table(name = "users", projection(Summary = (id, email)))]
+/// struct User {
+///     #[sql(pk)]
+///     id: i64,
+///     email: String,
+///     bio: Option<String>,
+/// }
+///
+/// let summaries: Vec<UserSummary> = User::query().select_summary().fetch_all(&pool).await?;
+/// ```
+///
+/// # Opting out of generated code
+///
+/// `skip_generate(...)` disables parts of the generated API, e.g. to avoid name clashes with
+/// your own methods or to save compile time:
+/// - `find_by` - the `find_by_*` methods
+/// - `delete` - `delete()`, `force_delete()`, `delete_where()` and `truncate()`
+/// - `relations_trait` - the `{Entity}Relations` trait with the `with_*` loaders
+///
+/// ```rust,ignore
+/// #[table(name = "users", skip_generate(find_by, relations_trait))]
+/// struct User {
+///     #[sql(pk)]
+///     id: i64,
+/// }
+/// ```
+///
+/// # Visibility
+///
+/// Generated traits, structs, methods, constants and injected relation fields are `pub`.
+/// `visibility` sets another visibility, e.g. to keep them out of a library's public API:
+///
+/// ```rust,ignore
+/// #[table(name = "users", visibility = "pub(crate)")]
+/// pub struct User {
+///     #[sql(pk)]
+///     id: i64,
+/// }
+/// ```
+///
+/// # Attribute order
+///
+/// # **⚠️ Important:**
+/// On entities with relations, [`sqlorm::table`] must go before `#[derive(...)]`. Derives placed
+/// before it are expanded on the struct as written, and `#[table]` doesn't see them, so it can't
+/// report the mistake: derives building the struct, such as `Clone` or `Default`, fail with a
+/// missing field error for the injected relation fields.
+///
+/// Entities without relations, and entities declaring their relation fields themselves as
+/// `#[sql(skip)]`, accept either order:
+/// ```rust,ignore
+/// #[derive(Debug, Clone, Default)]
+/// #[table(name = "jars")]
+/// struct Jar {
+///     #[sql(pk)]
+///     id: i64,
+///     #[sql(relation(belongs_to -> User, name = "owner", on = id))]
+///     owner_id: i64,
+///     #[sql(skip)]
+///     owner: Option<User>,
+/// }
+/// ```
+/// Placed first, `#[table]` injects `owner` itself:
+/// ```rust,ignore
+/// #[table(name = "jars")]
+/// #[derive(Debug, Clone, Default)]
+/// struct Jar {
+///     #[sql(pk)]
+///     id: i64,
+///     #[sql(relation(belongs_to -> User, name = "owner", on = id))]
+///     owner_id: i64,
+/// }
 /// ```
 /// After applying this macro, you can use standard ORM operations:
 /// - `user.save(&pool).await`
@@ -94,10 +387,16 @@ pub fn entity(input: TokenStream) -> TokenStream {
 ///   - `created_at` - Set on insert
 ///   - `updated_at` - Set on insert and update  
 ///   - `deleted_at` - For soft deletes
+/// - **`flatten(street: String, city: String)`** - Store an embedded struct in one column per
+///   listed field, named `{field}_street` etc. or with `prefix = "..."`. The embedded struct
+///   must implement `Default`, as the entity is read column by column
+/// - **`validate(...)`** - Checks run before writes: `length(min = .., max = ..)`,
+///   `range(min = .., max = ..)`, `email` and `custom = path::to_fn`
 /// - **`relation(...)`** - Define relationships:
 ///   - `belongs_to -> SomeOtherStruct, relation = "some_other_struct", on = field`
 ///   - `has_many -> SomeOtherStruct, relation = "some_other_structs", on = field`
 ///   - `has_one -> SomeOtherStruct, relation = "some_other_struct", on = field`
+///   - `many_to_many -> SomeOtherStruct, relation = "some_other_structs", on = field, through = "join_table", keys = (my_key, other_key)`
 ///
 ///
 /// # Complete Example
@@ -153,45 +452,73 @@ pub fn entity(input: TokenStream) -> TokenStream {
 pub fn table(args: TokenStream, input: TokenStream) -> TokenStream {
     let mut model = parse_macro_input!(input as ItemStruct);
 
+    // `Entity` is derived below, a hand-written one would implement everything twice
     let mut existing_derives = Vec::new();
-    model.attrs.retain(|attr| {
-        if attr.path().is_ident("derive") {
-            existing_derives.push(attr.clone());
-            false
-        } else {
-            true
+    for attr in std::mem::take(&mut model.attrs) {
+        if !attr.path().is_ident("derive") {
+            model.attrs.push(attr);
+            continue;
         }
-    });
+        let paths = match attr.parse_args_with(
+            syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+        ) {
+            Ok(paths) => paths,
+            Err(e) => return e.to_compile_error().into(),
+        };
+        existing_derives.extend(
+            paths
+                .into_iter()
+                .filter(|path| {
+                    let path = quote::quote!(#path).to_string().replace(' ', "");
+                    !matches!(path.as_str(), "Entity" | "sqlorm::Entity" | "::sqlorm::Entity")
+                }),
+        );
+    }
 
-    let table_name = if args.is_empty() {
-        model.ident.to_string().to_lowercase()
-    } else {
-        let meta_list: syn::punctuated::Punctuated<syn::MetaNameValue, syn::Token![,]> =
-            syn::parse_macro_input!(args with syn::punctuated::Punctuated::parse_terminated);
+    let meta_list: syn::punctuated::Punctuated<syn::Meta, syn::Token![,]> =
+        syn::parse_macro_input!(args with syn::punctuated::Punctuated::parse_terminated);
 
-        let mut table_name = model.ident.to_string().to_lowercase();
-        for meta in meta_list {
-            if meta.path.is_ident("name") {
+    let mut table_name = model.ident.to_string().to_lowercase();
+    let mut vis: syn::Visibility = syn::parse_quote!(pub);
+    // everything besides `name` (e.g. `index(...)`) is forwarded to the derive as `#[sql(...)]`
+    let mut forwarded = Vec::new();
+    for meta in meta_list {
+        match &meta {
+            syn::Meta::NameValue(nv) if nv.path.is_ident("visibility") => {
+                if let syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(lit_str),
+                    ..
+                }) = &nv.value
+                {
+                    vis = match lit_str.parse() {
+                        Ok(vis) => vis,
+                        Err(e) => return e.to_compile_error().into(),
+                    };
+                }
+                forwarded.push(meta);
+            }
+            syn::Meta::NameValue(nv) if nv.path.is_ident("name") => {
                 if let syn::Expr::Lit(syn::ExprLit {
                     lit: syn::Lit::Str(lit_str),
                     ..
-                }) = meta.value
+                }) = &nv.value
                 {
                     table_name = lit_str.value();
-                    break;
                 }
             }
+            _ => forwarded.push(meta),
         }
-        table_name
-    };
+    }
 
-    inject_relation_fields(&mut model).expect("Failed to inject relation fields");
+    if let Err(e) = inject_relation_fields(&mut model, &vis) {
+        return e.to_compile_error().into();
+    }
 
     // reapply the derive attributes after field injection
     quote::quote! {
-        #(#existing_derives)*
-        #[derive(::sqlorm::Entity)]
+        #[derive(#(#existing_derives,)* ::sqlorm::Entity)]
         #[sql(name = #table_name)]
+        #(#[sql(#forwarded)])*
         #model
     }
     .into()
@@ -199,9 +526,9 @@ pub fn table(args: TokenStream, input: TokenStream) -> TokenStream {
 
 /// Scans struct fields for relation attributes and automatically injects
 /// corresponding relation fields (e.g., posts: Option<Vec<Post>>) with proper attributes.
-/// Throws compile errors if the relation field names are already used.
+/// Relation fields declared by hand are left as they are, and validated by the derive.
 /// Uses existing attribute parsing logic to extract relation information.
-fn inject_relation_fields(model: &mut ItemStruct) -> syn::Result<()> {
+fn inject_relation_fields(model: &mut ItemStruct, vis: &syn::Visibility) -> syn::Result<()> {
     use crate::attrs::parse_entity_field;
     use crate::relations::RelationType;
 
@@ -217,40 +544,50 @@ fn inject_relation_fields(model: &mut ItemStruct) -> syn::Result<()> {
     }
 
     if let Fields::Named(ref mut fields) = model.fields {
-        for relation in &relations_to_inject {
-            for existing_field in fields.named.iter() {
-                if let Some(field_name) = &existing_field.ident {
-                    if field_name.to_string() == relation.relation_name {
-                        return Err(syn::Error::new_spanned(
-                            field_name,
-                            format!(
-                                "Field '{}' is reserved for auto-generated relation field. Remove this field as it will be injected automatically based on relation attributes.",
-                                relation.relation_name
-                            ),
-                        ));
-                    }
-                }
-            }
-        }
+        // fields declared by hand are kept, e.g. because derives placed before `#[table]`
+        // only see the struct as written
+        let declared: Vec<String> = fields
+            .named
+            .iter()
+            .filter_map(|f| f.ident.as_ref().map(|i| i.to_string()))
+            .collect();
 
         for relation in relations_to_inject {
             let field_ident = format_ident!("{}", relation.relation_name);
             let field_type: syn::Type = match relation.kind {
-                RelationType::HasMany => {
+                RelationType::HasMany | RelationType::ManyToMany => {
                     let other_type = &relation.other;
                     syn::parse_quote! { Option<Vec<#other_type>> }
                 }
+                // boxed when self-referential, as the struct would otherwise contain itself
+                RelationType::BelongsTo | RelationType::HasOne if relation.other == model.ident => {
+                    let other_type = &relation.other;
+                    syn::parse_quote! { Option<Box<#other_type>> }
+                }
                 RelationType::BelongsTo | RelationType::HasOne => {
                     let other_type = &relation.other;
                     syn::parse_quote! { Option<#other_type> }
                 }
             };
 
-            let new_field: Field = syn::parse_quote! {
-                #[sql(skip)]
-                pub #field_ident: #field_type
-            };
-            fields.named.push(new_field);
+            if !declared.contains(&relation.relation_name) {
+                let new_field: Field = syn::parse_quote! {
+                    #[sql(skip)]
+                    #vis #field_ident: #field_type
+                };
+                fields.named.push(new_field);
+            }
+
+            let count_name = format!("{}_count", relation.relation_name);
+            if relation.count && !declared.contains(&count_name)
+            {
+                let count_ident = format_ident!("{}", count_name);
+                let count_field: Field = syn::parse_quote! {
+                    #[sql(skip)]
+                    #vis #count_ident: Option<i64>
+                };
+                fields.named.push(count_field);
+            }
         }
     }
     Ok(())
@@ -7,3 +7,30 @@ pub fn executor_from_entity_ident(entity_ident: &Ident) -> Ident {
 pub fn relations_from_entity_ident(entity_ident: &Ident) -> Ident {
     Ident::new(&format!("{entity_ident}Relations"), entity_ident.span())
 }
+
+pub fn projections_from_entity_ident(entity_ident: &Ident) -> Ident {
+    Ident::new(&format!("{entity_ident}Projections"), entity_ident.span())
+}
+
+/// `snake_case` form of a `CamelCase` name, e.g. `ActiveUser` -> `active_user`.
+pub fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            out.push('_');
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+/// Naive singular form of a relation name, e.g. `jars` -> `jar`, `categories` -> `category`.
+pub fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        format!("{stem}y")
+    } else if let Some(stem) = name.strip_suffix('s') {
+        stem.to_string()
+    } else {
+        name.to_string()
+    }
+}
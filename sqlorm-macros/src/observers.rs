@@ -0,0 +1,31 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+use crate::EntityStruct;
+
+/// Implements `sqlorm::Observable` for entities declared with `#[table(observable)]`.
+pub fn observable(es: &EntityStruct) -> TokenStream {
+    if !es.observable || es.kind.is_read_only() {
+        return quote! {};
+    }
+    let ident = &es.struct_ident;
+    quote! {
+        #[automatically_derived]
+        impl ::sqlorm::Observable for #ident {}
+    }
+}
+
+/// Notifies the observers of the entity of change `event`, e.g. `Inserted`, of `entity`, a
+/// place of the entity type.
+///
+/// Empty unless the entity is declared with `#[table(observable)]`.
+pub fn notify(es: &EntityStruct, event: &str, entity: TokenStream) -> TokenStream {
+    if !es.observable {
+        return quote! {};
+    }
+    let event = Ident::new(event, Span::call_site());
+    quote! {
+        ::sqlorm::observer::notify(::sqlorm::ChangeEvent::#event(&#entity));
+    }
+}
@@ -0,0 +1,215 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, Ident, Path, Result, Type};
+
+use crate::{
+    EntityStruct,
+    naming::{projections_from_entity_ident, snake_case},
+};
+
+/// A field of a projection struct.
+struct ProjectionField {
+    ident: Ident,
+    ty: Type,
+    /// Field marked with `#[sql(skip)]`, filled with `Default` instead of being selected.
+    skip: bool,
+}
+
+/// Struct deriving `FromAliasedRow` outside of an entity, e.g. a DTO used with `select_as`.
+pub struct ProjectionStruct {
+    ident: Ident,
+    /// Entity the projected columns are taken from, set with `#[sql(entity = User)]`.
+    entity: Path,
+    fields: Vec<ProjectionField>,
+}
+
+impl ProjectionStruct {
+    pub fn parse(input: DeriveInput) -> Result<Self> {
+        let mut entity = None;
+        for attr in input.attrs.iter().filter(|a| a.path().is_ident("sql")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("entity") {
+                    entity = Some(meta.value()?.parse::<Path>()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported projection attribute, expected `entity`"))
+                }
+            })?;
+        }
+        let entity = entity.ok_or_else(|| {
+            syn::Error::new_spanned(
+                &input.ident,
+                "projection requires `#[sql(entity = SomeEntity)]`",
+            )
+        })?;
+
+        let named = match input.data {
+            Data::Struct(data) => match data.fields {
+                Fields::Named(named) => named.named,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &input.ident,
+                        "projection must have named fields",
+                    ));
+                }
+            },
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "projection must be a struct",
+                ));
+            }
+        };
+
+        let mut fields = Vec::new();
+        for field in named {
+            let mut skip = false;
+            for attr in field.attrs.iter().filter(|a| a.path().is_ident("sql")) {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("skip") {
+                        skip = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("unsupported projection field attribute, expected `skip`"))
+                    }
+                })?;
+            }
+            fields.push(ProjectionField {
+                ident: field.ident.expect("named field"),
+                ty: field.ty,
+                skip,
+            });
+        }
+
+        Ok(ProjectionStruct {
+            ident: input.ident,
+            entity,
+            fields,
+        })
+    }
+}
+
+pub fn handle(ps: ProjectionStruct) -> TokenStream {
+    let ident = &ps.ident;
+    let entity = &ps.entity;
+    let selected: Vec<&ProjectionField> = ps.fields.iter().filter(|f| !f.skip).collect();
+
+    let field_idents: Vec<&Ident> = selected.iter().map(|f| &f.ident).collect();
+    let field_tys: Vec<&Type> = selected.iter().map(|f| &f.ty).collect();
+    // Column constants of the entity, so a misspelled field fails to compile.
+    let const_idents: Vec<Ident> = selected
+        .iter()
+        .map(|f| Ident::new(&f.ident.to_string().to_uppercase(), f.ident.span()))
+        .collect();
+
+    let rest = if selected.len() < ps.fields.len() {
+        quote! { ..Default::default() }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        #[automatically_derived]
+        impl ::sqlorm::Projection for #ident {
+            fn columns() -> Vec<&'static str> {
+                vec![#(#entity::#const_idents.name),*]
+            }
+        }
+
+        #[automatically_derived]
+        impl ::sqlorm::FromAliasedRow for #ident {
+            fn from_aliased_row(row: &::sqlorm::Row) -> ::sqlorm::sqlx::Result<Self>
+            where
+                Self: Sized + Default,
+            {
+                use ::sqlorm::sqlx::Row as _;
+                Ok(Self {
+                    #(#field_idents: row.try_get::<#field_tys, &str>(#entity::#const_idents.aliased_name)?,)*
+                    #rest
+                })
+            }
+        }
+    }
+}
+
+/// Generates the structs declared with `#[table(projection(Summary = (id, email)))]` and a
+/// `{Entity}Projections` trait with a `select_summary()` method on the entity's query builder.
+pub fn entity_projections(es: &EntityStruct) -> TokenStream {
+    let vis = &es.vis;
+    if es.projections.is_empty() {
+        return TokenStream::new();
+    }
+    let s_ident = &es.struct_ident;
+    let trait_ident = projections_from_entity_ident(s_ident);
+
+    let mut structs = Vec::new();
+    let mut struct_idents = Vec::new();
+    let mut fn_idents = Vec::new();
+    for projection in &es.projections {
+        let ident = format_ident!("{}{}", s_ident, projection.name);
+        let fields: Vec<ProjectionField> = projection
+            .columns
+            .iter()
+            .map(|column| {
+                let field = es
+                    .fields
+                    .iter()
+                    .find(|f| &f.ident == column)
+                    .expect("projection columns are validated on parse");
+                ProjectionField {
+                    ident: field.ident.clone(),
+                    ty: field.ty.clone(),
+                    skip: false,
+                }
+            })
+            .collect();
+        let doc = format!(
+            "Projection of [`{}`] selected with `select_{}()`.",
+            s_ident,
+            snake_case(&projection.name.to_string())
+        );
+        let field_idents: Vec<_> = fields.iter().map(|f| f.ident.clone()).collect();
+        let field_tys: Vec<_> = fields.iter().map(|f| f.ty.clone()).collect();
+        let impls = handle(ProjectionStruct {
+            ident: ident.clone(),
+            entity: Path::from(s_ident.clone()),
+            fields,
+        });
+
+        structs.push(quote! {
+            #[doc = #doc]
+            #[derive(Debug, Clone, Default)]
+            #vis struct #ident {
+                #(#vis #field_idents: #field_tys,)*
+            }
+
+            #impls
+        });
+        fn_idents.push(format_ident!(
+            "select_{}",
+            snake_case(&projection.name.to_string())
+        ));
+        struct_idents.push(ident);
+    }
+
+    quote! {
+        #(#structs)*
+
+        #[automatically_derived]
+        #vis trait #trait_ident {
+            #(
+                /// Selects only the columns of the projection.
+                fn #fn_idents(self) -> ::sqlorm::QB<#struct_idents>;
+            )*
+        }
+
+        #[automatically_derived]
+        impl #trait_ident for ::sqlorm::QB<#s_ident> {
+            #(
+                fn #fn_idents(self) -> ::sqlorm::QB<#struct_idents> {
+                    self.select_as::<#struct_idents>()
+                }
+            )*
+        }
+    }
+}
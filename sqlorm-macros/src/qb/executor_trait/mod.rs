@@ -1,7 +1,10 @@
 use proc_macro2::TokenStream;
 use syn::Ident;
 
-use crate::{naming::executor_from_entity_ident, relations::RelationType};
+use crate::{
+    naming::executor_from_entity_ident,
+    relations::{Relation, RelationType, Through},
+};
 
 #[derive(PartialEq)]
 pub enum FetchVariant {
@@ -12,16 +15,16 @@ impl From<&RelationType> for FetchVariant {
     fn from(rt: &RelationType) -> Self {
         match rt {
             RelationType::BelongsTo | RelationType::HasOne => FetchVariant::Eager,
-            RelationType::HasMany => FetchVariant::Batch,
+            RelationType::HasMany | RelationType::ManyToMany => FetchVariant::Batch,
         }
     }
 }
 
-pub fn executor_trait(es: &crate::EntityStruct) -> proc_macro2::TokenStream {
-    let s_name = &es.struct_ident;
-    let tident = executor_from_entity_ident(&es.struct_ident);
-    let eager: Vec<TokenStream> = es
-        .relations
+/// Generates code which hydrates eagerly joined relations of `core` from `row`.
+///
+/// `eager` is an expression evaluating to the list of eager join specs of the query.
+fn eager_loaders(es: &crate::EntityStruct, eager: &TokenStream) -> Vec<TokenStream> {
+    es.relations
         .iter()
         .filter_map(|r| {
             if FetchVariant::from(&r.kind) != FetchVariant::Eager {
@@ -31,13 +34,209 @@ pub fn executor_trait(es: &crate::EntityStruct) -> proc_macro2::TokenStream {
             let other = &r.other;
             let on = Ident::new(r_name, other.span());
             Some(quote::quote! {
-                if let Some(relation) = self.eager.iter().find(|rel| rel.relation_name == #r_name) {
-                    let related_entity: #other = ::sqlorm::FromAliasedRow::from_aliased_row(&row)?;
-                    core.#on = Some(related_entity);
+                if let Some(relation) = #eager.iter().find(|rel| rel.relation_name == #r_name)
+                    && relation.is_joined(&row, <#other as ::sqlorm::Table>::PK)?
+                {
+                    let related_entity: #other =
+                        ::sqlorm::FromAliasedRow::from_row_with_alias(&row, relation.alias())?;
+                    core.#on = Some(related_entity.into());
                 }
             })
         })
-        .collect();
+        .collect()
+}
+
+/// Generates code which reads relation counts projected by `with_<relation>_count()` from
+/// `row` into `core`.
+///
+/// `exprs` is an expression evaluating to the projected expressions of the query.
+fn count_loaders(es: &crate::EntityStruct, exprs: &TokenStream) -> Vec<TokenStream> {
+    es.relations
+        .iter()
+        .filter(|r| r.count)
+        .map(|r| {
+            let alias = format!("{}_count", r.relation_name);
+            let field = Ident::new(&alias, r.other.span());
+            quote::quote! {
+                if #exprs.iter().any(|expr| expr.alias == #alias) {
+                    let count: i64 = <::sqlorm::Row as ::sqlorm::sqlx::Row>::try_get(&row, #alias)?;
+                    core.#field = Some(count);
+                }
+            }
+        })
+        .collect()
+}
+
+/// Generates code which reads related rows projected by `with_<relation>_json()` from `row`
+/// into `core`.
+///
+/// `exprs` is an expression evaluating to the projected expressions of the query.
+fn json_loaders(es: &crate::EntityStruct, exprs: &TokenStream) -> Vec<TokenStream> {
+    es.relations
+        .iter()
+        .filter(|r| r.load_json)
+        .map(|r| {
+            let alias = format!("{}_json", r.relation_name);
+            let other = &r.other;
+            let field = Ident::new(&r.relation_name, other.span());
+            quote::quote! {
+                if #exprs.iter().any(|expr| expr.alias == #alias) {
+                    let ::sqlorm::sqlx::types::Json(related): ::sqlorm::sqlx::types::Json<Vec<#other>> =
+                        <::sqlorm::Row as ::sqlorm::sqlx::Row>::try_get(&row, #alias)?;
+                    core.#field = Some(related);
+                }
+            }
+        })
+        .collect()
+}
+
+/// Generates code loading a many-to-many relation of all `results`: join table rows first,
+/// then every related row once. A per-parent limit is applied while grouping.
+fn many_to_many_batch_all(r: &Relation) -> TokenStream {
+    let Some(Through::Table {
+        table,
+        self_key,
+        other_key,
+    }) = &r.through
+    else {
+        return TokenStream::new();
+    };
+    let r_name = &r.relation_name;
+    let other = &r.other;
+    let on = Ident::new(r_name, other.span());
+    let (parent_key, related_key) = (&r.on.0, &r.on.1);
+    let related_key_const =
+        Ident::new(&related_key.to_string().to_uppercase(), related_key.span());
+    let (self_key, other_key) = (self_key.to_string(), other_key.to_string());
+
+    quote::quote! {
+        if let Some(relation) = self.batch.iter().find(|rel| rel.relation_name == #r_name) {
+            let parent_ids: Vec<_> = results.iter().map(|p| p.#parent_key.clone()).collect();
+            let links = ::sqlorm::fetch_pivot(
+                &mut *conn,
+                #table,
+                #self_key,
+                #other_key,
+                parent_ids,
+                #other::#related_key_const,
+            )
+            .await?;
+
+            let related_ids: Vec<_> = links
+                .iter()
+                .map(|(_, id)| id.clone())
+                .collect::<::std::collections::HashSet<_>>()
+                .into_iter()
+                .collect();
+            let related: Vec<#other> = if related_ids.is_empty() {
+                Vec::new()
+            } else {
+                relation
+                    .refine(#other::query().filter(#other::#related_key_const.in_(related_ids)))
+                    .fetch_all(&mut *conn)
+                    .await?
+            };
+
+            let mut parents_of: ::sqlorm::HashMap<_, Vec<_>> = ::sqlorm::HashMap::new();
+            for (parent_id, related_id) in links {
+                parents_of.entry(related_id).or_default().push(parent_id);
+            }
+            // walk related rows in query order so requested ordering is kept per parent
+            let mut grouped: ::sqlorm::HashMap<_, Vec<#other>> = ::sqlorm::HashMap::new();
+            for rel in related {
+                for parent_id in parents_of.get(&rel.#related_key).into_iter().flatten() {
+                    grouped.entry(parent_id.clone()).or_default().push(rel.clone());
+                }
+            }
+            if let Some(limit) = relation.limit {
+                for children in grouped.values_mut() {
+                    children.truncate(limit as usize);
+                }
+            }
+
+            for parent in &mut results {
+                parent.#on = Some(grouped.remove(&parent.#parent_key).unwrap_or_default());
+            }
+        }
+    }
+}
+
+/// Generates code loading a has_many through relation of all `results`: intermediate rows
+/// first, then the related rows referencing them. A per-parent limit is applied while grouping.
+fn has_many_through_batch_all(r: &Relation) -> TokenStream {
+    let Some(Through::Entity {
+        entity: through,
+        self_key,
+        other_key,
+    }) = &r.through
+    else {
+        return TokenStream::new();
+    };
+    let r_name = &r.relation_name;
+    let other = &r.other;
+    let on = Ident::new(r_name, other.span());
+    let (parent_key, foreign_key) = (&r.on.0, &r.on.1);
+    let foreign_key_const =
+        Ident::new(&foreign_key.to_string().to_uppercase(), foreign_key.span());
+    let self_key_const = Ident::new(&self_key.to_string().to_uppercase(), self_key.span());
+    let other_key_const = Ident::new(&other_key.to_string().to_uppercase(), other_key.span());
+
+    quote::quote! {
+        if let Some(relation) = self.batch.iter().find(|rel| rel.relation_name == #r_name) {
+            let parent_ids: Vec<_> = results.iter().map(|p| p.#parent_key.clone()).collect();
+            let links = ::sqlorm::fetch_pivot(
+                &mut *conn,
+                <#through as ::sqlorm::Table>::TABLE_NAME,
+                #through::#self_key_const.name,
+                #through::#other_key_const.name,
+                parent_ids,
+                #through::#other_key_const,
+            )
+            .await?;
+
+            let through_ids: Vec<_> = links.iter().map(|(_, id)| id.clone()).collect();
+            let related: Vec<#other> = if through_ids.is_empty() {
+                Vec::new()
+            } else {
+                relation
+                    .refine(#other::query().filter(#other::#foreign_key_const.in_(through_ids)))
+                    .fetch_all(&mut *conn)
+                    .await?
+            };
+
+            let parent_of: ::sqlorm::HashMap<_, _> = links
+                .into_iter()
+                .map(|(parent_id, through_id)| (through_id, parent_id))
+                .collect();
+            let mut grouped: ::sqlorm::HashMap<_, Vec<#other>> = ::sqlorm::HashMap::new();
+            for rel in related {
+                if let Some(parent_id) = parent_of.get(&rel.#foreign_key) {
+                    grouped.entry(parent_id.clone()).or_default().push(rel);
+                }
+            }
+            if let Some(limit) = relation.limit {
+                for children in grouped.values_mut() {
+                    children.truncate(limit as usize);
+                }
+            }
+
+            for parent in &mut results {
+                parent.#on = Some(grouped.remove(&parent.#parent_key).unwrap_or_default());
+            }
+        }
+    }
+}
+
+pub fn executor_trait(es: &crate::EntityStruct) -> proc_macro2::TokenStream {
+    let vis = &es.vis;
+    let s_name = &es.struct_ident;
+    let tident = executor_from_entity_ident(&es.struct_ident);
+    let eager = eager_loaders(es, &quote::quote! { self.eager });
+    let eager_stream = eager_loaders(es, &quote::quote! { eager });
+    let mut counts = count_loaders(es, &quote::quote! { self.exprs });
+    counts.extend(json_loaders(es, &quote::quote! { self.exprs }));
+    let mut counts_stream = count_loaders(es, &quote::quote! { exprs });
+    counts_stream.extend(json_loaders(es, &quote::quote! { exprs }));
 
     let batch_one: Vec<TokenStream> = es
         .relations
@@ -54,14 +253,33 @@ pub fn executor_trait(es: &crate::EntityStruct) -> proc_macro2::TokenStream {
             let foreign_key_const =
                 Ident::new(&foreign_key.to_string().to_uppercase(), foreign_key.span());
 
+            // relations through another table load as a batch of one
+            let load_all = match r.through {
+                Some(Through::Table { .. }) => Some(many_to_many_batch_all(r)),
+                Some(Through::Entity { .. }) => Some(has_many_through_batch_all(r)),
+                None => None,
+            };
+            if let Some(load_all) = load_all {
+                return Some(quote::quote! {
+                    if self.batch.iter().any(|rel| rel.relation_name == #r_name) {
+                        let mut results = vec![core];
+                        #load_all
+                        core = results.pop().expect("batch of one parent");
+                    }
+                });
+            }
+
             Some(quote::quote! {
                 if let Some(relation) = self.batch.iter().find(|rel| rel.relation_name == #r_name) {
-                    let parent_id = core.#parent_key;
+                    let parent_id = core.#parent_key.clone();
 
-                    let children: Vec<#other> = #other::query()
-                        .filter(#other::#foreign_key_const.eq(parent_id.clone()))
-                        .fetch_all(&mut *conn)
-                        .await?;
+                    let mut children_query = relation.refine(
+                        #other::query().filter(#other::#foreign_key_const.eq(parent_id.into())),
+                    );
+                    if let Some(limit) = relation.limit {
+                        children_query = children_query.limit(limit);
+                    }
+                    let children: Vec<#other> = children_query.fetch_all(&mut *conn).await?;
 
                     core.#on = Some(children);
                 }
@@ -84,28 +302,36 @@ pub fn executor_trait(es: &crate::EntityStruct) -> proc_macro2::TokenStream {
             let foreign_key_const =
                 Ident::new(&foreign_key.to_string().to_uppercase(), foreign_key.span());
 
+            match r.through {
+                Some(Through::Table { .. }) => return Some(many_to_many_batch_all(r)),
+                Some(Through::Entity { .. }) => return Some(has_many_through_batch_all(r)),
+                None => {}
+            }
+
             Some(quote::quote! {
                 if let Some(relation) = self.batch.iter().find(|rel| rel.relation_name == #r_name) {
-                    let parent_ids: Vec<_> = results.iter().map(|p| p.#parent_key).collect();
+                    // converted to the foreign key type, e.g. `Option<i64>` for nullable keys
+                    let parent_ids: Vec<_> =
+                        results.iter().map(|p| p.#parent_key.clone().into()).collect();
 
                     if !parent_ids.is_empty() {
-                        let related: Vec<#other> = #other::query()
-                            .filter(#other::#foreign_key_const.in_(parent_ids.clone()))
-                            .fetch_all(&mut *conn)
-                            .await?;
+                        let mut related_query = relation.refine(
+                            #other::query().filter(#other::#foreign_key_const.in_(parent_ids.clone())),
+                        );
+                        if let Some(limit) = relation.limit {
+                            related_query = related_query.limit_per(#other::#foreign_key_const, limit);
+                        }
+                        let related: Vec<#other> = related_query.fetch_all(&mut *conn).await?;
 
                         let mut grouped: ::sqlorm::HashMap<_, Vec<#other>> = ::sqlorm::HashMap::new();
                         for rel in related {
-                            let key = rel.#foreign_key;
+                            let key = rel.#foreign_key.clone();
                             grouped.entry(key).or_default().push(rel);
                         }
 
                         for parent in &mut results {
-                            if let Some(children) = grouped.remove(&parent.#parent_key) {
-                                parent.#on = Some(children);
-                            } else {
-                                parent.#on = Some(Vec::new());
-                            }
+                            let children = grouped.entry(parent.#parent_key.clone().into()).or_default();
+                            parent.#on = Some(::std::mem::take(children));
                         }
                     }
                 }
@@ -115,19 +341,35 @@ pub fn executor_trait(es: &crate::EntityStruct) -> proc_macro2::TokenStream {
 
     quote::quote! {
         #[::sqlorm::async_trait]
-        pub trait #tident
+        #vis trait #tident
         where
             #s_name: Send + Sync + ::sqlorm::Table + 'static,
         {
+            /// Fetches exactly one row, loading requested relations.
+            ///
+            /// `acquirer` may be a `&Pool`, a `&mut Connection` or a `&mut Transaction`;
+            /// relation sub-queries run on the same connection.
             async fn fetch_one<'a, A>(self, acquirer: A) -> ::sqlorm::sqlx::Result<#s_name>
             where
                 A: Send + ::sqlorm::sqlx::Acquire<'a, Database =::sqlorm::Driver>;
+            /// Fetches at most one row, loading requested relations.
             async fn fetch_optional<'a, A>(self, acquirer: A) -> ::sqlorm::sqlx::Result<Option<#s_name>>
             where
                 A: Send + ::sqlorm::sqlx::Acquire<'a, Database =::sqlorm::Driver>;
+            /// Fetches all rows, loading requested relations.
             async fn fetch_all<'a, A>(self, acquirer: A) -> ::sqlorm::sqlx::Result<Vec<#s_name>>
             where
                 A: Send + ::sqlorm::sqlx::Acquire<'a, Database =::sqlorm::Driver>;
+            /// Fetches a single page along with the total number of matching rows.
+            async fn paginate<'a, A>(self, page: u64, per_page: u64, acquirer: A) -> ::sqlorm::sqlx::Result<::sqlorm::Page<#s_name>>
+            where
+                A: Send + ::sqlorm::sqlx::Acquire<'a, Database =::sqlorm::Driver>;
+            /// Streams matching rows one by one instead of buffering them.
+            ///
+            /// Eager (belongs_to, has_one) relations are loaded, batch (has_many) relations are not.
+            fn fetch_stream<'e, E>(self, executor: E) -> ::sqlorm::BoxStream<'e, ::sqlorm::sqlx::Result<#s_name>>
+            where
+                E: 'e + ::sqlorm::sqlx::Executor<'e, Database =::sqlorm::Driver>;
         }
 
         #[automatically_derived]
@@ -139,16 +381,33 @@ pub fn executor_trait(es: &crate::EntityStruct) -> proc_macro2::TokenStream {
             {
                 let mut conn = acquirer.acquire().await?;
 
-                if self.eager.is_empty() && self.batch.is_empty() {
-                    let row = self.build_query().build().fetch_one(&mut *conn).await?;
+                if self.eager.is_empty() && self.batch.is_empty() && self.exprs.is_empty() {
+                    let timer = self.slow_query_timer();
+                    let row = self
+                        .build_query()
+                        .build()
+                        .persistent(self.persistent)
+                        .fetch_one(&mut *conn)
+                        .await;
+                    timer.finish();
+                    let row = row?;
                     let core:#s_name = ::sqlorm::FromAliasedRow::from_aliased_row(&row)?;
                     return Ok(core);
                 }
 
-                let row = self.build_query().build().fetch_one(&mut *conn).await?;
+                let timer = self.slow_query_timer();
+                let row = self
+                    .build_query()
+                    .build()
+                    .persistent(self.persistent)
+                    .fetch_one(&mut *conn)
+                    .await;
+                timer.finish();
+                let row = row?;
                 let mut core:#s_name = ::sqlorm::FromAliasedRow::from_aliased_row(&row)?;
 
                 #(#eager)*
+                #(#counts)*
                 #(#batch_one)*
 
                 Ok(core)
@@ -160,8 +419,16 @@ pub fn executor_trait(es: &crate::EntityStruct) -> proc_macro2::TokenStream {
             {
                 let mut conn = acquirer.acquire().await?;
 
-                if self.eager.is_empty() && self.batch.is_empty() {
-                    let row = self.build_query().build().fetch_optional(&mut *conn).await?;
+                if self.eager.is_empty() && self.batch.is_empty() && self.exprs.is_empty() {
+                    let timer = self.slow_query_timer();
+                    let row = self
+                        .build_query()
+                        .build()
+                        .persistent(self.persistent)
+                        .fetch_optional(&mut *conn)
+                        .await;
+                    timer.finish();
+                    let row = row?;
                     if let Some(row) = row {
                         let core:#s_name = ::sqlorm::FromAliasedRow::from_aliased_row(&row)?;
                         return Ok(Some(core));
@@ -169,11 +436,20 @@ pub fn executor_trait(es: &crate::EntityStruct) -> proc_macro2::TokenStream {
                     return Ok(None);
                 }
 
-                let row = self.build_query().build().fetch_optional(&mut *conn).await?;
+                let timer = self.slow_query_timer();
+                let row = self
+                    .build_query()
+                    .build()
+                    .persistent(self.persistent)
+                    .fetch_optional(&mut *conn)
+                    .await;
+                timer.finish();
+                let row = row?;
                 if let Some(row) = row {
                     let mut core:#s_name = ::sqlorm::FromAliasedRow::from_aliased_row(&row)?;
 
                     #(#eager)*
+                    #(#counts)*
                     #(#batch_one)*
 
                     Ok(Some(core))
@@ -187,12 +463,21 @@ pub fn executor_trait(es: &crate::EntityStruct) -> proc_macro2::TokenStream {
                 A: Send + ::sqlorm::sqlx::Acquire<'a, Database =::sqlorm::Driver>,
             {
                 let mut conn = acquirer.acquire().await?;
-                let rows = self.build_query().build().fetch_all(&mut *conn).await?;
+                let timer = self.slow_query_timer();
+                let rows = self
+                    .build_query()
+                    .build()
+                    .persistent(self.persistent)
+                    .fetch_all(&mut *conn)
+                    .await;
+                timer.finish();
+                let rows = rows?;
                 let mut results = Vec::new();
 
                 for row in rows {
                     let mut core: #s_name = ::sqlorm::FromAliasedRow::from_aliased_row(&row)?;
                     #(#eager)*
+                    #(#counts)*
                     results.push(core);
                 }
 
@@ -200,6 +485,40 @@ pub fn executor_trait(es: &crate::EntityStruct) -> proc_macro2::TokenStream {
 
                 Ok(results)
             }
+
+            async fn paginate<'a, A>(self, page: u64, per_page: u64, acquirer: A) -> ::sqlorm::sqlx::Result<::sqlorm::Page<#s_name>>
+            where
+                A: Send + ::sqlorm::sqlx::Acquire<'a, Database =::sqlorm::Driver>,
+            {
+                let mut conn = acquirer.acquire().await?;
+                let timer = self.count_query_timer();
+                let total = self
+                    .build_count_query()
+                    .build_query_as::<(i64,)>()
+                    .persistent(self.persistent)
+                    .fetch_one(&mut *conn)
+                    .await;
+                timer.finish();
+                let (total,) = total?;
+
+                let items = self.for_page(page, per_page).fetch_all(&mut *conn).await?;
+
+                Ok(::sqlorm::Page::new(items, total, page, per_page))
+            }
+
+            fn fetch_stream<'e, E>(self, executor: E) -> ::sqlorm::BoxStream<'e, ::sqlorm::sqlx::Result<#s_name>>
+            where
+                E: 'e + ::sqlorm::sqlx::Executor<'e, Database =::sqlorm::Driver>,
+            {
+                let eager = self.eager.clone();
+                let exprs = self.exprs.clone();
+                self.stream_with(executor, move |row| {
+                    let mut core: #s_name = ::sqlorm::FromAliasedRow::from_aliased_row(row)?;
+                    #(#eager_stream)*
+                    #(#counts_stream)*
+                    Ok(core)
+                })
+            }
         }
     }
 }
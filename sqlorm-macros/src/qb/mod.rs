@@ -1,18 +1,32 @@
-use crate::EntityStruct;
+use crate::{EntityStruct, entity::SoftDelete};
 // use rel::Relations
 // trait Relations {
 //  pub fn with_jars()
 //  pub fn with_donations()
 // }
-mod executor_trait;
+pub(crate) mod executor_trait;
 // User::query().with_jars()
 mod relations_trait;
 
 pub fn qb(es: &EntityStruct) -> proc_macro2::TokenStream {
+    let vis = &es.vis;
     let s_ident = &es.struct_ident;
     let relations_trait = relations_trait::relations_trait(es);
     let executor = executor_trait::executor_trait(es);
 
+    // soft deleted rows are hidden unless `with_deleted()` / `only_deleted()` is used
+    let soft_delete = match es.soft_delete() {
+        Some(SoftDelete::Timestamp { field, .. }) => {
+            let col = &field.name;
+            quote::quote! { .soft_delete(#col) }
+        }
+        Some(SoftDelete::Flag { field }) => {
+            let col = &field.name;
+            quote::quote! { .soft_delete_flag(#col) }
+        }
+        None => quote::quote! {},
+    };
+
     quote::quote! {
         #relations_trait
 
@@ -20,8 +34,9 @@ pub fn qb(es: &EntityStruct) -> proc_macro2::TokenStream {
 
         #[automatically_derived]
         impl #s_ident {
-            pub fn query() -> ::sqlorm::QB<#s_ident> {
+            #vis fn query() -> ::sqlorm::QB<#s_ident> {
                 ::sqlorm::QB::new(<#s_ident as ::sqlorm::Table>::table_info())
+                    #soft_delete
             }
         }
 
@@ -1,6 +1,13 @@
 use syn::Ident;
 
-use crate::{EntityStruct, naming::relations_from_entity_ident, qb::executor_trait::FetchVariant};
+use sqlorm_core::with_quotes;
+
+use crate::{
+    EntityStruct,
+    naming::relations_from_entity_ident,
+    qb::executor_trait::FetchVariant,
+    relations::{Relation, Through},
+};
 
 // This module is supposed to generate Relation trait for each entity
 // Relations trait is supposed to contain all possible relations fo
@@ -24,21 +31,48 @@ use crate::{EntityStruct, naming::relations_from_entity_ident, qb::executor_trai
 // }
 
 pub fn relations_trait(es: &EntityStruct) -> proc_macro2::TokenStream {
+    let vis = &es.vis;
     let s_ident = &es.struct_ident;
     let rel_ident = relations_from_entity_ident(&es.struct_ident);
     let fn_idents = declarations(es);
+    let where_idents = batch_declarations(es, "where");
+    let has_where_idents: Vec<Ident> = es
+        .relations
+        .iter()
+        .map(|rel| Ident::new(&format!("has_{}_where", rel.relation_name), rel.other.span()))
+        .collect();
+    let ordered_idents = batch_declarations(es, "ordered");
     let implementations = implementations(es, &rel_ident);
 
-    if es.relations.is_empty() {
+    if es.relations.is_empty() || es.skip_generate.relations_trait {
         return proc_macro2::TokenStream::new();
     }
 
     quote::quote! {
         #[automatically_derived]
-        pub trait #rel_ident {
+        #vis trait #rel_ident {
             #(
                 fn #fn_idents(self) -> ::sqlorm::QB<#s_ident>;
             )*
+            /// Loads every declared relation: joins belongs_to/has_one relations and batches
+            /// has_many/many_to_many relations.
+            fn with_all(self) -> ::sqlorm::QB<#s_ident>;
+            #(
+                /// Loads only related rows matching `cond`.
+                fn #where_idents(self, cond: ::sqlorm::Condition) -> ::sqlorm::QB<#s_ident>;
+            )*
+            #(
+                /// Keeps only rows having a related row matching `cond`.
+                fn #has_where_idents(self, cond: ::sqlorm::Condition) -> ::sqlorm::QB<#s_ident>;
+            )*
+            #(
+                /// Loads related rows in `order`, at most `limit` per parent.
+                fn #ordered_idents(
+                    self,
+                    order: ::sqlorm::OrderBySpec,
+                    limit: Option<u64>,
+                ) -> ::sqlorm::QB<#s_ident>;
+            )*
         }
 
         #implementations
@@ -57,45 +91,257 @@ fn implementations(es: &EntityStruct, trait_name: &Ident) -> proc_macro2::TokenS
             let (on1, on2) = (&rel.on.0.to_string(), &rel.on.1.to_string());
             let fetch_variant: FetchVariant = (&rel.kind).into();
 
-            match fetch_variant {
+            let exists_fn = exists_implementation(es, rel);
+
+            let loaders = match fetch_variant {
                 FetchVariant::Eager => {
-                    quote::quote! {
-                        fn #fn_ident(self) -> ::sqlorm::QB<#s_ident> {
-                            let join_type = ::sqlorm::JoinType::Left;
-                            let foreign_table = <#other as ::sqlorm::Table>::table_info();
-                            let spec = ::sqlorm::JoinSpec {
+                    let inner_ident =
+                        Ident::new(&format!("with_{}_inner", rel.relation_name), rel.other.span());
+                    let spec = |join_type: proc_macro2::TokenStream| {
+                        quote::quote! {
+                            ::sqlorm::JoinSpec {
                                 relation_name: #relation_name,
-                                join_type,
-                                foreign_table,
+                                join_type: #join_type,
+                                foreign_table: <#other as ::sqlorm::Table>::table_info(),
                                 on: (#on1, #on2),
-                            };
-                            self.join_eager(spec)
+                                alias: None,
+                                filters: Vec::new(),
+                                order_by: Vec::new(),
+                                limit: None,
+                            }
+                        }
+                    };
+                    let left = spec(quote::quote! { ::sqlorm::JoinType::Left });
+                    let inner = spec(quote::quote! { ::sqlorm::JoinType::Inner });
+                    quote::quote! {
+                        fn #fn_ident(self) -> ::sqlorm::QB<#s_ident> {
+                            self.join_eager(#left)
+                        }
+
+                        fn #inner_ident(self) -> ::sqlorm::QB<#s_ident> {
+                            self.join_eager(#inner)
                         }
                     }
                 }
                 FetchVariant::Batch => {
-                    quote::quote! {
-                        fn #fn_ident(self) -> ::sqlorm::QB<#s_ident> {
-                            let join_type = ::sqlorm::JoinType::Left;
-                            let foreign_table = <#other as ::sqlorm::Table>::table_info();
-                            let spec = ::sqlorm::JoinSpec {
+                    let where_ident =
+                        Ident::new(&format!("with_{}_where", rel.relation_name), rel.other.span());
+                    let ordered_ident = Ident::new(
+                        &format!("with_{}_ordered", rel.relation_name),
+                        rel.other.span(),
+                    );
+                    let count_fn = count_implementation(es, rel);
+                    let json_fn = json_implementation(es, rel);
+                    let spec = |filters: proc_macro2::TokenStream,
+                                order_by: proc_macro2::TokenStream,
+                                limit: proc_macro2::TokenStream| {
+                        quote::quote! {
+                            ::sqlorm::JoinSpec {
                                 relation_name: #relation_name,
-                                join_type,
-                                foreign_table,
+                                join_type: ::sqlorm::JoinType::Left,
+                                foreign_table: <#other as ::sqlorm::Table>::table_info(),
                                 on: (#on1, #on2),
-                            };
-                            self.join_batch(spec)
+                                alias: None,
+                                filters: #filters,
+                                order_by: #order_by,
+                                limit: #limit,
+                            }
+                        }
+                    };
+                    let plain = spec(
+                        quote::quote! { Vec::new() },
+                        quote::quote! { Vec::new() },
+                        quote::quote! { None },
+                    );
+                    let filtered = spec(
+                        quote::quote! { vec![cond] },
+                        quote::quote! { Vec::new() },
+                        quote::quote! { None },
+                    );
+                    let ordered = spec(
+                        quote::quote! { Vec::new() },
+                        quote::quote! { vec![order] },
+                        quote::quote! { limit },
+                    );
+                    quote::quote! {
+                        fn #fn_ident(self) -> ::sqlorm::QB<#s_ident> {
+                            self.join_batch(#plain)
                         }
+
+                        fn #where_ident(self, cond: ::sqlorm::Condition) -> ::sqlorm::QB<#s_ident> {
+                            self.join_batch(#filtered)
+                        }
+
+                        fn #ordered_ident(
+                            self,
+                            order: ::sqlorm::OrderBySpec,
+                            limit: Option<u64>,
+                        ) -> ::sqlorm::QB<#s_ident> {
+                            self.join_batch(#ordered)
+                        }
+
+                        #count_fn
+
+                        #json_fn
                     }
                 }
+            };
+
+            quote::quote! {
+                #loaders
+
+                #exists_fn
             }
         })
         .collect();
 
+    let with_all: Vec<Ident> = es
+        .relations
+        .iter()
+        .map(|rel| Ident::new(&format!("with_{}", rel.relation_name), rel.other.span()))
+        .collect();
+
     quote::quote! {
         #[automatically_derived]
         impl #trait_name for ::sqlorm::QB<#s_ident> {
             #(#fns)*
+
+            fn with_all(self) -> ::sqlorm::QB<#s_ident> {
+                self #(.#with_all())*
+            }
+        }
+    }
+}
+
+/// Generates `with_<relation>_count()` for relations declared with `count`, projecting the
+/// number of related rows into the injected `<relation>_count` field.
+fn count_implementation(es: &EntityStruct, rel: &Relation) -> proc_macro2::TokenStream {
+    if !rel.count {
+        return proc_macro2::TokenStream::new();
+    }
+    let s_ident = &es.struct_ident;
+    let fn_ident = Ident::new(&format!("with_{}_count", rel.relation_name), rel.other.span());
+    let alias = format!("{}_count", rel.relation_name);
+    let related = related_query(rel, quote::quote! {});
+    let on = correlation(rel);
+
+    quote::quote! {
+        fn #fn_ident(self) -> ::sqlorm::QB<#s_ident> {
+            #related
+            let on = #on;
+            self.select_count(#alias, related, on)
+        }
+    }
+}
+
+/// Generates `with_<relation>_json()` for relations declared with `load = json`, projecting
+/// related rows as a JSON array read back into the relation field.
+fn json_implementation(es: &EntityStruct, rel: &Relation) -> proc_macro2::TokenStream {
+    if !rel.load_json {
+        return proc_macro2::TokenStream::new();
+    }
+    let s_ident = &es.struct_ident;
+    let fn_ident = Ident::new(&format!("with_{}_json", rel.relation_name), rel.other.span());
+    let alias = format!("{}_json", rel.relation_name);
+    let related = related_query(rel, quote::quote! {});
+    let on = correlation(rel);
+
+    quote::quote! {
+        fn #fn_ident(self) -> ::sqlorm::QB<#s_ident> {
+            #related
+            let on = #on;
+            self.select_json_agg(#alias, related, on)
+        }
+    }
+}
+
+/// Generates `has_<relation>()`, `has_<relation>_where()` and `doesnt_have_<relation>()`,
+/// filtering rows by the existence of related rows.
+fn exists_implementation(es: &EntityStruct, rel: &Relation) -> proc_macro2::TokenStream {
+    let s_ident = &es.struct_ident;
+    let name = &rel.relation_name;
+    let has_ident = Ident::new(&format!("has_{}", name), rel.other.span());
+    let has_where_ident = Ident::new(&format!("has_{}_where", name), rel.other.span());
+    let doesnt_have_ident = Ident::new(&format!("doesnt_have_{}", name), rel.other.span());
+    let related = related_query(rel, quote::quote! {});
+    let related_filtered = related_query(rel, quote::quote! { .filter(cond) });
+    let on = correlation(rel);
+
+    quote::quote! {
+        fn #has_ident(self) -> ::sqlorm::QB<#s_ident> {
+            #related
+            let on = #on;
+            self.filter_exists(related, on)
+        }
+
+        fn #has_where_ident(self, cond: ::sqlorm::Condition) -> ::sqlorm::QB<#s_ident> {
+            #related_filtered
+            let on = #on;
+            self.filter_exists(related, on)
+        }
+
+        fn #doesnt_have_ident(self) -> ::sqlorm::QB<#s_ident> {
+            #related
+            let on = #on;
+            self.filter_not_exists(related, on)
+        }
+    }
+}
+
+/// Generates a `related` query on the other side of `rel`, refined by `refine`.
+fn related_query(rel: &Relation, refine: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let other = &rel.other;
+    let relation_name = &rel.relation_name;
+    quote::quote! {
+        let mut related = #other::query() #refine;
+        // self-referential relations nest a query on the same table
+        if related.base.alias == self.base.alias {
+            let alias = format!("{}_{}", related.base.alias, #relation_name);
+            related = related.realias(alias);
+        }
+    }
+}
+
+/// Generates an expression evaluating to SQL which correlates `related` rows with the row
+/// of `self`.
+fn correlation(rel: &Relation) -> proc_macro2::TokenStream {
+    let (on1, on2) = (rel.on.0.to_string(), rel.on.1.to_string());
+    match &rel.through {
+        None => quote::quote! {
+            format!("{}.{} = {}.{}", related.base.alias, #on2, self.base.alias, #on1)
+        },
+        Some(Through::Table {
+            table,
+            self_key,
+            other_key,
+        }) => {
+            let table = with_quotes(table);
+            let (self_key, other_key) = (self_key.to_string(), other_key.to_string());
+            quote::quote! {
+                format!(
+                    "EXISTS (SELECT 1 FROM {0} WHERE {0}.{1} = {2}.{3} AND {0}.{4} = {5}.{6})",
+                    #table, #other_key, related.base.alias, #on2, #self_key, self.base.alias, #on1
+                )
+            }
+        }
+        Some(Through::Entity {
+            entity,
+            self_key,
+            other_key,
+        }) => {
+            let (self_key, other_key) = (self_key.to_string(), other_key.to_string());
+            quote::quote! {
+                format!(
+                    "EXISTS (SELECT 1 FROM {0} WHERE {0}.{1} = {2}.{3} AND {0}.{4} = {5}.{6})",
+                    <#entity as ::sqlorm::Table>::SQL_NAME,
+                    #other_key,
+                    related.base.alias,
+                    #on2,
+                    #self_key,
+                    self.base.alias,
+                    #on1
+                )
+            }
         }
     }
 }
@@ -103,7 +349,37 @@ fn implementations(es: &EntityStruct, trait_name: &Ident) -> proc_macro2::TokenS
 fn declarations(es: &EntityStruct) -> Vec<Ident> {
     es.relations
         .iter()
-        .map(|rel| format!("with_{}", &rel.relation_name))
+        .flat_map(|rel| {
+            let mut names = vec![
+                format!("with_{}", &rel.relation_name),
+                format!("has_{}", &rel.relation_name),
+                format!("doesnt_have_{}", &rel.relation_name),
+            ];
+            if FetchVariant::from(&rel.kind) == FetchVariant::Eager {
+                names.push(format!("with_{}_inner", &rel.relation_name));
+            }
+            if rel.count {
+                names.push(format!("with_{}_count", &rel.relation_name));
+            }
+            if rel.load_json {
+                names.push(format!("with_{}_json", &rel.relation_name));
+            }
+            names
+        })
         .map(|name| Ident::new(&name, es.struct_ident.span()))
         .collect()
 }
+
+/// Idents of `with_<relation>_<suffix>` methods of batch relations.
+fn batch_declarations(es: &EntityStruct, suffix: &str) -> Vec<Ident> {
+    es.relations
+        .iter()
+        .filter(|rel| FetchVariant::from(&rel.kind) == FetchVariant::Batch)
+        .map(|rel| {
+            Ident::new(
+                &format!("with_{}_{}", rel.relation_name, suffix),
+                rel.other.span(),
+            )
+        })
+        .collect()
+}
@@ -8,6 +8,7 @@ use crate::{
 };
 
 pub fn belongs_to(tbl: &EntityStruct) -> TokenStream {
+    let vis = &tbl.vis;
     let entity = &tbl.struct_ident;
 
     let belongs_to_rel: Vec<TokenStream> = tbl
@@ -21,19 +22,38 @@ pub fn belongs_to(tbl: &EntityStruct) -> TokenStream {
                 relation_name,
                 other,
                 on: (self_field, _other_field),
+                ..
             } = rel
             {
                 let fn_ident = Ident::new(relation_name, Span::call_site());
                 let const_field = Ident::new(&_other_field.to_string().to_uppercase(), other.span());
+                let nullable = tbl
+                    .fields
+                    .iter()
+                    .any(|f| &f.ident == self_field && f.is_nullable());
+                // a NULL foreign key references no row
+                let key = if nullable {
+                    quote! {
+                        let Some(key) = self.#self_field.clone() else {
+                            return Ok(None);
+                        };
+                    }
+                } else {
+                    quote! { let key = self.#self_field.clone(); }
+                };
                 Some(quote! {
-                    pub async fn #fn_ident<'a, E>(
+                    /// Loads the related row, if any.
+                    ///
+                    /// `acquirer` may be a `&Pool`, a `&mut Connection` or a `&mut Transaction`.
+                    #vis async fn #fn_ident<'a, A>(
                         &self,
-                        executor: E
+                        acquirer: A
                     ) -> ::sqlorm::sqlx::Result<Option<#other>>
                     where
-                        E: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>
+                        A: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>
                     {
-                        #other::query().filter(#other::#const_field.eq(self.#self_field)).fetch_optional(executor).await
+                        #key
+                        #other::query().filter(#other::#const_field.eq(key.into())).fetch_optional(acquirer).await
                     }
                 })
             } else {
@@ -2,9 +2,13 @@ use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::Ident;
 
-use crate::{EntityStruct, relations::RelationType};
+use crate::{
+    EntityStruct,
+    relations::{RelationType, Through},
+};
 
 pub fn has_many(tbl: &EntityStruct) -> TokenStream {
+    let vis = &tbl.vis;
     let entity = &tbl.struct_ident;
 
     let has_many_rel: Vec<TokenStream> = tbl
@@ -19,16 +23,46 @@ pub fn has_many(tbl: &EntityStruct) -> TokenStream {
 
                 let fn_ident = Ident::new(relation_name, Span::call_site());
 
+                let filter = match &r.through {
+                    Some(Through::Entity {
+                        entity: through,
+                        self_key,
+                        other_key,
+                    }) => {
+                        let const_self_key =
+                            Ident::new(&self_key.to_string().to_uppercase(), Span::call_site());
+                        let const_other_key =
+                            Ident::new(&other_key.to_string().to_uppercase(), Span::call_site());
+                        quote! {
+                            {
+                                let other_col = #other::#const_on_field;
+                                let linked = format!(
+                                    "EXISTS (SELECT 1 FROM {0} WHERE {0}.{1} = {2}.{3} AND {0}.{4} = ?)",
+                                    <#through as ::sqlorm::Table>::SQL_NAME,
+                                    #through::#const_other_key.name,
+                                    other_col.table_alias,
+                                    other_col.name,
+                                    #through::#const_self_key.name,
+                                );
+                                ::sqlorm::Condition::new(linked, self.#on_field.clone())
+                            }
+                        }
+                    }
+                    _ => quote! { #other::#const_on_field.eq(self.#on_field.clone().into()) },
+                };
 
                 Some(quote! {
-                    pub async fn #fn_ident<'a, E>(
+                    /// Loads the related rows.
+                    ///
+                    /// `acquirer` may be a `&Pool`, a `&mut Connection` or a `&mut Transaction`.
+                    #vis async fn #fn_ident<'a, A>(
                         &self,
-                        executor: E
+                        acquirer: A
                     ) -> ::sqlorm::sqlx::Result<Vec<#other>>
                     where
-                        E: ::sqlorm::sqlx::Acquire<'a, Database = sqlorm::Driver> + Send
+                        A: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>
                     {
-                        #other::query().filter(#other::#const_on_field.eq(self.#on_field)).fetch_all(executor).await
+                        #other::query().filter(#filter).fetch_all(acquirer).await
                     }
                 })
             }
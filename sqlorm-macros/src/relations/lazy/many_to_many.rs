@@ -0,0 +1,68 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+use crate::{
+    EntityStruct,
+    relations::{RelationType, Through},
+};
+use sqlorm_core::with_quotes;
+
+pub fn many_to_many(tbl: &EntityStruct) -> TokenStream {
+    let vis = &tbl.vis;
+    let entity = &tbl.struct_ident;
+
+    let many_to_many_rel: Vec<TokenStream> = tbl
+        .relations
+        .iter()
+        .filter_map(|r| match (&r.kind, &r.through) {
+            (
+                RelationType::ManyToMany,
+                Some(Through::Table {
+                    table,
+                    self_key,
+                    other_key,
+                }),
+            ) => {
+                let other = &r.other;
+                let self_field = &r.on.0;
+                let const_other_field =
+                    Ident::new(&r.on.1.to_string().to_uppercase(), Span::call_site());
+                let fn_ident = Ident::new(&r.relation_name, Span::call_site());
+                let table = with_quotes(table);
+                let (self_key, other_key) = (self_key.to_string(), other_key.to_string());
+
+                Some(quote! {
+                    /// Loads the rows linked through the join table.
+                    ///
+                    /// `acquirer` may be a `&Pool`, a `&mut Connection` or a `&mut Transaction`.
+                    #vis async fn #fn_ident<'a, A>(
+                        &self,
+                        acquirer: A
+                    ) -> ::sqlorm::sqlx::Result<Vec<#other>>
+                    where
+                        A: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>
+                    {
+                        let other_col = #other::#const_other_field;
+                        let linked = format!(
+                            "EXISTS (SELECT 1 FROM {0} WHERE {0}.{1} = {2}.{3} AND {0}.{4} = ?)",
+                            #table, #other_key, other_col.table_alias, other_col.name, #self_key
+                        );
+                        #other::query()
+                            .filter(::sqlorm::Condition::new(linked, self.#self_field.clone()))
+                            .fetch_all(acquirer)
+                            .await
+                    }
+                })
+            }
+            _ => None,
+        })
+        .collect();
+
+    quote! {
+        #[automatically_derived]
+        impl #entity {
+            #(#many_to_many_rel)*
+        }
+    }
+}
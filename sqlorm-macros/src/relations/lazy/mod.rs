@@ -1,15 +1,21 @@
 mod belongs_to;
 mod has_many;
+mod many_to_many;
+mod tree;
 
 use proc_macro2::TokenStream;
 
 use crate::{
     EntityStruct,
-    relations::lazy::{belongs_to::belongs_to, has_many::has_many},
+    relations::lazy::{
+        belongs_to::belongs_to, has_many::has_many, many_to_many::many_to_many, tree::tree,
+    },
 };
 
 pub fn lazy(es: &EntityStruct) -> TokenStream {
     let bt = belongs_to(es);
     let hm = has_many(es);
-    quote::quote! {#bt #hm}
+    let mm = many_to_many(es);
+    let tree = tree(es);
+    quote::quote! {#bt #hm #mm #tree}
 }
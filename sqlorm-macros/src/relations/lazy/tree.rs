@@ -0,0 +1,140 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+use crate::{
+    EntityStruct,
+    entity::SoftDelete,
+    relations::RelationType,
+};
+use sqlorm_core::with_quotes;
+
+/// Generates `load_descendants()` for entities having a has_many relation to themselves,
+/// e.g. `Category.parent_id -> Category`.
+pub fn tree(tbl: &EntityStruct) -> TokenStream {
+    let vis = &tbl.vis;
+    let entity = &tbl.struct_ident;
+
+    let self_relations: Vec<_> = tbl
+        .relations
+        .iter()
+        .filter(|r| {
+            matches!(r.kind, RelationType::HasMany) && r.through.is_none() && &r.other == entity
+        })
+        .collect();
+
+    let r = match self_relations.as_slice() {
+        [] => return TokenStream::new(),
+        [r] => *r,
+        [_, second, ..] => {
+            return syn::Error::new_spanned(
+                &second.other,
+                "only one self-referential has_many relation is supported per entity",
+            )
+            .to_compile_error();
+        }
+    };
+
+    let relation = Ident::new(&r.relation_name, Span::call_site());
+    let (parent_key, foreign_key) = (&r.on.0, &r.on.1);
+    let Some(foreign_key_field) = tbl.fields.iter().find(|f| &f.ident == foreign_key) else {
+        return syn::Error::new_spanned(foreign_key, "unknown field").to_compile_error();
+    };
+    let Some(parent_key_field) = tbl.fields.iter().find(|f| &f.ident == parent_key) else {
+        return syn::Error::new_spanned(parent_key, "unknown field").to_compile_error();
+    };
+    let foreign_key_ty = &foreign_key_field.ty;
+    let parent_key_ty = &parent_key_field.ty;
+    let table_raw = &tbl.table_name.raw;
+
+    let table = with_quotes(&tbl.table_name.qualified());
+    let (fk, pk) = (&foreign_key_field.name, &parent_key_field.name);
+    let not_deleted = match tbl.soft_delete() {
+        Some(SoftDelete::Timestamp { field, .. }) => {
+            format!(" AND {}.{} IS NULL", table, field.name)
+        }
+        Some(SoftDelete::Flag { field }) => format!(" AND {}.{} = FALSE", table, field.name),
+        None => String::new(),
+    };
+    let (root, max_depth) = if cfg!(feature = "postgres") {
+        ("$1", "$2")
+    } else {
+        ("?", "?")
+    };
+    let sql = format!(
+        "WITH RECURSIVE __tree AS (\
+         SELECT {table}.*, 1 AS __depth FROM {table} WHERE {table}.{fk} = {root}{not_deleted} \
+         UNION ALL \
+         SELECT {table}.*, __tree.__depth + 1 FROM {table} \
+         INNER JOIN __tree ON {table}.{fk} = __tree.{pk} \
+         WHERE __tree.__depth < {max_depth}{not_deleted}) \
+         SELECT * FROM __tree"
+    );
+
+    quote! {
+        #[automatically_derived]
+        impl #entity {
+            /// Loads the relation recursively, `depth` levels deep, with a single recursive query.
+            ///
+            /// Rows of the deepest loaded level keep the relation unloaded (`None`). Fails with
+            /// [`::sqlorm::RelationCycle`] if a loaded row is its own ancestor.
+            ///
+            /// Example usage:
+            /// ```rust ignore
+            /// let root = root.load_descendants(3, &pool).await?;
+            /// ```
+            #vis async fn load_descendants<'a, A>(
+                mut self,
+                depth: u32,
+                acquirer: A,
+            ) -> ::sqlorm::sqlx::Result<Self>
+            where
+                A: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+            {
+                fn attach(
+                    node: &mut #entity,
+                    level: u32,
+                    depth: u32,
+                    by_parent: &mut ::sqlorm::HashMap<#foreign_key_ty, Vec<#entity>>,
+                ) {
+                    if level == depth {
+                        return;
+                    }
+                    let key: #foreign_key_ty = node.#parent_key.clone().into();
+                    let mut children = by_parent.remove(&key).unwrap_or_default();
+                    for child in &mut children {
+                        attach(child, level + 1, depth, by_parent);
+                    }
+                    node.#relation = Some(children);
+                }
+
+                if depth == 0 {
+                    return Ok(self);
+                }
+
+                let mut conn = acquirer.acquire().await?;
+                let rows: Vec<Self> = ::sqlorm::sqlx::query_as::<_, Self>(#sql)
+                    .bind(self.#parent_key.clone())
+                    .bind(i32::try_from(depth).unwrap_or(i32::MAX))
+                    .fetch_all(&mut *conn)
+                    .await?;
+
+                // cyclic foreign keys would otherwise load the same rows again on every level
+                let mut seen: ::std::collections::HashSet<#parent_key_ty> =
+                    ::std::collections::HashSet::new();
+                seen.insert(self.#parent_key.clone());
+                let mut by_parent: ::sqlorm::HashMap<#foreign_key_ty, Vec<Self>> =
+                    ::sqlorm::HashMap::new();
+                for row in rows {
+                    if !seen.insert(row.#parent_key.clone()) {
+                        return Err(::sqlorm::RelationCycle::new(#table_raw).into());
+                    }
+                    by_parent.entry(row.#foreign_key.clone()).or_default().push(row);
+                }
+                attach(&mut self, 0, depth, &mut by_parent);
+
+                Ok(self)
+            }
+        }
+    }
+}
@@ -2,14 +2,23 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::Ident;
 mod lazy;
+mod mutation;
 mod validation;
 
 use crate::EntityStruct;
 
 pub fn relations(tbl: &EntityStruct) -> TokenStream {
     let lazy = lazy::lazy(tbl);
+    let mutation = if tbl.kind.is_read_only() {
+        quote! {}
+    } else {
+        mutation::mutation(tbl)
+    };
+    let on_columns = validation::assert_on_columns(tbl);
     quote! {
         #lazy
+        #mutation
+        #on_columns
     }
 }
 
@@ -18,6 +27,7 @@ pub enum RelationType {
     BelongsTo,
     HasMany,
     HasOne,
+    ManyToMany,
 }
 #[derive(Debug, Clone)]
 pub struct Relation {
@@ -28,5 +38,45 @@ pub struct Relation {
     /// User has_many Jar
     pub on: (Ident, Ident),
     pub relation_name: String,
+    /// Intermediate table linking both sides, set with `through = ...`
+    pub through: Option<Through>,
+    /// What `remove_<child>()` does with the removed row, set with `on_remove = ...`
+    pub on_remove: OnRemove,
+    /// Whether `with_<relation>_json()` is generated, set with `load = json`
+    pub load_json: bool,
+    /// Whether the `<relation>_count` field and `with_<relation>_count()` are generated, set
+    /// with `count`
+    pub count: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub enum OnRemove {
+    /// Deletes the removed row, softly if it has `deleted_at`
+    #[default]
+    Delete,
+    /// Resets the foreign key of the removed row to its default, i.e. `None` when nullable
+    Nullify,
+}
+
+#[derive(Debug, Clone)]
+pub enum Through {
+    /// Join table of a many-to-many relation, e.g.
+    /// `through = "post_tags", keys = (post_id, tag_id)`
+    Table {
+        table: String,
+        /// Join table column referencing my `on` field
+        self_key: Ident,
+        /// Join table column referencing other's `on` field
+        other_key: Ident,
+    },
+    /// Intermediate entity of a has_many through relation, e.g.
+    /// `through = Jar, keys = (owner_id, id)` for users receiving donations via their jars
+    Entity {
+        entity: Ident,
+        /// Intermediate field referencing my field
+        self_key: Ident,
+        /// Intermediate field referenced by other's `on` field
+        other_key: Ident,
+    },
 }
 pub use validation::validate_relations;
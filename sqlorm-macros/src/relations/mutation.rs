@@ -0,0 +1,192 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Ident;
+
+use crate::{
+    EntityStruct,
+    naming::singular,
+    relations::{OnRemove, Relation, RelationType, Through},
+};
+
+/// Generates `add_<child>()` and `remove_<child>()` for has_many relations, e.g.
+/// `user.add_jar(jar, &pool)` for `name = "jars"`, and `attach_<related>()`,
+/// `detach_<related>()` and `sync_<relation>()` for many-to-many relations.
+pub fn mutation(tbl: &EntityStruct) -> TokenStream {
+    let vis = &tbl.vis;
+    let entity = &tbl.struct_ident;
+
+    let mut helpers: Vec<TokenStream> = tbl
+        .relations
+        .iter()
+        .filter(|r| matches!(r.kind, RelationType::HasMany) && r.through.is_none())
+        .map(|r| {
+            let other = &r.other;
+            let (parent_key, foreign_key) = (&r.on.0, &r.on.1);
+            let child = singular(&r.relation_name);
+            let add_ident = Ident::new(&format!("add_{}", child), other.span());
+            let remove_ident = Ident::new(&format!("remove_{}", child), other.span());
+
+            let remove = match r.on_remove {
+                OnRemove::Delete => quote! {
+                    use ::sqlorm::StatementExecutor;
+                    // not `child.delete()`, which the child may opt out of with `skip_generate`
+                    ::sqlorm::SB::<#other, ::sqlorm::Delete>::new(
+                        <#other as ::sqlorm::Table>::table_info(),
+                        child,
+                    )
+                    .execute(acquirer)
+                    .await
+                },
+                OnRemove::Nullify => quote! {
+                    child.#foreign_key = Default::default();
+                    child.save(acquirer).await
+                },
+            };
+
+            quote! {
+                /// Links `child` to this row by setting its foreign key, then saves it.
+                #vis async fn #add_ident<'a, A>(
+                    &self,
+                    mut child: #other,
+                    acquirer: A,
+                ) -> ::sqlorm::sqlx::Result<#other>
+                where
+                    A: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+                {
+                    child.#foreign_key = self.#parent_key.clone().into();
+                    child.save(acquirer).await
+                }
+
+                /// Removes `child` from this row according to the relation's `on_remove`
+                /// strategy. Fails with `RowNotFound` if `child` belongs to another row.
+                #[allow(unused_mut)]
+                #vis async fn #remove_ident<'a, A>(
+                    &self,
+                    mut child: #other,
+                    acquirer: A,
+                ) -> ::sqlorm::sqlx::Result<#other>
+                where
+                    A: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+                {
+                    fn is_child_of<K: PartialEq>(foreign_key: &K, parent_key: K) -> bool {
+                        *foreign_key == parent_key
+                    }
+
+                    if !is_child_of(&child.#foreign_key, self.#parent_key.clone().into()) {
+                        return Err(::sqlorm::sqlx::Error::RowNotFound);
+                    }
+                    #remove
+                }
+            }
+        })
+        .collect();
+    helpers.extend(tbl.relations.iter().filter_map(|r| pivot_helpers(r, vis)));
+
+    if helpers.is_empty() {
+        return TokenStream::new();
+    }
+
+    quote! {
+        #[automatically_derived]
+        impl #entity {
+            #(#helpers)*
+        }
+    }
+}
+
+fn pivot_helpers(r: &Relation, vis: &syn::Visibility) -> Option<TokenStream> {
+    let Some(Through::Table {
+        table,
+        self_key,
+        other_key,
+    }) = &r.through
+    else {
+        return None;
+    };
+    if !matches!(r.kind, RelationType::ManyToMany) {
+        return None;
+    }
+
+    let span = r.other.span();
+    let parent_key = &r.on.0;
+    let (self_key, other_key) = (self_key.to_string(), other_key.to_string());
+    let related = singular(&r.relation_name);
+    let attach_ident = Ident::new(&format!("attach_{}", related), span);
+    let attach_with_ident = Ident::new(&format!("attach_{}_with", related), span);
+    let detach_ident = Ident::new(&format!("detach_{}", related), span);
+    let sync_ident = Ident::new(&format!("sync_{}", r.relation_name), span);
+
+    Some(quote! {
+        /// Links the row identified by `id` to this row through the join table.
+        /// Attaching an already linked row is a no-op.
+        #vis async fn #attach_ident<'a, V, A>(&self, id: V, acquirer: A) -> ::sqlorm::sqlx::Result<()>
+        where
+            V: ::sqlorm::BindValue + Clone,
+            A: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+        {
+            self.#attach_with_ident(id, ::sqlorm::PivotValues::new(), acquirer).await
+        }
+
+        /// Like the plain attach, also writing `values` to extra join table columns.
+        #vis async fn #attach_with_ident<'a, V, A>(
+            &self,
+            id: V,
+            values: ::sqlorm::PivotValues,
+            acquirer: A,
+        ) -> ::sqlorm::sqlx::Result<()>
+        where
+            V: ::sqlorm::BindValue + Clone,
+            A: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+        {
+            let mut conn = acquirer.acquire().await?;
+            ::sqlorm::attach_pivot(
+                &mut *conn,
+                #table,
+                #self_key,
+                #other_key,
+                self.#parent_key.clone(),
+                vec![id],
+                &values,
+            )
+            .await
+        }
+
+        /// Unlinks the row identified by `id` from this row, returning whether it was linked.
+        #vis async fn #detach_ident<'a, V, A>(&self, id: V, acquirer: A) -> ::sqlorm::sqlx::Result<bool>
+        where
+            V: ::sqlorm::BindValue,
+            A: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+        {
+            let mut conn = acquirer.acquire().await?;
+            let removed = ::sqlorm::detach_pivot(
+                &mut *conn,
+                #table,
+                #self_key,
+                #other_key,
+                self.#parent_key.clone(),
+                vec![id],
+            )
+            .await?;
+            Ok(removed > 0)
+        }
+
+        /// Makes `ids` the only rows linked to this row, atomically. Extra join table
+        /// columns of links that are kept are left untouched.
+        #vis async fn #sync_ident<'a, V, A>(&self, ids: Vec<V>, acquirer: A) -> ::sqlorm::sqlx::Result<()>
+        where
+            V: ::sqlorm::BindValue + Clone,
+            A: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+        {
+            let mut conn = acquirer.acquire().await?;
+            ::sqlorm::sync_pivot(
+                &mut *conn,
+                #table,
+                #self_key,
+                #other_key,
+                self.#parent_key.clone(),
+                ids,
+            )
+            .await
+        }
+    })
+}
@@ -1,8 +1,11 @@
-use syn::{Error, GenericArgument, PathArguments, Type};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, GenericArgument, Ident, PathArguments, Type};
 
 use crate::{
+    EntityStruct,
     entity::EntityField,
-    relations::{Relation, RelationType},
+    relations::{Relation, RelationType, Through},
 };
 
 pub fn validate_relations(rels: &[Relation], fields: &[EntityField]) -> syn::Result<()> {
@@ -36,15 +39,22 @@ pub fn validate_relations(rels: &[Relation], fields: &[EntityField]) -> syn::Res
                     && let Some(GenericArgument::Type(inner_ty)) = args.args.first()
                     && let Type::Path(inner_path) = inner_ty
                 {
-                    let inner_seg = inner_path.path.segments.last().unwrap();
+                    let mut inner_seg = inner_path.path.segments.last().unwrap();
+                    // self-referential relations are boxed, e.g. Option<Box<Category>>
+                    if inner_seg.ident == "Box"
+                        && let PathArguments::AngleBracketed(box_args) = &inner_seg.arguments
+                        && let Some(GenericArgument::Type(Type::Path(boxed))) = box_args.args.first()
+                    {
+                        inner_seg = boxed.path.segments.last().unwrap();
+                    }
                     match &rel.kind {
-                        RelationType::HasMany => {
+                        RelationType::HasMany | RelationType::ManyToMany => {
                             if inner_seg.ident != "Vec" {
                                 return Err(syn::Error::new_spanned(
                                     inner_ty,
                                     format!(
-                                        "Expected Vec<{}> inside Option because of HasMany",
-                                        &rel.other
+                                        "Expected Vec<{}> inside Option because of {:?}",
+                                        &rel.other, &rel.kind
                                     ),
                                 ));
                             }
@@ -95,7 +105,9 @@ pub fn validate_relations(rels: &[Relation], fields: &[EntityField]) -> syn::Res
             }
         } else {
             let expected_ty = match rel.kind {
-                RelationType::HasMany => format!("Option<Vec<{}>>", rel.other),
+                RelationType::HasMany | RelationType::ManyToMany => {
+                    format!("Option<Vec<{}>>", rel.other)
+                }
                 RelationType::HasOne => format!("Option<{}>", rel.other),
                 RelationType::BelongsTo => format!("Option<{}>", rel.other),
             };
@@ -111,3 +123,41 @@ pub fn validate_relations(rels: &[Relation], fields: &[EntityField]) -> syn::Res
     }
     Ok(())
 }
+
+/// Checks at compile time that the fields named by `on` (and `keys` of intermediate entities)
+/// exist on the referenced entities, by referencing their column constants.
+///
+/// A typo such as `on = owner_idd` then fails with an error spanned at `owner_idd`, instead of
+/// a SQL error at runtime.
+pub fn assert_on_columns(es: &EntityStruct) -> TokenStream {
+    let column = |entity: &Ident, field: &Ident| {
+        let col = Ident::new(&field.to_string().to_uppercase(), field.span());
+        quote! { let _ = #entity::#col; }
+    };
+    let checks: Vec<TokenStream> = es
+        .relations
+        .iter()
+        .flat_map(|rel| {
+            let mut checks = vec![column(&rel.other, &rel.on.1)];
+            if let Some(Through::Entity {
+                entity,
+                self_key,
+                other_key,
+            }) = &rel.through
+            {
+                checks.push(column(entity, self_key));
+                checks.push(column(entity, other_key));
+            }
+            checks
+        })
+        .collect();
+
+    if checks.is_empty() {
+        return TokenStream::new();
+    }
+    quote! {
+        const _: () = {
+            #(#checks)*
+        };
+    }
+}
@@ -1,13 +1,11 @@
-use crate::{
-    EntityStruct,
-    entity::{FieldKind, TimestampKind},
-};
-use quote::quote;
+use crate::{EntityStruct, hooks, observers};
+use quote::{format_ident, quote};
 use sqlorm_core::with_quotes;
 
 pub fn executor(es: &EntityStruct) -> proc_macro2::TokenStream {
     let ident = &es.struct_ident;
     let implementation = delete_implementation(es);
+    let delete_returning = delete_returning(es);
 
     quote! {
         #[automatically_derived]
@@ -15,31 +13,121 @@ pub fn executor(es: &EntityStruct) -> proc_macro2::TokenStream {
         impl ::sqlorm::StatementExecutor<#ident> for ::sqlorm::SB<#ident,::sqlorm::Delete> {
             #implementation
         }
+
+        #[automatically_derived]
+        impl #ident {
+            #delete_returning
+        }
+    }
+}
+
+/// `delete_returning()`: deletes like `delete()` and returns the affected row.
+fn delete_returning(es: &EntityStruct) -> proc_macro2::TokenStream {
+    let vis = &es.vis;
+    let table_name = with_quotes(&es.table_name.qualified());
+    let ident = &es.struct_ident;
+    let pk_ident = &es.pk.ident;
+    let pk_col = &es.pk.name;
+
+    let query = match es.soft_delete() {
+        Some(soft_delete) => {
+            let deleted_at_col = &soft_delete.field().name;
+            let deleted_value = soft_delete.deleted_value();
+            let (placeholder1, placeholder2) = if cfg!(feature = "postgres") {
+                ("$1", "$2")
+            } else {
+                ("?", "?")
+            };
+            let sql = format!(
+                "UPDATE {} SET {} = {} WHERE {} = {} RETURNING *",
+                table_name, deleted_at_col, placeholder1, pk_col, placeholder2
+            );
+            quote! {
+                ::sqlorm::sqlx::query_as::<_, #ident>(#sql)
+                    .bind(#deleted_value)
+                    .bind(&self.#pk_ident)
+            }
+        }
+        None => {
+            let placeholder = if cfg!(feature = "postgres") {
+                "$1"
+            } else {
+                "?"
+            };
+            let sql = format!(
+                "DELETE FROM {} WHERE {} = {} RETURNING *",
+                table_name, pk_col, placeholder
+            );
+            quote! {
+                ::sqlorm::sqlx::query_as::<_, #ident>(#sql)
+                    .bind(&self.#pk_ident)
+            }
+        }
+    };
+
+    let conn = quote! { conn };
+    let self_mut = if es.hooks { quote! { mut } } else { quote! {} };
+    let before_delete = hooks::call(es, "before_delete", quote! { self }, &conn);
+    let after_delete = {
+        let call = hooks::call_on(es, "after_delete", &format_ident!("deleted"), &conn);
+        let notify = observers::notify(es, "Deleted", quote! { deleted });
+        quote! { #call #notify }
+    };
+
+    quote! {
+        /// Deletes the entity like `delete()` and returns the affected row via `RETURNING *`,
+        /// so no prior `SELECT` is needed. Soft deleted rows are returned with the soft delete
+        /// column set.
+        ///
+        /// Fails with `RowNotFound` if the row no longer exists.
+        ///
+        /// Example usage:
+        /// ```rust ignore
+        /// let removed: Jar = jar.delete_returning(&pool).await?;
+        /// ```
+        #vis async fn delete_returning<'a, A>(#self_mut self, acquirer: A) -> ::sqlorm::sqlx::Result<#ident>
+        where
+            A: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+        {
+            let mut conn = acquirer.acquire().await?;
+            #before_delete
+            let deleted = #query
+                .fetch_one(&mut *conn)
+                .await?;
+            #after_delete
+            Ok(deleted)
+        }
     }
 }
 
 pub fn delete_implementation(es: &EntityStruct) -> proc_macro2::TokenStream {
-    let table_name = with_quotes(&es.table_name.raw);
+    let table_name = with_quotes(&es.table_name.qualified());
     let ident = &es.struct_ident;
     let pk_ident = &es.pk.ident;
     let pk_col = &es.pk.name;
+    let conn = quote! { conn };
+    let self_mut = if es.hooks { quote! { mut } } else { quote! {} };
+    let before_delete = hooks::call(es, "before_delete", quote! { self.entity }, &conn);
+    let after_delete = {
+        let call = hooks::call(es, "after_delete", quote! { self.entity }, &conn);
+        let notify = observers::notify(es, "Deleted", quote! { self.entity });
+        quote! { #call #notify }
+    };
+    let after_delete_affected = if es.hooks || es.observable {
+        quote! {
+            if result.rows_affected() > 0 {
+                #after_delete
+            }
+        }
+    } else {
+        quote! {}
+    };
 
-    if let Some(f) = es
-        .fields
-        .iter()
-        .find(|f| matches!(f.kind, FieldKind::Timestamp(TimestampKind::Deleted { .. })))
-    {
-        let deleted_at_col = &f.name;
-        let deleted_at_ident = &f.ident;
-        let factory = if let FieldKind::Timestamp(TimestampKind::Deleted { factory }) = &f.kind {
-            factory
-        } else {
-            return syn::Error::new_spanned(
-                deleted_at_ident,
-                "You must provide factory for deleted_at timestamp: e.g. chrono::Utc::now()",
-            )
-            .to_compile_error();
-        };
+    if let Some(soft_delete) = es.soft_delete() {
+        let deleted_at_col = &soft_delete.field().name;
+        let deleted_at_ident = &soft_delete.field().ident;
+        let factory = soft_delete.deleted_value();
+        let deleted_field_value = soft_delete.deleted_field_value(&quote! { deleted_at });
 
         let (placeholder1, placeholder2) = if cfg!(feature = "postgres") {
             ("$1", "$2")
@@ -56,19 +144,88 @@ pub fn delete_implementation(es: &EntityStruct) -> proc_macro2::TokenStream {
             {
                 use ::sqlorm::sqlx::Acquire;
                 let mut conn = acquirer.acquire().await?;
+                #before_delete
                 let deleted_at = #factory;
                 let sql = format!(
                     "UPDATE {} SET {} = {} WHERE {} = {}",
                     #table_name, #deleted_at_col, #placeholder1, #pk_col, #placeholder2
                 );
+                let sql = sql + &::sqlorm::comment::render(&self.comments);
                 ::sqlorm::sqlx::query(&sql)
                     .bind(&deleted_at)
                     .bind(&self.entity.#pk_ident)
                     .execute(&mut *conn)
                     .await?;
-                self.entity.#deleted_at_ident = Some(deleted_at);
+                self.entity.#deleted_at_ident = #deleted_field_value;
+                #after_delete
                 Ok(self.entity)
             }
+
+            async fn execute_affected<'a, E>(
+                #self_mut self,
+                acquirer: E
+            ) -> ::sqlorm::sqlx::Result<u64>
+            where E: ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver> + Send
+            {
+                use ::sqlorm::sqlx::Acquire;
+                let mut conn = acquirer.acquire().await?;
+                #before_delete
+                let sql = format!(
+                    "UPDATE {} SET {} = {} WHERE {} = {}",
+                    #table_name, #deleted_at_col, #placeholder1, #pk_col, #placeholder2
+                );
+                let sql = sql + &::sqlorm::comment::render(&self.comments);
+                let result = ::sqlorm::sqlx::query(&sql)
+                    .bind(#factory)
+                    .bind(&self.entity.#pk_ident)
+                    .execute(&mut *conn)
+                    .await?;
+                #after_delete_affected
+                Ok(result.rows_affected())
+            }
+
+            async fn execute_returning<'a, C, E>(
+                mut self,
+                columns: C,
+                acquirer: E
+            ) -> ::sqlorm::sqlx::Result<C::Row>
+            where
+                C: ::sqlorm::Selectable + Send,
+                C::Row: for<'r> ::sqlorm::sqlx::FromRow<'r, ::sqlorm::Row> + Send + Unpin,
+                E: ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver> + Send
+            {
+                use ::sqlorm::sqlx::Acquire;
+                let mut conn = acquirer.acquire().await?;
+                #before_delete
+                let deleted_at = #factory;
+                let sql = format!(
+                    "UPDATE {} SET {} = {} WHERE {} = {} RETURNING {}",
+                    #table_name, #deleted_at_col, #placeholder1, #pk_col, #placeholder2,
+                    columns.collect().join(", ")
+                );
+                let sql = sql + &::sqlorm::comment::render(&self.comments);
+                let returned = ::sqlorm::sqlx::query_as::<::sqlorm::Driver, C::Row>(&sql)
+                    .bind(&deleted_at)
+                    .bind(&self.entity.#pk_ident)
+                    .fetch_one(&mut *conn)
+                    .await?;
+                self.entity.#deleted_at_ident = #deleted_field_value;
+                #after_delete
+                Ok(returned)
+            }
+
+            fn debug_sql(&self) -> String {
+                let sql = format!(
+                    "UPDATE {} SET {} = {} WHERE {} = {}",
+                    #table_name, #deleted_at_col, #placeholder1, #pk_col, #placeholder2
+                );
+                let sql = sql + &::sqlorm::comment::render(&self.comments);
+                let values = vec![
+                    format!("{:?}", #factory),
+                    format!("{:?}", self.entity.#pk_ident),
+                ];
+                ::sqlorm::interpolate_debug(&sql, &values)
+            }
         }
     } else {
         let placeholder = if cfg!(feature = "postgres") {
@@ -86,16 +243,78 @@ pub fn delete_implementation(es: &EntityStruct) -> proc_macro2::TokenStream {
             {
                 use ::sqlorm::sqlx::Acquire;
                 let mut conn = acquirer.acquire().await?;
+                #before_delete
                 let sql = format!(
                     "DELETE FROM {} WHERE {} = {}",
                     #table_name, #pk_col, #placeholder
                 );
+                let sql = sql + &::sqlorm::comment::render(&self.comments);
                 ::sqlorm::sqlx::query(&sql)
                     .bind(&self.entity.#pk_ident)
                     .execute(&mut *conn)
                     .await?;
+                #after_delete
                 Ok(self.entity)
             }
+
+            async fn execute_affected<'a, E>(
+                #self_mut self,
+                acquirer: E
+            ) -> ::sqlorm::sqlx::Result<u64>
+            where E: ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver> + Send
+            {
+                use ::sqlorm::sqlx::Acquire;
+                let mut conn = acquirer.acquire().await?;
+                #before_delete
+                let sql = format!(
+                    "DELETE FROM {} WHERE {} = {}",
+                    #table_name, #pk_col, #placeholder
+                );
+                let sql = sql + &::sqlorm::comment::render(&self.comments);
+                let result = ::sqlorm::sqlx::query(&sql)
+                    .bind(&self.entity.#pk_ident)
+                    .execute(&mut *conn)
+                    .await?;
+                #after_delete_affected
+                Ok(result.rows_affected())
+            }
+
+            async fn execute_returning<'a, C, E>(
+                #self_mut self,
+                columns: C,
+                acquirer: E
+            ) -> ::sqlorm::sqlx::Result<C::Row>
+            where
+                C: ::sqlorm::Selectable + Send,
+                C::Row: for<'r> ::sqlorm::sqlx::FromRow<'r, ::sqlorm::Row> + Send + Unpin,
+                E: ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver> + Send
+            {
+                use ::sqlorm::sqlx::Acquire;
+                let mut conn = acquirer.acquire().await?;
+                #before_delete
+                let sql = format!(
+                    "DELETE FROM {} WHERE {} = {} RETURNING {}",
+                    #table_name, #pk_col, #placeholder,
+                    columns.collect().join(", ")
+                );
+                let sql = sql + &::sqlorm::comment::render(&self.comments);
+                let returned = ::sqlorm::sqlx::query_as::<::sqlorm::Driver, C::Row>(&sql)
+                    .bind(&self.entity.#pk_ident)
+                    .fetch_one(&mut *conn)
+                    .await?;
+                #after_delete
+                Ok(returned)
+            }
+
+            fn debug_sql(&self) -> String {
+                let sql = format!(
+                    "DELETE FROM {} WHERE {} = {}",
+                    #table_name, #pk_col, #placeholder
+                );
+                let sql = sql + &::sqlorm::comment::render(&self.comments);
+                let values = vec![format!("{:?}", self.entity.#pk_ident)];
+                ::sqlorm::interpolate_debug(&sql, &values)
+            }
         }
     }
 }
@@ -1,6 +1,7 @@
 use crate::{
     EntityStruct,
     entity::{FieldKind, TimestampKind},
+    hooks, observers, validators,
 };
 use quote::quote;
 use sqlorm_core::with_quotes;
@@ -19,7 +20,8 @@ pub fn executor(es: &EntityStruct) -> proc_macro2::TokenStream {
 }
 
 pub fn implementation(es: &EntityStruct) -> proc_macro2::TokenStream {
-    let table_name = with_quotes(&es.table_name.raw);
+    let table_name = with_quotes(&es.table_name.qualified());
+    let table_name_raw = &es.table_name.raw;
     let ident = &es.struct_ident;
     let pk_ident = &es.pk.ident;
     let pk_col = &es.pk.name;
@@ -27,9 +29,83 @@ pub fn implementation(es: &EntityStruct) -> proc_macro2::TokenStream {
     let updateable_fields: Vec<_> = es
         .fields
         .iter()
-        .filter(|f| !f.is_pk() && !f.is_ignored())
+        .filter(|f| !f.is_pk() && !f.is_ignored() && !f.is_version() && !f.immutable)
         .collect();
 
+    let version = es.fields.iter().find(|f| f.is_version());
+
+    // renders placeholder of the `bound`-th bound value
+    let placeholder = if cfg!(feature = "postgres") {
+        quote! { format!("${}", bound) }
+    } else {
+        quote! {{
+            let _ = bound;
+            "?".to_string()
+        }}
+    };
+
+    // `SET version = version + 1` and `AND version = ?`
+    let (version_set, version_where) = match version {
+        Some(f) => {
+            let col = &f.name;
+            (
+                quote! { set_clause.push(format!("{0} = {0} + 1", #col)); },
+                quote! {
+                    bound += 1;
+                    let where_clause = format!("{} AND {} = {}", where_clause, #col, #placeholder);
+                },
+            )
+        }
+        None => (quote! {}, quote! {}),
+    };
+    let version_bind = match version {
+        Some(f) => {
+            let ident = &f.ident;
+            quote! { query = query.bind(&self.entity.#ident); }
+        }
+        None => quote! {},
+    };
+    let version_debug = match version {
+        Some(f) => {
+            let ident = &f.ident;
+            quote! { values.push(format!("{:?}", self.entity.#ident)); }
+        }
+        None => quote! {},
+    };
+    let version_col = version.map(|f| f.name.clone()).unwrap_or_default();
+    let version_returning_check = match version {
+        Some(_) => quote! {
+            .map_err(|e| match e {
+                ::sqlorm::sqlx::Error::RowNotFound => {
+                    ::sqlorm::StaleObject::new(#table_name_raw).into()
+                }
+                e => e,
+            })
+        },
+        None => quote! {},
+    };
+    // the hooks and observers of `execute_returning` see the bumped version, like `execute`
+    let version_increment = match version {
+        Some(f) if es.hooks || es.observable => {
+            let ident = &f.ident;
+            quote! { self.entity.#ident += 1; }
+        }
+        _ => quote! {},
+    };
+    // zero affected rows means the row was changed since it was loaded
+    let version_check = match version {
+        Some(f) => {
+            let ident = &f.ident;
+            quote! {
+                if result.rows_affected() == 0 {
+                    return Err(::sqlorm::StaleObject::new(#table_name_raw).into());
+                }
+                self.entity.#ident += 1;
+            }
+        }
+        None => quote! { let _ = result; },
+    };
+
     let all_columns: Vec<String> = updateable_fields.iter().map(|f| f.name.clone()).collect();
 
     let updated_assign_update = es
@@ -46,30 +122,113 @@ pub fn implementation(es: &EntityStruct) -> proc_macro2::TokenStream {
         })
         .unwrap_or_else(|| quote! {});
 
-    let placeholder_generator = if cfg!(feature = "postgres") {
-        quote! {
-            let placeholders: Vec<String> = (1..=fields_to_update.len())
-                .map(|i| format!("${}", i))
-                .collect();
-            let where_placeholder = format!("${}", fields_to_update.len() + 1);
-        }
+    let first_placeholder = if cfg!(feature = "postgres") {
+        "$1"
     } else {
-        quote! {
-            let placeholders: Vec<String> = vec!["?".to_string(); fields_to_update.len()];
-            let where_placeholder = "?".to_string();
-        }
+        "?"
     };
 
-    let field_bindings = updateable_fields.iter().map(|field| {
-        let field_ident = &field.ident;
+    let field_bindings: Vec<_> = updateable_fields
+        .iter()
+        .map(|field| {
+            let field_name = &field.name;
+            let value = field.bind_value(&quote! { self.entity });
+            quote! {
+                #field_name => {
+                    query = query.bind(#value);
+                }
+            }
+        })
+        .collect();
+
+    let debug_bindings = updateable_fields.iter().map(|field| {
         let field_name = &field.name;
+        let value = match &field.kind {
+            _ if field.encrypted.is_some() => quote! { "<encrypted>" },
+            FieldKind::Timestamp(TimestampKind::Updated { factory }) => quote! { #factory },
+            _ => field.access(&quote! { self.entity }),
+        };
         quote! {
             #field_name => {
-                query = query.bind(&self.entity.#field_ident);
+                values.push(format!("{:?}", #value));
             }
         }
     });
 
+    let validate = if es.validates() {
+        let check = validators::check(es, quote! { self.entity });
+        quote! {
+            if self.validate {
+                #check
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let conn = quote! { conn };
+    let before_update = hooks::call(es, "before_update", quote! { self.entity }, &conn);
+    let after_update = {
+        let call = hooks::call(es, "after_update", quote! { self.entity }, &conn);
+        let notify = observers::notify(es, "Updated", quote! { self.entity });
+        quote! { #call #notify }
+    };
+    // a stale versioned entity updates no row
+    let after_update_affected = if es.hooks || es.observable {
+        quote! {
+            if result.rows_affected() > 0 {
+                #after_update
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // outputs `fields_to_update` and `sql` variables
+    let build_sql = quote! {
+        let fallback_columns = vec![#(#all_columns),*];
+        let fields_to_update: Vec<&str> = self
+            .fields
+            .as_ref()
+            .unwrap_or(&fallback_columns)
+            .iter()
+            .copied()
+            .filter(|field| *field != #version_col)
+            .filter(|field| !self.is_assigned(field))
+            .collect();
+
+        let mut bound = 0usize;
+        let mut set_clause: Vec<String> = Vec::new();
+        for field in &fields_to_update {
+            bound += 1;
+            set_clause.push(format!("{} = {}", field, #placeholder));
+        }
+        for (column, expr) in &self.exprs {
+            set_clause.push(format!("{} = {}", column, expr.render(&mut bound)));
+        }
+        for raw in &self.raw_sets {
+            set_clause.push(raw.render(&mut bound));
+        }
+        #version_set
+        bound += 1;
+        let where_clause = format!("{} = {}", #pk_col, #placeholder);
+        #version_where
+
+        let sql = format!(
+            "UPDATE {} SET {} WHERE {}",
+            #table_name,
+            set_clause.join(", "),
+            where_clause
+        );
+    };
+
+    let expr_bindings = quote! {
+        for expr in self.exprs.iter().map(|(_, expr)| expr).chain(&self.raw_sets) {
+            for value in &expr.values {
+                query = value.bind_query(query);
+            }
+        }
+    };
+
     quote! {
         async fn execute<'a, E>(
             mut self,
@@ -80,49 +239,159 @@ pub fn implementation(es: &EntityStruct) -> proc_macro2::TokenStream {
             let mut conn = acquirer.acquire().await?;
 
             #updated_assign_update
+            #validate
+            #before_update
 
-            let fallback_columns = vec![#(#all_columns),*];
-            let fields_to_update = if let Some(f) = &self.fields {
-                f
-            } else {
-                &fallback_columns
-            };
+            #build_sql
 
-            if fields_to_update.is_empty() {
+            if fields_to_update.is_empty() && self.exprs.is_empty() && self.raw_sets.is_empty() {
                 return Ok(self.entity);
             }
 
-            // outputs `placeholders` and `where_placeholder` variables
-            #placeholder_generator
+            let sql = sql + &::sqlorm::comment::render(&self.comments);
+            let mut query = ::sqlorm::sqlx::query::<::sqlorm::Driver>(&sql).persistent(self.persistent);
+
+            for field_name in &fields_to_update {
+                match *field_name {
+                    #(#field_bindings)*
+                    _ => {}
+                }
+            }
+            #expr_bindings
+
+            query = query.bind(&self.entity.#pk_ident);
+            #version_bind
+
+            let result = query.execute(&mut *conn).await?;
+            #version_check
+            #after_update
+
+            Ok(self.entity)
+        }
+
+        async fn execute_affected<'a, E>(
+            mut self,
+            acquirer: E
+        ) -> ::sqlorm::sqlx::Result<u64> where E: ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver> + Send{
+
+            use ::sqlorm::sqlx::Acquire;
+            let mut conn = acquirer.acquire().await?;
+
+            #updated_assign_update
+            #validate
+            #before_update
+
+            #build_sql
+
+            if fields_to_update.is_empty() && self.exprs.is_empty() && self.raw_sets.is_empty() {
+                return Ok(0);
+            }
+
+            let sql = sql + &::sqlorm::comment::render(&self.comments);
+            let mut query = ::sqlorm::sqlx::query::<::sqlorm::Driver>(&sql).persistent(self.persistent);
+
+            for field_name in &fields_to_update {
+                match *field_name {
+                    #(#field_bindings)*
+                    _ => {}
+                }
+            }
+            #expr_bindings
+
+            query = query.bind(&self.entity.#pk_ident);
+            #version_bind
+
+            let result = query.execute(&mut *conn).await?;
+            #after_update_affected
+            Ok(result.rows_affected())
+        }
+
+        async fn execute_returning<'a, C, E>(
+            mut self,
+            columns: C,
+            acquirer: E
+        ) -> ::sqlorm::sqlx::Result<C::Row>
+        where
+            C: ::sqlorm::Selectable + Send,
+            C::Row: for<'r> ::sqlorm::sqlx::FromRow<'r, ::sqlorm::Row> + Send + Unpin,
+            E: ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver> + Send
+        {
+            use ::sqlorm::sqlx::Acquire;
+            let mut conn = acquirer.acquire().await?;
+
+            #updated_assign_update
+            #validate
+            #before_update
 
-            let set_clause: Vec<String> = fields_to_update
-                .iter()
-                .zip(&placeholders)
-                .map(|(field, placeholder)| format!("{} = {}", field, placeholder))
-                .collect();
+            #build_sql
+
+            let returning = columns.collect().join(", ");
+
+            if fields_to_update.is_empty() && self.exprs.is_empty() && self.raw_sets.is_empty() {
+                let sql = format!(
+                    "SELECT {} FROM {} WHERE {} = {}{}",
+                    returning,
+                    #table_name,
+                    #pk_col,
+                    #first_placeholder,
+                    ::sqlorm::comment::render(&self.comments)
+                );
+                return ::sqlorm::sqlx::query_as::<::sqlorm::Driver, C::Row>(&sql)
+                    .bind(&self.entity.#pk_ident)
+                    .fetch_one(&mut *conn)
+                    .await;
+            }
 
             let sql = format!(
-                "UPDATE {} SET {} WHERE {} = {}",
-                #table_name,
-                set_clause.join(", "),
-                #pk_col,
-                where_placeholder
+                "{} RETURNING {}{}",
+                sql,
+                returning,
+                ::sqlorm::comment::render(&self.comments)
             );
+            let mut query = ::sqlorm::sqlx::query::<::sqlorm::Driver>(&sql).persistent(self.persistent);
 
-            let mut query = ::sqlorm::sqlx::query::<::sqlorm::Driver>(&sql);
-
-            for field_name in fields_to_update {
-                match field_name.as_ref() {
+            for field_name in &fields_to_update {
+                match *field_name {
                     #(#field_bindings)*
                     _ => {}
                 }
             }
+            #expr_bindings
 
             query = query.bind(&self.entity.#pk_ident);
+            #version_bind
 
-            query.execute(&mut *conn).await?;
+            let returned = query
+                .try_map(|row: ::sqlorm::Row| {
+                    <C::Row as ::sqlorm::sqlx::FromRow<'_, ::sqlorm::Row>>::from_row(&row)
+                })
+                .fetch_one(&mut *conn)
+                .await
+                #version_returning_check?;
+            #version_increment
+            #after_update
 
-            Ok(self.entity)
+            Ok(returned)
+        }
+
+        fn debug_sql(&self) -> String {
+            #build_sql
+
+            let mut values: Vec<String> = Vec::new();
+            for field_name in &fields_to_update {
+                match *field_name {
+                    #(#debug_bindings)*
+                    _ => {}
+                }
+            }
+            for expr in self.exprs.iter().map(|(_, expr)| expr).chain(&self.raw_sets) {
+                values.extend(expr.values.iter().map(|value| value.debug_value()));
+            }
+            values.push(format!("{:?}", self.entity.#pk_ident));
+            #version_debug
+
+            let sql = sql + &::sqlorm::comment::render(&self.comments);
+            ::sqlorm::interpolate_debug(&sql, &values)
         }
     }
 }
@@ -1,27 +1,266 @@
-use crate::EntityStruct;
+use crate::{
+    EntityStruct,
+    entity::{FieldKind, SoftDelete, TimestampKind},
+};
+use sqlorm_core::with_quotes;
+use syn::Ident;
 use quote::quote;
 
 mod executor;
+mod patch;
 
 pub fn sb(es: &EntityStruct) -> proc_macro2::TokenStream {
+    let vis = &es.vis;
     let executor = executor::executor(es);
+    let patch = patch::patch(es);
     let s_ident = &es.struct_ident;
 
+    // bulk updates bump `updated_at` like instance updates do
+    let touch_updated_at = es
+        .fields
+        .iter()
+        .find_map(|f| match &f.kind {
+            FieldKind::Timestamp(TimestampKind::Updated { factory }) => {
+                let col = Ident::new(&f.ident.to_string().to_uppercase(), f.ident.span());
+                Some(quote! { .set(Self::#col, #factory) })
+            }
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    let bump_version = es
+        .fields
+        .iter()
+        .find(|f| f.is_version())
+        .map(|f| {
+            let col = Ident::new(&f.ident.to_string().to_uppercase(), f.ident.span());
+            quote! { .set_expr(Self::#col, ::sqlorm::Expr::col(Self::#col).add(1)) }
+        })
+        .unwrap_or_default();
+
+    let soft_delete = match es.soft_delete() {
+        Some(SoftDelete::Timestamp { field, factory }) => {
+            let col = Ident::new(&field.ident.to_string().to_uppercase(), field.ident.span());
+            quote! { .soft_delete(Self::#col, #factory) }
+        }
+        Some(SoftDelete::Flag { field }) => {
+            let col = Ident::new(&field.ident.to_string().to_uppercase(), field.ident.span());
+            quote! { .soft_delete_flag(Self::#col) }
+        }
+        None => quote! {},
+    };
+
+    let table_name = with_quotes(&es.table_name.qualified());
+    let table_name_raw = &es.table_name.raw;
+    let truncate_body = if cfg!(feature = "postgres") {
+        let sql = format!("TRUNCATE {} RESTART IDENTITY CASCADE", table_name);
+        quote! {
+            ::sqlorm::sqlx::query(#sql).execute(&mut *conn).await?;
+        }
+    } else {
+        let sql = format!("DELETE FROM {}", table_name);
+        quote! {
+            ::sqlorm::sqlx::query(#sql).execute(&mut *conn).await?;
+            // `sqlite_sequence` only exists once an AUTOINCREMENT table was created
+            let has_sequence: Option<i64> = ::sqlorm::sqlx::query_scalar(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'sqlite_sequence'",
+            )
+            .fetch_optional(&mut *conn)
+            .await?;
+            if has_sequence.is_some() {
+                ::sqlorm::sqlx::query("DELETE FROM sqlite_sequence WHERE name = ?")
+                    .bind(#table_name_raw)
+                    .execute(&mut *conn)
+                    .await?;
+            }
+        }
+    };
+    let (touch_placeholder1, touch_placeholder2) = if cfg!(feature = "postgres") {
+        ("$1", "$2")
+    } else {
+        ("?", "?")
+    };
+    let touch = es
+        .fields
+        .iter()
+        .find_map(|f| match &f.kind {
+            FieldKind::Timestamp(TimestampKind::Updated { factory }) => {
+                let updated_at_ident = &f.ident;
+                let updated_at_col = &f.name;
+                let pk_ident = &es.pk.ident;
+                let pk_col = &es.pk.name;
+                Some(quote! {
+                    /// Sets `updated_at` to now without writing any other column.
+                    ///
+                    /// Example usage:
+                    /// ```rust ignore
+                    /// let jar = jar.touch(&pool).await?;
+                    /// ```
+                    #vis async fn touch<'a, A>(mut self, acquirer: A) -> ::sqlorm::sqlx::Result<#s_ident>
+                    where
+                        A: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+                    {
+                        let mut conn = acquirer.acquire().await?;
+                        let updated_at = #factory;
+                        let sql = format!(
+                            "UPDATE {} SET {} = {} WHERE {} = {}",
+                            #table_name, #updated_at_col, #touch_placeholder1, #pk_col, #touch_placeholder2
+                        );
+                        ::sqlorm::sqlx::query(&sql)
+                            .bind(&updated_at)
+                            .bind(&self.#pk_ident)
+                            .execute(&mut *conn)
+                            .await?;
+                        self.#updated_at_ident = updated_at;
+                        Ok(self)
+                    }
+                })
+            }
+            _ => None,
+        })
+        .unwrap_or_default();
+    let pk_ident = &es.pk.ident;
+    let pk_col = &es.pk.name;
+    let pk_placeholder = if cfg!(feature = "postgres") { "$1" } else { "?" };
+    let restore = es
+        .soft_delete()
+        .map(|soft_delete| {
+            let field_ident = &soft_delete.field().ident;
+            let sql = format!(
+                "UPDATE {} SET {} = {} WHERE {} = {}",
+                table_name,
+                soft_delete.field().name,
+                soft_delete.restored_sql(),
+                pk_col,
+                pk_placeholder
+            );
+            let restored = soft_delete.restored_field_value();
+            quote! {
+                /// Undoes a soft delete, making the row visible to `query()` again.
+                ///
+                /// Example usage:
+                /// ```rust ignore
+                /// let user = user.restore(&pool).await?;
+                /// ```
+                #vis async fn restore<'a, A>(mut self, acquirer: A) -> ::sqlorm::sqlx::Result<#s_ident>
+                where
+                    A: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+                {
+                    let mut conn = acquirer.acquire().await?;
+                    ::sqlorm::sqlx::query(#sql)
+                        .bind(&self.#pk_ident)
+                        .execute(&mut *conn)
+                        .await?;
+                    self.#field_ident = #restored;
+                    Ok(self)
+                }
+            }
+        })
+        .unwrap_or_default();
+
+    let deletes = if es.skip_generate.delete {
+        quote! {}
+    } else {
+        quote! {
+            #[automatically_derived]
+            impl #s_ident {
+                #vis fn delete(self) -> ::sqlorm::SB<#s_ident,::sqlorm::Delete> {
+                    ::sqlorm::SB::new(<#s_ident as ::sqlorm::Table>::table_info(), self)
+                }
+
+                /// Removes the row with `DELETE FROM`, even when the entity has a `deleted_at`
+                /// timestamp or `soft_delete` flag and `delete()` would only soft delete it.
+                ///
+                /// Example usage:
+                /// ```rust ignore
+                /// user.force_delete(&pool).await?;
+                /// ```
+                #vis async fn force_delete<'a, A>(self, acquirer: A) -> ::sqlorm::sqlx::Result<#s_ident>
+                where
+                    A: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+                {
+                    let mut conn = acquirer.acquire().await?;
+                    let sql = format!(
+                        "DELETE FROM {} WHERE {} = {}",
+                        #table_name, #pk_col, #pk_placeholder
+                    );
+                    ::sqlorm::sqlx::query(&sql)
+                        .bind(&self.#pk_ident)
+                        .execute(&mut *conn)
+                        .await?;
+                    Ok(self)
+                }
+
+                /// Removes every row and resets the primary key sequence.
+                ///
+                /// Runs `TRUNCATE ... RESTART IDENTITY CASCADE` on PostgreSQL, so rows referencing
+                /// this table are removed too. On SQLite runs `DELETE FROM` and resets the
+                /// table's `sqlite_sequence` entry.
+                ///
+                /// Example usage:
+                /// ```rust ignore
+                /// User::truncate(&pool).await?;
+                /// ```
+                #vis async fn truncate<'a, A>(acquirer: A) -> ::sqlorm::sqlx::Result<()>
+                where
+                    A: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+                {
+                    let mut conn = acquirer.acquire().await?;
+                    #truncate_body
+                    Ok(())
+                }
+
+                /// Starts a delete of all rows matching the filters added to the returned builder.
+                ///
+                /// Soft deletes when the entity has a `deleted_at` timestamp.
+                #vis fn delete_where() -> ::sqlorm::DeleteWhere<#s_ident> {
+                    ::sqlorm::DeleteWhere::new(<#s_ident as ::sqlorm::Table>::table_info())
+                        #soft_delete
+                }
+            }
+        }
+    };
+
     quote! {
         #executor
 
+        #patch
+
         #[automatically_derived]
         impl #s_ident {
-            pub fn update(self) -> ::sqlorm::SB<#s_ident,::sqlorm::Update> {
+            #vis fn update(self) -> ::sqlorm::SB<#s_ident,::sqlorm::Update> {
                 ::sqlorm::SB::new(<#s_ident as ::sqlorm::Table>::table_info(), self)
             }
-        }
 
-        #[automatically_derived]
-        impl #s_ident {
-            pub fn delete(self) -> ::sqlorm::SB<#s_ident,::sqlorm::Delete> {
-                ::sqlorm::SB::new(<#s_ident as ::sqlorm::Table>::table_info(), self)
+            /// Starts an update of all rows matching the filters added to the returned builder.
+            #vis fn update_where() -> ::sqlorm::UpdateWhere<#s_ident> {
+                ::sqlorm::UpdateWhere::new(<#s_ident as ::sqlorm::Table>::table_info())
+                    #touch_updated_at
+                    #bump_version
+            }
+
+            /// Starts a detached update setting `column` to `value` on the rows matching the
+            /// filters, without loading or constructing the entity.
+            ///
+            /// Example usage:
+            /// ```rust ignore
+            /// User::set(User::BIO, Some("x".to_string()))
+            ///     .filter(User::ID.eq(5))
+            ///     .execute(&pool)
+            ///     .await?;
+            /// ```
+            #vis fn set<C>(column: ::sqlorm::Column<C>, value: C) -> ::sqlorm::UpdateWhere<#s_ident>
+            where
+                C: ::sqlorm::BindValue + Clone,
+            {
+                Self::update_where().set(column, value)
             }
+
+            #touch
+
+            #restore
         }
+
+        #deletes
     }
 }
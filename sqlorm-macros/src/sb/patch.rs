@@ -0,0 +1,103 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::{
+    EntityStruct,
+    entity::{EntityField, FieldKind},
+};
+
+/// Generates `{Entity}Patch` with an `Option` of every writable field, `apply_patch()` and
+/// its `Patch` impl used by `update_where().set_patch(...)`.
+pub fn patch(es: &EntityStruct) -> TokenStream {
+    let vis = &es.vis;
+    let s_ident = &es.struct_ident;
+    let patch_ident = format_ident!("{}Patch", s_ident);
+    let doc = format!(
+        "Partial update of [`{}`], fields left `None` are not written.",
+        s_ident
+    );
+
+    // timestamps, versions and soft delete flags are managed by sqlorm
+    let fields: Vec<&EntityField> = es
+        .fields
+        .iter()
+        .filter(|f| matches!(f.kind, FieldKind::Regular { .. }) && !f.immutable)
+        .collect();
+
+    let idents: Vec<&Ident> = fields.iter().map(|f| &f.ident).collect();
+    let tys: Vec<&syn::Type> = fields.iter().map(|f| &f.ty).collect();
+    let places: Vec<TokenStream> = fields.iter().map(|f| f.access(&quote! { self })).collect();
+    let assigns: Vec<TokenStream> = fields
+        .iter()
+        .map(|f| {
+            let ident = &f.ident;
+            let col = Ident::new(&ident.to_string().to_uppercase(), ident.span());
+            let set = if f.encrypted.is_some() {
+                quote! { update.set_encrypted(#s_ident::#col, value) }
+            } else if f.json && f.is_nullable() {
+                quote! {
+                    update.set_expr(
+                        #s_ident::#col,
+                        ::sqlorm::Expr::value(value.map(::sqlorm::sqlx::types::Json)),
+                    )
+                }
+            } else if f.json {
+                quote! {
+                    update.set_expr(
+                        #s_ident::#col,
+                        ::sqlorm::Expr::value(::sqlorm::sqlx::types::Json(value)),
+                    )
+                }
+            } else {
+                quote! { update.set(#s_ident::#col, value) }
+            };
+            quote! {
+                if let Some(value) = self.#ident {
+                    update = #set;
+                }
+            }
+        })
+        .collect();
+
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Default)]
+        #vis struct #patch_ident {
+            #(#vis #idents: Option<#tys>,)*
+        }
+
+        #[automatically_derived]
+        impl ::sqlorm::Patch for #patch_ident {
+            type Entity = #s_ident;
+
+            #[allow(unused_mut)]
+            fn assign(
+                self,
+                mut update: ::sqlorm::UpdateWhere<#s_ident>,
+            ) -> ::sqlorm::UpdateWhere<#s_ident> {
+                #(#assigns)*
+                update
+            }
+        }
+
+        #[automatically_derived]
+        impl #s_ident {
+            /// Overwrites the fields provided by `patch`, leaving the others untouched.
+            ///
+            /// Example usage:
+            /// ```rust ignore
+            /// user.apply_patch(patch);
+            /// let user = user.save(&pool).await?;
+            /// ```
+            #[allow(unused_variables)]
+            #vis fn apply_patch(&mut self, patch: #patch_ident) {
+                #(
+                    if let Some(value) = patch.#idents {
+                        #places = value;
+                    }
+                )*
+            }
+        }
+    }
+}
@@ -1,10 +1,11 @@
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::Ident;
 
 use crate::entity::EntityStruct;
 
 pub fn find_unique(es: &EntityStruct) -> TokenStream {
+    let vis = &es.vis;
     let s_ident = &es.struct_ident;
 
     let unique_fields: Vec<_> = es
@@ -46,7 +47,7 @@ pub fn find_unique(es: &EntityStruct) -> TokenStream {
 
             quote! {
                 #[doc = #doc_string]
-                pub async fn #method_name<'a, A>(
+                #vis async fn #method_name<'a, A>(
                     acquirer: A,
                     value: #ftype
                 ) -> ::sqlorm::sqlx::Result<Option<#s_ident>>
@@ -62,10 +63,99 @@ pub fn find_unique(es: &EntityStruct) -> TokenStream {
         })
         .collect();
 
+    let index_methods = find_indexed(es);
+
     quote! {
         #[automatically_derived]
         impl #s_ident {
             #(#methods)*
+            #(#index_methods)*
         }
     }
 }
+
+/// `find_by_{a}_and_{b}` for every `#[table(index(...))]`. Single-column indexes over fields that
+/// are already unique are skipped, as `find_unique` covers them.
+fn find_indexed(es: &EntityStruct) -> Vec<TokenStream> {
+    let vis = &es.vis;
+    let s_ident = &es.struct_ident;
+    let mut seen = Vec::new();
+
+    es.indexes
+        .iter()
+        .filter(|index| {
+            !(index.columns.len() == 1
+                && es
+                    .fields
+                    .iter()
+                    .any(|f| f.ident == index.columns[0] && f.is_unique()))
+        })
+        .filter_map(|index| {
+            let names: Vec<String> = index.columns.iter().map(|c| c.to_string()).collect();
+            let method_name = format_ident!("find_by_{}", names.join("_and_"));
+            if seen.contains(&method_name) {
+                return None;
+            }
+            seen.push(method_name.clone());
+
+            let params: Vec<TokenStream> = index
+                .columns
+                .iter()
+                .map(|c| {
+                    let ty = &es.fields.iter().find(|f| &f.ident == c).unwrap().ty;
+                    quote! { #c: #ty }
+                })
+                .collect();
+            let filters: Vec<TokenStream> = index
+                .columns
+                .iter()
+                .map(|c| {
+                    let col_const = Ident::new(&c.to_string().to_uppercase(), c.span());
+                    quote! { .filter(#s_ident::#col_const.eq(#c)) }
+                })
+                .collect();
+
+            Some(if index.unique {
+                let doc = format!(
+                    "Finds the record matching the unique index on ({}).",
+                    names.join(", ")
+                );
+                quote! {
+                    #[doc = #doc]
+                    #vis async fn #method_name<'a, A>(
+                        acquirer: A,
+                        #(#params),*
+                    ) -> ::sqlorm::sqlx::Result<Option<#s_ident>>
+                    where
+                        A: Send +  ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>
+                    {
+                        #s_ident::query()
+                            #(#filters)*
+                            .fetch_optional(acquirer)
+                            .await
+                    }
+                }
+            } else {
+                let doc = format!(
+                    "Finds all records matching the index on ({}).",
+                    names.join(", ")
+                );
+                quote! {
+                    #[doc = #doc]
+                    #vis async fn #method_name<'a, A>(
+                        acquirer: A,
+                        #(#params),*
+                    ) -> ::sqlorm::sqlx::Result<Vec<#s_ident>>
+                    where
+                        A: Send +  ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>
+                    {
+                        #s_ident::query()
+                            #(#filters)*
+                            .fetch_all(acquirer)
+                            .await
+                    }
+                }
+            })
+        })
+        .collect()
+}
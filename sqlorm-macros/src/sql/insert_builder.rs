@@ -0,0 +1,161 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::{
+    EntityStruct,
+    entity::{EntityField, FieldKind},
+};
+
+use super::save::is_uuid_type;
+
+/// Whether the insert builder has to be given a value for `f`.
+///
+/// Nullable, defaulted and generated uuid columns can be left out, everything else sqlorm
+/// manages (primary keys, timestamps, versions, soft delete flags) is not settable at all.
+fn is_required(f: &EntityField) -> bool {
+    matches!(f.kind, FieldKind::Regular { .. })
+        && f.embedded.is_none()
+        && !f.is_nullable()
+        && f.default.is_none()
+        && !(cfg!(feature = "uuid") && is_uuid_type(&f.ty))
+}
+
+/// Generates `{Entity}::insert_new()` returning a typestate `{Entity}Insert` builder, whose
+/// `save()` only exists once every required field has been provided.
+pub fn insert_builder(es: &EntityStruct) -> TokenStream {
+    let vis = &es.vis;
+    let s_ident = &es.struct_ident;
+    let builder_ident = format_ident!("{}Insert", s_ident);
+    let doc = format!(
+        "Builder inserting a new [`{}`], created with [`{}::insert_new`].",
+        s_ident, s_ident
+    );
+
+    let required: Vec<&EntityField> = es.fields.iter().filter(|f| is_required(f)).collect();
+    let optional: Vec<&EntityField> = es
+        .fields
+        .iter()
+        .filter(|f| {
+            // flattened fields are set as a whole rather than column by column
+            (matches!(f.kind, FieldKind::Regular { .. }) && f.embedded.is_none() && !is_required(f))
+                || !f.flattened.is_empty()
+        })
+        .collect();
+
+    let params: Vec<Ident> = (0..required.len()).map(|i| format_ident!("__R{}", i)).collect();
+    let req_idents: Vec<&Ident> = required.iter().map(|f| &f.ident).collect();
+    let req_tys: Vec<&syn::Type> = required.iter().map(|f| &f.ty).collect();
+    let opt_idents: Vec<&Ident> = optional.iter().map(|f| &f.ident).collect();
+    let opt_tys: Vec<&syn::Type> = optional.iter().map(|f| &f.ty).collect();
+
+    let unset = required.iter().map(|_| quote! { ::sqlorm::Unset });
+
+    let required_setters = required.iter().enumerate().map(|(i, f)| {
+        let (ident, ty) = (&f.ident, &f.ty);
+        let output = params.iter().enumerate().map(|(j, param)| {
+            if i == j {
+                quote! { #ty }
+            } else {
+                quote! { #param }
+            }
+        });
+        let moved = req_idents.iter().map(|other| {
+            if *other == ident {
+                quote! { #other: value.into() }
+            } else {
+                quote! { #other: self.#other }
+            }
+        });
+        quote! {
+            #vis fn #ident(self, value: impl Into<#ty>) -> #builder_ident<#(#output),*> {
+                #builder_ident {
+                    #(#moved,)*
+                    #(#opt_idents: self.#opt_idents,)*
+                }
+            }
+        }
+    });
+
+    let optional_setters = optional.iter().map(|f| {
+        let (ident, ty) = (&f.ident, &f.ty);
+        quote! {
+            #vis fn #ident(mut self, value: impl Into<#ty>) -> Self {
+                self.#ident = Some(value.into());
+                self
+            }
+        }
+    });
+
+    let values = es.fields.iter().filter(|f| f.embedded.is_none()).map(|f| {
+        let ident = &f.ident;
+        if is_required(f) {
+            quote! { #ident: self.#ident }
+        } else if !f.flattened.is_empty() {
+            quote! { #ident: self.#ident.unwrap_or_default() }
+        } else if !matches!(f.kind, FieldKind::Regular { .. }) {
+            // filled in by `save()` where sqlorm manages the column
+            quote! { #ident: Default::default() }
+        } else if let Some(default) = &f.default {
+            quote! { #ident: self.#ident.unwrap_or_else(|| #default) }
+        } else {
+            quote! { #ident: self.#ident.unwrap_or_default() }
+        }
+    });
+
+    quote! {
+        #[doc = #doc]
+        ///
+        /// Fields which are neither `Option` nor have a `default` must be set before `save()`
+        /// is available.
+        #[must_use]
+        #vis struct #builder_ident<#(#params),*> {
+            #(#req_idents: #params,)*
+            #(#opt_idents: Option<#opt_tys>,)*
+        }
+
+        #[automatically_derived]
+        impl<#(#params),*> #builder_ident<#(#params),*> {
+            #(#required_setters)*
+            #(#optional_setters)*
+        }
+
+        #[automatically_derived]
+        impl #builder_ident<#(#req_tys),*> {
+            /// Inserts the new record, filling timestamps, defaults and generated keys like `save()`.
+            #vis async fn save<'a, E>(self, executor: E) -> ::sqlorm::sqlx::Result<#s_ident>
+            where
+                E: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+            {
+                let entity = #s_ident {
+                    #(#values,)*
+                };
+                entity.save(executor).await
+            }
+        }
+
+        #[automatically_derived]
+        impl #s_ident {
+            /// Starts building a new record.
+            ///
+            /// Unlike a struct literal with `..Default::default()`, forgetting a required field
+            /// is a compile error rather than a silently inserted default value.
+            ///
+            /// Example usage:
+            /// ```rust ignore
+            /// let user = User::insert_new()
+            ///     .email("john@example.com")
+            ///     .username("john")
+            ///     .bio("Hello".to_string())
+            ///     .save(&pool)
+            ///     .await?;
+            /// ```
+            #vis fn insert_new() -> #builder_ident<#(#unset),*> {
+                #builder_ident {
+                    #(#req_idents: ::sqlorm::Unset,)*
+                    #(#opt_idents: None,)*
+                }
+            }
+        }
+    }
+}
@@ -4,16 +4,30 @@ use quote::quote;
 use crate::entity::EntityStruct;
 
 mod find;
+mod insert_builder;
+mod refresh;
 mod save;
 
 pub fn sql(es: &EntityStruct) -> TokenStream {
-    let save = save::save(es);
+    let (save, insert_builder) = if es.kind.is_read_only() {
+        (quote! {}, quote! {})
+    } else {
+        (save::save(es), insert_builder::insert_builder(es))
+    };
     let _find_unique = quote! {};
     #[cfg(feature = "extra-traits")]
-    let _find_unique = find::find_unique(es);
+    let _find_unique = if es.skip_generate.find_by {
+        quote! {}
+    } else {
+        find::find_unique(es)
+    };
+
+    let refresh = refresh::refresh(es);
 
     quote! {
         #save
+        #insert_builder
         #_find_unique
+        #refresh
     }
 }
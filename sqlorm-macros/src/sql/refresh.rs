@@ -0,0 +1,36 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use sqlorm_core::with_quotes;
+
+use crate::entity::{EntityStruct, TableKind};
+
+/// `refresh()` of materialized views.
+pub fn refresh(es: &EntityStruct) -> TokenStream {
+    if es.kind != TableKind::MaterializedView {
+        return quote! {};
+    }
+    let vis = &es.vis;
+    let s_ident = &es.struct_ident;
+    let view = with_quotes(&es.table_name.qualified());
+    let refresh_sql = format!("REFRESH MATERIALIZED VIEW {}", view);
+    let refresh_concurrently_sql = format!("REFRESH MATERIALIZED VIEW CONCURRENTLY {}", view);
+
+    quote! {
+        #[automatically_derived]
+        impl #s_ident {
+            /// Recomputes the rows of the materialized view.
+            ///
+            /// With `concurrently`, reads aren't blocked while refreshing, which requires a
+            /// unique index on the view.
+            #vis async fn refresh<'a, A>(acquirer: A, concurrently: bool) -> ::sqlorm::sqlx::Result<()>
+            where
+                A: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+            {
+                let mut conn = acquirer.acquire().await?;
+                let sql = if concurrently { #refresh_concurrently_sql } else { #refresh_sql };
+                ::sqlorm::sqlx::query(sql).execute(&mut *conn).await?;
+                Ok(())
+            }
+        }
+    }
+}
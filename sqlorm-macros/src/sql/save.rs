@@ -4,11 +4,12 @@
 //! handling automatic timestamp management and SQL generation for CRUD operations.
 
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use sqlorm_core::with_quotes;
 use syn::{Ident, Type};
 
 use crate::entity::{EntityStruct, FieldKind, TimestampKind};
+use crate::{hooks, observers, validators};
 
 /// Checks if a type is a UUID type that should be auto-generated.
 ///
@@ -60,19 +61,25 @@ pub fn is_uuid_type(ty: &Type) -> bool {
 /// RETURNING *
 /// ```
 pub fn save(es: &EntityStruct) -> TokenStream {
+    let vis = &es.vis;
     let s_ident = &es.struct_ident;
-    let table_name = &with_quotes(&es.table_name.raw);
+    let table_name = &with_quotes(&es.table_name.qualified());
 
     let pk_field = &es.pk;
     let pk_ident = &pk_field.ident;
     let pk_type = &pk_field.ty;
+    // UUID and factory primary keys are generated before insert instead of by the database
+    let client_pk = is_uuid_type(pk_type) || pk_field.pk_factory().is_some();
     let fields = es
         .fields
         .iter()
         .filter(|f| !f.is_ignored())
-        .filter(|f| !f.is_pk() || is_uuid_type(&f.ty));
+        .filter(|f| !f.is_pk() || client_pk);
 
     let insert_field_idents: Vec<&Ident> = fields.clone().map(|f| &f.ident).collect();
+    let insert_binds: Vec<TokenStream> = fields.clone().map(|f| f.bind_value(&quote! { self })).collect();
+    let insert_binds_entity: Vec<TokenStream> =
+        fields.clone().map(|f| f.bind_value(&quote! { entity })).collect();
 
     let insert_columns = fields
         .map(|id| id.name.clone())
@@ -98,6 +105,90 @@ pub fn save(es: &EntityStruct) -> TokenStream {
         table_name, insert_columns, insert_placeholders_str
     );
 
+    let insert_prefix_sql = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        table_name, insert_columns, insert_placeholders_str
+    );
+
+    let pk_col = &pk_field.name;
+    let insert_returning_none = if client_pk {
+        // client-side primary keys are generated before insert, nothing to read back
+        quote! {
+            ::sqlorm::sqlx::query(#insert_prefix_sql)
+                #(.bind(#insert_binds))*
+                .execute(&mut *connection)
+                .await?;
+        }
+    } else if cfg!(feature = "postgres") {
+        let sql = format!("{} RETURNING {}", insert_prefix_sql, pk_col);
+        quote! {
+            self.#pk_ident = ::sqlorm::sqlx::query_scalar::<_, #pk_type>(#sql)
+                #(.bind(#insert_binds))*
+                .fetch_one(&mut *connection)
+                .await?;
+        }
+    } else {
+        quote! {
+            let result = ::sqlorm::sqlx::query(#insert_prefix_sql)
+                #(.bind(#insert_binds))*
+                .execute(&mut *connection)
+                .await?;
+            self.#pk_ident = ::core::convert::TryFrom::try_from(result.last_insert_rowid())
+                .map_err(|e| ::sqlorm::sqlx::Error::Decode(Box::new(e)))?;
+        }
+    };
+
+    // `insert_with_pk()`: same INSERT, with the primary key always in the column list
+    let (insert_with_pk_sql, insert_with_pk_binds) = if client_pk {
+        (insert_sql.clone(), insert_binds.clone())
+    } else {
+        let idents: Vec<TokenStream> = std::iter::once(quote! { &self.#pk_ident })
+            .chain(insert_binds.iter().cloned())
+            .collect();
+        let placeholders = if cfg!(feature = "postgres") {
+            (1..=idents.len())
+                .map(|i| format!("${}", i))
+                .collect::<Vec<_>>()
+                .join(", ")
+        } else {
+            vec!["?"; idents.len()].join(", ")
+        };
+        let columns = std::iter::once(pk_field.name.clone())
+            .chain(insert_columns.split(", ").filter(|c| !c.is_empty()).map(String::from))
+            .collect::<Vec<_>>()
+            .join(", ");
+        (
+            format!(
+                "INSERT INTO {} ({}) VALUES ({}) RETURNING *",
+                table_name, columns, placeholders
+            ),
+            idents,
+        )
+    };
+    // explicit ids do not advance PostgreSQL serial sequences; `setval(NULL, ..)` is a no-op
+    // for primary keys without one
+    let sync_pk_sequence = if cfg!(feature = "postgres") && !client_pk {
+        let sql = format!(
+            "SELECT setval(pg_get_serial_sequence('{}', '{}'), (SELECT MAX({}) FROM {}))",
+            table_name, pk_field.name, pk_field.name, table_name
+        );
+        quote! {
+            ::sqlorm::sqlx::query(#sql)
+                .execute(&mut *connection)
+                .await?;
+        }
+    } else {
+        quote! {}
+    };
+
+    let insert_or_ignore_sql = format!(
+        "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT DO NOTHING RETURNING *",
+        table_name, insert_columns, insert_placeholders_str
+    );
+
+    let insert_many_prefix = format!("INSERT INTO {} ({}) ", table_name, insert_columns);
+    let insert_field_count = insert_field_idents.len();
+
     let created_assign = es
         .fields
         .iter()
@@ -129,15 +220,15 @@ pub fn save(es: &EntityStruct) -> TokenStream {
     let uuid_assigns = es
         .fields
         .iter()
-        .filter(|f| !f.is_ignored() && is_uuid_type(&f.ty))
+        .filter(|f| !f.is_ignored() && f.pk_factory().is_none() && is_uuid_type(&f.ty))
         .map(|f| {
             if cfg!(feature = "uuid") {
-                let ident = &f.ident;
+                let value = f.access(&quote! { self });
                 let ty = &f.ty;
 
                 quote! {
-                    if <#ty as Default>::default() == self.#ident {
-                        self.#ident = uuid::Uuid::new_v4();
+                    if <#ty as Default>::default() == #value {
+                        #value = uuid::Uuid::new_v4();
                     }
                 }
             } else {
@@ -145,7 +236,213 @@ pub fn save(es: &EntityStruct) -> TokenStream {
             }
         });
 
+    let pk_factory_assign = pk_field.pk_factory().map(|factory| {
+        quote! {
+            if <#pk_type as Default>::default() == self.#pk_ident {
+                self.#pk_ident = #factory;
+            }
+        }
+    });
+
+    let default_assigns = es.fields.iter().filter_map(|f| {
+        let (ident, ty, default) = (&f.ident, &f.ty, f.default.as_ref()?);
+        Some(quote! {
+            if <#ty as Default>::default() == self.#ident {
+                self.#ident = #default;
+            }
+        })
+    });
+
+    let insert_column_names: Vec<&str> = insert_columns.split(", ").filter(|c| !c.is_empty()).collect();
+
+    let validate_self = validators::check(es, quote! { self });
+    let validate_each = if es.validates() {
+        let check = validators::check(es, quote! { *entity });
+        quote! {
+            for entity in &entities {
+                #check
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let connection = quote! { connection };
+    let before_insert = hooks::call(es, "before_insert", quote! { self }, &connection);
+    let after_insert = {
+        let call = hooks::call_on(es, "after_insert", &format_ident!("inserted"), &connection);
+        let notify = observers::notify(es, "Inserted", quote! { inserted });
+        quote! { #call #notify }
+    };
+    let after_insert_self = {
+        let call = hooks::call(es, "after_insert", quote! { self }, &connection);
+        let notify = observers::notify(es, "Inserted", quote! { self });
+        quote! { #call #notify }
+    };
+    let before_bulk_insert = {
+        let validate = validators::check(es, quote! { *self });
+        let call = hooks::call(es, "before_insert", quote! { *self }, &connection);
+        quote! { #validate #call }
+    };
+    let after_bulk_insert = {
+        let call = hooks::call(es, "after_insert", quote! { *self }, &connection);
+        let notify = observers::notify(es, "Inserted", quote! { *self });
+        quote! { #call #notify }
+    };
+    // the connection is only used by the hooks
+    let unused_connection = if es.hooks {
+        quote! {}
+    } else {
+        quote! { let _ = connection; }
+    };
+    let after_insert_some = if es.hooks || es.observable {
+        let call = hooks::call(es, "after_insert", quote! { *entity }, &connection);
+        let notify = observers::notify(es, "Inserted", quote! { *entity });
+        quote! {
+            let mut inserted = inserted;
+            if let Some(entity) = &mut inserted {
+                #call
+                #notify
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let before_insert_each = if es.hooks {
+        let call = hooks::call(es, "before_insert", quote! { *entity }, &connection);
+        quote! {
+            let mut entities = entities;
+            for entity in &mut entities {
+                #call
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let after_insert_each = if es.hooks || es.observable {
+        let call = hooks::call(es, "after_insert", quote! { *entity }, &connection);
+        let notify = observers::notify(es, "Inserted", quote! { *entity });
+        quote! {
+            for entity in &mut inserted {
+                #call
+                #notify
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let insert_returning_columns = if es.hooks || es.observable {
+        // the whole row is read back too, for the after_insert hooks and the observers
+        quote! {
+            let sql = format!("{} RETURNING {}, *", #insert_prefix_sql, columns.collect().join(", "));
+            let row = ::sqlorm::sqlx::query(&sql)
+                #(.bind(#insert_binds))*
+                .fetch_one(&mut *connection)
+                .await?;
+            let inserted = <#s_ident as ::sqlorm::sqlx::FromRow<'_, ::sqlorm::Row>>::from_row(&row)?;
+            #after_insert
+
+            <C::Row as ::sqlorm::sqlx::FromRow<'_, ::sqlorm::Row>>::from_row(&row)
+        }
+    } else {
+        quote! {
+            let sql = format!("{} RETURNING {}", #insert_prefix_sql, columns.collect().join(", "));
+            ::sqlorm::sqlx::query_as::<_, C::Row>(&sql)
+                #(.bind(#insert_binds))*
+                .fetch_one(&mut *connection)
+                .await
+        }
+    };
+
+    let insert_body = |validate: &TokenStream| {
+        quote! {
+            let mut connection = executor.acquire().await?;
+            self.apply_insert_defaults();
+            #validate
+            #before_insert
+
+            let inserted = ::sqlorm::sqlx::query_as::<_, #s_ident>(#insert_sql)
+                #(.bind(#insert_binds))*
+                .fetch_one(&mut *connection)
+                .await?;
+            #after_insert
+
+            Ok(inserted)
+        }
+    };
+    let insert = insert_body(&validate_self);
+    let (insert_unchecked, save_unchecked) = if es.validates() {
+        let insert_unchecked = insert_body(&quote! {});
+        (
+            quote! {
+                /// Inserts the record like [`Self::insert`], without validating it.
+                #vis async fn insert_unchecked<'a, E>(mut self, executor: E) -> ::sqlorm::sqlx::Result<Self>
+                where
+                    E: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+                {
+                    #insert_unchecked
+                }
+            },
+            quote! {
+                /// Saves the record like [`Self::save`], without validating it.
+                #vis async fn save_unchecked<'a, E>(
+                    self,
+                    executor: E
+                ) -> ::sqlorm::sqlx::Result<Self>
+                where
+                    E: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>
+                {
+                    if <#pk_type as Default>::default() == self.#pk_ident {
+                        self.insert_unchecked(executor).await
+                    } else {
+                        use ::sqlorm::StatementExecutor;
+                        self.update().unchecked().execute(executor).await
+                    }
+                }
+            },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+
     quote! {
+        #[automatically_derived]
+        impl ::sqlorm::Insertable for #s_ident {
+            fn insert_columns() -> Vec<&'static str> {
+                vec![#(#insert_column_names),*]
+            }
+
+            fn prepare_insert(&mut self) {
+                self.apply_insert_defaults();
+            }
+
+            fn push_insert_values<'args>(
+                &'args self,
+                row: &mut ::sqlorm::sqlx::query_builder::Separated<'_, 'args, ::sqlorm::Driver, &'static str>,
+            ) {
+                #(row.push_bind(#insert_binds);)*
+            }
+
+            async fn before_bulk_insert(
+                &mut self,
+                connection: &mut ::sqlorm::Connection,
+            ) -> ::sqlorm::sqlx::Result<()> {
+                #unused_connection
+                #before_bulk_insert
+                Ok(())
+            }
+
+            async fn after_bulk_insert(
+                &mut self,
+                connection: &mut ::sqlorm::Connection,
+            ) -> ::sqlorm::sqlx::Result<()> {
+                #unused_connection
+                #after_bulk_insert
+                Ok(())
+            }
+        }
+
         #[automatically_derived]
         impl #s_ident {
             /// Inserts a new record into the database.
@@ -180,19 +477,199 @@ pub fn save(es: &EntityStruct) -> TokenStream {
             /// let inserted_user = user.insert(&pool).await?;
             /// println!("Inserted user with ID: {}", inserted_user.id);
             /// ```
-            pub async fn insert<'a, E>(mut self, executor: E) -> ::sqlorm::sqlx::Result<Self>
+            #vis async fn insert<'a, E>(mut self, executor: E) -> ::sqlorm::sqlx::Result<Self>
+            where
+                E: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+            {
+                #insert
+            }
+
+            #insert_unchecked
+
+            /// Inserts the record keeping its primary key value, e.g. for data imports or
+            /// fixed reference ids, where [`Self::insert`] would let the database assign one.
+            ///
+            /// On PostgreSQL the primary key sequence is moved past the highest id, so later
+            /// inserts do not collide with imported rows.
+            ///
+            /// # Example
+            ///
+            /// ```ignore
+            /// let admin = User { id: 1, ..admin }.insert_with_pk(&pool).await?;
+            /// ```
+            #vis async fn insert_with_pk<'a, E>(mut self, executor: E) -> ::sqlorm::sqlx::Result<Self>
             where
                 E: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
             {
                 let mut connection = executor.acquire().await?;
+                self.apply_insert_defaults();
+                #validate_self
+                #before_insert
+
+                let inserted = ::sqlorm::sqlx::query_as::<_, #s_ident>(#insert_with_pk_sql)
+                    #(.bind(#insert_with_pk_binds))*
+                    .fetch_one(&mut *connection)
+                    .await?;
+                #sync_pk_sequence
+                #after_insert
+
+                Ok(inserted)
+            }
+
+            /// Inserts the record and returns only `columns` of the inserted row.
+            ///
+            /// Hooks and observers still receive the whole inserted entity.
+            ///
+            /// # Example
+            ///
+            /// ```ignore
+            /// let (id,) = user.insert_returning((User::ID,), &pool).await?;
+            /// ```
+            #vis async fn insert_returning<'a, C, E>(
+                mut self,
+                columns: C,
+                executor: E,
+            ) -> ::sqlorm::sqlx::Result<C::Row>
+            where
+                C: ::sqlorm::Selectable,
+                C::Row: for<'r> ::sqlorm::sqlx::FromRow<'r, ::sqlorm::Row> + Send + Unpin,
+                E: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+            {
+                let mut connection = executor.acquire().await?;
+                self.apply_insert_defaults();
+                #validate_self
+                #before_insert
+
+                #insert_returning_columns
+            }
+
+            /// Inserts the record without reading the row back.
+            ///
+            /// Only the primary key is filled in: from `RETURNING` on PostgreSQL and from
+            /// `last_insert_rowid()` on SQLite. Database-side defaults are not reflected.
+            #vis async fn insert_returning_none<'a, E>(mut self, executor: E) -> ::sqlorm::sqlx::Result<Self>
+            where
+                E: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+            {
+                let mut connection = executor.acquire().await?;
+                self.apply_insert_defaults();
+                #validate_self
+                #before_insert
+
+                #insert_returning_none
+                #after_insert_self
+
+                Ok(self)
+            }
+
+            /// Inserts the record unless it conflicts with an existing one.
+            ///
+            /// Emits `ON CONFLICT DO NOTHING`, so unique violations are not reported as errors.
+            /// Returns `None` if the record was not inserted.
+            ///
+            /// # Example
+            ///
+            /// ```ignore
+            /// match event.insert_or_ignore(&pool).await? {
+            ///     Some(event) => process(event),
+            ///     None => {} // already seen
+            /// }
+            /// ```
+            #vis async fn insert_or_ignore<'a, E>(mut self, executor: E) -> ::sqlorm::sqlx::Result<Option<Self>>
+            where
+                E: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+            {
+                let mut connection = executor.acquire().await?;
+                self.apply_insert_defaults();
+                #validate_self
+                #before_insert
+
+                let inserted = ::sqlorm::sqlx::query_as::<_, #s_ident>(#insert_or_ignore_sql)
+                    #(.bind(#insert_binds))*
+                    .fetch_optional(&mut *connection)
+                    .await?;
+                #after_insert_some
+
+                Ok(inserted)
+            }
+
+            /// Inserts all records using multi-row `INSERT` statements.
+            ///
+            /// Records are inserted in batches staying below the driver's bind parameter limit.
+            /// Timestamps and UUID primary keys are populated like in [`Self::insert`].
+            ///
+            /// # Example
+            ///
+            /// ```ignore
+            /// let users = User::insert_many(vec![user1, user2], &pool).await?;
+            /// ```
+            #vis async fn insert_many<'a, E>(
+                entities: Vec<Self>,
+                executor: E,
+            ) -> ::sqlorm::sqlx::Result<Vec<Self>>
+            where
+                E: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>,
+            {
+                if entities.is_empty() {
+                    return Ok(Vec::new());
+                }
+
+                let mut connection = executor.acquire().await?;
+                let entities: Vec<Self> = entities
+                    .into_iter()
+                    .map(|mut entity| {
+                        entity.apply_insert_defaults();
+                        entity
+                    })
+                    .collect();
+                #validate_each
+                #before_insert_each
+
+                let batch_size = (::sqlorm::MAX_BIND_PARAMS / #insert_field_count.max(1)).max(1);
+                let mut inserted = Vec::with_capacity(entities.len());
+
+                for batch in entities.chunks(batch_size) {
+                    let mut builder =
+                        ::sqlorm::sqlx::QueryBuilder::<::sqlorm::Driver>::new(#insert_many_prefix);
+                    builder.push_values(batch, |mut row, entity| {
+                        #(row.push_bind(#insert_binds_entity);)*
+                    });
+                    builder.push(" RETURNING *");
+
+                    let rows: Vec<Self> = builder
+                        .build_query_as::<Self>()
+                        .fetch_all(&mut *connection)
+                        .await?;
+                    inserted.extend(rows);
+                }
+                #after_insert_each
+
+                Ok(inserted)
+            }
+
+            /// Populates timestamps, `default` fields and UUID or factory primary key
+            /// before insert.
+            fn apply_insert_defaults(&mut self) {
+                #pk_factory_assign
                 #(#uuid_assigns)*
+                #(#default_assigns)*
                 #created_assign
                 #updated_assign_insert
+            }
 
-                ::sqlorm::sqlx::query_as::<_, #s_ident>(#insert_sql)
-                    #(.bind(&self.#insert_field_idents))*
-                    .fetch_one(&mut *connection)
-                    .await
+            /// Starts a bulk insert updating rows that conflict, see `sqlorm::UpsertMany`.
+            ///
+            /// # Example
+            ///
+            /// ```ignore
+            /// let jars = Jar::upsert_many(jars)
+            ///     .on_conflict(Jar::ALIAS)
+            ///     .update((Jar::TITLE, Jar::GOAL))
+            ///     .execute(&pool)
+            ///     .await?;
+            /// ```
+            #vis fn upsert_many(entities: Vec<Self>) -> ::sqlorm::UpsertMany<Self> {
+                ::sqlorm::UpsertMany::new(entities)
             }
 
 
@@ -227,7 +704,7 @@ pub fn save(es: &EntityStruct) -> TokenStream {
             /// existing_user.name = "Modified".to_string();
             /// let updated = existing_user.save(&pool).await?; // Will UPDATE
             /// ```
-            pub async fn save<'a, E>(
+            #vis async fn save<'a, E>(
                 self,
                 executor: E
             ) -> ::sqlorm::sqlx::Result<Self>
@@ -241,6 +718,42 @@ pub fn save(es: &EntityStruct) -> TokenStream {
                     self.update().execute(executor).await
                 }
             }
+
+            #save_unchecked
+
+            /// Saves all records in a single transaction, choosing insert or update per record
+            /// like [`Self::save`].
+            ///
+            /// Either every record is saved or, on error, none of them.
+            ///
+            /// # Example
+            ///
+            /// ```rust ignore
+            /// let users = User::save_all(vec![new_user, existing_user], &pool).await?;
+            /// ```
+            #vis async fn save_all<'a, E>(
+                entities: Vec<Self>,
+                executor: E
+            ) -> ::sqlorm::sqlx::Result<Vec<Self>>
+            where
+                E: Send + ::sqlorm::sqlx::Acquire<'a, Database = ::sqlorm::Driver>
+            {
+                let mut tx = executor.begin().await?;
+                // after-commit observers are notified once the whole batch is committed
+                let (saved, committed) = ::sqlorm::observer::collect(async {
+                    let mut saved = Vec::with_capacity(entities.len());
+                    for entity in entities {
+                        saved.push(entity.save(&mut *tx).await?);
+                    }
+                    Ok::<_, ::sqlorm::sqlx::Error>(saved)
+                })
+                .await;
+                let saved = saved?;
+
+                tx.commit().await?;
+                committed.defer();
+                Ok(saved)
+            }
         }
     }
 }
@@ -0,0 +1,214 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Ident, LitStr, Result};
+
+/// A unit variant of an enum deriving `SqlEnum`.
+struct SqlEnumVariant {
+    ident: Ident,
+    /// Stored value, the variant name unless set with `#[sql(rename("..."))]`.
+    name: String,
+}
+
+/// Enum deriving `SqlEnum`, stored in a TEXT column as its variant names, or in an integer
+/// column as its discriminants with `#[sql(enum(repr = i16))]`.
+pub struct SqlEnum {
+    ident: Ident,
+    /// Integer type of the discriminants, `None` for TEXT storage.
+    repr: Option<Ident>,
+    variants: Vec<SqlEnumVariant>,
+}
+
+impl SqlEnum {
+    pub fn parse(input: DeriveInput) -> Result<Self> {
+        let mut repr = None;
+        for attr in input.attrs.iter().filter(|a| a.path().is_ident("sql")) {
+            attr.parse_nested_meta(|meta| {
+                if !meta.path.is_ident("enum") {
+                    return Err(meta.error("unsupported enum attribute, expected `enum(repr = ...)`"));
+                }
+                meta.parse_nested_meta(|inner| {
+                    if !inner.path.is_ident("repr") {
+                        return Err(inner.error("expected `repr = i16|i32|i64`"));
+                    }
+                    let ty: Ident = inner.value()?.parse()?;
+                    if !matches!(ty.to_string().as_str(), "i16" | "i32" | "i64") {
+                        return Err(syn::Error::new_spanned(ty, "expected `i16`, `i32` or `i64`"));
+                    }
+                    repr = Some(ty);
+                    Ok(())
+                })
+            })?;
+        }
+
+        let Data::Enum(data) = input.data else {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "SqlEnum can only be derived for enums",
+            ));
+        };
+
+        let mut variants = Vec::new();
+        for variant in data.variants {
+            if !matches!(variant.fields, Fields::Unit) {
+                return Err(syn::Error::new_spanned(
+                    &variant,
+                    "SqlEnum variants must not have fields",
+                ));
+            }
+            let mut name = variant.ident.to_string();
+            for attr in variant.attrs.iter().filter(|a| a.path().is_ident("sql")) {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("rename") {
+                        let content;
+                        syn::parenthesized!(content in meta.input);
+                        name = content.parse::<LitStr>()?.value();
+                        Ok(())
+                    } else {
+                        Err(meta.error("unsupported variant attribute, expected `rename`"))
+                    }
+                })?;
+            }
+            variants.push(SqlEnumVariant {
+                ident: variant.ident,
+                name,
+            });
+        }
+
+        Ok(SqlEnum {
+            ident: input.ident,
+            repr,
+            variants,
+        })
+    }
+}
+
+pub fn handle(se: SqlEnum) -> TokenStream {
+    match &se.repr {
+        Some(repr) => handle_int(&se, repr),
+        None => handle_text(&se),
+    }
+}
+
+fn handle_text(se: &SqlEnum) -> TokenStream {
+    let ident = &se.ident;
+    let type_name = ident.to_string();
+    let variant_idents: Vec<&Ident> = se.variants.iter().map(|v| &v.ident).collect();
+    let names: Vec<&str> = se.variants.iter().map(|v| v.name.as_str()).collect();
+
+    quote! {
+        #[automatically_derived]
+        impl #ident {
+            /// Value stored in the database for this variant.
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    #(Self::#variant_idents => #names,)*
+                }
+            }
+        }
+
+        #[automatically_derived]
+        impl ::sqlorm::sqlx::Type<::sqlorm::Driver> for #ident {
+            fn type_info() -> <::sqlorm::Driver as ::sqlorm::sqlx::Database>::TypeInfo {
+                <&str as ::sqlorm::sqlx::Type<::sqlorm::Driver>>::type_info()
+            }
+
+            fn compatible(ty: &<::sqlorm::Driver as ::sqlorm::sqlx::Database>::TypeInfo) -> bool {
+                <&str as ::sqlorm::sqlx::Type<::sqlorm::Driver>>::compatible(ty)
+            }
+        }
+
+        #[automatically_derived]
+        impl<'q> ::sqlorm::sqlx::Encode<'q, ::sqlorm::Driver> for #ident {
+            fn encode_by_ref(
+                &self,
+                buf: &mut <::sqlorm::Driver as ::sqlorm::sqlx::Database>::ArgumentBuffer<'q>,
+            ) -> ::std::result::Result<
+                ::sqlorm::sqlx::encode::IsNull,
+                ::sqlorm::sqlx::error::BoxDynError,
+            > {
+                <&str as ::sqlorm::sqlx::Encode<'q, ::sqlorm::Driver>>::encode_by_ref(
+                    &self.as_str(),
+                    buf,
+                )
+            }
+        }
+
+        #[automatically_derived]
+        impl<'r> ::sqlorm::sqlx::Decode<'r, ::sqlorm::Driver> for #ident {
+            fn decode(
+                value: <::sqlorm::Driver as ::sqlorm::sqlx::Database>::ValueRef<'r>,
+            ) -> ::std::result::Result<Self, ::sqlorm::sqlx::error::BoxDynError> {
+                match <&str as ::sqlorm::sqlx::Decode<'r, ::sqlorm::Driver>>::decode(value)? {
+                    #(#names => Ok(Self::#variant_idents),)*
+                    other => Err(format!("unknown {} value `{}`", #type_name, other).into()),
+                }
+            }
+        }
+    }
+}
+
+fn handle_int(se: &SqlEnum, repr: &Ident) -> TokenStream {
+    let ident = &se.ident;
+    let type_name = ident.to_string();
+    let variant_idents: Vec<&Ident> = se.variants.iter().map(|v| &v.ident).collect();
+
+    quote! {
+        #[automatically_derived]
+        impl #ident {
+            /// Discriminant stored in the database for this variant.
+            pub fn to_repr(&self) -> #repr {
+                match self {
+                    #(Self::#variant_idents => Self::#variant_idents as #repr,)*
+                }
+            }
+
+            /// Variant stored as `value`, if any.
+            pub fn from_repr(value: #repr) -> Option<Self> {
+                #(
+                    if value == Self::#variant_idents as #repr {
+                        return Some(Self::#variant_idents);
+                    }
+                )*
+                None
+            }
+        }
+
+        #[automatically_derived]
+        impl ::sqlorm::sqlx::Type<::sqlorm::Driver> for #ident {
+            fn type_info() -> <::sqlorm::Driver as ::sqlorm::sqlx::Database>::TypeInfo {
+                <#repr as ::sqlorm::sqlx::Type<::sqlorm::Driver>>::type_info()
+            }
+
+            fn compatible(ty: &<::sqlorm::Driver as ::sqlorm::sqlx::Database>::TypeInfo) -> bool {
+                <#repr as ::sqlorm::sqlx::Type<::sqlorm::Driver>>::compatible(ty)
+            }
+        }
+
+        #[automatically_derived]
+        impl<'q> ::sqlorm::sqlx::Encode<'q, ::sqlorm::Driver> for #ident {
+            fn encode_by_ref(
+                &self,
+                buf: &mut <::sqlorm::Driver as ::sqlorm::sqlx::Database>::ArgumentBuffer<'q>,
+            ) -> ::std::result::Result<
+                ::sqlorm::sqlx::encode::IsNull,
+                ::sqlorm::sqlx::error::BoxDynError,
+            > {
+                <#repr as ::sqlorm::sqlx::Encode<'q, ::sqlorm::Driver>>::encode_by_ref(
+                    &self.to_repr(),
+                    buf,
+                )
+            }
+        }
+
+        #[automatically_derived]
+        impl<'r> ::sqlorm::sqlx::Decode<'r, ::sqlorm::Driver> for #ident {
+            fn decode(
+                value: <::sqlorm::Driver as ::sqlorm::sqlx::Database>::ValueRef<'r>,
+            ) -> ::std::result::Result<Self, ::sqlorm::sqlx::error::BoxDynError> {
+                let value = <#repr as ::sqlorm::sqlx::Decode<'r, ::sqlorm::Driver>>::decode(value)?;
+                Self::from_repr(value)
+                    .ok_or_else(|| format!("unknown {} value `{}`", #type_name, value).into())
+            }
+        }
+    }
+}
@@ -2,16 +2,52 @@ use crate::{EntityStruct, entity::EntityField};
 use quote::quote;
 use sqlorm_core::format_alised_col_name;
 
+/// Columns read into the struct literal, and columns of flattened fields assigned afterwards
+/// into the embedded struct, which starts out as `Default`.
+fn columns(es: &EntityStruct) -> (Vec<&EntityField>, Vec<&EntityField>) {
+    es.fields
+        .iter()
+        .filter(|f| !f.is_ignored())
+        .partition(|f| f.embedded.is_none())
+}
+
 pub fn from_aliased_row(es: &EntityStruct) -> proc_macro2::TokenStream {
     let name = &es.struct_ident;
     let alias = &es.table_name.alias;
 
-    let fields: Vec<&EntityField> = es.fields.iter().filter(|f| !f.is_ignored()).collect();
+    let (fields, embedded) = columns(es);
     let field_idents: Vec<_> = fields.iter().map(|f| &f.ident).collect();
-    let field_types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
-    let col_names: Vec<_> = fields
+    let aliased_values: Vec<_> = fields
         .iter()
-        .map(|f| format_alised_col_name(alias, &f.name))
+        .map(|f| {
+            let col = format_alised_col_name(alias, &f.name);
+            f.decode_value(&quote! { #col })
+        })
+        .collect();
+    let realiased_values: Vec<_> = fields
+        .iter()
+        .map(|f| {
+            let name = &f.name;
+            f.decode_value(&quote! { &::sqlorm::format_alised_col_name(alias, #name) })
+        })
+        .collect();
+    let aliased_embedded: Vec<_> = embedded
+        .iter()
+        .map(|f| {
+            let col = format_alised_col_name(alias, &f.name);
+            let (place, value) = (f.access(&quote! { entity }), f.decode_value(&quote! { #col }));
+            quote! { #place = #value; }
+        })
+        .collect();
+    let realiased_embedded: Vec<_> = embedded
+        .iter()
+        .map(|f| {
+            let name = &f.name;
+            let place = f.access(&quote! { entity });
+            let value =
+                f.decode_value(&quote! { &::sqlorm::format_alised_col_name(alias, #name) });
+            quote! { #place = #value; }
+        })
         .collect();
 
     let has_ignored = es.fields.iter().any(|f| f.is_ignored());
@@ -29,12 +65,31 @@ pub fn from_aliased_row(es: &EntityStruct) -> proc_macro2::TokenStream {
                 row: &::sqlorm::Row,
             ) -> ::sqlorm::sqlx::Result<Self> where Self: Sized+Default {
                 use ::sqlorm::sqlx::Row;
-                Ok(Self {
+                #[allow(unused_mut)]
+                let mut entity = Self {
                     #(
-                        #field_idents: row.try_get::<#field_types, &str>(#col_names)?
+                        #field_idents: #aliased_values
                     ),*,
                     #default_part
-                })
+                };
+                #(#aliased_embedded)*
+                Ok(entity)
+            }
+
+            fn from_row_with_alias(
+                row: &::sqlorm::Row,
+                alias: &str,
+            ) -> ::sqlorm::sqlx::Result<Self> where Self: Sized+Default {
+                use ::sqlorm::sqlx::Row;
+                #[allow(unused_mut)]
+                let mut entity = Self {
+                    #(
+                        #field_idents: #realiased_values
+                    ),*,
+                    #default_part
+                };
+                #(#realiased_embedded)*
+                Ok(entity)
             }
         }
     }
@@ -43,10 +98,23 @@ pub fn from_aliased_row(es: &EntityStruct) -> proc_macro2::TokenStream {
 pub fn from_row_impl(es: &EntityStruct) -> proc_macro2::TokenStream {
     let ident = &es.struct_ident;
 
-    let fields: Vec<&EntityField> = es.fields.iter().filter(|f| !f.is_ignored()).collect();
+    let (fields, embedded) = columns(es);
     let field_idents: Vec<_> = fields.iter().map(|f| &f.ident).collect();
-    let field_types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
-    let col_names: Vec<_> = fields.iter().map(|f| f.name.clone()).collect();
+    let values: Vec<_> = fields
+        .iter()
+        .map(|f| {
+            let col = &f.name;
+            f.decode_value(&quote! { #col })
+        })
+        .collect();
+    let embedded_values: Vec<_> = embedded
+        .iter()
+        .map(|f| {
+            let col = &f.name;
+            let (place, value) = (f.access(&quote! { entity }), f.decode_value(&quote! { #col }));
+            quote! { #place = #value; }
+        })
+        .collect();
 
     let has_ignored = es.fields.iter().any(|f| f.is_ignored());
 
@@ -63,12 +131,15 @@ pub fn from_row_impl(es: &EntityStruct) -> proc_macro2::TokenStream {
                 row: &'r ::sqlorm::Row
             ) -> ::std::result::Result<Self, ::sqlorm::sqlx::Error> {
                 use ::sqlorm::sqlx::Row;
-                Ok(Self {
+                #[allow(unused_mut)]
+                let mut entity = Self {
                     #(
-                        #field_idents: row.try_get::<#field_types, &str>(#col_names)?
+                        #field_idents: #values
                     ),*,
                     #default_part
-                })
+                };
+                #(#embedded_values)*
+                Ok(entity)
             }
         }
     }
@@ -1,12 +1,14 @@
 use crate::EntityStruct;
 
 mod from_aliased_row;
+mod schema;
 mod table;
 
 pub fn traits(es: &EntityStruct) -> proc_macro2::TokenStream {
     let table = table::table(es);
     let from_aliased_row = from_aliased_row::from_aliased_row(es);
     let from_row = from_aliased_row::from_row_impl(es);
+    let schema = schema::schema(es);
     quote::quote! {
 
         #table
@@ -15,5 +17,7 @@ pub fn traits(es: &EntityStruct) -> proc_macro2::TokenStream {
 
         #from_row
 
+        #schema
+
     }
 }
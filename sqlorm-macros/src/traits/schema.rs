@@ -0,0 +1,159 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Expr, ExprLit, Ident, Lit, UnOp};
+
+use crate::{
+    EntityStruct,
+    entity::{EntityField, FieldKind, TimestampKind},
+    relations::RelationType,
+};
+
+pub fn schema(es: &EntityStruct) -> TokenStream {
+    let struct_ident = &es.struct_ident;
+    let fields: Vec<&EntityField> = es.fields.iter().filter(|f| !f.is_ignored()).collect();
+
+    let columns: Vec<TokenStream> = fields.iter().copied().map(column).collect();
+
+    let indexes: Vec<TokenStream> = es
+        .indexes
+        .iter()
+        .map(|index| {
+            let names: Vec<String> = index
+                .columns
+                .iter()
+                .map(|c| {
+                    es.fields
+                        .iter()
+                        .find(|f| &f.ident == c)
+                        .map_or_else(|| c.to_string(), |f| f.name.clone())
+                })
+                .collect();
+            let name = format!(
+                "{}_{}_{}",
+                es.table_name.raw,
+                names.join("_"),
+                if index.unique { "key" } else { "idx" }
+            );
+            let unique = index.unique;
+            quote! {
+                ::sqlorm::schema::IndexSchema {
+                    name: #name.to_string(),
+                    columns: vec![#(#names),*],
+                    unique: #unique,
+                }
+            }
+        })
+        .collect();
+
+    let foreign_keys: Vec<TokenStream> = fields
+        .iter()
+        .filter_map(|f| f.relations.as_ref().map(|rels| (f, rels)))
+        .flat_map(|(f, rels)| rels.iter().map(move |rel| (f, rel)))
+        .filter(|(_, rel)| matches!(rel.kind, RelationType::BelongsTo))
+        .map(|(f, rel)| {
+            let column = &f.name;
+            let other = &rel.other;
+            let other_field = Ident::new(&rel.on.1.to_string().to_uppercase(), Span::call_site());
+            quote! {
+                ::sqlorm::schema::ForeignKey {
+                    column: #column,
+                    table: <#other as ::sqlorm::Table>::TABLE_NAME,
+                    references: #other::#other_field.name,
+                }
+            }
+        })
+        .collect();
+
+    quote! {
+        #[automatically_derived]
+        impl ::sqlorm::schema::Schema for #struct_ident {
+            fn schema() -> ::sqlorm::schema::TableSchema {
+                use ::sqlorm::sqlx::TypeInfo as _;
+                ::sqlorm::schema::TableSchema {
+                    name: <Self as ::sqlorm::Table>::TABLE_NAME,
+                    columns: vec![#(#columns),*],
+                    indexes: vec![#(#indexes),*],
+                    foreign_keys: vec![#(#foreign_keys),*],
+                }
+            }
+        }
+    }
+}
+
+fn column(f: &EntityField) -> TokenStream {
+    let name = &f.name;
+    let ty = &f.ty;
+
+    // the stored type: ciphertext for encrypted and JSON for json fields
+    let sql_type = match (&f.column_type, &f.encrypted, f.json) {
+        (Some(column_type), _, _) => quote! { #column_type.to_string() },
+        (None, Some(_), _) => quote! {
+            <::std::vec::Vec<u8> as ::sqlorm::sqlx::Type<::sqlorm::Driver>>::type_info().name().to_string()
+        },
+        (None, None, true) => quote! {
+            <::sqlorm::sqlx::types::Json<()> as ::sqlorm::sqlx::Type<::sqlorm::Driver>>::type_info().name().to_string()
+        },
+        (None, None, false) => quote! {
+            <#ty as ::sqlorm::sqlx::Type<::sqlorm::Driver>>::type_info().name().to_string()
+        },
+    };
+
+    let nullable = f.is_nullable();
+    let primary_key = f.is_pk();
+    let auto_increment = primary_key && f.pk_factory().is_none();
+    let unique = matches!(f.kind, FieldKind::Regular { unique: true });
+    let default = match column_default(f) {
+        Some(default) => quote! { Some(#default.to_string()) },
+        None => quote! { None },
+    };
+
+    quote! {
+        ::sqlorm::schema::ColumnSchema {
+            name: #name,
+            sql_type: #sql_type,
+            nullable: #nullable,
+            primary_key: #primary_key,
+            auto_increment: #auto_increment,
+            unique: #unique,
+            default: #default,
+        }
+    }
+}
+
+/// SQL default of the column, if it can be derived at compile time.
+fn column_default(f: &EntityField) -> Option<String> {
+    match &f.kind {
+        FieldKind::Timestamp(TimestampKind::Created { .. } | TimestampKind::Updated { .. }) => {
+            Some("CURRENT_TIMESTAMP".to_string())
+        }
+        FieldKind::SoftDelete => Some("FALSE".to_string()),
+        _ => f.default.as_ref().and_then(sql_literal),
+    }
+}
+
+/// SQL literal of a `#[sql(default = ...)]` expression made of a literal, e.g. `0`, `-1`,
+/// `true` or `"draft".to_string()`. Other expressions have no SQL default.
+fn sql_literal(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(ExprLit { lit, .. }) => match lit {
+            Lit::Str(s) => Some(format!("'{}'", s.value().replace('\'', "''"))),
+            Lit::Int(i) => Some(i.base10_digits().to_string()),
+            Lit::Float(f) => Some(f.base10_digits().to_string()),
+            Lit::Bool(b) => Some(if b.value { "TRUE" } else { "FALSE" }.to_string()),
+            _ => None,
+        },
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => {
+            sql_literal(&unary.expr).map(|value| format!("-{}", value))
+        }
+        Expr::MethodCall(call)
+            if call.args.is_empty()
+                && ["to_string", "to_owned", "into"]
+                    .iter()
+                    .any(|method| call.method == method) =>
+        {
+            sql_literal(&call.receiver)
+        }
+        Expr::Paren(paren) => sql_literal(&paren.expr),
+        _ => None,
+    }
+}
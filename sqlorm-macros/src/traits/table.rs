@@ -6,10 +6,10 @@ use crate::{EntityStruct, entity::EntityField};
 
 pub fn table(es: &EntityStruct) -> TokenStream {
     let struct_ident = &es.struct_ident;
-    let name = &es.table_name.raw;
+    let name = &es.table_name.qualified();
     let alias = &es.table_name.alias;
-    let sql_name = with_quotes(&name);
-    let aliased_sql_name = with_quotes(&format!("{}{}", alias, name));
+    let sql_name = with_quotes(name);
+    let aliased_sql_name = with_quotes(&format!("{}{}", alias, es.table_name.raw));
     let pk = &es.pk;
     let pk_name = &pk.name;
 
@@ -35,6 +35,10 @@ pub fn table(es: &EntityStruct) -> TokenStream {
                 }
             }
 
+            fn columns() -> &'static [::sqlorm::ColumnMeta] {
+                &Self::COLUMN_META
+            }
+
         }
     }
 }
@@ -0,0 +1,160 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Expr, Path, Result, meta::ParseNestedMeta};
+
+use crate::EntityStruct;
+
+/// Check of a field declared with `#[sql(validate(...))]`.
+#[derive(Debug, Clone)]
+pub enum Validator {
+    /// `length(min = 1, max = 255)`, in characters for strings and items for vectors
+    Length {
+        min: Option<Expr>,
+        max: Option<Expr>,
+    },
+    /// `range(min = 0, max = 100)`, inclusive
+    Range {
+        min: Option<Expr>,
+        max: Option<Expr>,
+    },
+    /// `email`
+    Email,
+    /// `custom = path::to_fn`, a `fn(&T) -> Result<(), String>`
+    Custom(Path),
+}
+
+impl Validator {
+    /// Parses the validators listed in `validate(...)` into `validators`.
+    pub fn parse(meta: &ParseNestedMeta, validators: &mut Vec<Validator>) -> Result<()> {
+        meta.parse_nested_meta(|inner| {
+            if inner.path.is_ident("length") || inner.path.is_ident("range") {
+                let (min, max) = parse_bounds(&inner)?;
+                validators.push(if inner.path.is_ident("length") {
+                    Validator::Length { min, max }
+                } else {
+                    Validator::Range { min, max }
+                });
+                Ok(())
+            } else if inner.path.is_ident("email") {
+                validators.push(Validator::Email);
+                Ok(())
+            } else if inner.path.is_ident("custom") {
+                validators.push(Validator::Custom(inner.value()?.parse()?));
+                Ok(())
+            } else {
+                Err(inner.error("unexpected validator, expected `length`, `range`, `email` or `custom`"))
+            }
+        })
+    }
+
+    /// Statement adding the error of `value`, a reference to the field value, to `errors`.
+    fn check(&self, field: &str) -> TokenStream {
+        let message = match self {
+            Validator::Length { min, max } => {
+                let (min, max) = (option(min), option(max));
+                quote! { ::sqlorm::validation::length(value, #min, #max) }
+            }
+            Validator::Range { min, max } => {
+                let (min, max) = (option(min), option(max));
+                quote! { ::sqlorm::validation::range(value, #min, #max) }
+            }
+            Validator::Email => quote! { ::sqlorm::validation::email(value) },
+            Validator::Custom(path) => quote! { #path(value).err() },
+        };
+        quote! {
+            if let Some(message) = #message {
+                errors.add(#field, message);
+            }
+        }
+    }
+}
+
+fn parse_bounds(meta: &ParseNestedMeta) -> Result<(Option<Expr>, Option<Expr>)> {
+    let (mut min, mut max) = (None, None);
+    meta.parse_nested_meta(|bound| {
+        if bound.path.is_ident("min") {
+            min = Some(bound.value()?.parse()?);
+        } else if bound.path.is_ident("max") {
+            max = Some(bound.value()?.parse()?);
+        } else {
+            return Err(bound.error("expected `min` or `max`"));
+        }
+        Ok(())
+    })?;
+    if min.is_none() && max.is_none() {
+        return Err(meta.error("expected `min`, `max` or both"));
+    }
+    Ok((min, max))
+}
+
+fn option(expr: &Option<Expr>) -> TokenStream {
+    match expr {
+        Some(expr) => quote! { ::std::option::Option::Some(#expr) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
+
+/// Generates `validate()`, running the field validators and `sqlorm::Validate` for entities
+/// declared with `#[table(validate)]`.
+pub fn validate(es: &EntityStruct) -> TokenStream {
+    if !es.validates() || es.kind.is_read_only() {
+        return quote! {};
+    }
+    let ident = &es.struct_ident;
+    let vis = &es.vis;
+
+    let fields = es.fields.iter().filter(|f| !f.validators.is_empty()).map(|f| {
+        let field_ident = &f.ident;
+        let field = field_ident.to_string();
+        let checks = f.validators.iter().map(|v| v.check(&field));
+        // `None` is valid, the other checks apply to the value
+        if f.is_nullable() {
+            quote! {
+                if let Some(value) = &self.#field_ident {
+                    #(#checks)*
+                }
+            }
+        } else {
+            quote! {
+                let value = &self.#field_ident;
+                #(#checks)*
+            }
+        }
+    });
+    let custom = es.validate.then(|| {
+        quote! {
+            if let Err(custom) = <Self as ::sqlorm::Validate>::validate(self) {
+                errors.merge(custom);
+            }
+        }
+    });
+
+    quote! {
+        #[automatically_derived]
+        impl #ident {
+            /// Checks the `#[sql(validate(...))]` fields, and `sqlorm::Validate` for entities
+            /// declared with `#[table(validate)]`, collecting the errors of all fields.
+            ///
+            /// Called by `insert()`, `update()` and `save()`, which fail with the errors.
+            #vis fn validate(&self) -> ::std::result::Result<(), ::sqlorm::ValidationErrors> {
+                let mut errors = ::sqlorm::ValidationErrors::new();
+                #({ #fields })*
+                #custom
+                errors.into_result()
+            }
+        }
+    }
+}
+
+/// Validates `entity`, returning the errors from the surrounding function.
+///
+/// Empty unless the entity has validators.
+pub fn check(es: &EntityStruct, entity: TokenStream) -> TokenStream {
+    if !es.validates() {
+        return quote! {};
+    }
+    let ident = &es.struct_ident;
+    quote! {
+        #ident::validate(&#entity)?;
+    }
+}
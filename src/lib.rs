@@ -32,9 +32,9 @@
 //! - [ ] Cross-relations filters
 //! - [ ] Add support for custom, user-defined selectable types
 //! - Problems:
-//!     When loading relations batch, we need to move all foreign filters from original query to
-//!     batch query. For that we need to rebuild qb and executor
-//! //! e.g. User::query().with_posts().filter(Post::read_time.gt_(4))
+//!   When loading relations batch, we need to move all foreign filters from original query to
+//!   batch query. For that we need to rebuild qb and executor
+//!   e.g. User::query().with_posts().filter(Post::read_time.gt_(4))
 //!
 //! Optional todos:
 //! -[ ] Compile-time entity<->schema type check
@@ -46,6 +46,17 @@
 //! To use this crate you must activate **one** of the following features (else the crate wont compile):
 //! - `postgres`
 //! - `sqlite`
+//!
+//! Pools are opened with [`Sqlorm::connect`], or configured through [`Sqlorm::options`]:
+//!
+//! ```rust,ignore
+//! let pool = sqlorm::Sqlorm::options()
+//!     .max_connections(10)
+//!     .acquire_timeout(std::time::Duration::from_secs(3))
+//!     .on_connect("PRAGMA foreign_keys = ON")
+//!     .connect(&std::env::var("DATABASE_URL")?)
+//!     .await?;
+//! ```
 
 #![cfg(any(feature = "postgres", feature = "sqlite"))]
 
@@ -53,6 +64,8 @@ pub use hashbrown::HashMap;
 pub use sqlorm_core::*;
 pub use sqlorm_core::{Connection, Driver, GenericExecutor, Pool, Row};
 pub use sqlorm_macros::Entity;
+pub use sqlorm_macros::FromAliasedRow;
+pub use sqlorm_macros::SqlEnum;
 pub use sqlorm_macros::table;
 
 pub mod prelude {
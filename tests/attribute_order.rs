@@ -0,0 +1,42 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::{Jar, User, UserExecutor};
+use sqlorm::table;
+
+// derives placed before `#[table]` see the struct as written, so `owner` is declared here
+#[derive(Debug, Clone, Default)]
+#[table(name = "jar")]
+pub struct JarSummary {
+    #[sql(pk)]
+    pub id: i64,
+    pub title: String,
+    #[sql(relation(belongs_to -> User, name = "owner", on = id))]
+    pub owner_id: i64,
+    #[sql(skip)]
+    pub owner: Option<User>,
+}
+
+#[tokio::test]
+async fn test_derive_before_table() {
+    let pool = create_clean_db().await;
+    let user = User::test_user("order@example.com", "order")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    let jar = Jar::test_jar(user.id, "order")
+        .save(&pool)
+        .await
+        .expect("Failed to save jar");
+
+    let summary = JarSummary::query()
+        .filter(JarSummary::ID.eq(jar.id))
+        .with_owner()
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch jar");
+
+    let copy = summary.clone();
+    assert_eq!(copy.title, jar.title);
+    assert_eq!(copy.owner.expect("Owner should be loaded").id, user.id);
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use std::path::PathBuf;
+
+use common::create_clean_db;
+use common::entities::{Jar, User};
+use sqlorm::Schema;
+use sqlorm::cli::Command;
+
+#[test]
+fn test_parse_commands() {
+    assert_eq!(
+        Command::parse(["sqlorm", "generate", "entity", "jar"]).unwrap(),
+        (
+            Command::GenerateEntity {
+                table: "jar".to_string()
+            },
+            None
+        )
+    );
+    assert_eq!(
+        Command::parse(["--database-url", "sqlite::memory:", "schema", "diff"]).unwrap(),
+        (Command::SchemaDiff, Some("sqlite::memory:".to_string()))
+    );
+    assert_eq!(
+        Command::parse(["seed", "run"]).unwrap().0,
+        Command::SeedRun {
+            dir: PathBuf::from("seeds")
+        }
+    );
+    assert!(Command::parse(["schema"]).is_err());
+    assert!(Command::parse(["schema", "diff", "--database-url"]).is_err());
+}
+
+#[tokio::test]
+async fn test_schema_commands() {
+    let pool = create_clean_db().await;
+    let schemas = [User::schema(), Jar::schema()];
+
+    let report = Command::SchemaValidate.execute(&pool, &schemas).await.unwrap();
+    assert!(report.success, "{}", report.output);
+    assert_eq!(report.output, "2 tables match\n");
+
+    let report = Command::SchemaDiff.execute(&pool, &schemas).await.unwrap();
+    assert!(report.success);
+    assert_eq!(report.output, "");
+
+    // the stock binary has no entities
+    let report = Command::SchemaValidate.execute(&pool, &[]).await.unwrap();
+    assert!(!report.success);
+}
+
+#[tokio::test]
+async fn test_generate_and_seed_commands() {
+    let pool = create_clean_db().await;
+    let report = Command::GenerateEntity {
+        table: "jar".to_string(),
+    }
+    .execute(&pool, &[])
+    .await
+    .unwrap();
+    assert!(report.output.contains("pub struct Jar {"));
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("01_users.sql"),
+        "INSERT INTO \"user\" (email, password, username, first_name, last_name) \
+         VALUES ('seed@example.com', 'x', 'seed', 'Seed', 'User');",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "not sql").unwrap();
+
+    let report = Command::SeedRun {
+        dir: dir.path().to_path_buf(),
+    }
+    .execute(&pool, &[])
+    .await
+    .unwrap();
+    assert_eq!(report.output.lines().count(), 1);
+    assert!(report.output.contains("01_users.sql"));
+
+    let count: i64 = sqlorm::sqlx::query_scalar("SELECT COUNT(*) FROM \"user\" WHERE username = 'seed'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+}
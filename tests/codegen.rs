@@ -0,0 +1,68 @@
+mod common;
+
+use common::create_clean_db;
+use sqlorm::codegen;
+use sqlorm::schema::{DbColumn, DbForeignKey};
+
+fn column(name: &str, sql_type: &str, nullable: bool) -> DbColumn {
+    DbColumn {
+        name: name.to_string(),
+        sql_type: sql_type.to_string(),
+        nullable,
+        primary_key: false,
+        unique: false,
+    }
+}
+
+#[tokio::test]
+async fn test_generate_entity_from_table() {
+    let pool = create_clean_db().await;
+    let source = codegen::generate_entity(&pool, "jar")
+        .await
+        .expect("Failed to generate entity");
+
+    assert!(source.starts_with("#[table(name = \"jar\")]\n#[derive(Debug, Clone, Default)]\npub struct Jar {\n"));
+    assert!(source.contains("    #[sql(pk)]\n    pub id: i64,\n"));
+    assert!(source.contains("    #[sql(unique)]\n    pub alias: String,\n"));
+    assert!(source.contains("    pub description: Option<String>,\n"));
+    assert!(source.contains("    pub hide_earnings: "));
+    assert!(source.contains(
+        "    #[sql(relation(belongs_to -> User, name = \"owner\", on = id))]\n    pub owner_id: i64,\n"
+    ));
+    assert!(source.ends_with("}\n"));
+}
+
+#[tokio::test]
+async fn test_generate_entity_of_missing_table() {
+    let pool = create_clean_db().await;
+    let result = codegen::generate_entity(&pool, "missing").await;
+    assert!(matches!(result, Err(sqlorm::sqlx::Error::RowNotFound)));
+}
+
+#[test]
+fn test_render_entity_names() {
+    let mut id = column("id", "TEXT", false);
+    id.primary_key = true;
+    let columns = vec![
+        id,
+        column("CategoryId", "TEXT", true),
+        column("type", "TEXT", false),
+        column("deleted_at", "TEXT", true),
+    ];
+    let foreign_keys = vec![DbForeignKey {
+        column: "CategoryId".to_string(),
+        table: "categories".to_string(),
+        references: "id".to_string(),
+    }];
+
+    let source = codegen::render_entity("shop.order_items", &columns, &foreign_keys);
+    assert!(source.starts_with("#[table(name = \"order_items\", schema = \"shop\")]\n"));
+    assert!(source.contains("pub struct OrderItem {"));
+    assert!(source.contains(
+        "    #[sql(rename(\"CategoryId\"))]\n    #[sql(relation(belongs_to -> Category, name = \"category\", on = id))]\n    pub category_id: Option<String>,\n"
+    ));
+    assert!(source.contains("    #[sql(rename(\"type\"))]\n    pub type_: String,\n"));
+    // only timestamp columns get `timestamp(...)`
+    assert!(source.contains("    pub deleted_at: Option<String>,\n"));
+    assert!(!source.contains("timestamp("));
+}
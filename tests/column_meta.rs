@@ -0,0 +1,58 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::User;
+use sqlorm::Table;
+
+#[test]
+fn test_columns_metadata() {
+    let columns = User::columns();
+    let names: Vec<&str> = columns.iter().map(|c| c.name).collect();
+    assert_eq!(names, User::COLUMNS);
+
+    let id = columns.iter().find(|c| c.name == "id").unwrap();
+    assert!(id.is_pk);
+    assert!(id.is_unique);
+    assert!(!id.nullable);
+    assert_eq!(id.rust_type, "i64");
+
+    let email = columns.iter().find(|c| c.name == "email").unwrap();
+    assert!(!email.is_pk);
+    assert!(email.is_unique);
+
+    let avatar = columns.iter().find(|c| c.name == "avatar").unwrap();
+    assert!(avatar.nullable);
+    assert!(!avatar.is_unique);
+    assert_eq!(avatar.rust_type, "Option<String>");
+}
+
+#[test]
+fn test_table_constants() {
+    assert_eq!(User::TABLE_NAME, "user");
+    assert_eq!(User::SQL_NAME, "\"user\"");
+    assert!(User::ALL_COLUMNS.starts_with("\"id\", \"email\", \"password\""));
+    assert!(User::ALL_COLUMNS.contains("\"avatar\""));
+    assert_eq!(User::qualified(User::EMAIL), "\"user\".\"email\"");
+    assert_eq!(User::qualified(User::AVATAR_URL), "\"user\".\"avatar\"");
+}
+
+#[tokio::test]
+async fn test_table_constants_in_raw_query() {
+    let pool = create_clean_db().await;
+    User::test_user("raw@example.com", "raw")
+        .save(&pool)
+        .await
+        .expect("Failed to insert user");
+
+    let sql = format!(
+        "SELECT {} FROM {} WHERE {} = 'raw@example.com'",
+        User::qualified(User::USERNAME),
+        User::SQL_NAME,
+        User::qualified(User::EMAIL),
+    );
+    let username: String = sqlorm::sqlx::query_scalar(&sql)
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to run raw query");
+    assert_eq!(username, "raw");
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::{Jar, JarExecutor, User};
+use sqlorm::StatementExecutor;
+
+// the trace id provider is global, so everything runs in a single test
+#[tokio::test]
+async fn test_comments_are_rendered_and_executed() {
+    let pool = create_clean_db().await;
+    let owner = User::test_user("owner@example.com", "owner")
+        .save(&pool)
+        .await
+        .unwrap();
+    let jar = Jar::test_jar(owner.id, "tagged").save(&pool).await.unwrap();
+
+    let found = Jar::query()
+        .filter(Jar::ID.eq(jar.id))
+        .comment("endpoint=GET /jars")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(found.id, jar.id);
+
+    let update = jar.clone().update().comment("endpoint=PUT /jars");
+    assert!(
+        update.debug_sql().ends_with(" /* endpoint=PUT /jars */"),
+        "{}",
+        update.debug_sql()
+    );
+    update.execute(&pool).await.unwrap();
+
+    let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+    sqlorm::comment::inject_trace_id(move || Some(traceparent.to_string()));
+    let sql = Jar::query().comment("endpoint=GET /jars").to_sql();
+    let expected = format!(" /* endpoint=GET /jars, traceparent='{}' */", traceparent);
+    assert!(sql.ends_with(&expected), "{}", sql);
+    let count = Jar::query().not_persistent().fetch_all(&pool).await.unwrap().len();
+    assert_eq!(count, 1);
+
+    jar.delete().comment("endpoint=DELETE /jars").execute(&pool).await.unwrap();
+
+    // PostgreSQL nests block comments, so `/*` must not open one
+    sqlorm::comment::inject_trace_id(|| Some("00-'quoted'-01".to_string()));
+    let tagged = Jar::query().comment("endpoint=GET /jars/*");
+    let sql = tagged.to_sql();
+    assert!(
+        sql.ends_with(r" /* endpoint=GET /jars/ *, traceparent='00-\'quoted\'-01' */"),
+        "{}",
+        sql
+    );
+    tagged.fetch_all(&pool).await.unwrap();
+
+    sqlorm::comment::clear_trace_id();
+    assert!(!Jar::query().to_sql().contains("/*"));
+}
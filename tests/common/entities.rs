@@ -3,12 +3,16 @@ use serde::{Deserialize, Serialize};
 use sqlorm::table;
 use uuid::Uuid;
 
-#[table]
+#[table(
+    index(columns(first_name, last_name)),
+    projection(Contact = (id, email, username))
+)]
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct User {
     #[sql(pk)]
-    #[sql(relation(has_many -> Jar, name = "jars", on = owner_id))]
-    #[sql(relation(has_many -> Donation, name = "payed_donations", on = payer_id))]
+    #[sql(relation(has_many -> Jar, name = "jars", on = owner_id, count))]
+    #[sql(relation(has_many -> Donation, name = "payed_donations", on = payer_id, count))]
+    #[sql(relation(has_many -> Donation, name = "received_donations", on = jar_id, through = Jar, keys = (owner_id, id), count))]
     pub id: i64,
     #[sql(unique)]
     pub email: String,
@@ -0,0 +1,5 @@
+CREATE TABLE "versioned_entities" (
+    "id" BIGSERIAL PRIMARY KEY,
+    "name" TEXT NOT NULL,
+    "version" BIGINT NOT NULL DEFAULT 0
+);
@@ -0,0 +1,15 @@
+CREATE TABLE "posts" (
+    "id" BIGSERIAL PRIMARY KEY,
+    "title" TEXT NOT NULL
+);
+
+CREATE TABLE "tags" (
+    "id" BIGSERIAL PRIMARY KEY,
+    "name" TEXT NOT NULL UNIQUE
+);
+
+CREATE TABLE "post_tags" (
+    "post_id" BIGINT NOT NULL REFERENCES "posts"("id"),
+    "tag_id" BIGINT NOT NULL REFERENCES "tags"("id"),
+    PRIMARY KEY ("post_id", "tag_id")
+);
@@ -0,0 +1,6 @@
+-- root categories use parent_id = 0
+CREATE TABLE "categories" (
+    "id" BIGSERIAL PRIMARY KEY,
+    "name" TEXT NOT NULL,
+    "parent_id" BIGINT NOT NULL DEFAULT 0
+);
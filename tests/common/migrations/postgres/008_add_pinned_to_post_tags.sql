@@ -0,0 +1 @@
+ALTER TABLE "post_tags" ADD COLUMN "pinned" BOOLEAN NOT NULL DEFAULT FALSE;
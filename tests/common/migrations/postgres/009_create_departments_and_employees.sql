@@ -0,0 +1,11 @@
+CREATE TABLE "departments" (
+    "id" BIGSERIAL PRIMARY KEY,
+    "name" TEXT NOT NULL
+);
+
+-- employees may be unassigned
+CREATE TABLE "employees" (
+    "id" BIGSERIAL PRIMARY KEY,
+    "name" TEXT NOT NULL,
+    "department_id" BIGINT REFERENCES "departments"("id")
+);
@@ -0,0 +1,5 @@
+-- ids are generated client-side
+CREATE TABLE "short_links" (
+    "id" TEXT PRIMARY KEY,
+    "url" TEXT NOT NULL
+);
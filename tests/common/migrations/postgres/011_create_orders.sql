@@ -0,0 +1,4 @@
+CREATE TABLE "orders" (
+    "id" BIGSERIAL PRIMARY KEY,
+    "status" TEXT NOT NULL
+);
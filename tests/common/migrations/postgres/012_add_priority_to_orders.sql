@@ -0,0 +1 @@
+ALTER TABLE "orders" ADD COLUMN "priority" SMALLINT NOT NULL DEFAULT 1;
@@ -0,0 +1,5 @@
+CREATE TABLE "settings" (
+    "id" BIGSERIAL PRIMARY KEY,
+    "preferences" JSONB NOT NULL,
+    "notifications" JSONB
+);
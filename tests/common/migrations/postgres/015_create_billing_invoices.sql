@@ -0,0 +1,6 @@
+CREATE SCHEMA "billing";
+CREATE TABLE "billing"."invoices" (
+    "id" BIGSERIAL PRIMARY KEY,
+    "number" TEXT NOT NULL,
+    "total" DOUBLE PRECISION NOT NULL
+);
@@ -0,0 +1,5 @@
+CREATE TABLE "documents" (
+    "id" BIGSERIAL PRIMARY KEY,
+    "title" TEXT NOT NULL,
+    "created_by" TEXT NOT NULL
+);
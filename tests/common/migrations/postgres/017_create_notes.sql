@@ -0,0 +1,5 @@
+CREATE TABLE "notes" (
+    "id" BIGSERIAL PRIMARY KEY,
+    "body" TEXT NOT NULL,
+    "is_deleted" BOOLEAN NOT NULL DEFAULT FALSE
+);
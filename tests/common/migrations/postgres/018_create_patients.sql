@@ -0,0 +1,6 @@
+CREATE TABLE "patients" (
+    "id" BIGSERIAL PRIMARY KEY,
+    "name" TEXT NOT NULL,
+    "ssn" BYTEA NOT NULL,
+    "notes" BYTEA
+);
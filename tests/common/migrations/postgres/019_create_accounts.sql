@@ -0,0 +1,7 @@
+CREATE TABLE "accounts" (
+    "id" BIGSERIAL PRIMARY KEY,
+    "tenant_id" BIGINT NOT NULL,
+    "email" TEXT NOT NULL,
+    "name" TEXT NOT NULL,
+    UNIQUE ("tenant_id", "email")
+);
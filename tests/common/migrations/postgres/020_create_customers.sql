@@ -0,0 +1,10 @@
+CREATE TABLE "customers" (
+    "id" BIGSERIAL PRIMARY KEY,
+    "name" TEXT NOT NULL,
+    "address_street" TEXT NOT NULL,
+    "address_city" TEXT NOT NULL,
+    "address_zip" TEXT,
+    "billing_street" TEXT NOT NULL,
+    "billing_city" TEXT NOT NULL,
+    "billing_zip" TEXT
+);
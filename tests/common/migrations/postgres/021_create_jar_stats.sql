@@ -0,0 +1,10 @@
+CREATE VIEW "jar_stats" AS
+SELECT
+    "jar"."id",
+    "jar"."title",
+    "jar"."owner_id",
+    COUNT("donation"."id") AS "donation_count",
+    COALESCE(SUM("donation"."amount"), 0.0) AS "donated"
+FROM "jar"
+LEFT JOIN "donation" ON "donation"."jar_id" = "jar"."id" AND "donation"."deleted_at" IS NULL
+GROUP BY "jar"."id", "jar"."title", "jar"."owner_id";
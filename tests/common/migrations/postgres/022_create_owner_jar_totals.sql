@@ -0,0 +1,9 @@
+CREATE MATERIALIZED VIEW "owner_jar_totals" AS
+SELECT
+    "owner_id",
+    COUNT(*) AS "jar_count",
+    SUM("total_amount") AS "total_amount"
+FROM "jar"
+GROUP BY "owner_id";
+
+CREATE UNIQUE INDEX "owner_jar_totals_owner_id_key" ON "owner_jar_totals" ("owner_id");
@@ -0,0 +1,5 @@
+CREATE TABLE "versioned_entities" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "name" TEXT NOT NULL,
+    "version" INTEGER NOT NULL DEFAULT 0
+);
@@ -0,0 +1,15 @@
+CREATE TABLE "posts" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "title" TEXT NOT NULL
+);
+
+CREATE TABLE "tags" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "name" TEXT NOT NULL UNIQUE
+);
+
+CREATE TABLE "post_tags" (
+    "post_id" INTEGER NOT NULL REFERENCES "posts"("id"),
+    "tag_id" INTEGER NOT NULL REFERENCES "tags"("id"),
+    PRIMARY KEY ("post_id", "tag_id")
+);
@@ -0,0 +1,6 @@
+-- root categories use parent_id = 0
+CREATE TABLE "categories" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "name" TEXT NOT NULL,
+    "parent_id" INTEGER NOT NULL DEFAULT 0
+);
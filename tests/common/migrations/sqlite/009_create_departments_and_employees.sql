@@ -0,0 +1,11 @@
+CREATE TABLE "departments" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "name" TEXT NOT NULL
+);
+
+-- employees may be unassigned
+CREATE TABLE "employees" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "name" TEXT NOT NULL,
+    "department_id" INTEGER REFERENCES "departments"("id")
+);
@@ -0,0 +1,4 @@
+CREATE TABLE "orders" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "status" TEXT NOT NULL
+);
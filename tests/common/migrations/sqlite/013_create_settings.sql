@@ -0,0 +1,5 @@
+CREATE TABLE "settings" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "preferences" TEXT NOT NULL,
+    "notifications" TEXT
+);
@@ -0,0 +1,5 @@
+CREATE TABLE "documents" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "title" TEXT NOT NULL,
+    "created_by" TEXT NOT NULL
+);
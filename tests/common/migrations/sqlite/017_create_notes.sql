@@ -0,0 +1,5 @@
+CREATE TABLE "notes" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "body" TEXT NOT NULL,
+    "is_deleted" BOOLEAN NOT NULL DEFAULT FALSE
+);
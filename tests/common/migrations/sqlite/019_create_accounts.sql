@@ -0,0 +1,7 @@
+CREATE TABLE "accounts" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "tenant_id" INTEGER NOT NULL,
+    "email" TEXT NOT NULL,
+    "name" TEXT NOT NULL,
+    UNIQUE ("tenant_id", "email")
+);
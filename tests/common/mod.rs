@@ -78,7 +78,9 @@ pub async fn create_clean_db() -> Pool {
 
 #[cfg(feature = "sqlite")]
 pub async fn create_memory_db() -> Pool {
-    let pool = sqlorm::Pool::connect("sqlite::memory:")
+    let pool = sqlorm::Sqlorm::options()
+        .on_connect("PRAGMA foreign_keys = ON")
+        .connect("sqlite::memory:")
         .await
         .expect("Failed to create in-memory SQLite database");
 
@@ -86,10 +88,5 @@ pub async fn create_memory_db() -> Pool {
         .await
         .expect("Failed to run migrations");
 
-    sqlorm::sqlx::query("PRAGMA foreign_keys = ON")
-        .execute(&pool)
-        .await
-        .expect("Failed to enable foreign keys");
-
     pool
 }
@@ -188,6 +188,8 @@ async fn test_delete_user() {
     // soft delete, since User::deleted_at exists
     user.delete().execute(&pool).await.unwrap();
 
-    let user = User::query().fetch_one(&pool).await.unwrap();
+    assert!(User::query().fetch_optional(&pool).await.unwrap().is_none());
+
+    let user = User::query().with_deleted().fetch_one(&pool).await.unwrap();
     assert!(user.deleted_at.is_some());
 }
@@ -0,0 +1,28 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::User;
+use sqlorm::StatementExecutor;
+
+#[tokio::test]
+async fn test_statement_debug_sql() {
+    let pool = create_clean_db().await;
+
+    let mut user = User::test_user("debug@example.com", "debuguser")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    user.bio = Some("debug bio".to_string());
+
+    let id = user.id;
+
+    let sql = user.clone().update().columns((User::BIO,)).debug_sql();
+    assert_eq!(
+        sql,
+        format!("UPDATE \"user\" SET bio = Some(\"debug bio\") WHERE id = {id}")
+    );
+
+    let sql = user.delete().debug_sql();
+    assert!(sql.starts_with("UPDATE \"user\" SET deleted_at = "));
+    assert!(sql.ends_with(&format!("WHERE id = {id}")));
+}
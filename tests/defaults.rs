@@ -0,0 +1,51 @@
+mod common;
+
+use common::create_clean_db;
+use sqlorm::table;
+
+#[table(name = "posts")]
+#[derive(Debug, Clone, Default)]
+pub struct Post {
+    #[sql(pk)]
+    pub id: i64,
+    #[sql(default = "Untitled".to_string())]
+    pub title: String,
+}
+
+#[tokio::test]
+async fn test_default_applied_on_insert() {
+    let pool = create_clean_db().await;
+
+    let post = Post::default()
+        .save(&pool)
+        .await
+        .expect("Failed to save post");
+    assert_eq!(post.title, "Untitled");
+
+    let titled = Post {
+        title: "Hello".to_string(),
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to save post");
+    assert_eq!(titled.title, "Hello", "Set values are kept");
+
+    let many = Post::insert_many(vec![Post::default()], &pool)
+        .await
+        .expect("Failed to insert posts");
+    assert_eq!(many[0].title, "Untitled");
+}
+
+#[tokio::test]
+async fn test_default_not_applied_on_update() {
+    let pool = create_clean_db().await;
+
+    let mut post = Post::default()
+        .save(&pool)
+        .await
+        .expect("Failed to save post");
+    post.title = String::new();
+    let post = post.save(&pool).await.expect("Failed to update post");
+    assert_eq!(post.title, "");
+}
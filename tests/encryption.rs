@@ -0,0 +1,79 @@
+mod common;
+
+use common::create_clean_db;
+use sqlorm::sqlx::error::BoxDynError;
+use sqlorm::{Cipher, DeterministicCipher, StatementExecutor, table};
+
+/// Toy cipher for tests only, XORs every byte with a fixed key.
+pub struct XorCipher;
+
+impl Cipher for XorCipher {
+    fn encrypt(plaintext: &[u8]) -> Vec<u8> {
+        plaintext.iter().map(|b| b ^ 0x5a).collect()
+    }
+
+    fn decrypt(ciphertext: &[u8]) -> Result<Vec<u8>, BoxDynError> {
+        Ok(ciphertext.iter().map(|b| b ^ 0x5a).collect())
+    }
+}
+
+impl DeterministicCipher for XorCipher {}
+
+#[table(name = "patients")]
+#[derive(Debug, Clone, Default)]
+pub struct Patient {
+    #[sql(pk)]
+    pub id: i64,
+    pub name: String,
+    #[sql(encrypted = XorCipher)]
+    pub ssn: String,
+    #[sql(encrypted = XorCipher)]
+    pub notes: Option<String>,
+}
+
+#[tokio::test]
+async fn test_encrypted_round_trip() {
+    let pool = create_clean_db().await;
+
+    let patient = Patient {
+        name: "Jane".to_string(),
+        ssn: "123-45-6789".to_string(),
+        notes: None,
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to save patient");
+    assert_eq!(patient.ssn, "123-45-6789");
+
+    let stored: Vec<u8> = sqlorm::sqlx::query_scalar("SELECT ssn FROM patients")
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to read raw column");
+    assert_ne!(stored, b"123-45-6789".to_vec(), "ssn must not be stored in plaintext");
+
+    let found = Patient::query()
+        .filter(Patient::SSN.eq("123-45-6789".to_string()))
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to find patient by encrypted column");
+    assert_eq!(found.id, patient.id);
+    assert_eq!(found.ssn, "123-45-6789");
+    assert_eq!(found.notes, None);
+
+    let mut found = found;
+    found.notes = Some("allergic to penicillin".to_string());
+    found
+        .update()
+        .columns((Patient::NOTES,))
+        .execute(&pool)
+        .await
+        .expect("Failed to update patient");
+
+    let reloaded = Patient::query()
+        .filter(Patient::NOTES.is_not_null())
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to reload patient");
+    assert_eq!(reloaded.notes.as_deref(), Some("allergic to penicillin"));
+}
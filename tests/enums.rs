@@ -0,0 +1,146 @@
+mod common;
+
+use common::create_clean_db;
+use sqlorm::{Pool, SqlEnum, table};
+
+#[derive(SqlEnum, Debug, Clone, PartialEq, Default)]
+pub enum OrderStatus {
+    #[default]
+    Pending,
+    Paid,
+    #[sql(rename("cancelled_by_user"))]
+    Cancelled,
+}
+
+#[derive(SqlEnum, Debug, Clone, PartialEq, Default)]
+#[sql(enum(repr = i16))]
+pub enum Priority {
+    #[default]
+    Low = 1,
+    Normal = 5,
+    Urgent = 10,
+}
+
+#[table(name = "orders")]
+#[derive(Debug, Clone, Default)]
+pub struct Order {
+    #[sql(pk)]
+    pub id: i64,
+    pub status: OrderStatus,
+    pub priority: Priority,
+}
+
+async fn save_order(pool: &Pool, status: OrderStatus) -> Order {
+    Order {
+        status,
+        ..Default::default()
+    }
+    .save(pool)
+    .await
+    .expect("Failed to save order")
+}
+
+#[tokio::test]
+async fn test_text_enum_round_trip() {
+    let pool = create_clean_db().await;
+    let pending = save_order(&pool, OrderStatus::Pending).await;
+    let paid = save_order(&pool, OrderStatus::Paid).await;
+    let cancelled = save_order(&pool, OrderStatus::Cancelled).await;
+    assert_eq!(cancelled.status, OrderStatus::Cancelled);
+
+    let found = Order::find_by_id(&pool, pending.id)
+        .await
+        .expect("Failed to find order")
+        .expect("Order not found");
+    assert_eq!(found.status, OrderStatus::Pending);
+
+    let paid_orders = Order::query()
+        .filter(Order::STATUS.eq(OrderStatus::Paid))
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to filter orders");
+    assert_eq!(paid_orders.len(), 1);
+    assert_eq!(paid_orders[0].id, paid.id);
+
+    #[cfg(feature = "postgres")]
+    let sql = "SELECT status FROM orders WHERE id = $1";
+    #[cfg(feature = "sqlite")]
+    let sql = "SELECT status FROM orders WHERE id = ?";
+    let stored: String = sqlorm::sqlx::query_scalar(sql)
+        .bind(cancelled.id)
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to read status");
+    assert_eq!(stored, "cancelled_by_user");
+}
+
+#[tokio::test]
+async fn test_text_enum_unknown_value_fails_to_decode() {
+    let pool = create_clean_db().await;
+    sqlorm::sqlx::query("INSERT INTO orders (status) VALUES ('shipped')")
+        .execute(&pool)
+        .await
+        .expect("Failed to insert raw order");
+
+    let err = Order::query()
+        .fetch_all(&pool)
+        .await
+        .expect_err("Unknown status should not decode");
+    assert!(
+        matches!(err, sqlorm::sqlx::Error::ColumnDecode { .. }),
+        "unexpected error: {err}"
+    );
+}
+
+#[tokio::test]
+async fn test_integer_enum_round_trip() {
+    let pool = create_clean_db().await;
+    let low = save_order(&pool, OrderStatus::Pending).await;
+    let urgent = Order {
+        priority: Priority::Urgent,
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to save order");
+    assert_eq!(low.priority, Priority::Low);
+
+    let urgent_orders = Order::query()
+        .filter(Order::PRIORITY.eq(Priority::Urgent))
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to filter orders");
+    assert_eq!(urgent_orders.len(), 1);
+    assert_eq!(urgent_orders[0].id, urgent.id);
+
+    #[cfg(feature = "postgres")]
+    let sql = "SELECT priority FROM orders WHERE id = $1";
+    #[cfg(feature = "sqlite")]
+    let sql = "SELECT priority FROM orders WHERE id = ?";
+    let stored: i16 = sqlorm::sqlx::query_scalar(sql)
+        .bind(urgent.id)
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to read priority");
+    assert_eq!(stored, 10);
+    assert_eq!(Priority::from_repr(5), Some(Priority::Normal));
+    assert_eq!(Priority::Normal.to_repr(), 5);
+}
+
+#[tokio::test]
+async fn test_integer_enum_unknown_value_fails_to_decode() {
+    let pool = create_clean_db().await;
+    sqlorm::sqlx::query("INSERT INTO orders (status, priority) VALUES ('Paid', 7)")
+        .execute(&pool)
+        .await
+        .expect("Failed to insert raw order");
+
+    let err = Order::query()
+        .fetch_all(&pool)
+        .await
+        .expect_err("Unknown priority should not decode");
+    assert!(
+        matches!(err, sqlorm::sqlx::Error::ColumnDecode { .. }),
+        "unexpected error: {err}"
+    );
+}
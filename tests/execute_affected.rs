@@ -0,0 +1,44 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::{Jar, User};
+use sqlorm::StatementExecutor;
+
+#[tokio::test]
+async fn test_execute_affected() {
+    let pool = create_clean_db().await;
+
+    let owner = User::test_user("affected@example.com", "affected")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    let mut jar = Jar::test_jar(owner.id, "affectedjar")
+        .save(&pool)
+        .await
+        .expect("Failed to save jar");
+    jar.title = "Renamed".to_string();
+
+    let updated = jar
+        .clone()
+        .update()
+        .columns((Jar::TITLE,))
+        .execute_affected(&pool)
+        .await
+        .expect("Failed to update jar");
+    assert_eq!(updated, 1);
+
+    let deleted = jar
+        .clone()
+        .delete()
+        .execute_affected(&pool)
+        .await
+        .expect("Failed to delete jar");
+    assert_eq!(deleted, 1);
+
+    let deleted_again = jar
+        .delete()
+        .execute_affected(&pool)
+        .await
+        .expect("Failed to delete jar");
+    assert_eq!(deleted_again, 0);
+}
@@ -274,3 +274,81 @@ async fn test_filter_and_or() {
     assert_eq!(results.len(), 1);
     assert_eq!(results[0].id, u3.id);
 }
+
+#[tokio::test]
+async fn test_filter_case_insensitive() {
+    let pool = create_clean_db().await;
+    let users = setup_test_users(&pool).await;
+    let like_me = &users[2];
+
+    let found = User::query()
+        .filter(User::USERNAME.ilike("LIKE%".to_string()))
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to filter with ilike");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, like_me.id);
+
+    let found = User::query()
+        .filter(User::EMAIL.eq_ignore_case("Like_Me@Example.com".to_string()))
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to filter with eq_ignore_case");
+    assert_eq!(found.id, like_me.id);
+}
+
+#[tokio::test]
+#[allow(clippy::unnecessary_literal_unwrap)]
+async fn test_filter_if_and_apply_if() {
+    let pool = create_clean_db().await;
+    let users = setup_test_users(&pool).await;
+
+    let search: Option<&str> = Some("eq1");
+    let found = User::query()
+        .filter_if(search.is_some(), || {
+            User::USERNAME.eq(search.unwrap_or_default().to_string())
+        })
+        .filter_if(false, || User::ID.eq(-1))
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to filter with filter_if");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, users[0].id);
+
+    let newest_first = true;
+    let ordered = User::query()
+        .apply_if(newest_first, |qb| qb.order_by(User::ID.desc()))
+        .apply_if(false, |qb| qb.limit(1))
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to query with apply_if");
+    assert_eq!(ordered.len(), users.len());
+    assert_eq!(ordered[0].id, users[users.len() - 1].id);
+}
+
+fn with_bio(qb: sqlorm::QB<User>) -> sqlorm::QB<User> {
+    qb.filter(User::BIO.is_not_null())
+}
+
+#[tokio::test]
+async fn test_scopes_and_cloned_queries() {
+    let pool = create_clean_db().await;
+    let users = setup_test_users(&pool).await;
+
+    let base = User::query().scope(with_bio);
+
+    let all = base
+        .clone()
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch scoped users");
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].id, users[2].id);
+
+    let none = base
+        .filter(User::ID.ne(users[2].id))
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch refined scoped users");
+    assert!(none.is_empty());
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "fixtures")]
+mod common;
+
+use common::create_clean_db;
+use common::entities::{Jar, JarExecutor, User};
+use sqlorm::fixtures::{FixtureFormat, Fixtures};
+
+#[tokio::test]
+async fn test_fixtures_with_references() {
+    let pool = create_clean_db().await;
+    let fixtures = sqlorm::fixtures!(&pool, "fixtures/users.json" => User, "fixtures/jars.json" => Jar)
+        .await
+        .expect("Failed to load fixtures");
+
+    let alice: User = fixtures.get("users", "alice").unwrap();
+    let bob: User = fixtures.get("users", "bob").unwrap();
+    assert_eq!(alice.first_name, "Alice");
+    assert_ne!(alice.id, bob.id);
+
+    let savings: Jar = fixtures.get("jars", "savings").unwrap();
+    assert_eq!(savings.owner_id, alice.id);
+    let travel: Jar = fixtures.get("jars", "travel").unwrap();
+    assert_eq!(travel.owner_id, bob.id);
+    assert_eq!(travel.description.as_deref(), Some("bob@example.com"));
+
+    let stored = Jar::query()
+        .filter(Jar::ALIAS.eq("savings".to_string()))
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to find jar");
+    assert_eq!(stored.owner_id, alice.id);
+}
+
+#[tokio::test]
+async fn test_fixtures_unknown_reference() {
+    let pool = create_clean_db().await;
+    let mut fixtures = Fixtures::new();
+    let result = fixtures
+        .load::<Jar, _>(
+            &pool,
+            "jars",
+            FixtureFormat::Json,
+            r#"{"savings": {"alias": "savings", "owner": "$users.alice"}}"#,
+        )
+        .await;
+    assert!(matches!(result, Err(sqlorm::sqlx::Error::Decode(_))));
+}
+
+#[cfg(feature = "fixtures-yaml")]
+#[tokio::test]
+async fn test_yaml_fixtures() {
+    let pool = create_clean_db().await;
+    let fixtures = sqlorm::fixtures!(&pool, "fixtures/users.yaml" => User)
+        .await
+        .expect("Failed to load fixtures");
+    let carol: User = fixtures.get("users", "carol").unwrap();
+    assert_eq!(carol.email, "carol@example.com");
+    assert!(User::find_by_email(&pool, "carol@example.com".to_string()).await.unwrap().is_some());
+}
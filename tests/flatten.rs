@@ -0,0 +1,86 @@
+mod common;
+
+use common::create_clean_db;
+use sqlorm::{StatementExecutor, table};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Address {
+    pub street: String,
+    pub city: String,
+    pub zip: Option<String>,
+}
+
+#[table(name = "customers")]
+#[derive(Debug, Clone, Default)]
+pub struct Customer {
+    #[sql(pk)]
+    pub id: i64,
+    pub name: String,
+    #[sql(flatten(street: String, city: String, zip: Option<String>))]
+    pub address: Address,
+    #[sql(flatten(street: String, city: String, zip: Option<String>), prefix = "billing_")]
+    pub billing: Address,
+}
+
+fn address(street: &str, city: &str) -> Address {
+    Address {
+        street: street.to_string(),
+        city: city.to_string(),
+        zip: None,
+    }
+}
+
+#[tokio::test]
+async fn test_flatten_roundtrip() {
+    let pool = create_clean_db().await;
+    let customer = Customer {
+        name: "Ada".to_string(),
+        address: Address {
+            zip: Some("10115".to_string()),
+            ..address("Main St 1", "Berlin")
+        },
+        billing: address("Billing Rd 2", "Hamburg"),
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to save customer");
+
+    let found = Customer::find_by_id(&pool, customer.id)
+        .await
+        .expect("Failed to find customer")
+        .expect("Customer not found");
+    assert_eq!(found.address, customer.address);
+    assert_eq!(found.billing, address("Billing Rd 2", "Hamburg"));
+}
+
+#[tokio::test]
+async fn test_flatten_columns_filter_and_update() {
+    let pool = create_clean_db().await;
+    let mut customer = Customer {
+        name: "Grace".to_string(),
+        address: address("Elm St 5", "Paris"),
+        billing: address("Elm St 5", "Paris"),
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to save customer");
+
+    customer.billing.city = "Lyon".to_string();
+    customer
+        .update()
+        .columns(Customer::BILLING_CITY)
+        .execute(&pool)
+        .await
+        .expect("Failed to update customer");
+
+    let in_lyon = Customer::query()
+        .filter(Customer::BILLING_CITY.eq("Lyon".to_string()))
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to query customers");
+    assert_eq!(in_lyon.len(), 1);
+    assert_eq!(in_lyon[0].billing.city, "Lyon");
+    assert_eq!(in_lyon[0].address.city, "Paris");
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use common::create_clean_db;
+use sqlorm::{HookContext, Hooks, StatementExecutor, table};
+
+#[table(name = "documents", hooks)]
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+    #[sql(pk)]
+    pub id: i64,
+    pub title: String,
+    pub created_by: String,
+    #[sql(skip)]
+    pub calls: Vec<&'static str>,
+}
+
+#[sqlorm::async_trait]
+impl Hooks for Document {
+    async fn before_insert(&mut self, _ctx: &mut HookContext<'_>) -> sqlx::Result<()> {
+        self.calls.push("before_insert");
+        self.title = self.title.trim().to_string();
+        if self.title.is_empty() {
+            return Err(sqlx::Error::Protocol("title is empty".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn after_insert(&mut self, _ctx: &mut HookContext<'_>) -> sqlx::Result<()> {
+        self.calls.push("after_insert");
+        Ok(())
+    }
+
+    async fn before_update(&mut self, _ctx: &mut HookContext<'_>) -> sqlx::Result<()> {
+        self.calls.push("before_update");
+        self.title = self.title.to_uppercase();
+        Ok(())
+    }
+
+    async fn after_update(&mut self, _ctx: &mut HookContext<'_>) -> sqlx::Result<()> {
+        self.calls.push("after_update");
+        Ok(())
+    }
+
+    async fn before_delete(&mut self, ctx: &mut HookContext<'_>) -> sqlx::Result<()> {
+        self.calls.push("before_delete");
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM documents WHERE created_by = 'admin'")
+                .fetch_one(&mut *ctx.conn)
+                .await?;
+        if self.created_by == "admin" && count == 1 {
+            return Err(sqlx::Error::Protocol("last admin document".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn after_delete(&mut self, _ctx: &mut HookContext<'_>) -> sqlx::Result<()> {
+        self.calls.push("after_delete");
+        Ok(())
+    }
+}
+
+fn document(title: &str, created_by: &str) -> Document {
+    Document {
+        title: title.to_string(),
+        created_by: created_by.to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_insert_hooks() {
+    let pool = create_clean_db().await;
+
+    let doc = document("  Draft  ", "alice").save(&pool).await.unwrap();
+    assert_eq!(doc.title, "Draft");
+    // the row returned by the database goes through `after_insert`
+    assert_eq!(doc.calls, vec!["after_insert"]);
+
+    let err = document("   ", "alice").save(&pool).await.unwrap_err();
+    assert!(matches!(err, sqlx::Error::Protocol(_)), "{:?}", err);
+    let count = Document::query().fetch_all(&pool).await.unwrap().len();
+    assert_eq!(count, 1);
+}
+
+#[tokio::test]
+async fn test_upsert_many_runs_insert_hooks() {
+    let pool = create_clean_db().await;
+
+    let docs = Document::upsert_many(vec![document(" first ", "alice"), document("second", "bob")])
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(docs[0].title, "first");
+    assert!(docs.iter().all(|doc| doc.calls == vec!["after_insert"]));
+
+    let err = Document::upsert_many(vec![document("third", "alice"), document(" ", "alice")])
+        .execute(&pool)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, sqlx::Error::Protocol(_)), "{:?}", err);
+    let count = Document::query().fetch_all(&pool).await.unwrap().len();
+    assert_eq!(count, 2);
+}
+
+#[tokio::test]
+async fn test_update_hooks() {
+    let pool = create_clean_db().await;
+    let doc = document("draft", "alice").save(&pool).await.unwrap();
+
+    let doc = doc.update().execute(&pool).await.unwrap();
+    assert_eq!(doc.title, "DRAFT");
+    assert_eq!(doc.calls, vec!["after_insert", "before_update", "after_update"]);
+
+    let found = Document::query()
+        .filter(Document::ID.eq(doc.id))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(found.title, "DRAFT");
+}
+
+#[tokio::test]
+async fn test_delete_hook_queries_and_aborts() {
+    let pool = create_clean_db().await;
+    let first = document("first", "admin").save(&pool).await.unwrap();
+    let second = document("second", "admin").save(&pool).await.unwrap();
+
+    let deleted = first.delete().execute(&pool).await.unwrap();
+    assert_eq!(deleted.calls, vec!["after_insert", "before_delete", "after_delete"]);
+
+    let err = second.clone().delete().execute(&pool).await.unwrap_err();
+    assert!(matches!(err, sqlx::Error::Protocol(_)), "{:?}", err);
+    let remaining = Document::query().fetch_all(&pool).await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].id, second.id);
+}
+
+#[tokio::test]
+async fn test_hooks_run_in_the_transaction() {
+    let pool = create_clean_db().await;
+    let mut tx = pool.begin().await.unwrap();
+    document("kept", "admin").save(&mut *tx).await.unwrap();
+    let doc = document("other", "admin").save(&mut *tx).await.unwrap();
+
+    // `before_delete` counts the admin documents inserted by the transaction
+    doc.delete().execute(&mut *tx).await.unwrap();
+    tx.rollback().await.unwrap();
+
+    let count = Document::query().fetch_all(&pool).await.unwrap().len();
+    assert_eq!(count, 0);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::create_clean_db;
+use sqlorm::{StatementExecutor, table};
+
+#[table(name = "documents")]
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+    #[sql(pk)]
+    pub id: i64,
+    pub title: String,
+    #[sql(immutable)]
+    pub created_by: String,
+}
+
+#[tokio::test]
+async fn test_immutable_field_is_inserted() {
+    let pool = create_clean_db().await;
+
+    let doc = Document {
+        title: "Draft".to_string(),
+        created_by: "alice".to_string(),
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to insert document");
+
+    let found = Document::query()
+        .filter(Document::CREATED_BY.eq("alice".to_string()))
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to query document");
+    assert_eq!(found.id, doc.id);
+}
+
+#[tokio::test]
+async fn test_immutable_field_is_not_updated() {
+    let pool = create_clean_db().await;
+
+    let mut doc = Document {
+        title: "Draft".to_string(),
+        created_by: "alice".to_string(),
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to insert document");
+
+    doc.title = "Final".to_string();
+    doc.created_by = "mallory".to_string();
+    let sql = doc.clone().update().debug_sql();
+    assert!(!sql.contains("created_by"), "{}", sql);
+
+    let doc = doc.save(&pool).await.expect("Failed to update document");
+
+    let reloaded = Document::query()
+        .filter(Document::ID.eq(doc.id))
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to reload document");
+    assert_eq!(reloaded.title, "Final");
+    assert_eq!(reloaded.created_by, "alice");
+}
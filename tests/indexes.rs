@@ -0,0 +1,36 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::User;
+
+#[tokio::test]
+async fn test_find_by_index() {
+    let pool = create_clean_db().await;
+
+    let first = User::test_user("first@example.com", "first")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    User::test_user("second@example.com", "second")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    User {
+        first_name: "Other".to_string(),
+        ..User::test_user("third@example.com", "third")
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to save user");
+
+    let found = User::find_by_first_name_and_last_name(&pool, "Test".to_string(), "User".to_string())
+        .await
+        .expect("Failed to find users by index");
+    assert_eq!(found.len(), 2);
+    assert!(found.iter().any(|u| u.id == first.id));
+
+    let none = User::find_by_first_name_and_last_name(&pool, "Nobody".to_string(), "User".to_string())
+        .await
+        .expect("Failed to find users by index");
+    assert!(none.is_empty());
+}
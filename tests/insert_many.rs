@@ -0,0 +1,46 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::{Donation, Jar, User};
+
+#[tokio::test]
+async fn test_insert_many() {
+    let pool = create_clean_db().await;
+
+    let users: Vec<User> = (0..5)
+        .map(|i| User::test_user(&format!("many{}@example.com", i), &format!("many{}", i)))
+        .collect();
+
+    let inserted = User::insert_many(users, &pool)
+        .await
+        .expect("Failed to insert users");
+    assert_eq!(inserted.len(), 5);
+    assert!(inserted.iter().all(|u| u.id > 0));
+    assert!(
+        inserted
+            .iter()
+            .all(|u| u.created_at.timestamp() > 0 && u.updated_at.timestamp() > 0)
+    );
+
+    let owner_id = inserted[0].id;
+    let jar = Jar::test_jar(owner_id, "manyjar")
+        .save(&pool)
+        .await
+        .expect("Failed to save jar");
+    let donations = Donation::insert_many(
+        vec![
+            Donation::test_donation(jar.id, owner_id, 1.0),
+            Donation::test_donation(jar.id, owner_id, 2.0),
+        ],
+        &pool,
+    )
+    .await
+    .expect("Failed to insert donations");
+    assert_eq!(donations.len(), 2);
+    assert_ne!(donations[0].id, donations[1].id);
+
+    let empty = User::insert_many(Vec::new(), &pool)
+        .await
+        .expect("Failed to insert no users");
+    assert!(empty.is_empty());
+}
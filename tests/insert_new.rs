@@ -0,0 +1,33 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::User;
+
+#[tokio::test]
+async fn test_insert_new_builder() {
+    let pool = create_clean_db().await;
+
+    let user = User::insert_new()
+        .email("builder@example.com")
+        .password("secret")
+        .username("builder")
+        .first_name("Built")
+        .last_name("User")
+        .bio("From the builder".to_string())
+        .save(&pool)
+        .await
+        .expect("Failed to insert user");
+
+    assert!(user.id > 0);
+    assert_eq!(user.email, "builder@example.com");
+    assert_eq!(user.first_name, "Built");
+    assert_eq!(user.bio.as_deref(), Some("From the builder"));
+    assert_eq!(user.wallpaper_url, None, "unset optional fields stay None");
+    assert_ne!(user.created_at, chrono::DateTime::<chrono::Utc>::default());
+
+    let found = User::find_by_id(&pool, user.id)
+        .await
+        .expect("Failed to find user")
+        .expect("User should exist");
+    assert_eq!(found.username, "builder");
+}
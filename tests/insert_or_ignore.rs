@@ -0,0 +1,21 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::User;
+
+#[tokio::test]
+async fn test_insert_or_ignore() {
+    let pool = create_clean_db().await;
+
+    let first = User::test_user("dedup@example.com", "dedup")
+        .insert_or_ignore(&pool)
+        .await
+        .expect("Failed to insert user");
+    assert!(first.is_some());
+
+    let duplicate = User::test_user("dedup@example.com", "dedup")
+        .insert_or_ignore(&pool)
+        .await
+        .expect("Conflicting insert should not fail");
+    assert!(duplicate.is_none());
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::{Jar, User};
+
+#[tokio::test]
+async fn test_insert_with_pk() {
+    let pool = create_clean_db().await;
+
+    let owner = User::test_user("withpk@example.com", "withpk")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+
+    let imported = Jar {
+        id: 1000,
+        ..Jar::test_jar(owner.id, "imported")
+    }
+    .insert_with_pk(&pool)
+    .await
+    .expect("Failed to insert jar with explicit id");
+    assert_eq!(imported.id, 1000);
+
+    let found = Jar::find_by_id(&pool, 1000)
+        .await
+        .unwrap()
+        .expect("Imported jar should exist");
+    assert_eq!(found.alias, "imported");
+
+    let next = Jar::test_jar(owner.id, "generated")
+        .save(&pool)
+        .await
+        .expect("Failed to save jar after import");
+    assert!(next.id > 1000, "generated ids should not collide with imported ones");
+}
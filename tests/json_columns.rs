@@ -0,0 +1,89 @@
+mod common;
+
+use common::create_clean_db;
+use serde::{Deserialize, Serialize};
+use sqlorm::table;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Preferences {
+    pub theme: String,
+    pub languages: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notifications {
+    pub email: bool,
+}
+
+#[table(name = "settings")]
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    #[sql(pk)]
+    pub id: i64,
+    #[sql(json)]
+    pub preferences: Preferences,
+    #[sql(json)]
+    pub notifications: Option<Notifications>,
+}
+
+#[tokio::test]
+async fn test_json_columns_round_trip() {
+    let pool = create_clean_db().await;
+    let preferences = Preferences {
+        theme: "dark".to_string(),
+        languages: vec!["en".to_string(), "uk".to_string()],
+    };
+
+    let settings = Settings {
+        preferences: preferences.clone(),
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to save settings");
+    assert_eq!(settings.preferences, preferences);
+    assert_eq!(settings.notifications, None);
+
+    let mut found = Settings::find_by_id(&pool, settings.id)
+        .await
+        .expect("Failed to find settings")
+        .expect("Settings not found");
+    assert_eq!(found.preferences, preferences);
+    assert_eq!(found.notifications, None);
+
+    found.notifications = Some(Notifications { email: true });
+    found.preferences.theme = "light".to_string();
+    let updated = found.save(&pool).await.expect("Failed to update settings");
+    assert_eq!(updated.notifications, Some(Notifications { email: true }));
+    assert_eq!(updated.preferences.theme, "light");
+
+    let all = Settings::query()
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to query settings");
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].preferences.theme, "light");
+
+    let nulls: i64 =
+        sqlorm::sqlx::query_scalar("SELECT COUNT(*) FROM settings WHERE notifications IS NULL")
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to count");
+    assert_eq!(nulls, 0);
+}
+
+#[tokio::test]
+async fn test_json_none_is_stored_as_null() {
+    let pool = create_clean_db().await;
+    Settings::default()
+        .save(&pool)
+        .await
+        .expect("Failed to save settings");
+
+    let nulls: i64 =
+        sqlorm::sqlx::query_scalar("SELECT COUNT(*) FROM settings WHERE notifications IS NULL")
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to count");
+    assert_eq!(nulls, 1);
+}
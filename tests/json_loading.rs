@@ -0,0 +1,70 @@
+#![cfg(feature = "postgres")]
+
+mod common;
+
+use common::create_clean_db;
+use serde::Deserialize;
+use sqlorm::{Pool, table};
+
+#[table(name = "categories")]
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Category {
+    #[sql(pk)]
+    #[sql(relation(has_many -> Category, name = "children", on = parent_id, load = json))]
+    pub id: i64,
+    pub name: String,
+    pub parent_id: i64,
+}
+
+async fn save_category(pool: &Pool, name: &str, parent_id: i64) -> Category {
+    Category {
+        name: name.to_string(),
+        parent_id,
+        ..Default::default()
+    }
+    .save(pool)
+    .await
+    .expect("Failed to save category")
+}
+
+#[tokio::test]
+async fn test_json_eager_loading() {
+    let pool = create_clean_db().await;
+    let root = save_category(&pool, "root", 0).await;
+    let empty = save_category(&pool, "empty", 0).await;
+    save_category(&pool, "first", root.id).await;
+    save_category(&pool, "second", root.id).await;
+
+    let roots = Category::query()
+        .filter(Category::PARENT_ID.eq(0))
+        .with_children_json()
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to load categories with children");
+    assert_eq!(roots.len(), 2);
+
+    let children_of = |id: i64| {
+        let mut names: Vec<String> = roots
+            .iter()
+            .find(|c| c.id == id)
+            .and_then(|c| c.children.clone())
+            .expect("children should be loaded")
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        names.sort();
+        names
+    };
+    assert_eq!(children_of(root.id), vec!["first", "second"]);
+    assert!(children_of(empty.id).is_empty());
+
+    let one = Category::query()
+        .filter(Category::ID.eq(root.id))
+        .with_children_json()
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to load category with children");
+    let children = one.children.expect("children should be loaded");
+    assert_eq!(children.len(), 2);
+    assert!(children.iter().all(|c| c.parent_id == root.id));
+}
@@ -0,0 +1,264 @@
+mod common;
+
+use common::create_clean_db;
+use sqlorm::{Pool, PivotValues, table};
+
+#[table(name = "posts")]
+#[derive(Debug, Clone, Default)]
+pub struct Post {
+    #[sql(pk)]
+    #[sql(relation(many_to_many -> Tag, name = "tags", on = id, through = "post_tags", keys = (post_id, tag_id)))]
+    pub id: i64,
+    pub title: String,
+}
+
+#[table(name = "tags")]
+#[derive(Debug, Clone, Default)]
+pub struct Tag {
+    #[sql(pk)]
+    pub id: i64,
+    pub name: String,
+}
+
+async fn tag_post(pool: &Pool, post: &Post, tag: &Tag) {
+    #[cfg(feature = "postgres")]
+    let sql = "INSERT INTO post_tags (post_id, tag_id) VALUES ($1, $2)";
+    #[cfg(feature = "sqlite")]
+    let sql = "INSERT INTO post_tags (post_id, tag_id) VALUES (?, ?)";
+
+    sqlorm::sqlx::query(sql)
+        .bind(post.id)
+        .bind(tag.id)
+        .execute(pool)
+        .await
+        .expect("Failed to link post and tag");
+}
+
+async fn save_tag(pool: &Pool, name: &str) -> Tag {
+    Tag {
+        name: name.to_string(),
+        ..Default::default()
+    }
+    .save(pool)
+    .await
+    .expect("Failed to save tag")
+}
+
+async fn save_post(pool: &Pool, title: &str) -> Post {
+    Post {
+        title: title.to_string(),
+        ..Default::default()
+    }
+    .save(pool)
+    .await
+    .expect("Failed to save post")
+}
+
+#[tokio::test]
+async fn test_many_to_many_lazy_loading() {
+    let pool = create_clean_db().await;
+
+    let post = save_post(&pool, "Hello").await;
+    let rust = save_tag(&pool, "rust").await;
+    let sql = save_tag(&pool, "sql").await;
+    save_tag(&pool, "unused").await;
+    tag_post(&pool, &post, &rust).await;
+    tag_post(&pool, &post, &sql).await;
+
+    let mut names: Vec<String> = post
+        .tags(&pool)
+        .await
+        .expect("Failed to load tags")
+        .into_iter()
+        .map(|t| t.name)
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["rust", "sql"]);
+}
+
+#[tokio::test]
+async fn test_many_to_many_eager_loading() {
+    let pool = create_clean_db().await;
+
+    let first = save_post(&pool, "First").await;
+    let second = save_post(&pool, "Second").await;
+    let untagged = save_post(&pool, "Untagged").await;
+    let shared = save_tag(&pool, "shared").await;
+    let own = save_tag(&pool, "own").await;
+    tag_post(&pool, &first, &shared).await;
+    tag_post(&pool, &first, &own).await;
+    tag_post(&pool, &second, &shared).await;
+
+    let posts = Post::query()
+        .with_tags()
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to load posts with tags");
+    assert_eq!(posts.len(), 3);
+
+    let tag_count = |id: i64| {
+        posts
+            .iter()
+            .find(|p| p.id == id)
+            .and_then(|p| p.tags.as_ref())
+            .map(|tags| tags.len())
+    };
+    assert_eq!(tag_count(first.id), Some(2));
+    assert_eq!(tag_count(second.id), Some(1));
+    assert_eq!(tag_count(untagged.id), Some(0));
+
+    let one = Post::query()
+        .filter(Post::ID.eq(second.id))
+        .with_tags()
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to load post with tags");
+    let tags = one.tags.expect("tags should be loaded");
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].name, "shared");
+}
+
+async fn tag_names(pool: &Pool, post: &Post) -> Vec<String> {
+    let mut names: Vec<String> = post
+        .tags(pool)
+        .await
+        .expect("Failed to load tags")
+        .into_iter()
+        .map(|t| t.name)
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn test_attach_and_detach() {
+    let pool = create_clean_db().await;
+
+    let post = save_post(&pool, "Hello").await;
+    let rust = save_tag(&pool, "rust").await;
+    let sql = save_tag(&pool, "sql").await;
+
+    post.attach_tag(rust.id, &pool)
+        .await
+        .expect("Failed to attach tag");
+    post.attach_tag(sql.id, &pool)
+        .await
+        .expect("Failed to attach tag");
+    post.attach_tag(rust.id, &pool)
+        .await
+        .expect("Attaching twice should be a no-op");
+    assert_eq!(tag_names(&pool, &post).await, vec!["rust", "sql"]);
+
+    assert!(post.detach_tag(rust.id, &pool).await.expect("Failed to detach tag"));
+    assert!(!post.detach_tag(rust.id, &pool).await.expect("Failed to detach tag"));
+    assert_eq!(tag_names(&pool, &post).await, vec!["sql"]);
+}
+
+#[tokio::test]
+async fn test_attach_with_pivot_values() {
+    let pool = create_clean_db().await;
+
+    let post = save_post(&pool, "Hello").await;
+    let rust = save_tag(&pool, "rust").await;
+    let sql = save_tag(&pool, "sql").await;
+
+    post.attach_tag_with(rust.id, PivotValues::new().set("pinned", true), &pool)
+        .await
+        .expect("Failed to attach tag");
+    post.attach_tag(sql.id, &pool)
+        .await
+        .expect("Failed to attach tag");
+
+    #[cfg(feature = "postgres")]
+    let sql_text = "SELECT tag_id FROM post_tags WHERE post_id = $1 AND pinned";
+    #[cfg(feature = "sqlite")]
+    let sql_text = "SELECT tag_id FROM post_tags WHERE post_id = ? AND pinned";
+    let pinned: Vec<i64> = sqlorm::sqlx::query_scalar(sql_text)
+        .bind(post.id)
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to read pivot rows");
+    assert_eq!(pinned, vec![rust.id]);
+}
+
+#[tokio::test]
+async fn test_sync_tags() {
+    let pool = create_clean_db().await;
+
+    let post = save_post(&pool, "Hello").await;
+    let other = save_post(&pool, "Other").await;
+    let rust = save_tag(&pool, "rust").await;
+    let sql = save_tag(&pool, "sql").await;
+    let orm = save_tag(&pool, "orm").await;
+    tag_post(&pool, &post, &rust).await;
+    tag_post(&pool, &post, &sql).await;
+    tag_post(&pool, &other, &rust).await;
+
+    post.sync_tags(vec![sql.id, orm.id], &pool)
+        .await
+        .expect("Failed to sync tags");
+    assert_eq!(tag_names(&pool, &post).await, vec!["orm", "sql"]);
+    assert_eq!(tag_names(&pool, &other).await, vec!["rust"]);
+
+    post.sync_tags(Vec::<i64>::new(), &pool)
+        .await
+        .expect("Failed to sync tags");
+    assert!(tag_names(&pool, &post).await.is_empty());
+
+    let mut tx = pool.begin().await.expect("Failed to begin transaction");
+    post.sync_tags(vec![rust.id], &mut *tx)
+        .await
+        .expect("Failed to sync tags in transaction");
+    tx.rollback().await.expect("Failed to roll back");
+    assert!(tag_names(&pool, &post).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_sync_tags_above_bind_limit() {
+    let pool = create_clean_db().await;
+
+    let post = save_post(&pool, "Hello").await;
+    let count = sqlorm::MAX_BIND_PARAMS + 10;
+    let tags: Vec<Tag> = (0..count)
+        .map(|i| Tag {
+            name: format!("tag{i}"),
+            ..Default::default()
+        })
+        .collect();
+    let tags = Tag::insert_many(tags, &pool)
+        .await
+        .expect("Failed to save tags");
+    let ids: Vec<i64> = tags.iter().map(|tag| tag.id).collect();
+
+    post.sync_tags(ids.clone(), &pool)
+        .await
+        .expect("Failed to sync tags");
+    post.sync_tags(ids[1..].to_vec(), &pool)
+        .await
+        .expect("Failed to sync tags again");
+
+    let linked: Vec<(i64,)> = sqlorm::sqlx::query_as("SELECT tag_id FROM post_tags")
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to read pivot rows");
+    assert_eq!(linked.len(), count - 1);
+    assert!(!linked.contains(&(ids[0],)));
+
+    let mut conn = pool.acquire().await.expect("Failed to acquire connection");
+    let links = sqlorm::fetch_pivot(
+        &mut conn,
+        "post_tags",
+        "tag_id",
+        "post_id",
+        ids.clone(),
+        Post::ID,
+    )
+    .await
+    .expect("Failed to fetch pivot rows");
+    assert_eq!(links.len(), count - 1);
+
+    let removed = sqlorm::detach_pivot(&mut conn, "post_tags", "post_id", "tag_id", post.id, ids)
+        .await
+        .expect("Failed to detach tags");
+    assert_eq!(removed as usize, count - 1);
+}
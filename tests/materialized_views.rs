@@ -0,0 +1,62 @@
+#![cfg(feature = "postgres")]
+mod common;
+
+use common::create_clean_db;
+use common::entities::{Jar, User, UserExecutor};
+use sqlorm::table;
+
+#[table(name = "owner_jar_totals", materialized_view)]
+#[derive(Debug, Clone, Default)]
+pub struct OwnerJarTotal {
+    #[sql(pk)]
+    #[sql(relation(belongs_to -> User, name = "owner", on = id))]
+    pub owner_id: i64,
+    pub jar_count: i64,
+    pub total_amount: f64,
+}
+
+async fn create_jars(pool: &sqlorm::Pool) -> User {
+    let owner = User::test_user("owner@example.com", "owner")
+        .save(pool)
+        .await
+        .unwrap();
+    for (alias, amount) in [("first", 10.0), ("second", 30.0)] {
+        let mut jar = Jar::test_jar(owner.id, alias);
+        jar.total_amount = amount;
+        jar.save(pool).await.unwrap();
+    }
+    owner
+}
+
+#[tokio::test]
+async fn test_refresh_materialized_view() {
+    let pool = create_clean_db().await;
+    let owner = create_jars(&pool).await;
+
+    let totals = OwnerJarTotal::query().fetch_all(&pool).await.unwrap();
+    assert!(totals.is_empty(), "view is only populated on refresh");
+
+    OwnerJarTotal::refresh(&pool, false).await.unwrap();
+    let totals = OwnerJarTotal::query()
+        .filter(OwnerJarTotal::OWNER_ID.eq(owner.id))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(totals.jar_count, 2);
+    assert_eq!(totals.total_amount, 40.0);
+}
+
+#[tokio::test]
+async fn test_refresh_materialized_view_concurrently() {
+    let pool = create_clean_db().await;
+    let owner = create_jars(&pool).await;
+
+    OwnerJarTotal::refresh(&pool, true).await.unwrap();
+
+    let totals = OwnerJarTotal::query()
+        .filter(OwnerJarTotal::OWNER_ID.eq(owner.id))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(totals.jar_count, 2);
+}
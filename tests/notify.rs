@@ -0,0 +1,50 @@
+#![cfg(feature = "postgres")]
+mod common;
+
+use std::time::Duration;
+
+use common::create_clean_db;
+use common::entities::User;
+use futures_util::StreamExt;
+use sqlorm::notify::{self, Change, ChangeOp, Notify};
+
+#[test]
+fn test_notify_channel() {
+    assert_eq!(User::notify_channel(), "sqlorm_user");
+    assert_eq!(
+        Change::parse("UPDATE:42"),
+        Some(Change {
+            op: ChangeOp::Update,
+            pk: "42".to_string()
+        })
+    );
+    assert_eq!(Change::parse("unrelated"), None);
+}
+
+#[tokio::test]
+async fn test_listen_receives_changes() {
+    let pool = create_clean_db().await;
+    notify::install_trigger::<User>(&pool)
+        .await
+        .expect("Failed to install trigger");
+    let mut changes = notify::listen::<User>(&pool)
+        .await
+        .expect("Failed to listen");
+
+    let mut user = User::test_user("notify@example.com", "notify")
+        .save(&pool)
+        .await
+        .expect("Failed to insert user");
+    user.bio = Some("changed".to_string());
+    let user = user.save(&pool).await.expect("Failed to update user");
+
+    for op in [ChangeOp::Insert, ChangeOp::Update] {
+        let change = tokio::time::timeout(Duration::from_secs(5), changes.next())
+            .await
+            .expect("Timed out waiting for notification")
+            .expect("Stream ended")
+            .expect("Failed to receive notification");
+        assert_eq!(change.op, op);
+        assert_eq!(change.pk, user.id.to_string());
+    }
+}
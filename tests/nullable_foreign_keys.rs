@@ -0,0 +1,147 @@
+mod common;
+
+use common::create_clean_db;
+use sqlorm::{Pool, table};
+
+#[table(name = "departments")]
+#[derive(Debug, Clone, Default)]
+pub struct Department {
+    #[sql(pk)]
+    #[sql(relation(has_many -> Employee, name = "employees", on = department_id, on_remove = nullify))]
+    pub id: i64,
+    pub name: String,
+}
+
+#[table(name = "employees")]
+#[derive(Debug, Clone, Default)]
+pub struct Employee {
+    #[sql(pk)]
+    pub id: i64,
+    pub name: String,
+    #[sql(relation(belongs_to -> Department, name = "department", on = id))]
+    pub department_id: Option<i64>,
+}
+
+async fn save_department(pool: &Pool, name: &str) -> Department {
+    Department {
+        name: name.to_string(),
+        ..Default::default()
+    }
+    .save(pool)
+    .await
+    .expect("Failed to save department")
+}
+
+async fn save_employee(pool: &Pool, name: &str, department_id: Option<i64>) -> Employee {
+    Employee {
+        name: name.to_string(),
+        department_id,
+        ..Default::default()
+    }
+    .save(pool)
+    .await
+    .expect("Failed to save employee")
+}
+
+#[tokio::test]
+async fn test_nullable_foreign_key_lazy_loading() {
+    let pool = create_clean_db().await;
+    let sales = save_department(&pool, "sales").await;
+    let assigned = save_employee(&pool, "assigned", Some(sales.id)).await;
+    let unassigned = save_employee(&pool, "unassigned", None).await;
+
+    let department = assigned
+        .department(&pool)
+        .await
+        .expect("Failed to load department")
+        .expect("Department should exist");
+    assert_eq!(department.id, sales.id);
+
+    let none = unassigned
+        .department(&pool)
+        .await
+        .expect("Failed to load department");
+    assert!(none.is_none());
+
+    let employees = sales
+        .employees(&pool)
+        .await
+        .expect("Failed to load employees");
+    assert_eq!(employees.len(), 1);
+    assert_eq!(employees[0].id, assigned.id);
+}
+
+#[tokio::test]
+async fn test_nullable_foreign_key_eager_loading() {
+    let pool = create_clean_db().await;
+    let sales = save_department(&pool, "sales").await;
+    let empty = save_department(&pool, "empty").await;
+    let assigned = save_employee(&pool, "assigned", Some(sales.id)).await;
+    let unassigned = save_employee(&pool, "unassigned", None).await;
+
+    let employees = Employee::query()
+        .with_department()
+        .order_by(Employee::ID.asc())
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to load employees with departments");
+    assert_eq!(employees.len(), 2);
+    assert_eq!(employees[0].id, assigned.id);
+    assert_eq!(
+        employees[0].department.as_ref().map(|d| d.id),
+        Some(sales.id)
+    );
+    assert_eq!(employees[1].id, unassigned.id);
+    assert!(employees[1].department.is_none());
+
+    let inner = Employee::query()
+        .with_department_inner()
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to load assigned employees");
+    assert_eq!(inner.len(), 1);
+
+    let departments = Department::query()
+        .with_employees()
+        .order_by(Department::ID.asc())
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to load departments with employees");
+    assert_eq!(departments[0].employees.as_ref().map(Vec::len), Some(1));
+    assert_eq!(departments[1].id, empty.id);
+    assert_eq!(departments[1].employees.as_ref().map(Vec::len), Some(0));
+
+    let one = Department::query()
+        .filter(Department::ID.eq(sales.id))
+        .with_employees()
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to load department with employees");
+    assert_eq!(one.employees.map(|e| e.len()), Some(1));
+}
+
+#[tokio::test]
+async fn test_nullable_foreign_key_add_and_remove() {
+    let pool = create_clean_db().await;
+    let sales = save_department(&pool, "sales").await;
+    let employee = save_employee(&pool, "new", None).await;
+
+    let employee = sales
+        .add_employee(employee, &pool)
+        .await
+        .expect("Failed to add employee");
+    assert_eq!(employee.department_id, Some(sales.id));
+
+    let employee = sales
+        .remove_employee(employee, &pool)
+        .await
+        .expect("Failed to remove employee");
+    assert_eq!(employee.department_id, None);
+    assert!(
+        sales
+            .employees(&pool)
+            .await
+            .expect("Failed to load employees")
+            .is_empty()
+    );
+}
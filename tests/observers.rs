@@ -0,0 +1,157 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use common::create_clean_db;
+use sqlorm::{ChangeEvent, NestedTransaction, StatementExecutor, table};
+
+#[table(name = "notes", observable)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Note {
+    #[sql(pk)]
+    pub id: i64,
+    pub body: String,
+    pub is_deleted: bool,
+}
+
+fn note(body: &str) -> Note {
+    Note {
+        body: body.to_string(),
+        ..Default::default()
+    }
+}
+
+fn describe(event: ChangeEvent<Note>) -> String {
+    match event {
+        ChangeEvent::Inserted(note) => format!("inserted {}", note.body),
+        ChangeEvent::Updated(note) => format!("updated {}", note.body),
+        ChangeEvent::Deleted(note) => format!("deleted {}", note.body),
+    }
+}
+
+// observers are global, so everything runs in a single test
+#[tokio::test]
+async fn test_observers_are_notified() {
+    let pool = create_clean_db().await;
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let committed = Arc::new(Mutex::new(Vec::new()));
+    {
+        let events = events.clone();
+        sqlorm::observe::<Note>(move |event| events.lock().unwrap().push(describe(event)));
+        let committed = committed.clone();
+        sqlorm::observe_after_commit::<Note>(move |event| {
+            committed.lock().unwrap().push(describe(event))
+        });
+    }
+
+    let mut first = note("first").save(&pool).await.unwrap();
+    first.body = "edited".to_string();
+    let first = first.save(&pool).await.unwrap();
+    first.delete().execute(&pool).await.unwrap();
+    let expected = vec!["inserted first", "updated edited", "deleted edited"];
+    assert_eq!(*events.lock().unwrap(), expected);
+    // writes outside of `sqlorm::transaction` are committed right away
+    assert_eq!(*committed.lock().unwrap(), expected);
+
+    events.lock().unwrap().clear();
+    committed.lock().unwrap().clear();
+    let result: Result<(), sqlx::Error> = sqlorm::transaction(&pool, async |tx| {
+        note("rolled back").save(&mut **tx).await?;
+        Err(sqlx::Error::RowNotFound)
+    })
+    .await;
+    assert!(result.is_err());
+    assert_eq!(*events.lock().unwrap(), vec!["inserted rolled back"]);
+    assert!(committed.lock().unwrap().is_empty());
+
+    let committed_in_tx = committed.clone();
+    sqlorm::transaction(&pool, async |tx| {
+        note("kept").save(&mut **tx).await?;
+        assert!(committed_in_tx.lock().unwrap().is_empty());
+        Ok::<_, sqlx::Error>(())
+    })
+    .await
+    .unwrap();
+    assert_eq!(*committed.lock().unwrap(), vec!["inserted kept"]);
+
+    // statements returning only some columns notify with the whole entity too
+    committed.lock().unwrap().clear();
+    let (id,) = note("returned")
+        .insert_returning((Note::ID,), &pool)
+        .await
+        .unwrap();
+    let mut returned = Note::query()
+        .filter(Note::ID.eq(id))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    returned.body = "returned edited".to_string();
+    let (body,) = returned
+        .clone()
+        .update()
+        .execute_returning((Note::BODY,), &pool)
+        .await
+        .unwrap();
+    assert_eq!(body, "returned edited");
+    returned
+        .delete()
+        .execute_returning((Note::ID,), &pool)
+        .await
+        .unwrap();
+    let expected = vec![
+        "inserted returned",
+        "updated returned edited",
+        "deleted returned edited",
+    ];
+    assert_eq!(*committed.lock().unwrap(), expected);
+
+    committed.lock().unwrap().clear();
+    Note::upsert_many(vec![note("bulk first"), note("bulk second")])
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        *committed.lock().unwrap(),
+        vec!["inserted bulk first", "inserted bulk second"]
+    );
+
+    // save_all notifies once the whole batch is committed
+    committed.lock().unwrap().clear();
+    sqlx::query("CREATE UNIQUE INDEX notes_body ON notes (body)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let result = Note::save_all(vec![note("duplicate"), note("duplicate")], &pool).await;
+    assert!(result.is_err());
+    assert!(committed.lock().unwrap().is_empty());
+    sqlx::query("DROP INDEX notes_body")
+        .execute(&pool)
+        .await
+        .unwrap();
+    Note::save_all(vec![note("batch")], &pool).await.unwrap();
+    assert_eq!(*committed.lock().unwrap(), vec!["inserted batch"]);
+
+    // a savepoint rolled back inside the transaction drops its own notifications
+    committed.lock().unwrap().clear();
+    let committed_in_tx = committed.clone();
+    sqlorm::transaction(&pool, async |tx| {
+        let nested: Result<(), sqlx::Error> = tx
+            .nested(async |inner| {
+                note("rolled back savepoint").save(&mut **inner).await?;
+                Err(sqlx::Error::RowNotFound)
+            })
+            .await;
+        assert!(nested.is_err());
+        tx.nested(async |inner| note("released savepoint").save(&mut **inner).await)
+            .await?;
+        assert!(committed_in_tx.lock().unwrap().is_empty());
+        Ok::<_, sqlx::Error>(())
+    })
+    .await
+    .unwrap();
+    assert_eq!(*committed.lock().unwrap(), vec!["inserted released savepoint"]);
+
+    sqlorm::observer::clear::<Note>();
+    note("unobserved").save(&pool).await.unwrap();
+    assert_eq!(committed.lock().unwrap().len(), 1);
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::create_clean_db;
+use sqlorm::{StaleObject, table};
+
+#[derive(Debug, Clone, Default)]
+#[table(name = "versioned_entities")]
+pub struct VersionedEntity {
+    #[sql(pk)]
+    pub id: i64,
+    pub name: String,
+    #[sql(version)]
+    pub version: i64,
+}
+
+#[tokio::test]
+async fn test_version_is_incremented_on_update() {
+    let pool = create_clean_db().await;
+
+    let entity = VersionedEntity {
+        name: "first".to_string(),
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to insert entity");
+    assert_eq!(entity.version, 0);
+
+    let mut entity = entity;
+    entity.name = "second".to_string();
+    let entity = entity.save(&pool).await.expect("Failed to update entity");
+    assert_eq!(entity.version, 1);
+}
+
+#[tokio::test]
+async fn test_stale_update_is_rejected() {
+    let pool = create_clean_db().await;
+
+    let entity = VersionedEntity {
+        name: "original".to_string(),
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to insert entity");
+
+    let mut first = entity.clone();
+    first.name = "first writer".to_string();
+    first.save(&pool).await.expect("Failed to update entity");
+
+    let mut second = entity;
+    second.name = "second writer".to_string();
+    let err = second
+        .save(&pool)
+        .await
+        .expect_err("Stale update must fail");
+    assert!(StaleObject::is(&err));
+}
@@ -1,7 +1,7 @@
 mod common;
 
 use common::create_clean_db;
-use common::entities::User;
+use common::entities::{Jar, JarExecutor, JarRelations, User};
 use sqlorm::GenericExecutor;
 
 #[tokio::test]
@@ -96,3 +96,91 @@ async fn test_order_by_multiple_columns() {
         results
     );
 }
+
+#[tokio::test]
+async fn test_order_by_nulls_first_and_last() {
+    let pool = create_clean_db().await;
+
+    let mut with_bio = User::test_user("nulls1@example.com", "nulls1");
+    with_bio.bio = Some("bio".to_string());
+    with_bio.save(&pool).await.expect("Failed to save user");
+    User::test_user("nulls2@example.com", "nulls2")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+
+    let first: Vec<(Option<String>,)> = User::query()
+        .select((User::BIO,))
+        .order_by(User::BIO.asc().nulls_first())
+        .fetch_all_as(&pool)
+        .await
+        .expect("Failed to order with nulls first");
+    assert_eq!(first, vec![(None,), (Some("bio".to_string()),)]);
+
+    let last: Vec<(Option<String>,)> = User::query()
+        .select((User::BIO,))
+        .order_by(User::BIO.desc().nulls_last())
+        .fetch_all_as(&pool)
+        .await
+        .expect("Failed to order with nulls last");
+    assert_eq!(last, vec![(Some("bio".to_string()),), (None,)]);
+}
+
+#[tokio::test]
+async fn test_order_by_joined_relation_column() {
+    let pool = create_clean_db().await;
+
+    let zed = User::test_user("zed@example.com", "zed")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    let amy = User::test_user("amy@example.com", "amy")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+
+    Jar::test_jar(zed.id, "zedjar")
+        .save(&pool)
+        .await
+        .expect("Failed to save jar");
+    Jar::test_jar(amy.id, "amyjar")
+        .save(&pool)
+        .await
+        .expect("Failed to save jar");
+
+    let jars = Jar::query()
+        .with_owner()
+        .order_by(User::USERNAME.asc())
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch jars ordered by owner");
+
+    let owners: Vec<String> = jars
+        .into_iter()
+        .map(|jar| jar.owner.expect("Owner should be loaded").username)
+        .collect();
+    assert_eq!(owners, vec!["amy", "zed"]);
+}
+
+#[tokio::test]
+async fn test_order_random() {
+    let pool = create_clean_db().await;
+
+    for i in 0..3 {
+        User::test_user(&format!("random{}@example.com", i), &format!("random{}", i))
+            .save(&pool)
+            .await
+            .expect("Failed to save user");
+    }
+
+    let results: Vec<(String,)> = User::query()
+        .select((User::USERNAME,))
+        .order_random()
+        .fetch_all_as(&pool)
+        .await
+        .expect("Failed to select in random order");
+
+    let mut usernames: Vec<String> = results.into_iter().map(|(name,)| name).collect();
+    usernames.sort();
+    assert_eq!(usernames, vec!["random0", "random1", "random2"]);
+}
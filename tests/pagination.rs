@@ -0,0 +1,65 @@
+use common::entities::UserExecutor;
+mod common;
+
+use common::create_clean_db;
+use common::entities::User;
+
+#[tokio::test]
+async fn test_user_paginate() {
+    let pool = create_clean_db().await;
+
+    for i in 0..7 {
+        let email = format!("paginate{i}@example.com");
+        let username = format!("paginate{i}");
+        User::test_user(&email, &username)
+            .save(&pool)
+            .await
+            .expect("Failed to save user");
+    }
+
+    let page = User::query()
+        .order_by(User::ID.asc())
+        .paginate(2, 3, &pool)
+        .await
+        .expect("Failed to paginate users");
+
+    assert_eq!(page.total, 7);
+    assert_eq!(page.page, 2);
+    assert_eq!(page.per_page, 3);
+    assert_eq!(page.total_pages, 3);
+    assert_eq!(page.items.len(), 3);
+    assert_eq!(page.items[0].email, "paginate3@example.com");
+
+    let last = User::query()
+        .order_by(User::ID.asc())
+        .paginate(3, 3, &pool)
+        .await
+        .expect("Failed to paginate users");
+
+    assert_eq!(last.items.len(), 1);
+    assert_eq!(last.items[0].email, "paginate6@example.com");
+}
+
+#[tokio::test]
+async fn test_user_paginate_with_filter() {
+    let pool = create_clean_db().await;
+
+    for i in 0..4 {
+        let email = format!("pfilter{i}@example.com");
+        let username = format!("pfilter{i}");
+        User::test_user(&email, &username)
+            .save(&pool)
+            .await
+            .expect("Failed to save user");
+    }
+
+    let page = User::query()
+        .filter(User::USERNAME.ne("pfilter0".to_string()))
+        .paginate(1, 10, &pool)
+        .await
+        .expect("Failed to paginate users");
+
+    assert_eq!(page.total, 3);
+    assert_eq!(page.total_pages, 1);
+    assert_eq!(page.items.len(), 3);
+}
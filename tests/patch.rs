@@ -0,0 +1,62 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::{User, UserPatch};
+
+#[tokio::test]
+async fn test_apply_patch() {
+    let pool = create_clean_db().await;
+    let mut user = User::test_user("patch@example.com", "patch")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    user.bio = Some("hello".to_string());
+
+    user.apply_patch(UserPatch {
+        first_name: Some("Patched".to_string()),
+        bio: Some(None),
+        ..Default::default()
+    });
+    assert_eq!(user.first_name, "Patched");
+    assert_eq!(user.last_name, "User", "fields not in the patch are kept");
+    assert_eq!(user.bio, None);
+}
+
+#[tokio::test]
+async fn test_update_where_set_patch() {
+    let pool = create_clean_db().await;
+    let target = User::test_user("target@example.com", "target")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    let other = User::test_user("other@example.com", "other")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+
+    let affected = User::update_where()
+        .set_patch(UserPatch {
+            last_name: Some("Patched".to_string()),
+            bio: Some(Some("patched bio".to_string())),
+            ..Default::default()
+        })
+        .filter(User::ID.eq(target.id))
+        .execute(&pool)
+        .await
+        .expect("Failed to patch users");
+    assert_eq!(affected, 1);
+
+    let target = User::find_by_id(&pool, target.id)
+        .await
+        .unwrap()
+        .expect("User not found");
+    assert_eq!(target.last_name, "Patched");
+    assert_eq!(target.bio.as_deref(), Some("patched bio"));
+    assert_eq!(target.first_name, "Test");
+
+    let other = User::find_by_id(&pool, other.id)
+        .await
+        .unwrap()
+        .expect("User not found");
+    assert_eq!(other.last_name, "User");
+}
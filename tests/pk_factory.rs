@@ -0,0 +1,72 @@
+mod common;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use common::create_clean_db;
+use sqlorm::table;
+
+static NEXT_CODE: AtomicU64 = AtomicU64::new(1);
+
+fn next_code() -> String {
+    format!("link-{}", NEXT_CODE.fetch_add(1, Ordering::Relaxed))
+}
+
+#[table(name = "short_links")]
+#[derive(Debug, Clone, Default)]
+pub struct ShortLink {
+    #[sql(pk, factory = next_code())]
+    pub id: String,
+    pub url: String,
+}
+
+#[tokio::test]
+async fn test_pk_factory_generates_ids() {
+    let pool = create_clean_db().await;
+
+    let link = ShortLink {
+        url: "https://example.com".to_string(),
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to save link");
+    assert!(link.id.starts_with("link-"), "unexpected id {}", link.id);
+
+    let found = ShortLink::find_by_id(&pool, link.id.clone())
+        .await
+        .expect("Failed to find link")
+        .expect("Link not found");
+    assert_eq!(found.url, "https://example.com");
+
+    let mut renamed = found;
+    renamed.url = "https://example.org".to_string();
+    let renamed = renamed.save(&pool).await.expect("Failed to update link");
+    assert_eq!(renamed.id, link.id, "Updates keep the id");
+
+    let explicit = ShortLink {
+        id: "custom".to_string(),
+        url: "https://example.net".to_string(),
+    }
+    .insert(&pool)
+    .await
+    .expect("Failed to insert link");
+    assert_eq!(explicit.id, "custom", "Explicit ids are kept");
+
+    let many = ShortLink::insert_many(
+        vec![
+            ShortLink {
+                url: "https://a.example".to_string(),
+                ..Default::default()
+            },
+            ShortLink {
+                url: "https://b.example".to_string(),
+                ..Default::default()
+            },
+        ],
+        &pool,
+    )
+    .await
+    .expect("Failed to insert links");
+    assert_eq!(many.len(), 2);
+    assert_ne!(many[0].id, many[1].id);
+}
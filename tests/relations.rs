@@ -1,9 +1,10 @@
-use common::entities::{JarExecutor, UserExecutor};
+use common::entities::{DonationExecutor, JarExecutor, UserExecutor};
 mod common;
-use common::entities::{JarRelations, UserRelations};
+use common::entities::{DonationRelations, JarRelations, UserRelations};
 
 use common::create_clean_db;
 use common::entities::{Donation, Jar, User};
+use uuid::Uuid;
 
 async fn setup_test_data(pool: &sqlorm::Pool) -> (User, User, Jar, Jar, Donation, Donation) {
     let user1 = User::test_user("owner1@example.com", "owner1")
@@ -183,3 +184,382 @@ async fn test_has_many_empty_relations() {
     let jars = user_with_jars.jars.expect("Jars should be loaded");
     assert!(jars.is_empty());
 }
+
+#[tokio::test]
+async fn test_relations_loading_in_transaction_and_connection() {
+    let pool = create_clean_db().await;
+    let (user1, _user2, jar1, _jar2, _donation1, _donation2) = setup_test_data(&pool).await;
+
+    let mut tx = pool.begin().await.expect("Failed to begin transaction");
+    let user = User::query()
+        .filter(User::ID.eq(user1.id))
+        .with_jars()
+        .fetch_one(&mut *tx)
+        .await
+        .expect("Failed to fetch user with jars in transaction");
+    tx.commit().await.expect("Failed to commit transaction");
+    assert_eq!(user.jars.expect("Jars should be loaded").len(), 1);
+
+    let mut conn = pool.acquire().await.expect("Failed to acquire connection");
+    let jars = Jar::query()
+        .filter(Jar::ID.eq(jar1.id))
+        .with_owner()
+        .with_donations()
+        .fetch_all(&mut *conn)
+        .await
+        .expect("Failed to fetch jars on connection");
+    assert_eq!(jars.len(), 1);
+    assert_eq!(jars[0].owner.as_ref().expect("Owner should be loaded").id, user1.id);
+    assert_eq!(jars[0].donations.as_ref().expect("Donations should be loaded").len(), 1);
+}
+
+#[tokio::test]
+async fn test_has_many_through_lazy_loading() {
+    let pool = create_clean_db().await;
+    let (user1, user2, jar1, _jar2, donation1, _donation2) = setup_test_data(&pool).await;
+
+    let mut jar3 = Jar::test_jar(user1.id, "jar3");
+    jar3.title = "User1's Second Jar".to_string();
+    let jar3 = jar3.save(&pool).await.expect("Failed to save jar3");
+    let donation3 = Donation::test_donation(jar3.id, user2.id, 10.0)
+        .save(&pool)
+        .await
+        .expect("Failed to save donation3");
+
+    let received = user1
+        .received_donations(&pool)
+        .await
+        .expect("Failed to load received donations");
+
+    let mut ids: Vec<Uuid> = received.iter().map(|d| d.id).collect();
+    ids.sort();
+    let mut expected = vec![donation1.id, donation3.id];
+    expected.sort();
+    assert_eq!(ids, expected);
+    assert!(received.iter().all(|d| d.jar_id == jar1.id || d.jar_id == jar3.id));
+}
+
+#[tokio::test]
+async fn test_has_many_through_eager_loading() {
+    let pool = create_clean_db().await;
+    let (user1, user2, _jar1, _jar2, donation1, donation2) = setup_test_data(&pool).await;
+    let user3 = User::test_user("owner3@example.com", "owner3")
+        .save(&pool)
+        .await
+        .expect("Failed to save user3");
+
+    let users = User::query()
+        .with_received_donations()
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch users with received donations");
+    assert_eq!(users.len(), 3);
+
+    for user in users {
+        let received = user
+            .received_donations
+            .expect("Received donations should be loaded");
+        let ids: Vec<Uuid> = received.iter().map(|d| d.id).collect();
+        if user.id == user1.id {
+            assert_eq!(ids, vec![donation1.id]);
+        } else if user.id == user2.id {
+            assert_eq!(ids, vec![donation2.id]);
+        } else {
+            assert_eq!(user.id, user3.id);
+            assert!(ids.is_empty());
+        }
+    }
+
+    let single = User::query()
+        .filter(User::ID.eq(user2.id))
+        .with_received_donations()
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch user2 with received donations");
+    let received = single.received_donations.expect("Should be loaded");
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].id, donation2.id);
+}
+
+#[tokio::test]
+async fn test_with_all() {
+    let pool = create_clean_db().await;
+    let (user1, user2, jar1, _jar2, donation1, _donation2) = setup_test_data(&pool).await;
+
+    let jar = Jar::query()
+        .filter(Jar::ID.eq(jar1.id))
+        .with_all()
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to load jar with all relations");
+    assert_eq!(jar.owner.expect("owner should be loaded").id, user1.id);
+    let donations = jar.donations.expect("donations should be loaded");
+    assert_eq!(donations.len(), 1);
+    assert_eq!(donations[0].id, donation1.id);
+
+    let users = User::query()
+        .with_all()
+        .order_by(User::ID.asc())
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to load users with all relations");
+    assert_eq!(users.len(), 2);
+    assert_eq!(users[0].jars.as_ref().map(Vec::len), Some(1));
+    assert_eq!(users[0].payed_donations.as_ref().map(Vec::len), Some(1));
+    assert_eq!(users[0].received_donations.as_ref().map(Vec::len), Some(1));
+    assert_eq!(users[1].id, user2.id);
+    assert_eq!(users[1].payed_donations.as_ref().map(Vec::len), Some(1));
+}
+
+#[tokio::test]
+async fn test_relation_counts() {
+    let pool = create_clean_db().await;
+    let (user1, user2, _jar1, _jar2, _donation1, _donation2) = setup_test_data(&pool).await;
+    let jar3 = Jar::test_jar(user1.id, "jar3")
+        .save(&pool)
+        .await
+        .expect("Failed to save jar3");
+    Donation::test_donation(jar3.id, user2.id, 10.0)
+        .save(&pool)
+        .await
+        .expect("Failed to save donation3");
+
+    let users = User::query()
+        .with_jars_count()
+        .with_received_donations_count()
+        .order_by(User::ID.asc())
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch users with counts");
+
+    assert_eq!(users.len(), 2);
+    assert_eq!(users[0].id, user1.id);
+    assert_eq!(users[0].jars_count, Some(2));
+    assert_eq!(users[0].received_donations_count, Some(2));
+    assert!(users[0].jars.is_none(), "Children should not be loaded");
+    assert_eq!(users[1].jars_count, Some(1));
+    assert_eq!(users[1].received_donations_count, Some(1));
+    assert_eq!(users[1].payed_donations_count, None);
+
+    let user = User::query()
+        .filter(User::ID.eq(user2.id))
+        .with_payed_donations_count()
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch user with count");
+    assert_eq!(user.payed_donations_count, Some(2));
+}
+
+#[tokio::test]
+async fn test_filtered_eager_loading() {
+    let pool = create_clean_db().await;
+    let (user1, _user2, jar1, _jar2, _donation1, _donation2) = setup_test_data(&pool).await;
+    let mut goal_jar = Jar::test_jar(user1.id, "goal_jar");
+    goal_jar.goal = Some(100.0);
+    let goal_jar = goal_jar.save(&pool).await.expect("Failed to save goal jar");
+
+    let user = User::query()
+        .filter(User::ID.eq(user1.id))
+        .with_jars_where(Jar::GOAL.is_not_null())
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch user with filtered jars");
+    let jars = user.jars.expect("Jars should be loaded");
+    assert_eq!(jars.len(), 1);
+    assert_eq!(jars[0].id, goal_jar.id);
+
+    let users = User::query()
+        .with_jars_where(Jar::GOAL.is_null())
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch users with filtered jars");
+    assert_eq!(users.len(), 2, "Parents must not be filtered");
+    let user1_jars = users
+        .into_iter()
+        .find(|u| u.id == user1.id)
+        .and_then(|u| u.jars)
+        .expect("Jars should be loaded");
+    assert_eq!(user1_jars.len(), 1);
+    assert_eq!(user1_jars[0].id, jar1.id);
+}
+
+#[tokio::test]
+async fn test_ordered_limited_eager_loading() {
+    let pool = create_clean_db().await;
+    let (_user1, user2, jar1, jar2, _donation1, _donation2) = setup_test_data(&pool).await;
+    for amount in [5.0, 75.0, 40.0] {
+        Donation::test_donation(jar1.id, user2.id, amount)
+            .save(&pool)
+            .await
+            .expect("Failed to save donation");
+    }
+
+    let jars = Jar::query()
+        .with_donations_ordered(Donation::AMOUNT.desc(), Some(2))
+        .order_by(Jar::ID.asc())
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch jars with ordered donations");
+    assert_eq!(jars.len(), 2);
+
+    let amounts: Vec<f64> = jars[0]
+        .donations
+        .as_ref()
+        .expect("Donations should be loaded")
+        .iter()
+        .map(|d| d.amount)
+        .collect();
+    assert_eq!(jars[0].id, jar1.id);
+    assert_eq!(amounts, vec![75.0, 40.0]);
+
+    let jar2_donations = jars[1].donations.as_ref().expect("Donations should be loaded");
+    assert_eq!(jars[1].id, jar2.id);
+    assert_eq!(jar2_donations.len(), 1);
+
+    let jar = Jar::query()
+        .filter(Jar::ID.eq(jar1.id))
+        .with_donations_ordered(Donation::AMOUNT.asc(), None)
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch jar with ordered donations");
+    let amounts: Vec<f64> = jar
+        .donations
+        .expect("Donations should be loaded")
+        .iter()
+        .map(|d| d.amount)
+        .collect();
+    assert_eq!(amounts, vec![5.0, 25.0, 40.0, 75.0]);
+}
+
+#[tokio::test]
+async fn test_limit_per_with_joined_filter() {
+    let pool = create_clean_db().await;
+    let (user1, user2, jar1, _jar2, _donation1, _donation2) = setup_test_data(&pool).await;
+    for (payer, amount) in [(user1.id, 75.0), (user2.id, 40.0), (user2.id, 5.0)] {
+        Donation::test_donation(jar1.id, payer, amount)
+            .save(&pool)
+            .await
+            .expect("Failed to save donation");
+    }
+
+    let donations = Donation::query()
+        .with_payer()
+        .filter(
+            User::USERNAME
+                .eq("owner2".to_string())
+                .or(User::USERNAME.eq("nobody".to_string())),
+        )
+        .order_by(Donation::AMOUNT.desc())
+        .limit_per(Donation::JAR_ID, 1)
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch limited donations");
+
+    let amounts: Vec<f64> = donations.iter().map(|d| d.amount).collect();
+    assert_eq!(amounts, vec![40.0]);
+}
+
+#[tokio::test]
+async fn test_relation_existence_filters() {
+    let pool = create_clean_db().await;
+    let (user1, user2, _jar1, _jar2, _donation1, _donation2) = setup_test_data(&pool).await;
+    let user3 = User::test_user("nojars@example.com", "nojars")
+        .save(&pool)
+        .await
+        .expect("Failed to save user3");
+    let mut goal_jar = Jar::test_jar(user2.id, "goal_jar");
+    goal_jar.goal = Some(500.0);
+    goal_jar.save(&pool).await.expect("Failed to save goal jar");
+
+    let with_jars = User::query()
+        .has_jars()
+        .order_by(User::ID.asc())
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch users with jars");
+    let ids: Vec<i64> = with_jars.iter().map(|u| u.id).collect();
+    assert_eq!(ids, vec![user1.id, user2.id]);
+    assert!(with_jars[0].jars.is_none(), "Children should not be loaded");
+
+    let without_jars = User::query()
+        .doesnt_have_jars()
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch users without jars");
+    assert_eq!(without_jars.len(), 1);
+    assert_eq!(without_jars[0].id, user3.id);
+
+    let with_goal = User::query()
+        .has_jars_where(Jar::GOAL.gt(Some(100.0)))
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch users with goal jars");
+    assert_eq!(with_goal.len(), 1);
+    assert_eq!(with_goal[0].id, user2.id);
+
+    let donors = User::query()
+        .has_payed_donations()
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch donors");
+    assert_eq!(donors.len(), 2);
+}
+
+#[tokio::test]
+async fn test_lazy_loading_in_transaction() {
+    let pool = create_clean_db().await;
+    let (user1, user2, _jar1, _jar2, _donation1, _donation2) = setup_test_data(&pool).await;
+
+    let mut tx = pool.begin().await.expect("Failed to begin transaction");
+    let jar = Jar::test_jar(user1.id, "uncommitted")
+        .save(&mut *tx)
+        .await
+        .expect("Failed to save jar in transaction");
+
+    let jars = user1.jars(&mut *tx).await.expect("Failed to load jars in transaction");
+    assert_eq!(jars.len(), 2, "Uncommitted jar should be visible in the transaction");
+
+    let owner = jar
+        .owner(&mut *tx)
+        .await
+        .expect("Failed to load owner in transaction")
+        .expect("Owner should exist");
+    assert_eq!(owner.id, user1.id);
+
+    let payed = user2
+        .payed_donations(&mut *tx)
+        .await
+        .expect("Failed to load donations in transaction");
+    assert_eq!(payed.len(), 1);
+    tx.rollback().await.expect("Failed to roll back transaction");
+
+    let jars = user1.jars(&pool).await.expect("Failed to load jars");
+    assert_eq!(jars.len(), 1);
+}
+
+#[tokio::test]
+async fn test_add_and_remove_children() {
+    let pool = create_clean_db().await;
+    let (user1, user2, jar1, _jar2, _donation1, _donation2) = setup_test_data(&pool).await;
+
+    let jar = user1
+        .add_jar(Jar::test_jar(user2.id, "added"), &pool)
+        .await
+        .expect("Failed to add jar");
+    assert_eq!(jar.owner_id, user1.id, "Foreign key should point to the parent");
+    assert_eq!(user1.jars(&pool).await.expect("Failed to load jars").len(), 2);
+
+    let err = user2
+        .remove_jar(jar1.clone(), &pool)
+        .await
+        .expect_err("Jar of another user must not be removed");
+    assert!(matches!(err, sqlorm::sqlx::Error::RowNotFound));
+
+    user1
+        .remove_jar(jar, &pool)
+        .await
+        .expect("Failed to remove jar");
+    let jars = user1.jars(&pool).await.expect("Failed to load jars");
+    assert_eq!(jars.len(), 1);
+    assert_eq!(jars[0].id, jar1.id);
+}
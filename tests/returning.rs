@@ -0,0 +1,33 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::User;
+use sqlorm::StatementExecutor;
+
+#[tokio::test]
+async fn test_configurable_returning() {
+    let pool = create_clean_db().await;
+
+    let (id, email): (i64, String) = User::test_user("ret@example.com", "ret")
+        .insert_returning((User::ID, User::EMAIL), &pool)
+        .await
+        .expect("Failed to insert user");
+    assert!(id > 0);
+    assert_eq!(email, "ret@example.com");
+
+    let user = User::test_user("none@example.com", "none")
+        .insert_returning_none(&pool)
+        .await
+        .expect("Failed to insert user");
+    assert!(user.id > id);
+
+    let mut user = user;
+    user.bio = Some("updated".to_string());
+    let (bio,): (Option<String>,) = user
+        .update()
+        .columns((User::BIO,))
+        .execute_returning((User::BIO,), &pool)
+        .await
+        .expect("Failed to update user");
+    assert_eq!(bio.as_deref(), Some("updated"));
+}
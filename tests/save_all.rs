@@ -0,0 +1,37 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::{User, UserExecutor};
+
+#[tokio::test]
+async fn test_save_all() {
+    let pool = create_clean_db().await;
+
+    let mut existing = User::test_user("existing@example.com", "existing")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    existing.bio = Some("changed".to_string());
+    let fresh = User::test_user("fresh@example.com", "fresh");
+
+    let saved = User::save_all(vec![existing.clone(), fresh], &pool)
+        .await
+        .expect("Failed to save users");
+    assert_eq!(saved.len(), 2);
+    assert_eq!(saved[0].id, existing.id);
+    assert_eq!(saved[0].bio.as_deref(), Some("changed"));
+    assert!(saved[1].id > 0);
+
+    let conflicting = vec![
+        User::test_user("atomic@example.com", "atomic"),
+        User::test_user("atomic@example.com", "atomic"),
+    ];
+    assert!(User::save_all(conflicting, &pool).await.is_err());
+
+    let atomic = User::query()
+        .filter(User::EMAIL.eq("atomic@example.com".to_string()))
+        .fetch_optional(&pool)
+        .await
+        .expect("Failed to fetch user");
+    assert!(atomic.is_none(), "failed batch must be rolled back");
+}
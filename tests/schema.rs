@@ -0,0 +1,239 @@
+mod common;
+
+use common::entities::{Donation, Jar, User};
+use sqlorm::Pool;
+use sqlorm::schema::{self, Schema, SchemaMismatch};
+
+#[cfg(feature = "postgres")]
+async fn create_empty_db() -> Pool {
+    common::create_test_db().await
+}
+
+#[cfg(feature = "sqlite")]
+async fn create_empty_db() -> Pool {
+    sqlorm::Sqlorm::options()
+        .max_connections(1)
+        .on_connect("PRAGMA foreign_keys = ON")
+        .connect("sqlite::memory:")
+        .await
+        .expect("Failed to create in-memory SQLite database")
+}
+
+#[test]
+fn test_schema_columns() {
+    let schema = User::schema();
+    assert_eq!(schema.name, "user");
+    let names: Vec<&str> = schema.columns.iter().map(|c| c.name).collect();
+    assert_eq!(names, User::COLUMNS);
+
+    let id = schema.columns.iter().find(|c| c.name == "id").unwrap();
+    assert!(id.primary_key);
+    assert!(id.auto_increment);
+
+    let email = schema.columns.iter().find(|c| c.name == "email").unwrap();
+    assert!(email.unique);
+    assert!(!email.nullable);
+
+    let avatar = schema.columns.iter().find(|c| c.name == "avatar").unwrap();
+    assert!(avatar.nullable);
+    assert_eq!(avatar.sql_type, "TEXT");
+
+    let created_at = schema.columns.iter().find(|c| c.name == "created_at").unwrap();
+    assert_eq!(created_at.default.as_deref(), Some("CURRENT_TIMESTAMP"));
+
+    assert_eq!(schema.indexes.len(), 1);
+    assert_eq!(schema.indexes[0].columns, vec!["first_name", "last_name"]);
+    assert!(!schema.indexes[0].unique);
+}
+
+#[test]
+fn test_schema_foreign_keys() {
+    let schema = Donation::schema();
+    let mut references: Vec<(&str, &str, &str)> = schema
+        .foreign_keys
+        .iter()
+        .map(|fk| (fk.column, fk.table, fk.references))
+        .collect();
+    references.sort();
+    assert_eq!(references, vec![("jar_id", "jar", "id"), ("payer_id", "user", "id")]);
+}
+
+#[test]
+fn test_create_table_sql() {
+    let sql = Jar::create_table_sql();
+    assert!(sql.starts_with("CREATE TABLE IF NOT EXISTS \"jar\" ("));
+    assert!(sql.contains("\"alias\" TEXT NOT NULL UNIQUE"));
+    assert!(sql.contains("\"description\" TEXT,"));
+    assert!(sql.contains("FOREIGN KEY (\"owner_id\") REFERENCES \"user\"(\"id\")"));
+
+    #[cfg(feature = "postgres")]
+    assert!(sql.contains("\"id\" BIGSERIAL PRIMARY KEY"));
+    #[cfg(feature = "sqlite")]
+    assert!(sql.contains("\"id\" INTEGER PRIMARY KEY AUTOINCREMENT"));
+
+    let sql = User::create_table_sql();
+    assert!(sql.contains(
+        "CREATE INDEX IF NOT EXISTS \"user_first_name_last_name_idx\" ON \"user\" (\"first_name\", \"last_name\")"
+    ));
+}
+
+#[tokio::test]
+async fn test_create_table() {
+    let pool = create_empty_db().await;
+    schema::create_table::<User>(&pool).await.expect("Failed to create users");
+    schema::create_table::<Jar>(&pool).await.expect("Failed to create jars");
+    schema::create_table::<Donation>(&pool)
+        .await
+        .expect("Failed to create donations");
+    // existing tables and indexes are left alone
+    schema::create_table::<User>(&pool)
+        .await
+        .expect("Failed to create users again");
+
+    let user = User::test_user("ddl@example.com", "ddl")
+        .save(&pool)
+        .await
+        .expect("Failed to insert user");
+    let jar = Jar::test_jar(user.id, "ddl")
+        .save(&pool)
+        .await
+        .expect("Failed to insert jar");
+    assert_eq!(jar.owner_id, user.id);
+}
+
+#[tokio::test]
+async fn test_sync_creates_tables_and_columns() {
+    let pool = create_empty_db().await;
+    let schemas = [User::schema(), Jar::schema(), Donation::schema()];
+    schema::sync(&pool, &schemas).await.expect("Failed to sync");
+    // syncing an up-to-date database changes nothing
+    schema::sync(&pool, &schemas).await.expect("Failed to sync again");
+
+    let user = User::test_user("sync@example.com", "sync")
+        .save(&pool)
+        .await
+        .expect("Failed to insert user");
+    Jar::test_jar(user.id, "sync")
+        .save(&pool)
+        .await
+        .expect("Failed to insert jar");
+
+    let mut jar = Jar::schema();
+    jar.columns.push(schema::ColumnSchema {
+        name: "color",
+        sql_type: "TEXT".to_string(),
+        nullable: true,
+        primary_key: false,
+        auto_increment: false,
+        unique: false,
+        default: Some("'red'".to_string()),
+    });
+    schema::sync(&pool, &[jar]).await.expect("Failed to add column");
+
+    let columns = schema::table_columns(&pool, Jar::TABLE_NAME)
+        .await
+        .expect("Failed to introspect jars");
+    assert_eq!(columns.len(), Jar::COLUMNS.len() + 1);
+    let color = columns.last().unwrap();
+    assert_eq!(color.name, "color");
+    assert!(color.nullable);
+}
+
+#[tokio::test]
+async fn test_table_columns_of_missing_table() {
+    let pool = create_empty_db().await;
+    let columns = schema::table_columns(&pool, "missing")
+        .await
+        .expect("Failed to introspect");
+    assert!(columns.is_empty());
+}
+
+#[tokio::test]
+async fn test_verify_matching_schema() {
+    let pool = common::create_clean_db().await;
+    assert_eq!(schema::verify::<User>(&pool).await.unwrap(), vec![]);
+    assert_eq!(schema::verify::<Jar>(&pool).await.unwrap(), vec![]);
+}
+
+#[tokio::test]
+async fn test_verify_reports_mismatches() {
+    let pool = create_empty_db().await;
+    assert_eq!(
+        schema::verify::<Jar>(&pool).await.unwrap(),
+        vec![SchemaMismatch::MissingTable { table: "jar" }]
+    );
+
+    #[cfg(feature = "postgres")]
+    let sql = r#"CREATE TABLE "jar" ("id" BIGSERIAL PRIMARY KEY, "title" BYTEA NOT NULL, "description" TEXT NOT NULL)"#;
+    #[cfg(feature = "sqlite")]
+    let sql = r#"CREATE TABLE "jar" ("id" INTEGER PRIMARY KEY, "title" BLOB NOT NULL, "description" TEXT NOT NULL)"#;
+    sqlorm::sqlx::raw_sql(sql)
+        .execute(&pool)
+        .await
+        .expect("Failed to create jars");
+
+    let mismatches = schema::verify::<Jar>(&pool).await.unwrap();
+    assert!(matches!(
+        &mismatches[0],
+        SchemaMismatch::TypeMismatch { column: "title", .. }
+    ));
+    assert_eq!(
+        mismatches[1],
+        SchemaMismatch::NullableMismatch {
+            column: "description",
+            expected: true,
+            found: false,
+        }
+    );
+    assert!(mismatches.contains(&SchemaMismatch::MissingColumn { column: "alias" }));
+    assert_eq!(
+        mismatches[1].to_string(),
+        "column \"description\" is NOT NULL, expected nullable"
+    );
+}
+
+#[test]
+fn test_alter_table_sql() {
+    let sql = schema::alter_table("billing.invoices")
+        .add_column(schema::ColumnSchema::new::<i64>("visits").default("0"))
+        .rename_column("bio", "about")
+        .rename_to("bills")
+        .drop_column("legacy")
+        .to_sql();
+    assert_eq!(sql[0], format!(
+        "ALTER TABLE \"billing\".\"invoices\" ADD COLUMN \"visits\" {} NOT NULL DEFAULT 0",
+        schema::ColumnSchema::new::<i64>("visits").sql_type
+    ));
+    assert_eq!(sql[1], "ALTER TABLE \"billing\".\"invoices\" RENAME COLUMN \"bio\" TO \"about\"");
+    assert_eq!(sql[2], "ALTER TABLE \"billing\".\"invoices\" RENAME TO \"bills\"");
+    assert_eq!(sql[3], "ALTER TABLE \"billing\".\"bills\" DROP COLUMN \"legacy\"");
+    assert_eq!(schema::drop_table_sql("bills"), "DROP TABLE IF EXISTS \"bills\"");
+}
+
+#[tokio::test]
+async fn test_alter_and_drop_table() {
+    let pool = create_empty_db().await;
+    sqlorm::sqlx::raw_sql(r#"CREATE TABLE "scratch" ("id" INTEGER PRIMARY KEY, "bio" TEXT, "legacy" TEXT)"#)
+        .execute(&pool)
+        .await
+        .expect("Failed to create table");
+
+    schema::alter_table("scratch")
+        .add_column(schema::ColumnSchema::new::<String>("nickname").nullable())
+        .rename_column("bio", "about")
+        .drop_column("legacy")
+        .rename_to("notes")
+        .add_index(&["nickname"], true)
+        .execute(&pool)
+        .await
+        .expect("Failed to alter table");
+
+    let columns = schema::table_columns(&pool, "notes").await.unwrap();
+    let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["id", "about", "nickname"]);
+    assert!(columns[2].unique);
+    assert!(schema::table_columns(&pool, "scratch").await.unwrap().is_empty());
+
+    schema::drop_table(&pool, "notes").await.expect("Failed to drop table");
+    assert!(schema::table_columns(&pool, "notes").await.unwrap().is_empty());
+}
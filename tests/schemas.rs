@@ -0,0 +1,76 @@
+#![cfg(feature = "postgres")]
+mod common;
+
+use common::create_clean_db;
+use sqlorm::StatementExecutor;
+use sqlorm::Table;
+use sqlorm::table;
+
+#[table(name = "invoices", schema = "billing")]
+#[derive(Debug, Clone, Default)]
+pub struct Invoice {
+    #[sql(pk)]
+    pub id: i64,
+    #[sql(unique)]
+    pub number: String,
+    pub total: f64,
+}
+
+#[table(name = "billing_invoices")]
+#[derive(Debug, Clone, Default)]
+pub struct UnqualifiedInvoice {
+    #[sql(pk)]
+    pub id: i64,
+}
+
+#[tokio::test]
+async fn test_schema_qualified_crud() {
+    let pool = create_clean_db().await;
+
+    let mut invoice = Invoice {
+        number: "INV-1".to_string(),
+        total: 10.0,
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to insert invoice");
+    assert!(invoice.id > 0);
+
+    invoice.total = 12.5;
+    let invoice = invoice.save(&pool).await.expect("Failed to update invoice");
+
+    let found = Invoice::query()
+        .filter(Invoice::NUMBER.eq("INV-1".to_string()))
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to query invoice");
+    assert_eq!(found.id, invoice.id);
+    assert_eq!(found.total, 12.5);
+
+    invoice
+        .delete()
+        .execute(&pool)
+        .await
+        .expect("Failed to delete invoice");
+    let remaining = Invoice::query()
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to query invoices");
+    assert!(remaining.is_empty());
+}
+
+#[test]
+fn test_schema_qualified_constants() {
+    assert_eq!(Invoice::TABLE_NAME, "billing.invoices");
+    assert_eq!(Invoice::SQL_NAME, "\"billing\".\"invoices\"");
+    assert_eq!(
+        Invoice::qualified(Invoice::NUMBER),
+        "\"billing\".\"invoices\".\"number\""
+    );
+    assert_eq!(Invoice::table_info().alias, "__7_billing_invoices");
+    assert_ne!(
+        Invoice::table_info().alias,
+        UnqualifiedInvoice::table_info().alias
+    );
+}
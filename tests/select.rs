@@ -1,7 +1,7 @@
 mod common;
 
 use common::create_clean_db;
-use common::entities::{Donation, Jar, User};
+use common::entities::{Donation, Jar, User, UserContact, UserProjections};
 use sqlorm::GenericExecutor;
 use uuid::Uuid;
 
@@ -202,3 +202,158 @@ async fn test_select_timestamp_fields() {
     assert!(created_at <= now, "created_at should not be in the future");
     assert!(updated_at <= now, "updated_at should not be in the future");
 }
+
+#[tokio::test]
+async fn test_donation_select_expr() {
+    let pool = create_clean_db().await;
+    let (_user, _jar, donation) = setup_select_test_data(&pool).await;
+
+    let (id, total): (Uuid, f64) = Donation::query()
+        .filter(Donation::ID.eq(donation.id))
+        .select((Donation::ID,))
+        .select_expr::<f64>("amount + tip", "total")
+        .fetch_one_as(&pool)
+        .await
+        .expect("Failed to select computed expression");
+
+    assert_eq!(id, donation.id);
+    assert!((total - (donation.amount + donation.tip)).abs() < f64::EPSILON);
+}
+
+#[tokio::test]
+async fn test_donation_select_window_function() {
+    use sqlorm::window::row_number;
+
+    let pool = create_clean_db().await;
+    let (user, jar, first) = setup_select_test_data(&pool).await;
+    let second = Donation::test_donation(jar.id, user.id, 10.0)
+        .save(&pool)
+        .await
+        .expect("Failed to save donation");
+
+    let rows: Vec<(Uuid, i64)> = Donation::query()
+        .select((Donation::ID,))
+        .select_expr::<i64>(
+            row_number().over(Donation::JAR_ID, vec![Donation::AMOUNT.desc()]),
+            "rn",
+        )
+        .order_by(Donation::AMOUNT.desc())
+        .fetch_all_as(&pool)
+        .await
+        .expect("Failed to select window function");
+
+    assert_eq!(rows, vec![(first.id, 1), (second.id, 2)]);
+}
+
+#[tokio::test]
+async fn test_user_explain() {
+    let pool = create_clean_db().await;
+    setup_select_test_data(&pool).await;
+
+    let plan = User::query()
+        .filter(User::EMAIL.eq("select@example.com".to_string()))
+        .explain(&pool)
+        .await
+        .expect("Failed to explain query");
+
+    assert!(!plan.is_empty());
+
+    #[cfg(feature = "postgres")]
+    {
+        let analyzed = User::query()
+            .explain_analyze(&pool)
+            .await
+            .expect("Failed to explain analyze query");
+        assert!(analyzed.contains("actual time"));
+    }
+}
+
+#[tokio::test]
+async fn test_user_pluck() {
+    let pool = create_clean_db().await;
+    setup_select_test_data(&pool).await;
+    User::test_user("pluck@example.com", "pluckuser")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+
+    let emails: Vec<String> = User::query()
+        .order_by(User::ID.asc())
+        .pluck(User::EMAIL, &pool)
+        .await
+        .expect("Failed to pluck emails");
+
+    assert_eq!(emails, vec!["select@example.com", "pluck@example.com"]);
+}
+
+#[derive(sqlorm::FromAliasedRow, Default, Debug)]
+#[sql(entity = User)]
+struct UserSummary {
+    id: i64,
+    email: String,
+    avatar_url: Option<String>,
+    #[sql(skip)]
+    display: String,
+}
+
+#[tokio::test]
+async fn test_user_select_as_projection() {
+    let pool = create_clean_db().await;
+    let (user, _jar, _donation) = setup_select_test_data(&pool).await;
+
+    let summaries: Vec<UserSummary> = User::query()
+        .filter(User::ID.eq(user.id))
+        .select_as::<UserSummary>()
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to select projection");
+
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].id, user.id);
+    assert_eq!(summaries[0].email, "select@example.com");
+    assert_eq!(summaries[0].avatar_url, user.avatar_url);
+    assert!(summaries[0].display.is_empty());
+}
+
+#[tokio::test]
+async fn test_declared_projection() {
+    let pool = create_clean_db().await;
+    let (user, _jar, _donation) = setup_select_test_data(&pool).await;
+
+    let contacts: Vec<UserContact> = User::query()
+        .filter(User::ID.eq(user.id))
+        .select_contact()
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to select declared projection");
+
+    assert_eq!(contacts.len(), 1);
+    assert_eq!(contacts[0].id, user.id);
+    assert_eq!(contacts[0].email, "select@example.com");
+    assert_eq!(contacts[0].username, user.username);
+}
+
+#[tokio::test]
+async fn test_not_persistent_queries() {
+    let pool = create_clean_db().await;
+    let (user, _jar, _donation) = setup_select_test_data(&pool).await;
+
+    let (id, email): (i64, String) = User::query()
+        .filter(User::EMAIL.eq("select@example.com".to_string()))
+        .not_persistent()
+        .select((User::ID, User::EMAIL))
+        .fetch_one_as(&pool)
+        .await
+        .expect("Failed to run non-persistent query");
+    assert_eq!(id, user.id);
+    assert_eq!(email, user.email);
+
+    let affected = User::update_where()
+        .set(User::BIO, Some("Updated".to_string()))
+        .filter(User::ID.eq(user.id))
+        .not_persistent()
+        .execute(&pool)
+        .await
+        .expect("Failed to run non-persistent update");
+    assert_eq!(affected, 1);
+}
@@ -0,0 +1,182 @@
+mod common;
+
+use common::create_clean_db;
+use sqlorm::{Pool, RelationCycle, table};
+
+#[table(name = "categories")]
+#[derive(Debug, Clone, Default)]
+pub struct Category {
+    #[sql(pk)]
+    #[sql(relation(has_many -> Category, name = "children", on = parent_id, on_remove = nullify, count))]
+    pub id: i64,
+    pub name: String,
+    #[sql(relation(belongs_to -> Category, name = "parent", on = id))]
+    pub parent_id: i64,
+}
+
+async fn save_category(pool: &Pool, name: &str, parent_id: i64) -> Category {
+    Category {
+        name: name.to_string(),
+        parent_id,
+        ..Default::default()
+    }
+    .save(pool)
+    .await
+    .expect("Failed to save category")
+}
+
+#[tokio::test]
+async fn test_self_referential_lazy_loading() {
+    let pool = create_clean_db().await;
+    let root = save_category(&pool, "root", 0).await;
+    let child = save_category(&pool, "child", root.id).await;
+
+    let children = root.children(&pool).await.expect("Failed to load children");
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].id, child.id);
+
+    let parent = child
+        .parent(&pool)
+        .await
+        .expect("Failed to load parent")
+        .expect("Parent should exist");
+    assert_eq!(parent.id, root.id);
+}
+
+#[tokio::test]
+async fn test_self_referential_eager_loading() {
+    let pool = create_clean_db().await;
+    let root = save_category(&pool, "root", 0).await;
+    let child = save_category(&pool, "child", root.id).await;
+    save_category(&pool, "grandchild", child.id).await;
+
+    let loaded = Category::query()
+        .filter(Category::ID.eq(child.id))
+        .with_parent()
+        .with_children()
+        .with_children_count()
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch category");
+
+    assert_eq!(loaded.name, "child");
+    let parent = loaded.parent.expect("Parent should be loaded");
+    assert_eq!(parent.id, root.id);
+    assert_eq!(parent.name, "root");
+    let children = loaded.children.expect("Children should be loaded");
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].name, "grandchild");
+    assert_eq!(loaded.children_count, Some(1));
+}
+
+#[tokio::test]
+async fn test_inner_eager_join_skips_rows_without_parent() {
+    let pool = create_clean_db().await;
+    let root = save_category(&pool, "root", 0).await;
+    let child = save_category(&pool, "child", root.id).await;
+
+    let categories = Category::query()
+        .with_parent_inner()
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch categories");
+
+    assert_eq!(categories.len(), 1);
+    assert_eq!(categories[0].id, child.id);
+    let parent = categories[0].parent.as_ref().expect("Parent should be loaded");
+    assert_eq!(parent.id, root.id);
+}
+
+#[tokio::test]
+async fn test_load_descendants() {
+    let pool = create_clean_db().await;
+    let root = save_category(&pool, "root", 0).await;
+    let a = save_category(&pool, "a", root.id).await;
+    save_category(&pool, "b", root.id).await;
+    let a1 = save_category(&pool, "a1", a.id).await;
+    save_category(&pool, "a1x", a1.id).await;
+
+    let tree = root
+        .clone()
+        .load_descendants(2, &pool)
+        .await
+        .expect("Failed to load descendants");
+
+    let mut children = tree.children.expect("Children should be loaded");
+    children.sort_by_key(|c| c.id);
+    assert_eq!(children.len(), 2);
+    assert_eq!(children[0].name, "a");
+
+    let grandchildren = children[0].children.as_ref().expect("Grandchildren should be loaded");
+    assert_eq!(grandchildren.len(), 1);
+    assert_eq!(grandchildren[0].name, "a1");
+    assert!(
+        grandchildren[0].children.is_none(),
+        "Levels below depth should not be loaded"
+    );
+    assert_eq!(children[1].children.as_ref().map(Vec::len), Some(0));
+
+    let unloaded = root.load_descendants(0, &pool).await.expect("Depth 0 is a no-op");
+    assert!(unloaded.children.is_none());
+}
+
+#[tokio::test]
+async fn test_load_descendants_detects_cycles() {
+    let pool = create_clean_db().await;
+    let mut first = save_category(&pool, "first", 0).await;
+    let second = save_category(&pool, "second", first.id).await;
+    first.parent_id = second.id;
+    let first = first.save(&pool).await.expect("Failed to close the cycle");
+
+    let err = first
+        .clone()
+        .load_descendants(5, &pool)
+        .await
+        .expect_err("Cyclic categories should fail to load");
+    assert!(RelationCycle::is(&err), "unexpected error: {err}");
+
+    let shallow = first
+        .load_descendants(1, &pool)
+        .await
+        .expect("A single level has no cycle yet");
+    assert_eq!(shallow.children.as_ref().map(Vec::len), Some(1));
+}
+
+#[tokio::test]
+async fn test_add_and_nullify_children() {
+    let pool = create_clean_db().await;
+    let root = save_category(&pool, "root", 0).await;
+    let other_root = save_category(&pool, "other", 0).await;
+
+    let child = root
+        .add_children(
+            Category {
+                name: "child".to_string(),
+                ..Default::default()
+            },
+            &pool,
+        )
+        .await
+        .expect("Failed to add child");
+    assert_eq!(child.parent_id, root.id);
+
+    let err = other_root
+        .remove_children(child.clone(), &pool)
+        .await
+        .expect_err("Child of another row must not be removed");
+    assert!(matches!(err, sqlorm::sqlx::Error::RowNotFound));
+
+    let detached = root
+        .remove_children(child, &pool)
+        .await
+        .expect("Failed to remove child");
+    assert_eq!(detached.parent_id, 0);
+    assert!(root.children(&pool).await.expect("Failed to load children").is_empty());
+    assert!(
+        Category::find_by_id(&pool, detached.id)
+            .await
+            .expect("Failed to find category")
+            .is_some(),
+        "Nullified child must be kept"
+    );
+}
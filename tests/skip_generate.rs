@@ -0,0 +1,39 @@
+mod common;
+
+use common::create_clean_db;
+use sqlorm::table;
+
+// reads `notes` with its own `delete`, which the generated one would clash with
+#[table(name = "notes", skip_generate(find_by, delete))]
+#[derive(Debug, Clone, Default)]
+pub struct ArchivedNote {
+    #[sql(pk)]
+    pub id: i64,
+    pub body: String,
+}
+
+impl ArchivedNote {
+    pub fn delete(&self) -> &'static str {
+        "archived notes are never deleted"
+    }
+}
+
+#[tokio::test]
+async fn test_skip_generate_keeps_remaining_api() {
+    let pool = create_clean_db().await;
+    let note = ArchivedNote {
+        body: "kept".to_string(),
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to save note");
+
+    let found = ArchivedNote::query()
+        .filter(ArchivedNote::ID.eq(note.id))
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to query note");
+    assert_eq!(found.body, "kept");
+    assert_eq!(found.delete(), "archived notes are never deleted");
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use common::create_clean_db;
+use common::entities::{User, UserExecutor};
+use sqlorm::slow_query::{self, SlowQuery};
+
+// the callback is global, so everything runs in a single test
+#[tokio::test]
+async fn test_slow_query_callback() {
+    let pool = create_clean_db().await;
+    User::test_user("slow@example.com", "slow")
+        .save(&pool)
+        .await
+        .unwrap();
+
+    let reported: Arc<Mutex<Vec<SlowQuery>>> = Arc::default();
+    let sink = Arc::clone(&reported);
+    slow_query::on_slow_query(Duration::ZERO, move |query| {
+        sink.lock().unwrap().push(query.clone());
+    });
+
+    User::query()
+        .filter(User::USERNAME.eq("slow".to_string()))
+        .limit(5)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    {
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), 1);
+        assert!(reported[0].sql.contains("WHERE"), "{}", reported[0].sql);
+        assert_eq!(reported[0].values[0], "\"slow\"");
+        assert!(reported[0].debug_sql().contains("\"slow\""));
+    }
+
+    User::delete_where()
+        .filter(User::USERNAME.eq("nobody".to_string()))
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(reported.lock().unwrap().len(), 2);
+
+    let page = User::query()
+        .filter(User::USERNAME.eq("slow".to_string()))
+        .paginate(1, 10, &pool)
+        .await
+        .unwrap();
+    assert_eq!(page.total, 1);
+    User::query().pluck(User::USERNAME, &pool).await.unwrap();
+    User::upsert_many(vec![User::test_user("upserted@example.com", "upserted")])
+        .execute(&pool)
+        .await
+        .unwrap();
+    {
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), 6);
+        assert!(reported[2].sql.starts_with("SELECT COUNT(*)"), "{}", reported[2].sql);
+        assert_eq!(reported[2].values, vec!["\"slow\"".to_string()]);
+        assert!(reported[5].sql.starts_with("INSERT INTO"), "{}", reported[5].sql);
+    }
+
+    slow_query::on_slow_query(Duration::from_secs(3600), {
+        let sink = Arc::clone(&reported);
+        move |query| sink.lock().unwrap().push(query.clone())
+    });
+    User::query().fetch_all(&pool).await.unwrap();
+    assert_eq!(reported.lock().unwrap().len(), 6, "fast queries aren't reported");
+
+    slow_query::on_slow_query(Duration::ZERO, {
+        let sink = Arc::clone(&reported);
+        move |query| sink.lock().unwrap().push(query.clone())
+    });
+    slow_query::clear();
+    User::query().fetch_all(&pool).await.unwrap();
+    assert_eq!(reported.lock().unwrap().len(), 6, "cleared callbacks aren't called");
+}
@@ -3,6 +3,17 @@ mod common;
 
 use common::create_clean_db;
 use common::entities::{Jar, JarExecutor, User, UserExecutor};
+use sqlorm::table;
+
+#[table(name = "notes")]
+#[derive(Debug, Clone, Default)]
+pub struct Note {
+    #[sql(pk)]
+    pub id: i64,
+    pub body: String,
+    #[sql(soft_delete)]
+    pub is_deleted: bool,
+}
 
 #[tokio::test]
 async fn test_user_soft_delete_method() {
@@ -16,6 +27,7 @@ async fn test_user_soft_delete_method() {
     user.delete().execute(&pool).await.unwrap();
 
     let db_user: User = User::query()
+        .with_deleted()
         .filter(User::ID.eq(id))
         .fetch_one(&pool)
         .await
@@ -55,3 +67,216 @@ async fn test_jar_hard_delete_method() {
         "Hard delete should physically remove Jar"
     );
 }
+
+#[tokio::test]
+async fn test_delete_where() {
+    let pool = create_clean_db().await;
+
+    let keep = User::test_user("keep@example.com", "keep")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    let gone = User::test_user("gone@example.com", "gone")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+
+    let affected = User::delete_where()
+        .filter(User::ID.eq(gone.id))
+        .execute(&pool)
+        .await
+        .expect("Failed to soft delete users");
+    assert_eq!(affected, 1);
+
+    let gone = User::query()
+        .with_deleted()
+        .filter(User::ID.eq(gone.id))
+        .fetch_one(&pool)
+        .await
+        .expect("Soft deleted user should still exist");
+    assert!(gone.deleted_at.is_some());
+
+    let again = User::delete_where()
+        .filter(User::ID.eq(gone.id))
+        .execute(&pool)
+        .await
+        .expect("Failed to soft delete users");
+    assert_eq!(again, 0, "already deleted rows are not deleted again");
+
+    Jar::test_jar(keep.id, "bulkjar")
+        .save(&pool)
+        .await
+        .expect("Failed to save jar");
+    let affected = Jar::delete_where()
+        .filter(Jar::OWNER_ID.eq(keep.id))
+        .execute(&pool)
+        .await
+        .expect("Failed to hard delete jars");
+    assert_eq!(affected, 1);
+
+    let jars = Jar::query()
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch jars");
+    assert!(jars.is_empty());
+}
+
+#[tokio::test]
+async fn test_query_excludes_soft_deleted() {
+    let pool = create_clean_db().await;
+
+    let alive = User::test_user("alive@example.com", "alive")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    let trashed = User::test_user("trashed@example.com", "trashed")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    let trashed_id = trashed.id;
+    trashed
+        .delete()
+        .execute(&pool)
+        .await
+        .expect("Failed to soft delete user");
+
+    let users = User::query().fetch_all(&pool).await.unwrap();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].id, alive.id);
+
+    let found = User::find_by_id(&pool, trashed_id).await.unwrap();
+    assert!(found.is_none(), "find_by_* should skip soft deleted rows");
+
+    let all = User::query().with_deleted().fetch_all(&pool).await.unwrap();
+    assert_eq!(all.len(), 2);
+
+    let only = User::query().only_deleted().fetch_all(&pool).await.unwrap();
+    assert_eq!(only.len(), 1);
+    assert_eq!(only[0].id, trashed_id);
+
+    let page = User::query().paginate(1, 10, &pool).await.unwrap();
+    assert_eq!(page.total, 1);
+}
+
+#[tokio::test]
+async fn test_force_delete() {
+    let pool = create_clean_db().await;
+    let user = User::test_user("erase@example.com", "erase")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    let id = user.id;
+
+    user.force_delete(&pool)
+        .await
+        .expect("Failed to force delete user");
+
+    let maybe_user: Option<User> = User::query()
+        .with_deleted()
+        .filter(User::ID.eq(id))
+        .fetch_optional(&pool)
+        .await
+        .unwrap();
+
+    assert!(
+        maybe_user.is_none(),
+        "Force delete should physically remove User"
+    );
+}
+
+#[tokio::test]
+async fn test_delete_returning() {
+    let pool = create_clean_db().await;
+    let user = User::test_user("returning@example.com", "returning")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+
+    let jar = Jar::test_jar(user.id, "returningjar")
+        .save(&pool)
+        .await
+        .expect("Failed to save jar");
+    let jar_id = jar.id;
+
+    let removed: Jar = jar
+        .delete_returning(&pool)
+        .await
+        .expect("Failed to delete jar");
+    assert_eq!(removed.id, jar_id);
+    assert_eq!(removed.alias, "returningjar");
+    assert!(Jar::find_by_id(&pool, jar_id).await.unwrap().is_none());
+
+    let trashed: User = user
+        .delete_returning(&pool)
+        .await
+        .expect("Failed to soft delete user");
+    assert!(trashed.deleted_at.is_some());
+}
+
+#[tokio::test]
+async fn test_restore() {
+    let pool = create_clean_db().await;
+    let user = User::test_user("restore@example.com", "restore")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+
+    let trashed = user
+        .delete()
+        .execute(&pool)
+        .await
+        .expect("Failed to soft delete user");
+    assert!(trashed.deleted_at.is_some());
+
+    let restored = trashed.restore(&pool).await.expect("Failed to restore user");
+    assert!(restored.deleted_at.is_none());
+
+    let found = User::find_by_id(&pool, restored.id).await.unwrap();
+    assert!(found.is_some(), "restored user should be visible again");
+}
+
+#[tokio::test]
+async fn test_soft_delete_flag() {
+    let pool = create_clean_db().await;
+    let kept = Note {
+        body: "kept".to_string(),
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to save note");
+    let trashed = Note {
+        body: "trashed".to_string(),
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to save note")
+    .delete()
+    .execute(&pool)
+    .await
+    .expect("Failed to soft delete note");
+    assert!(trashed.is_deleted);
+
+    let notes = Note::query().fetch_all(&pool).await.unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].id, kept.id);
+
+    let only = Note::query().only_deleted().fetch_all(&pool).await.unwrap();
+    assert_eq!(only.len(), 1);
+    assert_eq!(only[0].id, trashed.id);
+
+    let affected = Note::delete_where()
+        .filter(Note::ID.eq(kept.id))
+        .execute(&pool)
+        .await
+        .expect("Failed to soft delete notes");
+    assert_eq!(affected, 1);
+    assert!(Note::query().fetch_all(&pool).await.unwrap().is_empty());
+
+    let restored = trashed.restore(&pool).await.expect("Failed to restore note");
+    assert!(!restored.is_deleted);
+    let notes = Note::query().fetch_all(&pool).await.unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].id, restored.id);
+}
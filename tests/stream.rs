@@ -0,0 +1,71 @@
+use common::entities::{JarExecutor, JarRelations};
+mod common;
+
+use common::create_clean_db;
+use common::entities::{Jar, User};
+use futures_util::TryStreamExt;
+use sqlorm::GenericExecutor;
+
+#[tokio::test]
+async fn test_user_fetch_stream() {
+    let pool = create_clean_db().await;
+    let owner = User::test_user("streamer@example.com", "streamer")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+
+    for i in 0..5 {
+        Jar::test_jar(owner.id, &format!("stream{i}"))
+            .save(&pool)
+            .await
+            .expect("Failed to save jar");
+    }
+
+    let jars: Vec<Jar> = Jar::query()
+        .with_owner()
+        .order_by(Jar::ID.asc())
+        .fetch_stream(&pool)
+        .try_collect()
+        .await
+        .expect("Failed to stream jars");
+
+    assert_eq!(jars.len(), 5);
+    assert_eq!(jars[0].alias, "stream0");
+    assert!(
+        jars.iter()
+            .all(|j| j.owner.as_ref().map(|o| o.id) == Some(owner.id))
+    );
+}
+
+#[tokio::test]
+async fn test_select_fetch_stream_as() {
+    let pool = create_clean_db().await;
+
+    for i in 0..3 {
+        let email = format!("stream_as{i}@example.com");
+        let username = format!("stream_as{i}");
+        User::test_user(&email, &username)
+            .save(&pool)
+            .await
+            .expect("Failed to save user");
+    }
+
+    let mut stream = User::query()
+        .select((User::EMAIL,))
+        .order_by(User::ID.asc())
+        .fetch_stream_as(&pool);
+
+    let mut emails = Vec::new();
+    while let Some((email,)) = stream.try_next().await.expect("Failed to stream users") {
+        emails.push(email);
+    }
+
+    assert_eq!(
+        emails,
+        vec![
+            "stream_as0@example.com",
+            "stream_as1@example.com",
+            "stream_as2@example.com"
+        ]
+    );
+}
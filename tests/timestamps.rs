@@ -255,3 +255,31 @@ async fn test_timestamp_update_behavior() {
     );
     assert_eq!(final_entity.name, "updated_name");
 }
+
+#[tokio::test]
+async fn test_touch() {
+    let pool = create_clean_db().await;
+
+    let saved_entity = ChronoEntity {
+        name: "test_touch".to_string(),
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to save entity");
+    let original_updated_at = saved_entity.updated_at;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+    let mut touched = saved_entity.clone();
+    touched.name = "not_saved".to_string();
+    let touched = touched.touch(&pool).await.expect("Failed to touch entity");
+    assert!(touched.updated_at > original_updated_at);
+
+    let db_entity = ChronoEntity::find_by_id(&pool, saved_entity.id)
+        .await
+        .expect("Failed to fetch entity")
+        .expect("Entity should exist");
+    assert_eq!(db_entity.name, "test_touch", "touch should not write other columns");
+    assert!(db_entity.updated_at > original_updated_at);
+}
@@ -0,0 +1,167 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::{User, UserExecutor};
+use sqlorm::{Isolation, NestedTransaction, TransactionConfig};
+
+#[tokio::test]
+async fn test_transaction_commits_on_ok() {
+    let pool = create_clean_db().await;
+
+    let user = sqlorm::transaction(&pool, async |tx| {
+        User::test_user("tx@example.com", "tx")
+            .save(&mut **tx)
+            .await
+    })
+    .await
+    .expect("Failed to run transaction");
+
+    let users = User::query().fetch_all(&pool).await.unwrap();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].id, user.id);
+}
+
+#[tokio::test]
+async fn test_transaction_rolls_back_on_err() {
+    let pool = create_clean_db().await;
+
+    let result: Result<(), sqlorm::sqlx::Error> = sqlorm::transaction(&pool, async |tx| {
+        User::test_user("tx@example.com", "tx")
+            .save(&mut **tx)
+            .await?;
+        Err(sqlorm::sqlx::Error::RowNotFound)
+    })
+    .await;
+    assert!(matches!(result, Err(sqlorm::sqlx::Error::RowNotFound)));
+
+    let users = User::query().fetch_all(&pool).await.unwrap();
+    assert!(users.is_empty());
+}
+
+#[tokio::test]
+async fn test_transaction_retries() {
+    let pool = create_clean_db().await;
+    let mut attempts = 0;
+
+    TransactionConfig::default()
+        .retries(2)
+        .retry_if(|err: &sqlorm::sqlx::Error| matches!(err, sqlorm::sqlx::Error::PoolTimedOut))
+        .run(&pool, async |tx| {
+            attempts += 1;
+            User::test_user("tx@example.com", "tx")
+                .save(&mut **tx)
+                .await?;
+            if attempts < 3 {
+                return Err(sqlorm::sqlx::Error::PoolTimedOut);
+            }
+            Ok(())
+        })
+        .await
+        .expect("Failed to run transaction");
+
+    assert_eq!(attempts, 3);
+    let users = User::query().fetch_all(&pool).await.unwrap();
+    assert_eq!(users.len(), 1, "Failed attempts should have been rolled back");
+}
+
+#[tokio::test]
+async fn test_transaction_does_not_retry_other_errors() {
+    let pool = create_clean_db().await;
+    let mut attempts = 0;
+
+    let result: Result<(), sqlorm::sqlx::Error> = TransactionConfig::default()
+        .retries(2)
+        .run(&pool, async |_| {
+            attempts += 1;
+            Err(sqlorm::sqlx::Error::RowNotFound)
+        })
+        .await;
+
+    assert!(matches!(result, Err(sqlorm::sqlx::Error::RowNotFound)));
+    assert_eq!(attempts, 1);
+    assert!(!sqlorm::is_transient(&sqlorm::sqlx::Error::RowNotFound));
+}
+
+#[tokio::test]
+async fn test_nested_rollback_keeps_outer_changes() {
+    let pool = create_clean_db().await;
+
+    let mut tx = pool.begin().await.expect("Failed to begin transaction");
+    User::test_user("outer@example.com", "outer")
+        .save(&mut *tx)
+        .await
+        .expect("Failed to save outer user");
+
+    let nested: Result<User, sqlorm::sqlx::Error> = tx
+        .nested(async |inner| {
+            User::test_user("inner@example.com", "inner")
+                .save(&mut **inner)
+                .await?;
+            Err(sqlorm::sqlx::Error::RowNotFound)
+        })
+        .await;
+    assert!(nested.is_err());
+
+    tx.nested(async |inner| {
+        User::test_user("second@example.com", "second")
+            .save(&mut **inner)
+            .await
+    })
+    .await
+    .expect("Failed to run nested transaction");
+    tx.commit().await.expect("Failed to commit");
+
+    let mut emails: Vec<String> = User::query()
+        .fetch_all(&pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|u| u.email)
+        .collect();
+    emails.sort();
+    assert_eq!(emails, vec!["outer@example.com", "second@example.com"]);
+}
+
+#[tokio::test]
+async fn test_nested_without_transaction() {
+    let pool = create_clean_db().await;
+    let mut conn = pool.acquire().await.expect("Failed to acquire connection");
+
+    let result: Result<(), sqlorm::sqlx::Error> = conn
+        .nested(async |tx| {
+            User::test_user("tx@example.com", "tx")
+                .save(&mut **tx)
+                .await?;
+            Err(sqlorm::sqlx::Error::RowNotFound)
+        })
+        .await;
+    assert!(result.is_err());
+    drop(conn);
+
+    assert!(User::query().fetch_all(&pool).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_transaction_isolation() {
+    let pool = create_clean_db().await;
+
+    let user = TransactionConfig::default()
+        .isolation(Isolation::Serializable)
+        .run(&pool, async |tx| {
+            #[cfg(feature = "postgres")]
+            {
+                let level: String = sqlorm::sqlx::query_scalar("SHOW transaction_isolation")
+                    .fetch_one(&mut **tx)
+                    .await?;
+                assert_eq!(level, "serializable");
+            }
+            User::test_user("tx@example.com", "tx")
+                .save(&mut **tx)
+                .await
+        })
+        .await
+        .expect("Failed to run serializable transaction");
+
+    let users = User::query().fetch_all(&pool).await.unwrap();
+    assert_eq!(users[0].id, user.id);
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::{Jar, JarExecutor, User};
+
+#[tokio::test]
+async fn test_truncate() {
+    let pool = create_clean_db().await;
+
+    let owner = User::test_user("truncate@example.com", "truncate")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    for alias in ["first", "second"] {
+        Jar::test_jar(owner.id, alias)
+            .save(&pool)
+            .await
+            .expect("Failed to save jar");
+    }
+
+    Jar::truncate(&pool).await.expect("Failed to truncate jars");
+
+    let jars = Jar::query().fetch_all(&pool).await.unwrap();
+    assert!(jars.is_empty());
+
+    let jar = Jar::test_jar(owner.id, "after")
+        .save(&pool)
+        .await
+        .expect("Failed to save jar");
+    assert_eq!(jar.id, 1, "primary key sequence should be reset");
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::User;
+
+async fn setup_union_users(pool: &sqlorm::Pool) -> Vec<User> {
+    let mut saved = Vec::new();
+    for i in 0..4 {
+        let email = format!("union{i}@example.com");
+        let username = format!("union{i}");
+        let mut user = User::test_user(&email, &username);
+        if i % 2 == 0 {
+            user.bio = Some("even".to_string());
+        }
+        saved.push(user.save(pool).await.expect("Failed to save user"));
+    }
+    saved
+}
+
+#[tokio::test]
+async fn test_union_deduplicates_rows() {
+    let pool = create_clean_db().await;
+    let users = setup_union_users(&pool).await;
+
+    let results: Vec<User> = User::query()
+        .filter(User::ID.le(users[1].id))
+        .union(User::query().filter(User::BIO.is_not_null()))
+        .order_by(User::ID.asc())
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch union");
+
+    let ids: Vec<i64> = results.iter().map(|u| u.id).collect();
+    assert_eq!(ids, vec![users[0].id, users[1].id, users[2].id]);
+}
+
+#[tokio::test]
+async fn test_union_all_keeps_duplicates_and_limits() {
+    let pool = create_clean_db().await;
+    let users = setup_union_users(&pool).await;
+
+    let results: Vec<(i64,)> = User::query()
+        .filter(User::ID.eq(users[0].id))
+        .select((User::ID,))
+        .union_all(
+            User::query()
+                .filter(User::BIO.is_not_null())
+                .select((User::ID,)),
+        )
+        .order_by(User::ID.desc())
+        .limit(2)
+        .fetch_all_as(&pool)
+        .await
+        .expect("Failed to fetch union all");
+
+    assert_eq!(results, vec![(users[2].id,), (users[0].id,)]);
+}
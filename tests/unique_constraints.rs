@@ -0,0 +1,82 @@
+mod common;
+
+use common::create_clean_db;
+use sqlorm::table;
+
+#[table(name = "accounts", unique(tenant_id, email))]
+#[derive(Debug, Clone, Default)]
+pub struct Account {
+    #[sql(pk)]
+    pub id: i64,
+    pub tenant_id: i64,
+    pub email: String,
+    pub name: String,
+}
+
+fn account(tenant_id: i64, email: &str, name: &str) -> Account {
+    Account {
+        tenant_id,
+        email: email.to_string(),
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_find_by_composite_unique() {
+    let pool = create_clean_db().await;
+
+    let first = account(1, "a@example.com", "First")
+        .save(&pool)
+        .await
+        .expect("Failed to save account");
+    account(2, "a@example.com", "Second")
+        .save(&pool)
+        .await
+        .expect("Failed to save account");
+
+    let found = Account::find_by_tenant_id_and_email(&pool, 1, "a@example.com".to_string())
+        .await
+        .expect("Failed to find account")
+        .expect("Account not found");
+    assert_eq!(found.id, first.id);
+
+    let missing = Account::find_by_tenant_id_and_email(&pool, 3, "a@example.com".to_string())
+        .await
+        .expect("Failed to find account");
+    assert!(missing.is_none());
+}
+
+#[tokio::test]
+async fn test_upsert_on_composite_unique() {
+    let pool = create_clean_db().await;
+
+    account(1, "a@example.com", "Old")
+        .save(&pool)
+        .await
+        .expect("Failed to save account");
+
+    let saved = Account::upsert_many(vec![
+        account(1, "a@example.com", "New"),
+        account(1, "b@example.com", "Other"),
+    ])
+    .on_conflict(Account::TENANT_ID_AND_EMAIL)
+    .update((Account::NAME,))
+    .execute(&pool)
+    .await
+    .expect("Failed to upsert accounts");
+    assert_eq!(saved.len(), 2);
+
+    let updated = Account::find_by_tenant_id_and_email(&pool, 1, "a@example.com".to_string())
+        .await
+        .expect("Failed to find account")
+        .expect("Account not found");
+    assert_eq!(updated.name, "New");
+
+    let err = Account::upsert_many(vec![account(1, "a@example.com", "Newer")])
+        .update((Account::NAME,))
+        .execute(&pool)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, sqlx::Error::InvalidArgument(_)), "{:?}", err);
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::{Jar, JarExecutor, User};
+use sqlorm::{Expr, StatementExecutor};
+
+#[tokio::test]
+async fn test_update_set_expr() {
+    let pool = create_clean_db().await;
+
+    let owner = User::test_user("counter@example.com", "counter")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    let jar = Jar::test_jar(owner.id, "counterjar")
+        .save(&pool)
+        .await
+        .expect("Failed to save jar");
+
+    jar.clone()
+        .update()
+        .columns((Jar::TITLE,))
+        .set_expr(Jar::TOTAL_DONATIONS, Expr::col(Jar::TOTAL_DONATIONS).add(2))
+        .execute(&pool)
+        .await
+        .expect("Failed to increment counter");
+
+    Jar::update_where()
+        .set_expr(Jar::TOTAL_DONATIONS, Expr::col(Jar::TOTAL_DONATIONS).sub(1))
+        .filter(Jar::ID.eq(jar.id))
+        .execute(&pool)
+        .await
+        .expect("Failed to decrement counter");
+
+    let reloaded = Jar::query()
+        .filter(Jar::ID.eq(jar.id))
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch jar");
+    assert_eq!(reloaded.total_donations, jar.total_donations + 1);
+}
+
+#[tokio::test]
+async fn test_update_set_and_set_raw() {
+    let pool = create_clean_db().await;
+
+    let owner = User::test_user("setraw@example.com", "setraw")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    let mut jar = Jar::test_jar(owner.id, "setrawjar")
+        .save(&pool)
+        .await
+        .expect("Failed to save jar");
+    jar.description = Some("new description".to_string());
+
+    jar.clone()
+        .update()
+        .columns((Jar::TITLE, Jar::DESCRIPTION))
+        .set(Jar::HIDE_EARNINGS, true)
+        .set_raw("title = title || ?", vec![" (archived)".to_string()])
+        .execute(&pool)
+        .await
+        .expect("Failed to update jar");
+
+    let reloaded = Jar::query()
+        .filter(Jar::ID.eq(jar.id))
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch jar");
+    assert_eq!(reloaded.title, format!("{} (archived)", jar.title));
+    assert_eq!(reloaded.description.as_deref(), Some("new description"));
+    assert!(reloaded.hide_earnings);
+
+    let sql = jar
+        .update()
+        .columns((Jar::TITLE,))
+        .set_raw("title = title || ?", vec!["!".to_string()])
+        .debug_sql();
+    assert!(sql.contains("title = title || \"!\""));
+}
+
+#[tokio::test]
+async fn test_detached_set() {
+    let pool = create_clean_db().await;
+
+    let user = User::test_user("detached@example.com", "detached")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+
+    let affected = User::set(User::BIO, Some("detached bio".to_string()))
+        .filter(User::ID.eq(user.id))
+        .execute(&pool)
+        .await
+        .expect("Failed to update user");
+    assert_eq!(affected, 1);
+
+    let reloaded = User::find_by_id(&pool, user.id)
+        .await
+        .unwrap()
+        .expect("User should exist");
+    assert_eq!(reloaded.bio.as_deref(), Some("detached bio"));
+    assert!(reloaded.updated_at > user.updated_at);
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::{Donation, Jar, User, UserExecutor};
+use sqlorm::Expr;
+
+#[tokio::test]
+async fn test_update_where() {
+    let pool = create_clean_db().await;
+
+    let mut ids = Vec::new();
+    for i in 0..3 {
+        let mut user = User::test_user(&format!("bulk{}@example.com", i), &format!("bulk{}", i));
+        user.bio = Some("bio".to_string());
+        ids.push(user.save(&pool).await.expect("Failed to save user").id);
+    }
+
+    let affected = User::update_where()
+        .set(User::BIO, None::<String>)
+        .filter(User::ID.in_(ids[..2].to_vec()))
+        .execute(&pool)
+        .await
+        .expect("Failed to bulk update users");
+    assert_eq!(affected, 2);
+
+    let cleared = User::query()
+        .filter(User::BIO.is_null())
+        .fetch_all(&pool)
+        .await
+        .expect("Failed to fetch users");
+    assert_eq!(cleared.len(), 2);
+
+    let untouched = User::query()
+        .filter(User::ID.eq(ids[2]))
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch user");
+    assert_eq!(untouched.bio.as_deref(), Some("bio"));
+}
+
+#[tokio::test]
+async fn test_update_where_from() {
+    let pool = create_clean_db().await;
+
+    let owner = User::test_user("from@example.com", "from")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    let with_donation = Jar::test_jar(owner.id, "fromjar")
+        .save(&pool)
+        .await
+        .expect("Failed to save jar");
+    let without_donation = Jar::test_jar(owner.id, "emptyjar")
+        .save(&pool)
+        .await
+        .expect("Failed to save jar");
+
+    Donation {
+        note: Some("thanks!".to_string()),
+        ..Donation::test_donation(with_donation.id, owner.id, 10.0)
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to save donation");
+
+    let affected = Jar::update_where()
+        .set_expr(Jar::DESCRIPTION, Expr::qualified(Donation::NOTE))
+        .from(
+            Donation::query().filter(Donation::IS_PAYED.eq(true)),
+            Jar::ID.eq_col(Donation::JAR_ID),
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to update jars from donations");
+    assert_eq!(affected, 1);
+
+    let updated = Jar::find_by_id(&pool, with_donation.id)
+        .await
+        .unwrap()
+        .expect("Jar should exist");
+    assert_eq!(updated.description.as_deref(), Some("thanks!"));
+
+    let untouched = Jar::find_by_id(&pool, without_donation.id)
+        .await
+        .unwrap()
+        .expect("Jar should exist");
+    assert_eq!(untouched.description, without_donation.description);
+}
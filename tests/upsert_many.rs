@@ -0,0 +1,57 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::{Jar, JarExecutor, User};
+
+#[tokio::test]
+async fn test_upsert_many() {
+    let pool = create_clean_db().await;
+
+    let owner = User::test_user("upsert@example.com", "upsert")
+        .save(&pool)
+        .await
+        .expect("Failed to save user");
+    let existing = Jar::test_jar(owner.id, "existing")
+        .save(&pool)
+        .await
+        .expect("Failed to save jar");
+
+    let incoming = vec![
+        Jar {
+            title: "Synced title".to_string(),
+            goal: Some(500.0),
+            description: Some("not updated".to_string()),
+            ..Jar::test_jar(owner.id, "existing")
+        },
+        Jar {
+            title: "Fresh".to_string(),
+            ..Jar::test_jar(owner.id, "fresh")
+        },
+    ];
+
+    let saved = Jar::upsert_many(incoming)
+        .on_conflict(Jar::ALIAS)
+        .update((Jar::TITLE, Jar::GOAL))
+        .execute(&pool)
+        .await
+        .expect("Failed to upsert jars");
+    assert_eq!(saved.len(), 2);
+
+    let synced = Jar::find_by_id(&pool, existing.id)
+        .await
+        .unwrap()
+        .expect("Existing jar should still exist");
+    assert_eq!(synced.title, "Synced title");
+    assert_eq!(synced.goal, Some(500.0));
+    assert_eq!(synced.description, existing.description);
+
+    let jars = Jar::query().fetch_all(&pool).await.unwrap();
+    assert_eq!(jars.len(), 2);
+
+    let skipped = Jar::upsert_many(vec![Jar::test_jar(owner.id, "fresh")])
+        .on_conflict(Jar::ALIAS)
+        .execute(&pool)
+        .await
+        .expect("Failed to upsert jars");
+    assert!(skipped.is_empty(), "conflicting rows are skipped without update columns");
+}
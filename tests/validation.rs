@@ -0,0 +1,133 @@
+mod common;
+
+use common::create_clean_db;
+use sqlorm::{StatementExecutor, Validate, ValidationErrors, table};
+
+fn no_spaces(value: &str) -> Result<(), String> {
+    if value.contains(' ') {
+        Err("must not contain spaces".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+#[table(name = "accounts", validate)]
+#[derive(Debug, Clone, Default)]
+pub struct Account {
+    #[sql(pk)]
+    pub id: i64,
+    #[sql(validate(range(min = 1)))]
+    pub tenant_id: i64,
+    #[sql(validate(length(max = 64), email))]
+    pub email: String,
+    #[sql(validate(length(min = 3, max = 32), custom = no_spaces))]
+    pub name: String,
+}
+
+impl Validate for Account {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if self.email.starts_with(&format!("{}@", self.name)) {
+            errors.add("name", "must differ from the email address");
+        }
+        errors.into_result()
+    }
+}
+
+fn account(tenant_id: i64, email: &str, name: &str) -> Account {
+    Account {
+        tenant_id,
+        email: email.to_string(),
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_validate_collects_all_fields() {
+    assert!(account(1, "alice@example.com", "alice_w").validate().is_ok());
+
+    let errors = account(0, "not an email", "a ").validate().unwrap_err();
+    assert_eq!(errors.field("tenant_id"), ["must be at least 1"]);
+    assert_eq!(errors.field("email"), ["must be an email address"]);
+    assert_eq!(
+        errors.field("name"),
+        ["length must be between 3 and 32", "must not contain spaces"]
+    );
+
+    let errors = account(1, "alice@example.com", "alice").validate().unwrap_err();
+    assert_eq!(errors.field("name"), ["must differ from the email address"]);
+    assert!(errors.field("email").is_empty());
+}
+
+#[tokio::test]
+async fn test_invalid_insert_is_rejected() {
+    let pool = create_clean_db().await;
+
+    let err = account(1, "alice", "alice_w").save(&pool).await.unwrap_err();
+    assert!(ValidationErrors::is(&err), "{:?}", err);
+    let errors = ValidationErrors::from_error(&err).unwrap();
+    assert_eq!(errors.fields().count(), 1);
+    assert_eq!(errors.field("email"), ["must be an email address"]);
+
+    let err = Account::insert_many(vec![account(1, "bob@example.com", "bob b")], &pool)
+        .await
+        .unwrap_err();
+    assert!(ValidationErrors::is(&err), "{:?}", err);
+
+    let err = Account::upsert_many(vec![
+        account(1, "carol@example.com", "carol_w"),
+        account(1, "dave", "dave_w"),
+    ])
+    .execute(&pool)
+    .await
+    .unwrap_err();
+    assert!(ValidationErrors::is(&err), "{:?}", err);
+
+    let count = Account::query().fetch_all(&pool).await.unwrap().len();
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+async fn test_invalid_update_is_rejected() {
+    let pool = create_clean_db().await;
+    let mut saved = account(1, "alice@example.com", "alice_w")
+        .save(&pool)
+        .await
+        .unwrap();
+
+    saved.name = "al".to_string();
+    let err = saved.clone().save(&pool).await.unwrap_err();
+    assert!(ValidationErrors::is(&err), "{:?}", err);
+    let err = saved.clone().update().execute(&pool).await.unwrap_err();
+    assert!(ValidationErrors::is(&err), "{:?}", err);
+
+    let found = Account::query()
+        .filter(Account::ID.eq(saved.id))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(found.name, "alice_w");
+}
+
+#[tokio::test]
+async fn test_unchecked_writes_skip_validation() {
+    let pool = create_clean_db().await;
+
+    let mut saved = account(1, "imported", "legacy user")
+        .save_unchecked(&pool)
+        .await
+        .unwrap();
+    assert_ne!(saved.id, 0);
+
+    saved.name = "x".to_string();
+    let saved = saved.save_unchecked(&pool).await.unwrap();
+    let saved = saved.update().unchecked().execute(&pool).await.unwrap();
+
+    let found = Account::query()
+        .filter(Account::ID.eq(saved.id))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(found.name, "x");
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use common::create_clean_db;
+use common::entities::{Donation, Jar, User, UserExecutor};
+use sqlorm::table;
+
+#[table(name = "jar_stats", view)]
+#[derive(Debug, Clone, Default)]
+pub struct JarStat {
+    #[sql(pk)]
+    pub id: i64,
+    pub title: String,
+    #[sql(relation(belongs_to -> User, name = "owner", on = id))]
+    pub owner_id: i64,
+    pub donation_count: i64,
+    pub donated: f64,
+}
+
+#[tokio::test]
+async fn test_view_is_queried() {
+    let pool = create_clean_db().await;
+    let owner = User::test_user("owner@example.com", "owner")
+        .save(&pool)
+        .await
+        .unwrap();
+    let payer = User::test_user("payer@example.com", "payer")
+        .save(&pool)
+        .await
+        .unwrap();
+    let jar = Jar::test_jar(owner.id, "stats").save(&pool).await.unwrap();
+    let empty = Jar::test_jar(owner.id, "empty").save(&pool).await.unwrap();
+    for amount in [10.0, 15.0] {
+        Donation::test_donation(jar.id, payer.id, amount)
+            .save(&pool)
+            .await
+            .unwrap();
+    }
+
+    let stats = JarStat::query()
+        .filter(JarStat::ID.eq(jar.id))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(stats.donation_count, 2);
+    assert_eq!(stats.donated, 25.0);
+
+    let stats = JarStat::query()
+        .filter(JarStat::DONATION_COUNT.eq(0))
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].id, empty.id);
+}
+
+#[tokio::test]
+#[cfg(feature = "extra-traits")]
+async fn test_view_find_by() {
+    let pool = create_clean_db().await;
+    let owner = User::test_user("owner@example.com", "owner")
+        .save(&pool)
+        .await
+        .unwrap();
+    let jar = Jar::test_jar(owner.id, "stats").save(&pool).await.unwrap();
+
+    let stats = JarStat::find_by_id(&pool, jar.id)
+        .await
+        .unwrap()
+        .expect("jar stats not found");
+    assert_eq!(stats.title, jar.title);
+    assert_eq!(stats.donation_count, 0);
+}
+
+#[tokio::test]
+async fn test_view_relations() {
+    let pool = create_clean_db().await;
+    let owner = User::test_user("owner@example.com", "owner")
+        .save(&pool)
+        .await
+        .unwrap();
+    let jar = Jar::test_jar(owner.id, "stats").save(&pool).await.unwrap();
+
+    let stats = JarStat::query()
+        .filter(JarStat::ID.eq(jar.id))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let loaded = stats
+        .owner(&pool)
+        .await
+        .unwrap()
+        .expect("owner not found");
+    assert_eq!(loaded.id, owner.id);
+
+    let stats = JarStat::query()
+        .with_owner()
+        .filter(JarStat::ID.eq(jar.id))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(stats.owner.expect("owner not loaded").id, owner.id);
+}
+
+#[tokio::test]
+async fn test_view_limit_per_is_rejected_by_the_database() {
+    let pool = create_clean_db().await;
+
+    let result = JarStat::query()
+        .limit_per(JarStat::OWNER_ID, 1)
+        .fetch_all(&pool)
+        .await;
+    assert!(matches!(result, Err(sqlorm::sqlx::Error::Database(_))));
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use common::create_clean_db;
+
+mod model {
+    use sqlorm::table;
+
+    #[table(name = "notes", visibility = "pub(crate)")]
+    #[derive(Debug, Clone, Default)]
+    pub struct Note {
+        #[sql(pk)]
+        pub id: i64,
+        pub body: String,
+    }
+}
+
+use model::{Note, NoteExecutor};
+
+#[tokio::test]
+async fn test_crate_visible_api() {
+    let pool = create_clean_db().await;
+    let note = Note {
+        body: "internal".to_string(),
+        ..Default::default()
+    }
+    .save(&pool)
+    .await
+    .expect("Failed to save note");
+
+    let found = Note::query()
+        .filter(Note::BODY.eq("internal".to_string()))
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to query note");
+    assert_eq!(found.id, note.id);
+}